@@ -34,9 +34,11 @@ use crate::{
     metrics,
     observed_attestations::ObserveOutcome,
     observed_attesters::Error as ObservedAttestersError,
+    shuffling_cache::CacheItem,
     BeaconChain, BeaconChainError, BeaconChainTypes,
 };
 use bls::verify_signature_sets;
+use proto_array::Block as ProtoBlock;
 use slog::debug;
 use slot_clock::SlotClock;
 use state_processing::{
@@ -49,10 +51,11 @@ use state_processing::{
     },
 };
 use std::borrow::Cow;
+use std::sync::Arc;
 use tree_hash::TreeHash;
 use types::{
-    Attestation, BeaconCommittee, CommitteeIndex, Epoch, EthSpec, Hash256, IndexedAttestation,
-    RelativeEpoch, SelectionProof, SignedAggregateAndProof, Slot, SubnetId,
+    Attestation, BeaconCommittee, CommitteeCache, CommitteeIndex, Epoch, EthSpec, Hash256,
+    IndexedAttestation, RelativeEpoch, SelectionProof, SignedAggregateAndProof, Slot, SubnetId,
 };
 
 /// Returned when an attestation was not successfully verified. It might not have been verified for
@@ -616,7 +619,9 @@ pub fn obtain_indexed_attestation_and_committees_per_slot<T: BeaconChainTypes>(
 /// the complexities of `RwLock`s on the shuffling cache, a simple `Cow` isn't suitable here.
 ///
 /// If the committee for `attestation` isn't found in the `shuffling_cache`, we will read a state
-/// from disk and then update the `shuffling_cache`.
+/// from disk and then update the `shuffling_cache`. Whilst this happens, a promise is stored in
+/// the `shuffling_cache` so that any concurrent calls for the same shuffling will wait for this
+/// computation, rather than repeating it.
 pub fn map_attestation_committee<'a, T, F, R>(
     chain: &'a BeaconChain<T>,
     attestation: &Attestation<T::EthSpec>,
@@ -653,82 +658,115 @@ where
 
     metrics::stop_timer(cache_wait_timer);
 
-    if let Some(committee_cache) = shuffling_cache.get(attestation_epoch, target.root) {
-        let committees_per_slot = committee_cache.committees_per_slot();
-        committee_cache
-            .get_beacon_committee(attestation.data.slot, attestation.data.index)
-            .map(|committee| map_fn((committee, committees_per_slot)))
-            .unwrap_or_else(|| {
-                Err(Error::NoCommitteeForSlotAndIndex {
-                    slot: attestation.data.slot,
-                    index: attestation.data.index,
-                })
-            })
-    } else {
-        // Drop the shuffling cache to avoid holding the lock for any longer than
-        // required.
-        drop(shuffling_cache);
-
-        debug!(
-            chain.log,
-            "Attestation processing cache miss";
-            "attn_epoch" => attestation_epoch.as_u64(),
-            "target_block_epoch" => target_block.slot.epoch(T::EthSpec::slots_per_epoch()).as_u64(),
-        );
-
-        let state_read_timer =
-            metrics::start_timer(&metrics::ATTESTATION_PROCESSING_STATE_READ_TIMES);
-
-        let mut state = chain
-            .get_state(&target_block.state_root, Some(target_block.slot))?
-            .ok_or_else(|| BeaconChainError::MissingBeaconState(target_block.state_root))?;
-
-        metrics::stop_timer(state_read_timer);
-        let state_skip_timer =
-            metrics::start_timer(&metrics::ATTESTATION_PROCESSING_STATE_SKIP_TIMES);
-
-        while state.current_epoch() + 1 < attestation_epoch {
-            // Here we tell `per_slot_processing` to skip hashing the state and just
-            // use the zero hash instead.
-            //
-            // The state roots are not useful for the shuffling, so there's no need to
-            // compute them.
-            per_slot_processing(&mut state, Some(Hash256::zero()), &chain.spec)
-                .map_err(|e| BeaconChainError::from(e))?;
+    let committee_cache = match shuffling_cache.get(attestation_epoch, target.root) {
+        Some(CacheItem::Committee(committee_cache)) => committee_cache,
+        Some(CacheItem::Promise(promise)) => {
+            // Drop the shuffling cache so the thread computing the committee cache is able to
+            // insert it.
+            drop(shuffling_cache);
+
+            let _promise_wait_timer =
+                metrics::start_timer(&metrics::ATTESTATION_PROCESSING_SHUFFLING_PROMISE_WAIT_TIMES);
+
+            promise
+                .wait()
+                .ok_or_else(|| BeaconChainError::ShufflingCachePromiseFailed)?
+        }
+        None => {
+            let sender = shuffling_cache.create_promise(attestation_epoch, target.root);
+
+            // Drop the shuffling cache to avoid holding the lock for any longer than
+            // required.
+            drop(shuffling_cache);
+
+            debug!(
+                chain.log,
+                "Attestation processing cache miss";
+                "attn_epoch" => attestation_epoch.as_u64(),
+                "target_block_epoch" => target_block.slot.epoch(T::EthSpec::slots_per_epoch()).as_u64(),
+            );
+
+            // If this function returns early, `sender` is dropped and any threads waiting on the
+            // promise will return an error.
+            let committee_cache =
+                match build_committee_cache(chain, &target_block, attestation_epoch) {
+                    Ok(committee_cache) => Arc::new(committee_cache),
+                    Err(e) => {
+                        // Remove the promise so the next caller computes the committee cache
+                        // again, rather than receiving a promise that has already failed.
+                        if let Some(mut shuffling_cache) = chain
+                            .shuffling_cache
+                            .try_write_for(ATTESTATION_CACHE_LOCK_TIMEOUT)
+                        {
+                            shuffling_cache.remove_promise(attestation_epoch, target.root, &sender);
+                        }
+                        return Err(e);
+                    }
+                };
+
+            chain
+                .shuffling_cache
+                .try_write_for(ATTESTATION_CACHE_LOCK_TIMEOUT)
+                .ok_or_else(|| BeaconChainError::AttestationCacheLockTimeout)?
+                .insert_committee_cache(attestation_epoch, target.root, committee_cache.clone());
+
+            sender.send(committee_cache.clone());
+
+            committee_cache
         }
+    };
+
+    let committees_per_slot = committee_cache.committees_per_slot();
+    committee_cache
+        .get_beacon_committee(attestation.data.slot, attestation.data.index)
+        .map(|committee| map_fn((committee, committees_per_slot)))
+        .unwrap_or_else(|| {
+            Err(Error::NoCommitteeForSlotAndIndex {
+                slot: attestation.data.slot,
+                index: attestation.data.index,
+            })
+        })
+}
 
-        metrics::stop_timer(state_skip_timer);
-        let committee_building_timer =
-            metrics::start_timer(&metrics::ATTESTATION_PROCESSING_COMMITTEE_BUILDING_TIMES);
+/// Reads the state of `target_block` from the database and builds the committee cache for
+/// `attestation_epoch`.
+fn build_committee_cache<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    target_block: &ProtoBlock,
+    attestation_epoch: Epoch,
+) -> Result<CommitteeCache, Error> {
+    let state_read_timer = metrics::start_timer(&metrics::ATTESTATION_PROCESSING_STATE_READ_TIMES);
 
-        let relative_epoch = RelativeEpoch::from_epoch(state.current_epoch(), attestation_epoch)
-            .map_err(BeaconChainError::IncorrectStateForAttestation)?;
+    let mut state = chain
+        .get_state(&target_block.state_root, Some(target_block.slot))?
+        .ok_or_else(|| BeaconChainError::MissingBeaconState(target_block.state_root))?;
 
-        state
-            .build_committee_cache(relative_epoch, &chain.spec)
-            .map_err(|e| BeaconChainError::from(e))?;
+    metrics::stop_timer(state_read_timer);
+    let state_skip_timer = metrics::start_timer(&metrics::ATTESTATION_PROCESSING_STATE_SKIP_TIMES);
 
-        let committee_cache = state
-            .committee_cache(relative_epoch)
+    while state.current_epoch() + 1 < attestation_epoch {
+        // Here we tell `per_slot_processing` to skip hashing the state and just
+        // use the zero hash instead.
+        //
+        // The state roots are not useful for the shuffling, so there's no need to
+        // compute them.
+        per_slot_processing(&mut state, Some(Hash256::zero()), &chain.spec)
             .map_err(|e| BeaconChainError::from(e))?;
-
-        chain
-            .shuffling_cache
-            .try_write_for(ATTESTATION_CACHE_LOCK_TIMEOUT)
-            .ok_or_else(|| BeaconChainError::AttestationCacheLockTimeout)?
-            .insert(attestation_epoch, target.root, committee_cache);
-
-        metrics::stop_timer(committee_building_timer);
-
-        let committees_per_slot = committee_cache.committees_per_slot();
-        committee_cache
-            .get_beacon_committee(attestation.data.slot, attestation.data.index)
-            .map(|committee| map_fn((committee, committees_per_slot)))
-            .unwrap_or_else(|| {
-                Err(Error::NoCommitteeForSlotAndIndex {
-                    slot: attestation.data.slot,
-                    index: attestation.data.index,
-                })
-            })
     }
+
+    metrics::stop_timer(state_skip_timer);
+    let _committee_building_timer =
+        metrics::start_timer(&metrics::ATTESTATION_PROCESSING_COMMITTEE_BUILDING_TIMES);
+
+    let relative_epoch = RelativeEpoch::from_epoch(state.current_epoch(), attestation_epoch)
+        .map_err(BeaconChainError::IncorrectStateForAttestation)?;
+
+    state
+        .build_committee_cache(relative_epoch, &chain.spec)
+        .map_err(|e| BeaconChainError::from(e))?;
+
+    state
+        .committee_cache(relative_epoch)
+        .map(Clone::clone)
+        .map_err(|e| BeaconChainError::from(e).into())
 }
@@ -8,7 +8,7 @@ use crate::block_verification::{
 };
 use crate::errors::{BeaconChainError as Error, BlockProductionError};
 use crate::eth1_chain::{Eth1Chain, Eth1ChainBackend};
-use crate::events::{EventHandler, EventKind};
+use crate::events::{EventHandler, EventKind, EventTopic};
 use crate::head_tracker::HeadTracker;
 use crate::metrics;
 use crate::migrate::Migrate;
@@ -17,7 +17,7 @@ use crate::observed_attestations::{Error as AttestationObservationError, Observe
 use crate::observed_attesters::{ObservedAggregators, ObservedAttesters};
 use crate::observed_block_producers::ObservedBlockProducers;
 use crate::observed_operations::{ObservationOutcome, ObservedOperations};
-use crate::persisted_beacon_chain::PersistedBeaconChain;
+use crate::persisted_beacon_chain::{PersistedBeaconChain, PersistedTreeHashCache};
 use crate::persisted_fork_choice::PersistedForkChoice;
 use crate::shuffling_cache::ShufflingCache;
 use crate::snapshot_cache::SnapshotCache;
@@ -49,11 +49,13 @@ use types::*;
 
 pub type ForkChoiceError = fork_choice::Error<crate::ForkChoiceStoreError>;
 
-// Text included in blocks.
-// Must be 32-bytes or panic.
-//
-//                          |-------must be this long------|
-pub const GRAFFITI: &str = "sigp/lighthouse-0.1.2-prerelease";
+/// The default text included in blocks, if no other graffiti is configured.
+///
+/// Must be no longer than 32 bytes.
+pub const DEFAULT_GRAFFITI: &str = "sigp/lighthouse-0.1.2-prerelease";
+
+/// The default re-org depth (in slots) above which a re-org is logged as a warning.
+pub const DEFAULT_REORG_WARN_DEPTH: u64 = 1;
 
 /// The time-out before failure during an operation to take a read/write RwLock on the canonical
 /// head.
@@ -74,6 +76,7 @@ pub const BEACON_CHAIN_DB_KEY: [u8; 32] = [0; 32];
 pub const OP_POOL_DB_KEY: [u8; 32] = [0; 32];
 pub const ETH1_CACHE_DB_KEY: [u8; 32] = [0; 32];
 pub const FORK_CHOICE_DB_KEY: [u8; 32] = [0; 32];
+pub const HEAD_TREE_HASH_CACHE_DB_KEY: [u8; 32] = [0; 32];
 
 /// The result of a chain segment processing.
 #[derive(Debug)]
@@ -222,6 +225,12 @@ pub struct BeaconChain<T: BeaconChainTypes> {
     pub(crate) validator_pubkey_cache: TimeoutRwLock<ValidatorPubkeyCache>,
     /// A list of any hard-coded forks that have been disabled.
     pub disabled_forks: Vec<String>,
+    /// The graffiti included in produced blocks, unless the validator supplies its own.
+    pub graffiti: Graffiti,
+    /// Re-orgs deeper than this many slots are logged as warnings, others are logged as info.
+    pub reorg_warn_depth: u64,
+    /// If true, a `LightClientFinalityProof` is stored for each finalized block.
+    pub light_client_server: bool,
     /// Logging to CLI, etc.
     pub(crate) log: Logger,
 }
@@ -240,11 +249,43 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     /// We want to ensure that the head never out dates the fork choice to avoid having references
     /// to blocks that do not exist in fork choice.
     pub fn persist_head_and_fork_choice(&self) -> Result<(), Error> {
-        let canonical_head_block_root = self
+        self.persist_head_and_fork_choice_internal(false)
+    }
+
+    /// As per `Self::persist_head_and_fork_choice`, but also persists the tree hash cache of the
+    /// head state so that it needn't be rebuilt when the chain is resumed.
+    ///
+    /// The cache is large, so this is intended to be used when the chain shuts down.
+    pub fn persist_head_and_fork_choice_with_tree_hash_cache(&self) -> Result<(), Error> {
+        self.persist_head_and_fork_choice_internal(true)
+    }
+
+    fn persist_head_and_fork_choice_internal(
+        &self,
+        with_tree_hash_cache: bool,
+    ) -> Result<(), Error> {
+        let (canonical_head_block_root, canonical_head_state_root) = self
             .canonical_head
             .try_read_for(HEAD_LOCK_TIMEOUT)
-            .ok_or_else(|| Error::CanonicalHeadLockTimeout)?
-            .beacon_block_root;
+            .map(|head| (head.beacon_block_root, head.beacon_state_root))
+            .ok_or_else(|| Error::CanonicalHeadLockTimeout)?;
+
+        // The canonical head only retains the committee caches of its state, so the tree hash
+        // cache is read from the snapshot cache instead. It's just an optimization, so it's
+        // omitted if it's unavailable.
+        let head_tree_hash_cache = if with_tree_hash_cache {
+            self.snapshot_cache
+                .try_read_for(BLOCK_PROCESSING_CACHE_LOCK_TIMEOUT)
+                .and_then(|snapshot_cache| {
+                    snapshot_cache.get_tree_hash_cache(canonical_head_block_root)
+                })
+                .map(|tree_hash_cache| PersistedTreeHashCache {
+                    state_root: canonical_head_state_root,
+                    tree_hash_cache,
+                })
+        } else {
+            None
+        };
 
         let persisted_head = PersistedBeaconChain {
             canonical_head_block_root,
@@ -274,6 +315,13 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
         metrics::stop_timer(head_timer);
 
+        if let Some(head_tree_hash_cache) = head_tree_hash_cache {
+            self.store.put_item(
+                &Hash256::from_slice(&HEAD_TREE_HASH_CACHE_DB_KEY),
+                &head_tree_hash_cache,
+            )?;
+        }
+
         Ok(())
     }
 
@@ -404,6 +452,49 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         })
     }
 
+    /// Returns the root and slot of the most recent block which is an ancestor of (or equal to)
+    /// both `block_root_a` and `block_root_b`.
+    ///
+    /// Returns `None` if the two chains do not meet within the roots available in the database.
+    pub fn find_common_ancestor(
+        &self,
+        block_root_a: Hash256,
+        block_root_b: Hash256,
+    ) -> Result<Option<(Hash256, Slot)>, Error> {
+        let mut chain_a = self.rev_iter_block_roots_from(block_root_a)?;
+        let mut chain_b = self.rev_iter_block_roots_from(block_root_b)?;
+
+        // Both iterators yield descending slots, so step whichever chain is ahead until both are
+        // at the same slot and agree on the block root.
+        let mut a = chain_a.next().transpose()?;
+        let mut b = chain_b.next().transpose()?;
+
+        while let (Some((root_a, slot_a)), Some((root_b, slot_b))) = (a, b) {
+            match slot_a.cmp(&slot_b) {
+                Ordering::Greater => a = chain_a.next().transpose()?,
+                Ordering::Less => b = chain_b.next().transpose()?,
+                Ordering::Equal if root_a == root_b => {
+                    // The root is repeated for any skipped slots prior to this one, so continue
+                    // until the slot of the block itself is reached.
+                    let ancestor_slot = process_results(chain_a, |iter| {
+                        iter.take_while(|(root, _)| *root == root_a)
+                            .last()
+                            .map(|(_, slot)| slot)
+                    })?
+                    .unwrap_or(slot_a);
+
+                    return Ok(Some((root_a, ancestor_slot)));
+                }
+                Ordering::Equal => {
+                    a = chain_a.next().transpose()?;
+                    b = chain_b.next().transpose()?;
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
     /// Iterates across all `(state_root, slot)` pairs from the head of the chain (inclusive) to
     /// the earliest reachable ancestor (may or may not be genesis).
     ///
@@ -484,6 +575,18 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             .map(|v| v.clone_with_only_committee_caches())
     }
 
+    /// Returns a clone of the tree hash cache of the head state, if the snapshot cache holds one.
+    ///
+    /// Without it, the head state must be hashed from scratch when a block is built upon it.
+    pub fn head_tree_hash_cache(&self) -> Result<Option<BeaconTreeHashCache>, Error> {
+        let head_block_root = self.head_info()?.block_root;
+
+        Ok(self
+            .snapshot_cache
+            .try_read_for(BLOCK_PROCESSING_CACHE_LOCK_TIMEOUT)
+            .and_then(|snapshot_cache| snapshot_cache.get_tree_hash_cache(head_block_root)))
+    }
+
     /// Returns info representing the head block and state.
     ///
     /// A summarized version of `Self::head` that involves less cloning.
@@ -659,6 +762,29 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         Ok(pubkey_cache.get(validator_index).cloned())
     }
 
+    /// Returns the index, record and balance of the validator with `pubkey` in the restore point
+    /// state with `state_root`, or `None` if that state does not contain the validator.
+    ///
+    /// The validator is read straight from the stored restore point, without decoding the rest
+    /// of the state. Use `HotColdDB::is_restore_point` to check that `state_root` is a restore
+    /// point first.
+    pub fn restore_point_validator(
+        &self,
+        state_root: &Hash256,
+        pubkey: &PublicKeyBytes,
+    ) -> Result<Option<(usize, Validator, u64)>, Error> {
+        let validator_index = match self.validator_index(pubkey)? {
+            Some(validator_index) => validator_index,
+            None => return Ok(None),
+        };
+
+        Ok(self
+            .store
+            .load_restore_point_validator(state_root, validator_index)?
+            .filter(|(validator, _)| validator.pubkey == *pubkey)
+            .map(|(validator, balance)| (validator_index, validator, balance)))
+    }
+
     /// Returns the block canonical root of the current canonical chain at a given slot.
     ///
     /// Returns None if a block doesn't exist at the slot.
@@ -970,6 +1096,8 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             }
         };
 
+        self.register_attestation_imported(attestation);
+
         Ok(unaggregated_attestation)
     }
 
@@ -1004,9 +1132,26 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 .map_err(Error::from)?;
         }
 
+        self.register_attestation_imported(signed_aggregate.attestation());
+
         Ok(signed_aggregate)
     }
 
+    /// Notifies the event handler that `attestation` has been applied to fork choice and the
+    /// relevant attestation pool.
+    ///
+    /// The attestation is only cloned if there is someone to send it to, since this happens for
+    /// every attestation received via gossip.
+    fn register_attestation_imported(&self, attestation: &Attestation<T::EthSpec>) {
+        if self.event_handler.has_subscribers(EventTopic::Attestation) {
+            let _ = self
+                .event_handler
+                .register(EventKind::BeaconAttestationImported {
+                    attestation: Box::new(attestation.clone()),
+                });
+        }
+    }
+
     /// Check that the shuffling at `block_root` is equal to one of the shufflings of `state`.
     ///
     /// The `target_epoch` argument determines which shuffling to check compatibility with, it
@@ -1093,6 +1238,12 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
     /// Accept a pre-verified exit and queue it for inclusion in an appropriate block.
     pub fn import_voluntary_exit(&self, exit: SigVerifiedOp<SignedVoluntaryExit>) {
+        let _ = self
+            .event_handler
+            .register(EventKind::BeaconVoluntaryExitImported {
+                voluntary_exit: Box::new(exit.as_inner().clone()),
+            });
+
         if self.eth1_chain.is_some() {
             self.op_pool.insert_voluntary_exit(exit)
         }
@@ -1563,16 +1714,20 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     ///
     /// The produced block will not be inherently valid, it must be signed by a block producer.
     /// Block signing is out of the scope of this function and should be done by a separate program.
+    ///
+    /// The `validator_graffiti` is included in the block, if supplied. Otherwise, the graffiti of
+    /// the beacon node is used.
     pub fn produce_block(
         &self,
         randao_reveal: Signature,
         slot: Slot,
+        validator_graffiti: Option<Graffiti>,
     ) -> Result<BeaconBlockAndState<T::EthSpec>, BlockProductionError> {
         let state = self
             .state_at_slot(slot - 1, StateSkipConfig::WithStateRoots)
             .map_err(|_| BlockProductionError::UnableToProduceAtSlot(slot))?;
 
-        self.produce_block_on_state(state, slot, randao_reveal)
+        self.produce_block_on_state(state, slot, randao_reveal, validator_graffiti)
     }
 
     /// Produce a block for some `slot` upon the given `state`.
@@ -1588,6 +1743,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         mut state: BeaconState<T::EthSpec>,
         produce_at_slot: Slot,
         randao_reveal: Signature,
+        validator_graffiti: Option<Graffiti>,
     ) -> Result<BeaconBlockAndState<T::EthSpec>, BlockProductionError> {
         metrics::inc_counter(&metrics::BLOCK_PRODUCTION_REQUESTS);
         let timer = metrics::start_timer(&metrics::BLOCK_PRODUCTION_TIMES);
@@ -1615,8 +1771,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             state.latest_block_header.canonical_root()
         };
 
-        let mut graffiti: [u8; 32] = [0; 32];
-        graffiti.copy_from_slice(GRAFFITI.as_bytes());
+        let graffiti = validator_graffiti.unwrap_or(self.graffiti);
 
         let (proposer_slashings, attester_slashings) = self.op_pool.get_slashings(&state);
 
@@ -1691,6 +1846,89 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         Ok((block.message, state))
     }
 
+    /// Logs and records metrics for a re-org from `previous_head` to `new_head`, returning the
+    /// `BeaconChainReorg` event to be published once the new head is enthroned.
+    ///
+    /// Returns `None` if the common ancestor of the two heads could not be found.
+    fn process_reorg(
+        &self,
+        previous_head: &HeadInfo,
+        new_head: &BeaconSnapshot<T::EthSpec>,
+    ) -> Option<EventKind<T::EthSpec>> {
+        let (common_ancestor_root, common_ancestor_slot) =
+            match self.find_common_ancestor(previous_head.block_root, new_head.beacon_block_root) {
+                Ok(Some(common_ancestor)) => common_ancestor,
+                Ok(None) => {
+                    warn!(
+                        self.log,
+                        "Beacon chain re-org without common ancestor";
+                        "previous_head" => format!("{}", previous_head.block_root),
+                        "previous_slot" => previous_head.slot,
+                        "new_head" => format!("{}", new_head.beacon_block_root),
+                        "new_slot" => new_head.beacon_block.slot()
+                    );
+                    return None;
+                }
+                Err(e) => {
+                    error!(
+                        self.log,
+                        "Unable to find re-org common ancestor";
+                        "previous_head" => format!("{}", previous_head.block_root),
+                        "new_head" => format!("{}", new_head.beacon_block_root),
+                        "error" => format!("{:?}", e)
+                    );
+                    return None;
+                }
+            };
+
+        let depth = previous_head
+            .slot
+            .saturating_sub(common_ancestor_slot)
+            .as_u64();
+
+        metrics::observe(&metrics::FORK_CHOICE_REORG_DEPTH, depth as f64);
+
+        if depth > self.reorg_warn_depth {
+            warn!(
+                self.log,
+                "Beacon chain re-org";
+                "depth" => depth,
+                "previous_head" => format!("{}", previous_head.block_root),
+                "previous_slot" => previous_head.slot,
+                "common_ancestor" => format!("{}", common_ancestor_root),
+                "common_ancestor_slot" => common_ancestor_slot,
+                "new_head_parent" => format!("{}", new_head.beacon_block.parent_root()),
+                "new_head" => format!("{}", new_head.beacon_block_root),
+                "new_slot" => new_head.beacon_block.slot()
+            );
+        } else {
+            info!(
+                self.log,
+                "Beacon chain re-org";
+                "depth" => depth,
+                "previous_head" => format!("{}", previous_head.block_root),
+                "previous_slot" => previous_head.slot,
+                "common_ancestor" => format!("{}", common_ancestor_root),
+                "common_ancestor_slot" => common_ancestor_slot,
+                "new_head_parent" => format!("{}", new_head.beacon_block.parent_root()),
+                "new_head" => format!("{}", new_head.beacon_block_root),
+                "new_slot" => new_head.beacon_block.slot()
+            );
+        }
+
+        Some(EventKind::BeaconChainReorg {
+            depth,
+            previous_head_slot: previous_head.slot,
+            previous_head_beacon_block_root: previous_head.block_root,
+            previous_head_state_root: previous_head.state_root,
+            current_head_slot: new_head.beacon_block.slot(),
+            current_head_beacon_block_root: new_head.beacon_block_root,
+            current_head_state_root: new_head.beacon_state_root,
+            common_ancestor_slot,
+            common_ancestor_beacon_block_root: common_ancestor_root,
+        })
+    }
+
     /// Execute the fork choice algorithm and enthrone the result as the canonical head.
     pub fn fork_choice(&self) -> Result<(), Error> {
         metrics::inc_counter(&metrics::FORK_CHOICE_REQUESTS);
@@ -1721,7 +1959,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
         // Try and obtain the snapshot for `beacon_block_root` from the snapshot cache, falling
         // back to a database read if that fails.
-        let new_head = self
+        let mut new_head = self
             .snapshot_cache
             .try_read_for(BLOCK_PROCESSING_CACHE_LOCK_TIMEOUT)
             .and_then(|snapshot_cache| snapshot_cache.get_cloned(beacon_block_root))
@@ -1766,17 +2004,9 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 .map(|root| *root)
                 .unwrap_or_else(|_| Hash256::random());
 
-        if is_reorg {
+        let reorg_event = if is_reorg {
             metrics::inc_counter(&metrics::FORK_CHOICE_REORG_COUNT);
-            warn!(
-                self.log,
-                "Beacon chain re-org";
-                "previous_head" => format!("{}", current_head.block_root),
-                "previous_slot" => current_head.slot,
-                "new_head_parent" => format!("{}", new_head.beacon_block.parent_root()),
-                "new_head" => format!("{}", beacon_block_root),
-                "new_slot" => new_head.beacon_block.slot()
-            );
+            self.process_reorg(&current_head, &new_head)
         } else {
             debug!(
                 self.log,
@@ -1788,8 +2018,17 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 "root" => format!("{}", beacon_block_root),
                 "slot" => new_head.beacon_block.slot(),
             );
+            None
         };
 
+        let current_head_slot = new_head.beacon_block.slot();
+        let current_epoch_dependent_root = new_head
+            .beacon_state
+            .attester_shuffling_decision_root(self.genesis_block_root, RelativeEpoch::Current)?;
+        let next_epoch_dependent_root = new_head
+            .beacon_state
+            .attester_shuffling_decision_root(self.genesis_block_root, RelativeEpoch::Next)?;
+
         let old_finalized_epoch = current_head.finalized_checkpoint.epoch;
         let new_finalized_epoch = new_head.beacon_state.finalized_checkpoint.epoch;
         let finalized_root = new_head.beacon_state.finalized_checkpoint.root;
@@ -1802,6 +2041,23 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             });
         }
 
+        // The post-state of the new head is the one which records the new finalized checkpoint,
+        // so it is the state which can prove finality to a light client.
+        if new_finalized_epoch != old_finalized_epoch && self.light_client_server {
+            if let Err(e) = self.store_light_client_finality_proof(
+                finalized_root,
+                &new_head.beacon_block.message,
+                &mut new_head.beacon_state,
+            ) {
+                error!(
+                    self.log,
+                    "Failed to store light client finality proof";
+                    "finalized_root" => format!("{:?}", finalized_root),
+                    "error" => format!("{:?}", e)
+                );
+            }
+        }
+
         if current_head.slot.epoch(T::EthSpec::slots_per_epoch())
             < new_head
                 .beacon_state
@@ -1845,10 +2101,17 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             )?;
         }
 
+        if let Some(reorg_event) = reorg_event {
+            let _ = self.event_handler.register(reorg_event);
+        }
+
         let _ = self.event_handler.register(EventKind::BeaconHeadChanged {
             reorg: is_reorg,
+            current_head_slot,
             previous_head_beacon_block_root: current_head.block_root,
             current_head_beacon_block_root: beacon_block_root,
+            current_epoch_dependent_root,
+            next_epoch_dependent_root,
         });
 
         Ok(())
@@ -1864,6 +2127,32 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         }
     }
 
+    /// Computes a `LightClientFinalityProof` of the checkpoints of `state`, the post-state of
+    /// `block`, and stores it under `finalized_root`.
+    ///
+    /// The `finalized_checkpoint` of `state` must have `finalized_root` as its root.
+    fn store_light_client_finality_proof(
+        &self,
+        finalized_root: Hash256,
+        block: &BeaconBlock<T::EthSpec>,
+        state: &mut BeaconState<T::EthSpec>,
+    ) -> Result<(), Error> {
+        let proof = LightClientFinalityProof::new(block.block_header(), state)?;
+        self.store.put_item(&finalized_root, &proof)?;
+        Ok(())
+    }
+
+    /// Returns the `LightClientFinalityProof` stored for the finalized block with the given root,
+    /// if any.
+    ///
+    /// Proofs are only stored if the chain was started with `light_client_server` enabled.
+    pub fn get_light_client_finality_proof(
+        &self,
+        block_root: &Hash256,
+    ) -> Result<Option<LightClientFinalityProof>, Error> {
+        Ok(self.store.get_item(block_root)?)
+    }
+
     /// Called after `self` has had a new block finalized.
     ///
     /// Performs pruning and finality-based optimizations.
@@ -2103,7 +2392,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 impl<T: BeaconChainTypes> Drop for BeaconChain<T> {
     fn drop(&mut self) {
         let drop = || -> Result<(), Error> {
-            self.persist_head_and_fork_choice()?;
+            self.persist_head_and_fork_choice_with_tree_hash_cache()?;
             self.persist_op_pool()?;
             self.persist_eth1_cache()
         };
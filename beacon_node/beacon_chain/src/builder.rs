@@ -1,11 +1,12 @@
 use crate::beacon_chain::{
-    BEACON_CHAIN_DB_KEY, ETH1_CACHE_DB_KEY, FORK_CHOICE_DB_KEY, OP_POOL_DB_KEY,
+    BEACON_CHAIN_DB_KEY, DEFAULT_GRAFFITI, DEFAULT_REORG_WARN_DEPTH, ETH1_CACHE_DB_KEY,
+    FORK_CHOICE_DB_KEY, HEAD_TREE_HASH_CACHE_DB_KEY, OP_POOL_DB_KEY,
 };
 use crate::eth1_chain::{CachingEth1Backend, SszEth1};
 use crate::events::NullEventHandler;
 use crate::head_tracker::HeadTracker;
 use crate::migrate::Migrate;
-use crate::persisted_beacon_chain::PersistedBeaconChain;
+use crate::persisted_beacon_chain::{PersistedBeaconChain, PersistedTreeHashCache};
 use crate::persisted_fork_choice::PersistedForkChoice;
 use crate::shuffling_cache::ShufflingCache;
 use crate::snapshot_cache::{SnapshotCache, DEFAULT_SNAPSHOT_CACHE_SIZE};
@@ -19,7 +20,7 @@ use eth1::Config as Eth1Config;
 use fork_choice::ForkChoice;
 use operation_pool::{OperationPool, PersistedOperationPool};
 use parking_lot::RwLock;
-use slog::{info, Logger};
+use slog::{debug, info, warn, Logger};
 use slot_clock::{SlotClock, TestingSlotClock};
 use std::marker::PhantomData;
 use std::path::PathBuf;
@@ -27,7 +28,8 @@ use std::sync::Arc;
 use std::time::Duration;
 use store::{HotColdDB, ItemStore};
 use types::{
-    BeaconBlock, BeaconState, ChainSpec, EthSpec, Hash256, Signature, SignedBeaconBlock, Slot,
+    graffiti_from_str, BeaconBlock, BeaconState, ChainSpec, EthSpec, Graffiti, Hash256, Signature,
+    SignedBeaconBlock, Slot,
 };
 
 pub const PUBKEY_CACHE_FILENAME: &str = "pubkey_cache.ssz";
@@ -109,6 +111,9 @@ pub struct BeaconChainBuilder<T: BeaconChainTypes> {
     validator_pubkey_cache: Option<ValidatorPubkeyCache>,
     spec: ChainSpec,
     disabled_forks: Vec<String>,
+    graffiti: Graffiti,
+    reorg_warn_depth: u64,
+    light_client_server: bool,
     log: Option<Logger>,
 }
 
@@ -152,6 +157,9 @@ where
             pubkey_cache_path: None,
             data_dir: None,
             disabled_forks: Vec::new(),
+            graffiti: graffiti_from_str(DEFAULT_GRAFFITI).expect("default graffiti is valid"),
+            reorg_warn_depth: DEFAULT_REORG_WARN_DEPTH,
+            light_client_server: false,
             validator_pubkey_cache: None,
             spec: TEthSpec::default_spec(),
             log: None,
@@ -204,6 +212,24 @@ where
         self
     }
 
+    /// Sets the graffiti included in produced blocks, unless the validator supplies its own.
+    pub fn graffiti(mut self, graffiti: Graffiti) -> Self {
+        self.graffiti = graffiti;
+        self
+    }
+
+    /// Sets the re-org depth (in slots) above which a re-org is logged as a warning.
+    pub fn reorg_warn_depth(mut self, reorg_warn_depth: u64) -> Self {
+        self.reorg_warn_depth = reorg_warn_depth;
+        self
+    }
+
+    /// Sets whether finality proofs are stored for light clients upon finalization.
+    pub fn light_client_server(mut self, light_client_server: bool) -> Self {
+        self.light_client_server = light_client_server;
+        self
+    }
+
     /// Attempt to load an existing eth1 cache from the builder's `Store`.
     pub fn get_persisted_eth1_backend(&self) -> Result<Option<SszEth1>, String> {
         let store = self
@@ -274,11 +300,46 @@ where
             .map_err(|e| format!("DB error when reading head block: {:?}", e))?
             .ok_or_else(|| "Head block not found in store".to_string())?;
         let head_state_root = head_block.state_root();
-        let head_state = store
+        let mut head_state = store
             .get_state(&head_state_root, Some(head_block.slot()))
             .map_err(|e| format!("DB error when reading head state: {:?}", e))?
             .ok_or_else(|| "Head state not found in store".to_string())?;
 
+        // The tree hash cache is just an optimization, so the chain is resumed without it if it
+        // can't be loaded (e.g., if it was persisted by an incompatible version).
+        let head_tree_hash_cache = store
+            .get_item::<PersistedTreeHashCache>(&Hash256::from_slice(&HEAD_TREE_HASH_CACHE_DB_KEY))
+            .unwrap_or_else(|e| {
+                warn!(
+                    log,
+                    "Unable to load persisted tree hash cache";
+                    "error" => format!("{:?}", e),
+                );
+                None
+            })
+            .filter(|persisted| persisted.state_root == head_state_root);
+
+        if let Some(persisted) = head_tree_hash_cache {
+            head_state.tree_hash_cache = Some(persisted.tree_hash_cache);
+
+            // Only keep the persisted cache if it produces the correct root, otherwise it will be
+            // rebuilt from scratch.
+            match head_state.update_tree_hash_cache() {
+                Ok(root) if root == head_state_root => {
+                    debug!(log, "Restored tree hash cache of head state");
+                }
+                other => {
+                    warn!(
+                        log,
+                        "Discarding persisted tree hash cache";
+                        "result" => format!("{:?}", other),
+                        "head_state_root" => format!("{:?}", head_state_root),
+                    );
+                    head_state.drop_tree_hash_cache();
+                }
+            }
+        }
+
         self.op_pool = Some(
             store
                 .get_item::<PersistedOperationPool<TEthSpec>>(&Hash256::from_slice(&OP_POOL_DB_KEY))
@@ -522,6 +583,9 @@ where
             shuffling_cache: TimeoutRwLock::new(ShufflingCache::new()),
             validator_pubkey_cache: TimeoutRwLock::new(validator_pubkey_cache),
             disabled_forks: self.disabled_forks,
+            graffiti: self.graffiti,
+            reorg_warn_depth: self.reorg_warn_depth,
+            light_client_server: self.light_client_server,
             log: log.clone(),
         };
 
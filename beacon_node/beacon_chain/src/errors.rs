@@ -67,6 +67,8 @@ pub enum BeaconChainError {
     SszTypesError(SszTypesError),
     CanonicalHeadLockTimeout,
     AttestationCacheLockTimeout,
+    /// Another thread failed to compute a committee cache which this thread was waiting upon.
+    ShufflingCachePromiseFailed,
     ValidatorPubkeyCacheLockTimeout,
     IncorrectStateForAttestation(RelativeEpochError),
     InvalidValidatorPubkeyBytes(DecodeError),
@@ -1,68 +1,222 @@
-use bus::Bus;
+use futures::channel::mpsc;
 use parking_lot::Mutex;
 use serde_derive::{Deserialize, Serialize};
-use slog::{error, Logger};
+use slog::{debug, warn, Logger};
+use std::fmt;
 use std::marker::PhantomData;
+use std::str::FromStr;
 use std::sync::Arc;
-use types::{Attestation, Epoch, EthSpec, Hash256, SignedBeaconBlock, SignedBeaconBlockHash};
+use types::{Attestation, Epoch, EthSpec, Hash256, SignedBeaconBlock, SignedVoluntaryExit, Slot};
 pub use websocket_server::WebSocketSender;
 
 pub trait EventHandler<T: EthSpec>: Sized + Send + Sync {
     fn register(&self, kind: EventKind<T>) -> Result<(), String>;
+
+    /// Returns `true` if events of `topic` may be delivered anywhere.
+    ///
+    /// Used to avoid building events which are expensive to clone when nobody will receive them.
+    fn has_subscribers(&self, topic: EventTopic) -> bool;
 }
 
 pub struct NullEventHandler<T: EthSpec>(PhantomData<T>);
 
 impl<T: EthSpec> EventHandler<T> for WebSocketSender<T> {
     fn register(&self, kind: EventKind<T>) -> Result<(), String> {
+        if !self.has_clients() {
+            return Ok(());
+        }
+
         self.send_string(
             serde_json::to_string(&kind)
                 .map_err(|e| format!("Unable to serialize event: {:?}", e))?,
         )
     }
+
+    fn has_subscribers(&self, _topic: EventTopic) -> bool {
+        self.has_clients()
+    }
+}
+
+/// The number of events which may be buffered for each `ServerSentEvents` subscriber.
+///
+/// Events are dropped for any subscriber which falls this far behind, so that a slow client can
+/// neither block the `BeaconChain` nor delay other subscribers.
+pub const SUBSCRIBER_BUFFER_SIZE: usize = 1_024;
+
+/// The topics to which a `ServerSentEvents` subscriber may subscribe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventTopic {
+    Head,
+    Block,
+    Attestation,
+    FinalizedCheckpoint,
+    ChainReorg,
+    VoluntaryExit,
+}
+
+impl EventTopic {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EventTopic::Head => "head",
+            EventTopic::Block => "block",
+            EventTopic::Attestation => "attestation",
+            EventTopic::FinalizedCheckpoint => "finalized_checkpoint",
+            EventTopic::ChainReorg => "chain_reorg",
+            EventTopic::VoluntaryExit => "voluntary_exit",
+        }
+    }
+}
+
+impl FromStr for EventTopic {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "head" => Ok(EventTopic::Head),
+            "block" => Ok(EventTopic::Block),
+            "attestation" => Ok(EventTopic::Attestation),
+            "finalized_checkpoint" => Ok(EventTopic::FinalizedCheckpoint),
+            "chain_reorg" => Ok(EventTopic::ChainReorg),
+            "voluntary_exit" => Ok(EventTopic::VoluntaryExit),
+            other => Err(format!("Unknown event topic: {}", other)),
+        }
+    }
+}
+
+impl fmt::Display for EventTopic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// A client subscribed to the events of some `topics`.
+struct Subscriber<T: EthSpec> {
+    topics: Vec<EventTopic>,
+    sender: mpsc::Sender<EventKind<T>>,
+    /// The number of events dropped since the subscriber last had room in its buffer.
+    dropped_events: usize,
+}
+
+/// The set of clients subscribed to the events of a `ServerSentEvents` handler.
+///
+/// Each subscriber has its own buffer of `SUBSCRIBER_BUFFER_SIZE` events.
+pub struct EventSubscribers<T: EthSpec> {
+    subscribers: Arc<Mutex<Vec<Subscriber<T>>>>,
+}
+
+impl<T: EthSpec> Clone for EventSubscribers<T> {
+    fn clone(&self) -> Self {
+        Self {
+            subscribers: self.subscribers.clone(),
+        }
+    }
+}
+
+impl<T: EthSpec> Default for EventSubscribers<T> {
+    fn default() -> Self {
+        Self {
+            subscribers: Arc::new(Mutex::new(vec![])),
+        }
+    }
+}
+
+impl<T: EthSpec> EventSubscribers<T> {
+    /// Returns a stream of all future events which match any of the given `topics`.
+    ///
+    /// The subscription is removed once the receiver is dropped.
+    pub fn subscribe(&self, topics: Vec<EventTopic>) -> mpsc::Receiver<EventKind<T>> {
+        let (sender, receiver) = mpsc::channel(SUBSCRIBER_BUFFER_SIZE);
+        self.subscribers.lock().push(Subscriber {
+            topics,
+            sender,
+            dropped_events: 0,
+        });
+        receiver
+    }
+
+    /// Returns the number of subscribers which have not yet been removed.
+    pub fn len(&self) -> usize {
+        self.subscribers.lock().len()
+    }
+
+    /// Returns `true` if there are no subscribers.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if any subscriber (which may have since disconnected) is subscribed to
+    /// `topic`.
+    pub fn has_subscribers(&self, topic: EventTopic) -> bool {
+        self.subscribers
+            .lock()
+            .iter()
+            .any(|subscriber| subscriber.topics.contains(&topic))
+    }
+
+    /// Sends `kind` to each subscriber of its topics, removing any subscribers which have
+    /// disconnected.
+    fn publish(&self, kind: &EventKind<T>, log: &Logger) {
+        let topics = kind.topics();
+        let mut subscribers = self.subscribers.lock();
+
+        for subscriber in subscribers
+            .iter_mut()
+            .filter(|subscriber| subscriber.topics.iter().any(|t| topics.contains(t)))
+        {
+            match subscriber.sender.try_send(kind.clone()) {
+                Ok(()) if subscriber.dropped_events > 0 => {
+                    debug!(
+                        log,
+                        "Event stream subscriber caught up";
+                        "dropped_events" => subscriber.dropped_events,
+                    );
+                    subscriber.dropped_events = 0;
+                }
+                // Only warn once each time a subscriber starts lagging, rather than for every
+                // event it misses.
+                Err(e) if e.is_full() => {
+                    if subscriber.dropped_events == 0 {
+                        warn!(
+                            log,
+                            "Event stream subscriber is lagging";
+                            "dropped_event" => kind.name(),
+                        );
+                    }
+                    subscriber.dropped_events += 1;
+                }
+                _ => {}
+            }
+        }
+
+        subscribers.retain(|subscriber| !subscriber.sender.is_closed());
+    }
 }
 
+/// Forwards all events to any HTTP clients subscribed to their topics via `EventSubscribers`.
 pub struct ServerSentEvents<T: EthSpec> {
-    // Bus<> is itself Sync + Send.  We use Mutex<> here only because of the surrounding code does
-    // not enforce mutability statically (i.e. relies on interior mutability).
-    head_changed_queue: Arc<Mutex<Bus<SignedBeaconBlockHash>>>,
+    subscribers: EventSubscribers<T>,
     log: Logger,
-    _phantom: PhantomData<T>,
 }
 
 impl<T: EthSpec> ServerSentEvents<T> {
-    pub fn new(log: Logger) -> (Self, Arc<Mutex<Bus<SignedBeaconBlockHash>>>) {
-        let bus = Bus::new(T::slots_per_epoch() as usize);
-        let mutex = Mutex::new(bus);
-        let arc = Arc::new(mutex);
+    pub fn new(log: Logger) -> (Self, EventSubscribers<T>) {
+        let subscribers = EventSubscribers::default();
         let this = Self {
-            head_changed_queue: arc.clone(),
-            log: log,
-            _phantom: PhantomData,
+            subscribers: subscribers.clone(),
+            log,
         };
-        (this, arc)
+        (this, subscribers)
     }
 }
 
 impl<T: EthSpec> EventHandler<T> for ServerSentEvents<T> {
     fn register(&self, kind: EventKind<T>) -> Result<(), String> {
-        match kind {
-            EventKind::BeaconHeadChanged {
-                current_head_beacon_block_root,
-                ..
-            } => {
-                let mut guard = self.head_changed_queue.lock();
-                if let Err(_) = guard.try_broadcast(current_head_beacon_block_root.into()) {
-                    error!(
-                        self.log,
-                        "Head change streaming queue full";
-                        "dropped_change" => format!("{}", current_head_beacon_block_root),
-                    );
-                }
-                Ok(())
-            }
-            _ => Ok(()),
-        }
+        self.subscribers.publish(&kind, &self.log);
+        Ok(())
+    }
+
+    fn has_subscribers(&self, topic: EventTopic) -> bool {
+        self.subscribers.has_subscribers(topic)
     }
 }
 
@@ -78,28 +232,38 @@ impl<E: EthSpec> TeeEventHandler<E> {
     pub fn new(
         log: Logger,
         websockets_handler: WebSocketSender<E>,
-    ) -> Result<(Self, Arc<Mutex<Bus<SignedBeaconBlockHash>>>), String> {
-        let (sse_handler, bus) = ServerSentEvents::new(log);
+    ) -> Result<(Self, EventSubscribers<E>), String> {
+        let (sse_handler, subscribers) = ServerSentEvents::new(log);
         let result = Self {
-            websockets_handler: websockets_handler,
-            sse_handler: sse_handler,
+            websockets_handler,
+            sse_handler,
         };
-        Ok((result, bus))
+        Ok((result, subscribers))
     }
 }
 
 impl<E: EthSpec> EventHandler<E> for TeeEventHandler<E> {
     fn register(&self, kind: EventKind<E>) -> Result<(), String> {
-        self.websockets_handler.register(kind.clone())?;
+        if self.websockets_handler.has_clients() {
+            self.websockets_handler.register(kind.clone())?;
+        }
         self.sse_handler.register(kind)?;
         Ok(())
     }
+
+    fn has_subscribers(&self, topic: EventTopic) -> bool {
+        self.websockets_handler.has_subscribers(topic) || self.sse_handler.has_subscribers(topic)
+    }
 }
 
 impl<T: EthSpec> EventHandler<T> for NullEventHandler<T> {
     fn register(&self, _kind: EventKind<T>) -> Result<(), String> {
         Ok(())
     }
+
+    fn has_subscribers(&self, _topic: EventTopic) -> bool {
+        false
+    }
 }
 
 impl<T: EthSpec> Default for NullEventHandler<T> {
@@ -118,8 +282,27 @@ impl<T: EthSpec> Default for NullEventHandler<T> {
 pub enum EventKind<T: EthSpec> {
     BeaconHeadChanged {
         reorg: bool,
+        current_head_slot: Slot,
         current_head_beacon_block_root: Hash256,
         previous_head_beacon_block_root: Hash256,
+        /// The root of the block which decided the attester shuffling for the epoch of
+        /// `current_head_slot`.
+        current_epoch_dependent_root: Hash256,
+        /// The root of the block which decided the attester shuffling for the epoch after that of
+        /// `current_head_slot`.
+        next_epoch_dependent_root: Hash256,
+    },
+    BeaconChainReorg {
+        /// The number of slots between the previous head and the common ancestor.
+        depth: u64,
+        previous_head_slot: Slot,
+        previous_head_beacon_block_root: Hash256,
+        previous_head_state_root: Hash256,
+        current_head_slot: Slot,
+        current_head_beacon_block_root: Hash256,
+        current_head_state_root: Hash256,
+        common_ancestor_slot: Slot,
+        common_ancestor_beacon_block_root: Hash256,
     },
     BeaconFinalization {
         epoch: Epoch,
@@ -140,4 +323,187 @@ pub enum EventKind<T: EthSpec> {
         reason: String,
         attestation: Box<Attestation<T>>,
     },
+    BeaconVoluntaryExitImported {
+        voluntary_exit: Box<SignedVoluntaryExit>,
+    },
+}
+
+impl<T: EthSpec> EventKind<T> {
+    /// Returns the name of the event, as used for the `event` field of its JSON encoding.
+    pub fn name(&self) -> &'static str {
+        match self {
+            EventKind::BeaconHeadChanged { .. } => "beacon_head_changed",
+            EventKind::BeaconChainReorg { .. } => "beacon_chain_reorg",
+            EventKind::BeaconFinalization { .. } => "beacon_finalization",
+            EventKind::BeaconBlockImported { .. } => "beacon_block_imported",
+            EventKind::BeaconBlockRejected { .. } => "beacon_block_rejected",
+            EventKind::BeaconAttestationImported { .. } => "beacon_attestation_imported",
+            EventKind::BeaconAttestationRejected { .. } => "beacon_attestation_rejected",
+            EventKind::BeaconVoluntaryExitImported { .. } => "beacon_voluntary_exit_imported",
+        }
+    }
+
+    /// Returns the `ServerSentEvents` topics which include this event.
+    pub fn topics(&self) -> &'static [EventTopic] {
+        match self {
+            EventKind::BeaconHeadChanged { .. } => &[EventTopic::Head],
+            EventKind::BeaconChainReorg { .. } => &[EventTopic::ChainReorg],
+            EventKind::BeaconFinalization { .. } => &[EventTopic::FinalizedCheckpoint],
+            EventKind::BeaconBlockImported { .. } | EventKind::BeaconBlockRejected { .. } => {
+                &[EventTopic::Block]
+            }
+            EventKind::BeaconAttestationImported { .. }
+            | EventKind::BeaconAttestationRejected { .. } => &[EventTopic::Attestation],
+            EventKind::BeaconVoluntaryExitImported { .. } => &[EventTopic::VoluntaryExit],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use slog::{o, Discard};
+    use types::MinimalEthSpec;
+
+    type E = MinimalEthSpec;
+
+    fn finalization(epoch: u64) -> EventKind<E> {
+        EventKind::BeaconFinalization {
+            epoch: Epoch::new(epoch),
+            root: Hash256::from_low_u64_be(epoch),
+        }
+    }
+
+    fn head_changed(reorg: bool) -> EventKind<E> {
+        EventKind::BeaconHeadChanged {
+            reorg,
+            current_head_slot: Slot::new(2),
+            current_head_beacon_block_root: Hash256::from_low_u64_be(1),
+            previous_head_beacon_block_root: Hash256::from_low_u64_be(2),
+            current_epoch_dependent_root: Hash256::zero(),
+            next_epoch_dependent_root: Hash256::zero(),
+        }
+    }
+
+    fn chain_reorg() -> EventKind<E> {
+        EventKind::BeaconChainReorg {
+            depth: 1,
+            previous_head_slot: Slot::new(2),
+            previous_head_beacon_block_root: Hash256::from_low_u64_be(2),
+            previous_head_state_root: Hash256::from_low_u64_be(3),
+            current_head_slot: Slot::new(2),
+            current_head_beacon_block_root: Hash256::from_low_u64_be(1),
+            current_head_state_root: Hash256::from_low_u64_be(4),
+            common_ancestor_slot: Slot::new(1),
+            common_ancestor_beacon_block_root: Hash256::from_low_u64_be(5),
+        }
+    }
+
+    fn handler() -> (ServerSentEvents<E>, EventSubscribers<E>) {
+        ServerSentEvents::new(Logger::root(Discard, o!()))
+    }
+
+    #[test]
+    fn topics_round_trip() {
+        for topic in &[
+            EventTopic::Head,
+            EventTopic::Block,
+            EventTopic::Attestation,
+            EventTopic::FinalizedCheckpoint,
+            EventTopic::ChainReorg,
+            EventTopic::VoluntaryExit,
+        ] {
+            assert_eq!(topic.as_str().parse::<EventTopic>(), Ok(*topic));
+        }
+
+        assert!("unknown".parse::<EventTopic>().is_err());
+    }
+
+    #[test]
+    fn events_are_filtered_by_topic() {
+        let (handler, subscribers) = handler();
+
+        let mut head = subscribers.subscribe(vec![EventTopic::Head]);
+        let mut reorg = subscribers.subscribe(vec![EventTopic::ChainReorg]);
+        let mut finalized = subscribers.subscribe(vec![EventTopic::FinalizedCheckpoint]);
+
+        handler.register(head_changed(false)).unwrap();
+        handler.register(head_changed(true)).unwrap();
+        handler.register(chain_reorg()).unwrap();
+        handler.register(finalization(1)).unwrap();
+
+        let received = |receiver: &mut mpsc::Receiver<EventKind<E>>| {
+            let mut names = vec![];
+            while let Ok(Some(event)) = receiver.try_next() {
+                names.push(event.name());
+            }
+            names
+        };
+
+        assert_eq!(
+            received(&mut head),
+            vec!["beacon_head_changed", "beacon_head_changed"]
+        );
+        assert_eq!(received(&mut reorg), vec!["beacon_chain_reorg"]);
+        assert_eq!(received(&mut finalized), vec!["beacon_finalization"]);
+    }
+
+    #[test]
+    fn lagging_subscriber_drops_events() {
+        let (handler, subscribers) = handler();
+
+        let mut lagging = subscribers.subscribe(vec![EventTopic::FinalizedCheckpoint]);
+
+        let event_count = SUBSCRIBER_BUFFER_SIZE as u64 * 2;
+        for epoch in 0..event_count {
+            handler.register(finalization(epoch)).unwrap();
+        }
+
+        let mut received = 0;
+        while let Ok(Some(_)) = lagging.try_next() {
+            received += 1;
+        }
+
+        assert!(received > 0, "should receive buffered events");
+        assert!(received < event_count, "should drop events once full");
+        assert_eq!(subscribers.len(), 1, "lagging subscriber should remain");
+
+        let dropped_events = || subscribers.subscribers.lock()[0].dropped_events as u64;
+        assert_eq!(dropped_events(), event_count - received);
+
+        handler.register(finalization(event_count)).unwrap();
+        assert_eq!(dropped_events(), 0, "should reset once caught up");
+        assert!(
+            matches!(lagging.try_next(), Ok(Some(_))),
+            "should receive new events"
+        );
+    }
+
+    #[test]
+    fn subscribers_are_found_by_topic() {
+        let (handler, subscribers) = handler();
+        assert!(!handler.has_subscribers(EventTopic::Attestation));
+
+        let receiver = subscribers.subscribe(vec![EventTopic::Head, EventTopic::Attestation]);
+        assert!(handler.has_subscribers(EventTopic::Attestation));
+        assert!(!handler.has_subscribers(EventTopic::Block));
+
+        drop(receiver);
+        handler.register(finalization(1)).unwrap();
+        assert!(!handler.has_subscribers(EventTopic::Attestation));
+    }
+
+    #[test]
+    fn disconnected_subscribers_are_removed() {
+        let (handler, subscribers) = handler();
+
+        let receiver = subscribers.subscribe(vec![EventTopic::Head]);
+        let _other = subscribers.subscribe(vec![EventTopic::Block]);
+        assert_eq!(subscribers.len(), 2);
+
+        drop(receiver);
+        handler.register(finalization(1)).unwrap();
+
+        assert_eq!(subscribers.len(), 1);
+    }
 }
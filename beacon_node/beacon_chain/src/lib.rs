@@ -29,7 +29,7 @@ mod validator_pubkey_cache;
 
 pub use self::beacon_chain::{
     AttestationProcessingOutcome, BeaconChain, BeaconChainTypes, ChainSegmentResult,
-    ForkChoiceError, StateSkipConfig,
+    ForkChoiceError, HeadInfo, StateSkipConfig, DEFAULT_GRAFFITI, DEFAULT_REORG_WARN_DEPTH,
 };
 pub use self::beacon_snapshot::BeaconSnapshot;
 pub use self::errors::{BeaconChainError, BlockProductionError};
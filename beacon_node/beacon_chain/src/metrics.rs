@@ -151,6 +151,10 @@ lazy_static! {
         "beacon_attestation_processing_shuffling_cache_wait_seconds",
         "Time spent on waiting for the shuffling cache lock during attestation processing"
     );
+    pub static ref ATTESTATION_PROCESSING_SHUFFLING_PROMISE_WAIT_TIMES: Result<Histogram> = try_create_histogram(
+        "beacon_attestation_processing_shuffling_promise_wait_seconds",
+        "Time spent waiting for another thread to build committees during attestation processing"
+    );
     pub static ref ATTESTATION_PROCESSING_COMMITTEE_BUILDING_TIMES: Result<Histogram> = try_create_histogram(
         "beacon_attestation_processing_committee_building_seconds",
         "Time spent on building committees during attestation processing"
@@ -179,6 +183,8 @@ lazy_static! {
         try_create_int_counter("beacon_shuffling_cache_hits_total", "Count of times shuffling cache fulfils request");
     pub static ref SHUFFLING_CACHE_MISSES: Result<IntCounter> =
         try_create_int_counter("beacon_shuffling_cache_misses_total", "Count of times shuffling cache fulfils request");
+    pub static ref SHUFFLING_CACHE_PROMISE_HITS: Result<IntCounter> =
+        try_create_int_counter("beacon_shuffling_cache_promise_hits_total", "Count of times shuffling cache returns a promise for a committee which is being computed");
 
     /*
      * Attestation Production
@@ -218,6 +224,11 @@ lazy_static! {
         "beacon_fork_choice_reorg_total",
         "Count of occasions fork choice has switched to a different chain"
     );
+    pub static ref FORK_CHOICE_REORG_DEPTH: Result<Histogram> = try_create_histogram_with_buckets(
+        "beacon_fork_choice_reorg_depth",
+        "Number of slots between the previous head and the common ancestor of each re-org",
+        vec![1.0, 2.0, 3.0, 4.0, 8.0, 16.0, 32.0, 64.0]
+    );
     pub static ref FORK_CHOICE_TIMES: Result<Histogram> =
         try_create_histogram("beacon_fork_choice_seconds", "Full runtime of fork choice");
     pub static ref FORK_CHOICE_FIND_HEAD_TIMES: Result<Histogram> =
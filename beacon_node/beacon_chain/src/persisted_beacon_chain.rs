@@ -2,7 +2,7 @@ use crate::head_tracker::SszHeadTracker;
 use ssz::{Decode, Encode};
 use ssz_derive::{Decode, Encode};
 use store::{DBColumn, Error as StoreError, StoreItem};
-use types::Hash256;
+use types::{BeaconTreeHashCache, Hash256};
 
 #[derive(Clone, Encode, Decode)]
 pub struct PersistedBeaconChain {
@@ -24,3 +24,30 @@ impl StoreItem for PersistedBeaconChain {
         Self::from_ssz_bytes(bytes).map_err(Into::into)
     }
 }
+
+/// The tree hash cache of the canonical head state, which avoids hashing the entire state when the
+/// chain is resumed.
+///
+/// It's stored separately to `PersistedBeaconChain` since it's only persisted when the chain shuts
+/// down (see `BeaconChain::persist_head_and_fork_choice_with_tree_hash_cache`) and is discarded
+/// if it can't be loaded.
+#[derive(Clone, Encode, Decode)]
+pub struct PersistedTreeHashCache {
+    /// The root of the state which `tree_hash_cache` belongs to.
+    pub state_root: Hash256,
+    pub tree_hash_cache: BeaconTreeHashCache,
+}
+
+impl StoreItem for PersistedTreeHashCache {
+    fn db_column() -> DBColumn {
+        DBColumn::BeaconTreeHashCache
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, StoreError> {
+        Self::from_ssz_bytes(bytes).map_err(Into::into)
+    }
+}
@@ -1,5 +1,7 @@
 use crate::metrics;
 use lru::LruCache;
+use parking_lot::{Condvar, Mutex};
+use std::sync::Arc;
 use types::{beacon_state::CommitteeCache, Epoch, Hash256};
 
 /// The size of the LRU cache that stores committee caches for quicker verification.
@@ -9,12 +11,91 @@ use types::{beacon_state::CommitteeCache, Epoch, Hash256};
 /// ignores a few extra bytes in the caches that should be insignificant compared to the indices).
 const CACHE_SIZE: usize = 16;
 
+/// An item in the `ShufflingCache`.
+#[derive(Clone)]
+pub enum CacheItem {
+    /// A committee cache which is ready to use.
+    Committee(Arc<CommitteeCache>),
+    /// A committee cache which is currently being computed by some other thread.
+    Promise(CommitteeCachePromise),
+}
+
+/// The state shared between a `CommitteeCacheSender` and its `CommitteeCachePromise`s.
+enum PromiseState {
+    Pending,
+    Ready(Arc<CommitteeCache>),
+    /// The sender was dropped without providing a committee cache (e.g., due to an error).
+    Failed,
+}
+
+struct PromiseInner {
+    state: Mutex<PromiseState>,
+    condvar: Condvar,
+}
+
+/// A handle to a committee cache which is being computed by some other thread.
+#[derive(Clone)]
+pub struct CommitteeCachePromise {
+    inner: Arc<PromiseInner>,
+}
+
+impl CommitteeCachePromise {
+    /// Blocks until the committee cache has been computed.
+    ///
+    /// Returns `None` if the thread computing the committee cache failed to produce it.
+    pub fn wait(&self) -> Option<Arc<CommitteeCache>> {
+        let mut state = self.inner.state.lock();
+        loop {
+            match &*state {
+                PromiseState::Pending => self.inner.condvar.wait(&mut state),
+                PromiseState::Ready(committee_cache) => return Some(committee_cache.clone()),
+                PromiseState::Failed => return None,
+            }
+        }
+    }
+
+    /// Returns `true` if the sender was dropped without providing a committee cache.
+    fn is_failed(&self) -> bool {
+        matches!(*self.inner.state.lock(), PromiseState::Failed)
+    }
+}
+
+/// Used by the thread computing a committee cache to provide it to any `CommitteeCachePromise`
+/// waiting upon it.
+///
+/// If this is dropped without calling `send`, all waiting promises will return `None`.
+pub struct CommitteeCacheSender {
+    inner: Arc<PromiseInner>,
+}
+
+impl CommitteeCacheSender {
+    /// Provides `committee_cache` to all current and future waiters on the promise.
+    pub fn send(self, committee_cache: Arc<CommitteeCache>) {
+        *self.inner.state.lock() = PromiseState::Ready(committee_cache);
+        self.inner.condvar.notify_all();
+    }
+}
+
+impl Drop for CommitteeCacheSender {
+    fn drop(&mut self) {
+        let mut state = self.inner.state.lock();
+        if let PromiseState::Pending = *state {
+            *state = PromiseState::Failed;
+            self.inner.condvar.notify_all();
+        }
+    }
+}
+
 /// Provides an LRU cache for `CommitteeCache`.
 ///
 /// It has been named `ShufflingCache` because `CommitteeCacheCache` is a bit weird and looks like
 /// a find/replace error.
+///
+/// Whilst a committee cache is being computed, the cache may hold a promise for it. This allows
+/// concurrent callers to wait for a single computation, instead of each computing the same
+/// committee cache.
 pub struct ShufflingCache {
-    cache: LruCache<(Epoch, Hash256), CommitteeCache>,
+    cache: LruCache<(Epoch, Hash256), CacheItem>,
 }
 
 impl ShufflingCache {
@@ -24,23 +105,195 @@ impl ShufflingCache {
         }
     }
 
-    pub fn get(&mut self, epoch: Epoch, root: Hash256) -> Option<&CommitteeCache> {
-        let opt = self.cache.get(&(epoch, root));
+    /// Returns the committee cache (or a promise for it) for the given `epoch` and shuffling
+    /// decision `root`.
+    ///
+    /// Promises whose computation has failed are removed and treated as a miss.
+    pub fn get(&mut self, epoch: Epoch, root: Hash256) -> Option<CacheItem> {
+        let key = (epoch, root);
 
-        if opt.is_some() {
-            metrics::inc_counter(&metrics::SHUFFLING_CACHE_HITS);
-        } else {
-            metrics::inc_counter(&metrics::SHUFFLING_CACHE_MISSES);
+        let item = match self.cache.get(&key) {
+            Some(CacheItem::Promise(promise)) if promise.is_failed() => {
+                self.cache.pop(&key);
+                None
+            }
+            other => other.cloned(),
+        };
+
+        match &item {
+            Some(CacheItem::Committee(_)) => {
+                metrics::inc_counter(&metrics::SHUFFLING_CACHE_HITS);
+            }
+            Some(CacheItem::Promise(_)) => {
+                metrics::inc_counter(&metrics::SHUFFLING_CACHE_PROMISE_HITS);
+            }
+            None => {
+                metrics::inc_counter(&metrics::SHUFFLING_CACHE_MISSES);
+            }
         }
 
-        opt
+        item
     }
 
+    /// Inserts `committee_cache`, replacing any promise for it.
     pub fn insert(&mut self, epoch: Epoch, root: Hash256, committee_cache: &CommitteeCache) {
         let key = (epoch, root);
 
-        if !self.cache.contains(&key) {
-            self.cache.put(key, committee_cache.clone());
+        if let Some(CacheItem::Committee(_)) = self.cache.peek(&key) {
+            return;
+        }
+
+        self.insert_committee_cache(epoch, root, Arc::new(committee_cache.clone()));
+    }
+
+    /// As per `Self::insert`, but for a committee cache which is already wrapped in an `Arc`.
+    pub fn insert_committee_cache(
+        &mut self,
+        epoch: Epoch,
+        root: Hash256,
+        committee_cache: Arc<CommitteeCache>,
+    ) {
+        self.cache
+            .put((epoch, root), CacheItem::Committee(committee_cache));
+    }
+
+    /// Inserts a promise for the committee cache for `epoch` and `root`, returning the sender
+    /// which must be used to fulfil it.
+    ///
+    /// Other callers of `Self::get` will receive the promise until the committee cache is
+    /// inserted, or the sender is dropped.
+    pub fn create_promise(&mut self, epoch: Epoch, root: Hash256) -> CommitteeCacheSender {
+        let inner = Arc::new(PromiseInner {
+            state: Mutex::new(PromiseState::Pending),
+            condvar: Condvar::new(),
+        });
+
+        self.cache.put(
+            (epoch, root),
+            CacheItem::Promise(CommitteeCachePromise {
+                inner: inner.clone(),
+            }),
+        );
+
+        CommitteeCacheSender { inner }
+    }
+
+    /// Removes the promise for `epoch` and `root` which is fulfilled by `sender`, so the next
+    /// caller of `Self::get` computes the committee cache again.
+    ///
+    /// Committee caches and promises belonging to other senders are left in place.
+    pub fn remove_promise(&mut self, epoch: Epoch, root: Hash256, sender: &CommitteeCacheSender) {
+        let key = (epoch, root);
+
+        if let Some(CacheItem::Promise(promise)) = self.cache.peek(&key) {
+            if Arc::ptr_eq(&promise.inner, &sender.inner) {
+                self.cache.pop(&key);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    fn key(i: u64) -> (Epoch, Hash256) {
+        (Epoch::new(i), Hash256::from_low_u64_be(i))
+    }
+
+    #[test]
+    fn promise_resolves_for_all_waiters() {
+        let mut cache = ShufflingCache::new();
+        let (epoch, root) = key(1);
+
+        let sender = cache.create_promise(epoch, root);
+
+        let waiters = (0..4)
+            .map(|_| match cache.get(epoch, root) {
+                Some(CacheItem::Promise(promise)) => thread::spawn(move || promise.wait()),
+                _ => panic!("should return a promise"),
+            })
+            .collect::<Vec<_>>();
+
+        let committee_cache = Arc::new(CommitteeCache::default());
+        cache.insert_committee_cache(epoch, root, committee_cache.clone());
+        sender.send(committee_cache.clone());
+
+        for waiter in waiters {
+            let result = waiter.join().unwrap().expect("promise should resolve");
+            assert!(Arc::ptr_eq(&result, &committee_cache));
+        }
+
+        assert!(matches!(
+            cache.get(epoch, root),
+            Some(CacheItem::Committee(_))
+        ));
+    }
+
+    #[test]
+    fn dropped_sender_fails_promise() {
+        let mut cache = ShufflingCache::new();
+        let (epoch, root) = key(1);
+
+        let sender = cache.create_promise(epoch, root);
+
+        let promise = match cache.get(epoch, root) {
+            Some(CacheItem::Promise(promise)) => promise,
+            _ => panic!("should return a promise"),
+        };
+
+        drop(sender);
+
+        assert!(promise.wait().is_none());
+        assert!(
+            cache.get(epoch, root).is_none(),
+            "failed promise should be removed"
+        );
+    }
+
+    #[test]
+    fn remove_promise_allows_recompute() {
+        let mut cache = ShufflingCache::new();
+        let (epoch, root) = key(1);
+
+        let sender = cache.create_promise(epoch, root);
+        cache.remove_promise(epoch, root, &sender);
+        assert!(
+            cache.get(epoch, root).is_none(),
+            "removed promise should be a miss"
+        );
+
+        // Only the promise belonging to `sender` is removed.
+        let other_sender = cache.create_promise(epoch, root);
+        cache.remove_promise(epoch, root, &sender);
+        assert!(matches!(
+            cache.get(epoch, root),
+            Some(CacheItem::Promise(_))
+        ));
+
+        let committee_cache = Arc::new(CommitteeCache::default());
+        cache.insert_committee_cache(epoch, root, committee_cache);
+        other_sender.send(Arc::new(CommitteeCache::default()));
+        cache.remove_promise(epoch, root, &sender);
+        assert!(matches!(
+            cache.get(epoch, root),
+            Some(CacheItem::Committee(_))
+        ));
+    }
+
+    #[test]
+    fn insert_does_not_replace_committee() {
+        let mut cache = ShufflingCache::new();
+        let (epoch, root) = key(1);
+
+        let committee_cache = Arc::new(CommitteeCache::default());
+        cache.insert_committee_cache(epoch, root, committee_cache.clone());
+        cache.insert(epoch, root, &CommitteeCache::default());
+
+        match cache.get(epoch, root) {
+            Some(CacheItem::Committee(cached)) => assert!(Arc::ptr_eq(&cached, &committee_cache)),
+            _ => panic!("should return the committee"),
         }
     }
 }
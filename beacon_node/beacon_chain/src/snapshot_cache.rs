@@ -1,6 +1,6 @@
 use crate::BeaconSnapshot;
 use std::cmp;
-use types::{Epoch, EthSpec, Hash256};
+use types::{BeaconTreeHashCache, Epoch, EthSpec, Hash256};
 
 /// The default size of the cache.
 pub const DEFAULT_SNAPSHOT_CACHE_SIZE: usize = 4;
@@ -78,6 +78,15 @@ impl<T: EthSpec> SnapshotCache<T> {
             .map(|snapshot| snapshot.clone_with_only_committee_caches())
     }
 
+    /// If there is a snapshot with `block_root`, return a clone of the tree hash cache of its state
+    /// (if it has been built).
+    pub fn get_tree_hash_cache(&self, block_root: Hash256) -> Option<BeaconTreeHashCache> {
+        self.snapshots
+            .iter()
+            .find(|snapshot| snapshot.beacon_block_root == block_root)
+            .and_then(|snapshot| snapshot.beacon_state.tree_hash_cache.clone())
+    }
+
     /// Removes all snapshots from the queue that are less than or equal to the finalized epoch.
     pub fn prune(&mut self, finalized_epoch: Epoch) {
         self.snapshots.retain(|snapshot| {
@@ -1,12 +1,13 @@
 pub use crate::beacon_chain::{
-    BEACON_CHAIN_DB_KEY, ETH1_CACHE_DB_KEY, FORK_CHOICE_DB_KEY, OP_POOL_DB_KEY,
+    BEACON_CHAIN_DB_KEY, ETH1_CACHE_DB_KEY, FORK_CHOICE_DB_KEY, HEAD_TREE_HASH_CACHE_DB_KEY,
+    OP_POOL_DB_KEY,
 };
 use crate::migrate::{BlockingMigrator, Migrate, NullMigrator};
-pub use crate::persisted_beacon_chain::PersistedBeaconChain;
+pub use crate::persisted_beacon_chain::{PersistedBeaconChain, PersistedTreeHashCache};
 use crate::{
     builder::{BeaconChainBuilder, Witness},
     eth1_chain::CachingEth1Backend,
-    events::NullEventHandler,
+    events::{EventHandler, EventSubscribers, NullEventHandler, ServerSentEvents},
     BeaconChain, BeaconChainTypes, StateSkipConfig,
 };
 use genesis::interop_genesis_state;
@@ -34,17 +35,25 @@ pub const HARNESS_GENESIS_TIME: u64 = 1_567_552_690;
 // This parameter is required by a builder but not used because we use the `TestingSlotClock`.
 pub const HARNESS_SLOT_TIME: Duration = Duration::from_secs(1);
 
-pub type BaseHarnessType<TStoreMigrator, TEthSpec, THotStore, TColdStore> = Witness<
+pub type BaseHarnessType<
+    TStoreMigrator,
+    TEthSpec,
+    THotStore,
+    TColdStore,
+    TEventHandler = NullEventHandler<TEthSpec>,
+> = Witness<
     TStoreMigrator,
     TestingSlotClock,
     CachingEth1Backend<TEthSpec>,
     TEthSpec,
-    NullEventHandler<TEthSpec>,
+    TEventHandler,
     THotStore,
     TColdStore,
 >;
 
 pub type HarnessType<E> = BaseHarnessType<NullMigrator, E, MemoryStore<E>, MemoryStore<E>>;
+pub type EventHarnessType<E> =
+    BaseHarnessType<NullMigrator, E, MemoryStore<E>, MemoryStore<E>, ServerSentEvents<E>>;
 pub type DiskHarnessType<E> =
     BaseHarnessType<BlockingMigrator<E, LevelDB<E>, LevelDB<E>>, E, LevelDB<E>, LevelDB<E>>;
 
@@ -137,6 +146,49 @@ impl<E: EthSpec> BeaconChainHarness<HarnessType<E>> {
     }
 }
 
+impl<E: EthSpec> BeaconChainHarness<EventHarnessType<E>> {
+    /// Instantiate a new harness which publishes its events to the returned `EventSubscribers`.
+    pub fn new_with_event_subscribers(
+        eth_spec_instance: E,
+        keypairs: Vec<Keypair>,
+    ) -> (Self, EventSubscribers<E>) {
+        let data_dir = tempdir().expect("should create temporary data_dir");
+        let spec = E::default_spec();
+
+        let log = NullLoggerBuilder.build().expect("logger should build");
+        let (event_handler, subscribers) = ServerSentEvents::new(log.clone());
+        let store =
+            HotColdDB::open_ephemeral(StoreConfig::default(), spec.clone(), log.clone()).unwrap();
+        let chain = BeaconChainBuilder::new(eth_spec_instance)
+            .logger(log)
+            .custom_spec(spec.clone())
+            .store(Arc::new(store))
+            .store_migrator(NullMigrator)
+            .data_dir(data_dir.path().to_path_buf())
+            .genesis_state(
+                interop_genesis_state::<E>(&keypairs, HARNESS_GENESIS_TIME, &spec)
+                    .expect("should generate interop state"),
+            )
+            .expect("should build state using recent genesis")
+            .dummy_eth1_backend()
+            .expect("should build dummy backend")
+            .event_handler(event_handler)
+            .testing_slot_clock(HARNESS_SLOT_TIME)
+            .expect("should configure testing slot clock")
+            .build()
+            .expect("should build");
+
+        let harness = Self {
+            spec: chain.spec.clone(),
+            chain,
+            keypairs,
+            data_dir,
+        };
+
+        (harness, subscribers)
+    }
+}
+
 impl<E: EthSpec> BeaconChainHarness<DiskHarnessType<E>> {
     /// Instantiate a new harness with `validator_count` initial validators.
     pub fn new_with_disk_store(
@@ -215,12 +267,13 @@ impl<E: EthSpec> BeaconChainHarness<DiskHarnessType<E>> {
     }
 }
 
-impl<M, E, Hot, Cold> BeaconChainHarness<BaseHarnessType<M, E, Hot, Cold>>
+impl<M, E, Hot, Cold, Ev> BeaconChainHarness<BaseHarnessType<M, E, Hot, Cold, Ev>>
 where
     M: Migrate<E, Hot, Cold>,
     E: EthSpec,
     Hot: ItemStore<E>,
     Cold: ItemStore<E>,
+    Ev: EventHandler<E> + 'static,
 {
     /// Advance the slot of the `BeaconChain`.
     ///
@@ -517,7 +570,7 @@ where
 
         let (block, state) = self
             .chain
-            .produce_block_on_state(state, slot, randao_reveal)
+            .produce_block_on_state(state, slot, randao_reveal, None)
             .expect("should produce block");
 
         let signed_block = block.sign(sk, &state.fork, state.genesis_validators_root, &self.spec);
@@ -4,14 +4,16 @@
 extern crate lazy_static;
 
 use beacon_chain::{
-    test_utils::{AttestationStrategy, BeaconChainHarness, BlockStrategy},
+    test_utils::{
+        AttestationStrategy, BeaconChainHarness, BlockStrategy, HEAD_TREE_HASH_CACHE_DB_KEY,
+    },
     BeaconChain, BeaconChainTypes,
 };
 use sloggers::{null::NullLoggerBuilder, Build};
 use std::sync::Arc;
-use store::{HotColdDB, LevelDB, StoreConfig};
+use store::{DBColumn, Error as StoreError, HotColdDB, LevelDB, StoreConfig, StoreItem};
 use tempfile::{tempdir, TempDir};
-use types::{EthSpec, Keypair, MinimalEthSpec};
+use types::{EthSpec, Hash256, Keypair, MinimalEthSpec};
 
 type E = MinimalEthSpec;
 
@@ -37,6 +39,15 @@ fn get_store(db_path: &TempDir) -> Arc<HotColdDB<E, LevelDB<E>, LevelDB<E>>> {
 
 #[test]
 fn finalizes_after_resuming_from_db() {
+    finalizes_after_resuming_from_db_internal(false)
+}
+
+#[test]
+fn finalizes_after_resuming_from_db_with_tree_hash_cache() {
+    finalizes_after_resuming_from_db_internal(true)
+}
+
+fn finalizes_after_resuming_from_db_internal(with_tree_hash_cache: bool) {
     let validator_count = 16;
     let num_blocks_produced = MinimalEthSpec::slots_per_epoch() * 8;
     let first_half = num_blocks_produced / 2;
@@ -72,10 +83,17 @@ fn finalizes_after_resuming_from_db() {
 
     let latest_slot = harness.chain.slot().expect("should have a slot");
 
-    harness
-        .chain
-        .persist_head_and_fork_choice()
-        .expect("should persist the head and fork choice");
+    if with_tree_hash_cache {
+        harness
+            .chain
+            .persist_head_and_fork_choice_with_tree_hash_cache()
+            .expect("should persist the head, fork choice and tree hash cache");
+    } else {
+        harness
+            .chain
+            .persist_head_and_fork_choice()
+            .expect("should persist the head and fork choice");
+    }
     harness
         .chain
         .persist_op_pool()
@@ -97,6 +115,30 @@ fn finalizes_after_resuming_from_db() {
 
     assert_chains_pretty_much_the_same(&original_chain, &resumed_harness.chain);
 
+    let resumed_tree_hash_cache = resumed_harness
+        .chain
+        .head_tree_hash_cache()
+        .expect("should read tree hash cache");
+    if with_tree_hash_cache {
+        // The restored cache must already describe the head state, so that hashing it doesn't
+        // require a full rehash.
+        let mut tree_hash_cache =
+            resumed_tree_hash_cache.expect("the tree hash cache should be restored");
+        let head = resumed_harness.chain.head().expect("should read head");
+        assert_eq!(
+            tree_hash_cache
+                .recalculate_tree_hash_root(&head.beacon_state)
+                .expect("should hash the head state"),
+            head.beacon_state_root,
+            "the restored tree hash cache should match the head state"
+        );
+    } else {
+        assert!(
+            resumed_tree_hash_cache.is_none(),
+            "there should be no tree hash cache to restore"
+        );
+    }
+
     // Set the slot clock of the resumed harness to be in the slot following the previous harness.
     //
     // This allows us to produce the block at the next slot.
@@ -137,6 +179,86 @@ fn finalizes_after_resuming_from_db() {
     );
 }
 
+/// An item which occupies the key of the persisted tree hash cache with bytes that can't be decoded.
+struct CorruptTreeHashCache;
+
+impl StoreItem for CorruptTreeHashCache {
+    fn db_column() -> DBColumn {
+        DBColumn::BeaconTreeHashCache
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        vec![42; 3]
+    }
+
+    fn from_store_bytes(_bytes: &[u8]) -> Result<Self, StoreError> {
+        Ok(Self)
+    }
+}
+
+#[test]
+fn resumes_from_db_with_corrupt_tree_hash_cache() {
+    let validator_count = 16;
+    let num_blocks_produced = MinimalEthSpec::slots_per_epoch() * 2;
+
+    let db_path = tempdir().unwrap();
+    let store = get_store(&db_path);
+
+    let harness = BeaconChainHarness::new_with_disk_store(
+        MinimalEthSpec,
+        store.clone(),
+        KEYPAIRS[0..validator_count].to_vec(),
+    );
+
+    harness.advance_slot();
+
+    harness.extend_chain(
+        num_blocks_produced as usize,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    harness
+        .chain
+        .persist_head_and_fork_choice_with_tree_hash_cache()
+        .expect("should persist the head, fork choice and tree hash cache");
+    harness
+        .chain
+        .persist_op_pool()
+        .expect("should persist the op pool");
+    harness
+        .chain
+        .persist_eth1_cache()
+        .expect("should persist the eth1 cache");
+
+    store
+        .put_item(
+            &Hash256::from_slice(&HEAD_TREE_HASH_CACHE_DB_KEY),
+            &CorruptTreeHashCache,
+        )
+        .expect("should overwrite the tree hash cache");
+
+    let data_dir = harness.data_dir;
+    let original_chain = harness.chain;
+
+    let resumed_harness = BeaconChainHarness::resume_from_disk_store(
+        MinimalEthSpec,
+        store,
+        KEYPAIRS[0..validator_count].to_vec(),
+        data_dir,
+    );
+
+    assert_chains_pretty_much_the_same(&original_chain, &resumed_harness.chain);
+    assert!(
+        resumed_harness
+            .chain
+            .head_tree_hash_cache()
+            .expect("should read tree hash cache")
+            .is_none(),
+        "the corrupt tree hash cache should be discarded"
+    );
+}
+
 /// Checks that two chains are the same, for the purpose of this tests.
 ///
 /// Several fields that are hard/impossible to check are ignored (e.g., the store).
@@ -85,6 +85,7 @@ fn full_participation_no_skips() {
     check_split_slot(&harness, store);
     check_chain_dump(&harness, num_blocks_produced + 1);
     check_iterators(&harness);
+    check_restore_point_view(&harness);
 }
 
 #[test]
@@ -1396,6 +1397,60 @@ fn check_chain_dump(harness: &TestHarness, expected_len: u64) {
     }
 }
 
+/// Check that validators can be read from the genesis restore point without decoding the state.
+fn check_restore_point_view(harness: &TestHarness) {
+    let genesis = harness.chain.chain_dump().unwrap().remove(0);
+    let genesis_state_root = genesis.beacon_state_root;
+    let genesis_state = genesis.beacon_state;
+    assert_eq!(genesis_state.slot, 0);
+
+    for (i, validator) in genesis_state.validators.iter().enumerate() {
+        assert_eq!(
+            harness
+                .chain
+                .store
+                .load_restore_point_validator(&genesis_state_root, i)
+                .expect("should read restore point"),
+            Some((validator.clone(), genesis_state.balances[i]))
+        );
+    }
+
+    assert_eq!(
+        harness
+            .chain
+            .store
+            .load_restore_point_validator(&genesis_state_root, genesis_state.validators.len())
+            .expect("should read restore point"),
+        None
+    );
+
+    // Validators are looked up by pubkey at restore points without loading the state.
+    let store = &harness.chain.store;
+    assert!(store.is_restore_point(&genesis_state_root).unwrap());
+    assert!(!store
+        .is_restore_point(&harness.chain.head_info().unwrap().state_root)
+        .unwrap());
+
+    for (i, validator) in genesis_state.validators.iter().enumerate() {
+        assert_eq!(
+            harness
+                .chain
+                .restore_point_validator(&genesis_state_root, &validator.pubkey)
+                .expect("should read restore point"),
+            Some((i, validator.clone(), genesis_state.balances[i]))
+        );
+    }
+
+    let unknown_pubkey = PublicKeyBytes::from(KEYPAIRS[KEYPAIRS.len() - 1].pk.clone());
+    assert_eq!(
+        harness
+            .chain
+            .restore_point_validator(&genesis_state_root, &unknown_pubkey)
+            .expect("should read restore point"),
+        None
+    );
+}
+
 /// Check that state and block root iterators can reach genesis
 fn check_iterators(harness: &TestHarness) {
     assert_eq!(
@@ -5,6 +5,7 @@ extern crate lazy_static;
 
 use beacon_chain::{
     attestation_verification::Error as AttnError,
+    events::{EventKind, EventTopic},
     test_utils::{
         AttestationStrategy, BeaconChainHarness, BlockStrategy, HarnessType, OP_POOL_DB_KEY,
     },
@@ -186,6 +187,133 @@ fn chooses_fork() {
     );
 }
 
+#[test]
+fn finds_common_ancestor_of_forks() {
+    let harness = get_harness(VALIDATOR_COUNT);
+
+    let two_thirds = (VALIDATOR_COUNT / 3) * 2;
+    let honest_validators: Vec<usize> = (0..two_thirds).collect();
+    let faulty_validators: Vec<usize> = (two_thirds..VALIDATOR_COUNT).collect();
+
+    harness.extend_chain(
+        2,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    let initial_head = harness.chain.head_info().expect("should get head");
+
+    let (honest_head, faulty_head) = harness.generate_two_forks_by_skipping_a_block(
+        &honest_validators,
+        &faulty_validators,
+        2,
+        2,
+    );
+
+    assert_eq!(
+        harness
+            .chain
+            .find_common_ancestor(honest_head, faulty_head)
+            .expect("should find common ancestor"),
+        Some((initial_head.block_root, initial_head.slot)),
+        "the forks should meet at the last block before the fork"
+    );
+    assert_eq!(
+        harness
+            .chain
+            .find_common_ancestor(faulty_head, honest_head)
+            .expect("should find common ancestor"),
+        Some((initial_head.block_root, initial_head.slot)),
+        "the common ancestor should not depend upon the order of the forks"
+    );
+
+    let honest_head_slot = harness
+        .chain
+        .get_block(&honest_head)
+        .expect("should read block")
+        .expect("block should exist")
+        .slot();
+
+    assert_eq!(
+        harness
+            .chain
+            .find_common_ancestor(honest_head, honest_head)
+            .expect("should find common ancestor"),
+        Some((honest_head, honest_head_slot)),
+        "a block should be its own common ancestor"
+    );
+}
+
+#[test]
+fn reorg_publishes_event_with_depth() {
+    let (harness, subscribers) =
+        BeaconChainHarness::new_with_event_subscribers(MinimalEthSpec, KEYPAIRS.to_vec());
+    harness.advance_slot();
+
+    let mut events = subscribers.subscribe(vec![EventTopic::ChainReorg]);
+
+    harness.extend_chain(
+        2,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+    let common_ancestor = harness.chain.head_info().expect("should get head");
+
+    // Build a fork which no validator attests to.
+    harness.advance_slot();
+    harness.extend_chain(
+        2,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::SomeValidators(vec![]),
+    );
+    let previous_head = harness.chain.head_info().expect("should get head");
+    assert_ne!(previous_head.block_root, common_ancestor.block_root);
+
+    // Build a competing fork from the common ancestor which every validator attests to.
+    let new_head_root = harness.extend_chain(
+        2,
+        BlockStrategy::ForkCanonicalChainAt {
+            previous_slot: common_ancestor.slot,
+            first_slot: previous_head.slot + 1,
+        },
+        AttestationStrategy::AllValidators,
+    );
+    let new_head = harness.chain.head_info().expect("should get head");
+    assert_eq!(
+        new_head.block_root, new_head_root,
+        "the attested fork should become the head"
+    );
+
+    let reorgs = std::iter::from_fn(|| events.try_next().ok().flatten()).collect::<Vec<_>>();
+    assert_eq!(reorgs.len(), 1, "should publish a single re-org");
+
+    match &reorgs[0] {
+        EventKind::BeaconChainReorg {
+            depth,
+            previous_head_slot,
+            previous_head_beacon_block_root,
+            common_ancestor_slot,
+            common_ancestor_beacon_block_root,
+            ..
+        } => {
+            assert_eq!(
+                *depth,
+                (previous_head.slot - common_ancestor.slot).as_u64(),
+                "depth should be measured from the previous head to the common ancestor"
+            );
+            assert_eq!(*depth, 2);
+            assert_eq!(*previous_head_slot, previous_head.slot);
+            assert_eq!(*previous_head_beacon_block_root, previous_head.block_root);
+            assert_eq!(*common_ancestor_slot, common_ancestor.slot);
+            assert_eq!(
+                *common_ancestor_beacon_block_root,
+                common_ancestor.block_root
+            );
+        }
+        other => panic!("unexpected event: {:?}", other),
+    }
+}
+
 #[test]
 fn finalizes_with_full_participation() {
     let num_blocks_produced = MinimalEthSpec::slots_per_epoch() * 5;
@@ -334,6 +462,80 @@ fn does_not_finalize_without_attestation() {
     );
 }
 
+#[test]
+fn stores_light_client_finality_proofs() {
+    let num_blocks_produced = MinimalEthSpec::slots_per_epoch() * 5;
+
+    let mut harness = get_harness(VALIDATOR_COUNT);
+    harness.chain.light_client_server = true;
+
+    harness.extend_chain(
+        num_blocks_produced as usize,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    let finalized_root = harness
+        .chain
+        .head_info()
+        .expect("should get head info")
+        .finalized_checkpoint
+        .root;
+    let proof = harness
+        .chain
+        .get_light_client_finality_proof(&finalized_root)
+        .expect("should read proof")
+        .expect("should store proof for finalized block");
+
+    assert_eq!(
+        proof.header.canonical_root(),
+        finalized_root,
+        "proof header should be the finalized block"
+    );
+    assert!(proof.verify(), "proof branches should be valid");
+
+    let finalized_state = harness
+        .chain
+        .get_state(&proof.header.state_root, Some(proof.header.slot))
+        .expect("should read state")
+        .expect("should find finalized state");
+    assert_eq!(
+        proof.finalized_checkpoint,
+        finalized_state.finalized_checkpoint
+    );
+    assert_eq!(
+        proof.current_justified_checkpoint,
+        finalized_state.current_justified_checkpoint
+    );
+}
+
+#[test]
+fn does_not_store_light_client_finality_proofs_by_default() {
+    let num_blocks_produced = MinimalEthSpec::slots_per_epoch() * 5;
+
+    let harness = get_harness(VALIDATOR_COUNT);
+
+    harness.extend_chain(
+        num_blocks_produced as usize,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    let finalized_root = harness
+        .chain
+        .head_info()
+        .expect("should get head info")
+        .finalized_checkpoint
+        .root;
+    assert_eq!(
+        harness
+            .chain
+            .get_light_client_finality_proof(&finalized_root)
+            .expect("should read proof"),
+        None
+    );
+}
+
 #[test]
 fn roundtrip_operation_pool() {
     let num_blocks_produced = MinimalEthSpec::slots_per_epoch() * 5;
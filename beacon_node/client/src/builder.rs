@@ -1,7 +1,7 @@
 use crate::config::{ClientGenesis, Config as ClientConfig};
 use crate::notifier::spawn_notifier;
 use crate::Client;
-use beacon_chain::events::TeeEventHandler;
+use beacon_chain::events::{EventSubscribers, TeeEventHandler};
 use beacon_chain::{
     builder::{BeaconChainBuilder, Witness},
     eth1_chain::{CachingEth1Backend, Eth1Chain},
@@ -10,14 +10,12 @@ use beacon_chain::{
     store::{HotColdDB, ItemStore, LevelDB, StoreConfig},
     BeaconChain, BeaconChainTypes, Eth1ChainBackend, EventHandler,
 };
-use bus::Bus;
 use environment::RuntimeContext;
 use eth1::{Config as Eth1Config, Service as Eth1Service};
 use eth2_config::Eth2Config;
 use eth2_libp2p::NetworkGlobals;
 use genesis::{interop_genesis_state, Eth1GenesisService};
 use network::{NetworkConfig, NetworkMessage, NetworkService};
-use parking_lot::Mutex;
 use slog::info;
 use ssz::Decode;
 use std::net::SocketAddr;
@@ -26,10 +24,7 @@ use std::sync::Arc;
 use std::time::Duration;
 use timer::spawn_timer;
 use tokio::sync::mpsc::UnboundedSender;
-use types::{
-    test_utils::generate_deterministic_keypairs, BeaconState, ChainSpec, EthSpec,
-    SignedBeaconBlockHash,
-};
+use types::{test_utils::generate_deterministic_keypairs, BeaconState, ChainSpec, EthSpec};
 use websocket_server::{Config as WebSocketConfig, WebSocketSender};
 
 /// Interval between polling the eth1 node for genesis information.
@@ -120,6 +115,14 @@ where
         self
     }
 
+    /// Specifies the slot clock for the `BeaconChain`.
+    ///
+    /// Useful for testing with a `ManualSlotClock`, see `system_time_slot_clock` for production.
+    pub fn slot_clock(mut self, slot_clock: TSlotClock) -> Self {
+        self.slot_clock = Some(slot_clock);
+        self
+    }
+
     /// Initializes the `BeaconChainBuilder`. The `build_beacon_chain` method will need to be
     /// called later in order to actually instantiate the `BeaconChain`.
     pub async fn beacon_chain_builder(
@@ -134,6 +137,9 @@ where
         let eth_spec_instance = self.eth_spec_instance.clone();
         let data_dir = config.data_dir.clone();
         let disabled_forks = config.disabled_forks.clone();
+        let graffiti = config.graffiti;
+        let reorg_warn_depth = config.reorg_warn_depth;
+        let light_client_server = config.light_client_server;
 
         let store =
             store.ok_or_else(|| "beacon_chain_start_method requires a store".to_string())?;
@@ -151,7 +157,10 @@ where
             .store_migrator(store_migrator)
             .data_dir(data_dir)
             .custom_spec(spec.clone())
-            .disabled_forks(disabled_forks);
+            .disabled_forks(disabled_forks)
+            .graffiti(graffiti)
+            .reorg_warn_depth(reorg_warn_depth)
+            .light_client_server(light_client_server);
 
         let chain_exists = builder
             .store_contains_beacon_chain()
@@ -279,7 +288,7 @@ where
         mut self,
         client_config: &ClientConfig,
         eth2_config: &Eth2Config,
-        events: Arc<Mutex<Bus<SignedBeaconBlockHash>>>,
+        events: EventSubscribers<TEthSpec>,
     ) -> Result<Self, String> {
         let beacon_chain = self
             .beacon_chain
@@ -318,7 +327,7 @@ where
             eth2_config.clone(),
             events,
         )
-        .map_err(|e| format!("Failed to start HTTP API: {:?}", e))?;
+        .map_err(|e| format!("Failed to start HTTP API: {}", e))?;
 
         self.http_listen_addr = Some(listening_addr);
 
@@ -377,6 +386,7 @@ where
         Client {
             beacon_chain: self.beacon_chain,
             network_globals: self.network_globals,
+            network_send: self.network_send,
             http_listen_addr: self.http_listen_addr,
             websocket_listen_addr: self.websocket_listen_addr,
         }
@@ -497,7 +507,7 @@ where
     pub fn tee_event_handler(
         mut self,
         config: WebSocketConfig,
-    ) -> Result<(Self, Arc<Mutex<Bus<SignedBeaconBlockHash>>>), String> {
+    ) -> Result<(Self, EventSubscribers<TEthSpec>), String> {
         let context = self
             .runtime_context
             .as_ref()
@@ -514,9 +524,9 @@ where
         };
 
         self.websocket_listen_addr = listening_addr;
-        let (tee_event_handler, bus) = TeeEventHandler::new(log, sender)?;
+        let (tee_event_handler, events) = TeeEventHandler::new(log, sender)?;
         self.event_handler = Some(tee_event_handler);
-        Ok((self, bus))
+        Ok((self, events))
     }
 }
 
@@ -1,7 +1,9 @@
+use beacon_chain::{DEFAULT_GRAFFITI, DEFAULT_REORG_WARN_DEPTH};
 use network::NetworkConfig;
 use serde_derive::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
+use types::{graffiti_from_str, Graffiti};
 
 pub const DEFAULT_DATADIR: &str = ".lighthouse";
 
@@ -55,6 +57,12 @@ pub struct Config {
     pub sync_eth1_chain: bool,
     /// A list of hard-coded forks that will be disabled.
     pub disabled_forks: Vec<String>,
+    /// The graffiti included in produced blocks, unless the validator supplies its own.
+    pub graffiti: Graffiti,
+    /// Re-orgs deeper than this many slots are logged as warnings.
+    pub reorg_warn_depth: u64,
+    /// If true, finality proofs are stored and served to light clients.
+    pub light_client_server: bool,
     #[serde(skip)]
     /// The `genesis` field is not serialized or deserialized by `serde` to ensure it is defined
     /// via the CLI at runtime, instead of from a configuration file saved to disk.
@@ -84,6 +92,9 @@ impl Default for Config {
             sync_eth1_chain: false,
             eth1: <_>::default(),
             disabled_forks: Vec::new(),
+            graffiti: graffiti_from_str(DEFAULT_GRAFFITI).expect("default graffiti is valid"),
+            reorg_warn_depth: DEFAULT_REORG_WARN_DEPTH,
+            light_client_server: false,
         }
     }
 }
@@ -9,8 +9,10 @@ pub mod error;
 
 use beacon_chain::BeaconChain;
 use eth2_libp2p::{Enr, Multiaddr, NetworkGlobals};
+use network::NetworkMessage;
 use std::net::SocketAddr;
 use std::sync::Arc;
+use tokio::sync::mpsc::UnboundedSender;
 
 pub use beacon_chain::{BeaconChainTypes, Eth1ChainBackend};
 pub use builder::ClientBuilder;
@@ -23,6 +25,7 @@ pub use eth2_config::Eth2Config;
 pub struct Client<T: BeaconChainTypes> {
     beacon_chain: Option<Arc<BeaconChain<T>>>,
     network_globals: Option<Arc<NetworkGlobals<T::EthSpec>>>,
+    network_send: Option<UnboundedSender<NetworkMessage<T::EthSpec>>>,
     http_listen_addr: Option<SocketAddr>,
     websocket_listen_addr: Option<SocketAddr>,
 }
@@ -33,6 +36,16 @@ impl<T: BeaconChainTypes> Client<T> {
         self.beacon_chain.clone()
     }
 
+    /// Returns an `Arc` reference to the client's libp2p globals, if the network was started.
+    pub fn network_globals(&self) -> Option<Arc<NetworkGlobals<T::EthSpec>>> {
+        self.network_globals.clone()
+    }
+
+    /// Returns a channel for sending messages to the client's network service, if it was started.
+    pub fn network_send(&self) -> Option<UnboundedSender<NetworkMessage<T::EthSpec>>> {
+        self.network_send.clone()
+    }
+
     /// Returns the address of the client's HTTP API server, if it was started.
     pub fn http_listen_addr(&self) -> Option<SocketAddr> {
         self.http_listen_addr
@@ -1,7 +1,7 @@
 use crate::peer_manager::{score::PeerAction, PeerManager, PeerManagerEvent};
 use crate::rpc::*;
 use crate::types::{GossipEncoding, GossipKind, GossipTopic};
-use crate::Eth2Enr;
+use crate::{EnrExt, Eth2Enr};
 use crate::{error, Enr, NetworkConfig, NetworkGlobals, PubsubMessage, TopicHash};
 use futures::prelude::*;
 use handler::{BehaviourHandler, BehaviourHandlerIn, BehaviourHandlerOut, DelegateIn, DelegateOut};
@@ -19,6 +19,7 @@ use libp2p::{
     },
     PeerId,
 };
+use lru::LruCache;
 use slog::{crit, debug, o};
 use std::{
     collections::VecDeque,
@@ -33,6 +34,10 @@ mod handler;
 
 const MAX_IDENTIFY_ADDRESSES: usize = 10;
 
+/// The maximum number of gossip messages awaiting validation by the application that are tracked
+/// for peer scoring.
+const MAX_PENDING_VALIDATIONS: usize = 4_096;
+
 /// Builds the network behaviour that manages the core protocols of eth2.
 /// This core behaviour is managed by `Behaviour` which adds peer management to all core
 /// behaviours.
@@ -53,6 +58,8 @@ pub struct Behaviour<TSpec: EthSpec> {
     handler_events: VecDeque<NBAction<BehaviourHandlerIn<TSpec>, BehaviourEvent<TSpec>>>,
     /// Queue of peers to disconnect.
     peers_to_dc: VecDeque<PeerId>,
+    /// The topic kinds of gossip messages that are awaiting validation by the application.
+    pending_validations: LruCache<MessageId, Vec<GossipKind>>,
     /// The current meta data of the node, so respond to pings and get metadata
     meta_data: MetaData<TSpec>,
     /// A collections of variables accessible outside the network service.
@@ -109,6 +116,7 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
             events: VecDeque::new(),
             handler_events: VecDeque::new(),
             peers_to_dc: VecDeque::new(),
+            pending_validations: LruCache::new(MAX_PENDING_VALIDATIONS),
             meta_data,
             network_globals,
             enr_fork_id,
@@ -221,11 +229,28 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
         }
     }
 
-    /// Forwards a message that is waiting in gossipsub's mcache. Messages are only propagated
-    /// once validated by the beacon chain.
-    pub fn propagate_message(&mut self, propagation_source: &PeerId, message_id: MessageId) {
-        self.gossipsub
-            .propagate_message(&message_id, propagation_source);
+    /// Informs the behaviour of the application's validation result of a gossip message.
+    ///
+    /// Accepted messages are forwarded from gossipsub's mcache; messages are only propagated once
+    /// validated by the beacon chain. The result is recorded against the peer that sent us the
+    /// message for scoring.
+    pub fn report_message_validation_result(
+        &mut self,
+        propagation_source: &PeerId,
+        message_id: MessageId,
+        acceptance: MessageAcceptance,
+    ) {
+        if let MessageAcceptance::Accept = acceptance {
+            self.gossipsub
+                .propagate_message(&message_id, propagation_source);
+        }
+
+        if let Some(kinds) = self.pending_validations.pop(&message_id) {
+            for kind in kinds {
+                self.peer_manager
+                    .gossip_validation_result(propagation_source, kind, acceptance);
+            }
+        }
     }
 
     /* Eth2 RPC behaviour functions */
@@ -276,6 +301,40 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
         self.peer_manager.goodbye_peer(peer_id, reason);
     }
 
+    /// Disconnects from a peer providing a reason, without penalising the peer's score.
+    pub fn disconnect_peer(&mut self, peer_id: &PeerId, reason: GoodbyeReason) {
+        self.peer_manager.disconnect_peer(peer_id, reason);
+    }
+
+    /// Bans a peer until it is explicitly unbanned, disconnecting it if required.
+    pub fn ban_peer(&mut self, peer_id: &PeerId) {
+        self.peer_manager.ban_peer(peer_id);
+    }
+
+    /// Removes a ban on a peer and resets its score.
+    pub fn unban_peer(&mut self, peer_id: &PeerId) {
+        self.peer_manager.unban_peer(peer_id);
+    }
+
+    /// Adds the ENR to the routing table and attempts to connect to the peer it describes.
+    pub fn dial_enr(&mut self, enr: Enr) {
+        let peer_id = enr.peer_id();
+        self.add_enr(enr);
+
+        let is_dialable = {
+            let peers = self.network_globals.peers.read();
+            !peers.is_connected_or_dialing(&peer_id) && !peers.is_banned(&peer_id)
+        };
+        if is_dialable {
+            self.peer_manager.dial_peer(&peer_id);
+        }
+    }
+
+    /// Queues a discovery query to find new peers.
+    pub fn discover_peers(&mut self) {
+        self.peer_manager.discover_peers();
+    }
+
     /// Returns an iterator over all enr entries in the DHT.
     pub fn enr_entries(&mut self) -> Vec<Enr> {
         self.peer_manager.discovery_mut().table_entries_enr()
@@ -400,6 +459,15 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
                         debug!(self.log, "Could not decode gossipsub message"; "error" => format!("{}", e))
                     }
                     Ok(msg) => {
+                        // keep track of the topics of the message until it has been validated
+                        let kinds = gs_msg
+                            .topics
+                            .iter()
+                            .filter_map(|topic| GossipTopic::decode(topic.as_str()).ok())
+                            .map(|topic| topic.kind().clone())
+                            .collect();
+                        self.pending_validations.put(id.clone(), kinds);
+
                         // if this message isn't a duplicate, notify the network
                         self.add_event(BehaviourEvent::PubsubMessage {
                             id,
@@ -411,9 +479,18 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
                 }
             }
             GossipsubEvent::Subscribed { peer_id, topic } => {
+                if let Ok(gossip_topic) = GossipTopic::decode(topic.as_str()) {
+                    self.peer_manager
+                        .gossip_subscribed(&peer_id, gossip_topic.kind().clone());
+                }
                 self.add_event(BehaviourEvent::PeerSubscribed(peer_id, topic));
             }
-            GossipsubEvent::Unsubscribed { .. } => {}
+            GossipsubEvent::Unsubscribed { peer_id, topic } => {
+                if let Ok(gossip_topic) = GossipTopic::decode(topic.as_str()) {
+                    self.peer_manager
+                        .gossip_unsubscribed(&peer_id, gossip_topic.kind());
+                }
+            }
         }
     }
 
@@ -881,6 +958,19 @@ impl<TSpec: EthSpec> NetworkBehaviour for Behaviour<TSpec> {
 
 /* Public API types */
 
+/// The application's verdict on a gossip message, reported back to the behaviour once the message
+/// has been validated.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MessageAcceptance {
+    /// The message is valid and is propagated to our peers.
+    Accept,
+    /// The message is invalid. It is not propagated and the peer that sent it is penalised.
+    Reject,
+    /// The message is not propagated, but the peer that sent it is not penalised. For example,
+    /// the message is a duplicate or arrived too late to be useful.
+    Ignore,
+}
+
 /// The type of RPC requests the Behaviour informs it has received and allows for sending.
 ///
 // NOTE: This is an application-level wrapper over the lower network level requests that can be
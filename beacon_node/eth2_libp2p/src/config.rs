@@ -59,6 +59,12 @@ pub struct Config {
     /// Disables the discovery protocol from starting.
     pub disable_discovery: bool,
 
+    /// Listens on libp2p's in-process memory transport instead of TCP.
+    ///
+    /// Only peers within the same process can connect, so this is intended for testing.
+    #[serde(skip)]
+    pub in_memory_transport: bool,
+
     /// List of extra topics to initially subscribe to as strings.
     pub topics: Vec<GossipKind>,
 }
@@ -129,6 +135,7 @@ impl Default for Config {
             libp2p_nodes: vec![],
             client_version: version::version(),
             disable_discovery: false,
+            in_memory_transport: false,
             topics,
         }
     }
@@ -0,0 +1,313 @@
+//! Gossipsub v1.1-style topic scoring.
+//!
+//! The gossipsub behaviour currently used by lighthouse does not score peers itself. Instead, the
+//! application reports the validation result of each gossip message and the deliveries of each
+//! peer are tallied here, per `GossipKind`. The resulting score is combined into the peer's
+//! `Score` by the `PeerManager`.
+//!
+//! Counters decay once per peer manager heartbeat, so all decay factors below are expressed per
+//! heartbeat.
+use crate::types::GossipKind;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Counters that have decayed below this value are reset to zero.
+const DECAY_TO_ZERO: f64 = 0.01;
+
+/// The scoring parameters for a single gossipsub topic.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TopicScoreParams {
+    /// The weight applied to the sum of all the topic's components.
+    pub topic_weight: f64,
+    /// The (positive) weight of valid messages that this peer delivered to us first.
+    pub first_message_deliveries_weight: f64,
+    /// The decay factor of the first message deliveries counter.
+    pub first_message_deliveries_decay: f64,
+    /// The maximum value of the first message deliveries counter.
+    pub first_message_deliveries_cap: f64,
+    /// The (negative) weight of the squared deficit of mesh message deliveries.
+    ///
+    /// Our gossipsub behaviour only reports the first delivery of each message, so the expected
+    /// delivery rate is counted across all peers subscribed to a topic rather than only mesh
+    /// peers.
+    pub mesh_message_deliveries_weight: f64,
+    /// The decay factor of the mesh message deliveries counter.
+    pub mesh_message_deliveries_decay: f64,
+    /// The number of deliveries below which a peer is penalised.
+    pub mesh_message_deliveries_threshold: f64,
+    /// The maximum value of the mesh message deliveries counter.
+    pub mesh_message_deliveries_cap: f64,
+    /// The time a peer must be subscribed to the topic before the deficit is penalised.
+    pub mesh_message_deliveries_activation: Duration,
+    /// The (negative) weight of the squared count of invalid messages delivered by the peer.
+    pub invalid_message_deliveries_weight: f64,
+    /// The decay factor of the invalid message deliveries counter.
+    pub invalid_message_deliveries_decay: f64,
+}
+
+impl TopicScoreParams {
+    /// Returns the scoring parameters for the given topic kind.
+    pub fn for_kind(kind: &GossipKind) -> Self {
+        match kind {
+            // One block is expected per slot. An invalid block is a serious offence.
+            GossipKind::BeaconBlock => Self {
+                topic_weight: 1.0,
+                first_message_deliveries_weight: 0.5,
+                first_message_deliveries_decay: 0.9,
+                first_message_deliveries_cap: 20.0,
+                mesh_message_deliveries_weight: -0.5,
+                mesh_message_deliveries_decay: 0.9,
+                mesh_message_deliveries_threshold: 0.5,
+                mesh_message_deliveries_cap: 20.0,
+                mesh_message_deliveries_activation: Duration::from_secs(384),
+                invalid_message_deliveries_weight: -10.0,
+                invalid_message_deliveries_decay: 0.99,
+            },
+            // Aggregates arrive in bulk each slot.
+            GossipKind::BeaconAggregateAndProof => Self {
+                topic_weight: 0.5,
+                first_message_deliveries_weight: 0.1,
+                first_message_deliveries_decay: 0.8,
+                first_message_deliveries_cap: 100.0,
+                mesh_message_deliveries_weight: -0.1,
+                mesh_message_deliveries_decay: 0.8,
+                mesh_message_deliveries_threshold: 1.0,
+                mesh_message_deliveries_cap: 100.0,
+                mesh_message_deliveries_activation: Duration::from_secs(384),
+                invalid_message_deliveries_weight: -4.0,
+                invalid_message_deliveries_decay: 0.99,
+            },
+            // There are many subnets and each peer is only expected on a few of them, so the
+            // contribution of a single subnet is kept small.
+            GossipKind::Attestation(_) => Self {
+                topic_weight: 0.25,
+                first_message_deliveries_weight: 0.05,
+                first_message_deliveries_decay: 0.8,
+                first_message_deliveries_cap: 50.0,
+                mesh_message_deliveries_weight: 0.0,
+                mesh_message_deliveries_decay: 0.8,
+                mesh_message_deliveries_threshold: 0.0,
+                mesh_message_deliveries_cap: 50.0,
+                mesh_message_deliveries_activation: Duration::from_secs(384),
+                invalid_message_deliveries_weight: -4.0,
+                invalid_message_deliveries_decay: 0.99,
+            },
+            // Exits and slashings are rare, so there is no expected delivery rate. Invalid
+            // messages are penalised heavily as they are cheap to check.
+            GossipKind::VoluntaryExit
+            | GossipKind::ProposerSlashing
+            | GossipKind::AttesterSlashing => Self {
+                topic_weight: 1.0,
+                first_message_deliveries_weight: 0.5,
+                first_message_deliveries_decay: 0.9,
+                first_message_deliveries_cap: 4.0,
+                mesh_message_deliveries_weight: 0.0,
+                mesh_message_deliveries_decay: 0.9,
+                mesh_message_deliveries_threshold: 0.0,
+                mesh_message_deliveries_cap: 4.0,
+                mesh_message_deliveries_activation: Duration::from_secs(384),
+                invalid_message_deliveries_weight: -10.0,
+                invalid_message_deliveries_decay: 0.99,
+            },
+        }
+    }
+}
+
+/// The per-topic delivery counters of a single peer.
+#[derive(Debug, Clone, Default)]
+struct TopicStats {
+    /// The time the peer subscribed to the topic, if it is subscribed.
+    subscribed_since: Option<Instant>,
+    /// The decaying count of valid messages first delivered by this peer.
+    first_message_deliveries: f64,
+    /// The decaying count of valid messages delivered by this peer while subscribed.
+    mesh_message_deliveries: f64,
+    /// The decaying count of invalid messages delivered by this peer.
+    invalid_message_deliveries: f64,
+}
+
+impl TopicStats {
+    fn score(&self, params: &TopicScoreParams) -> f64 {
+        let mut score = params.first_message_deliveries_weight * self.first_message_deliveries;
+
+        let mesh_active = self.subscribed_since.map_or(false, |since| {
+            since.elapsed() >= params.mesh_message_deliveries_activation
+        });
+        if mesh_active && self.mesh_message_deliveries < params.mesh_message_deliveries_threshold {
+            let deficit = params.mesh_message_deliveries_threshold - self.mesh_message_deliveries;
+            score += params.mesh_message_deliveries_weight * deficit * deficit;
+        }
+
+        score += params.invalid_message_deliveries_weight
+            * self.invalid_message_deliveries
+            * self.invalid_message_deliveries;
+
+        params.topic_weight * score
+    }
+
+    fn decay(&mut self, params: &TopicScoreParams) {
+        let decay = |value: &mut f64, factor: f64| {
+            *value *= factor;
+            if *value < DECAY_TO_ZERO {
+                *value = 0.0;
+            }
+        };
+        decay(
+            &mut self.first_message_deliveries,
+            params.first_message_deliveries_decay,
+        );
+        decay(
+            &mut self.mesh_message_deliveries,
+            params.mesh_message_deliveries_decay,
+        );
+        decay(
+            &mut self.invalid_message_deliveries,
+            params.invalid_message_deliveries_decay,
+        );
+    }
+
+    fn is_empty(&self) -> bool {
+        self.subscribed_since.is_none()
+            && self.first_message_deliveries == 0.0
+            && self.mesh_message_deliveries == 0.0
+            && self.invalid_message_deliveries == 0.0
+    }
+}
+
+/// A peer's gossipsub behaviour, tallied across all topics.
+#[derive(Debug, Clone, Default)]
+pub struct GossipScore {
+    topics: HashMap<GossipKind, TopicStats>,
+}
+
+impl GossipScore {
+    /// The peer has subscribed to a topic.
+    pub fn subscribed(&mut self, kind: GossipKind) {
+        self.topics
+            .entry(kind)
+            .or_default()
+            .subscribed_since
+            .get_or_insert_with(Instant::now);
+    }
+
+    /// The peer has unsubscribed from a topic.
+    pub fn unsubscribed(&mut self, kind: &GossipKind) {
+        if let Some(stats) = self.topics.get_mut(kind) {
+            stats.subscribed_since = None;
+        }
+    }
+
+    /// The peer delivered a message that was accepted by the application.
+    pub fn message_delivered(&mut self, kind: GossipKind) {
+        let params = TopicScoreParams::for_kind(&kind);
+        let stats = self.topics.entry(kind).or_default();
+
+        stats.first_message_deliveries =
+            (stats.first_message_deliveries + 1.0).min(params.first_message_deliveries_cap);
+        if stats.subscribed_since.is_some() {
+            stats.mesh_message_deliveries =
+                (stats.mesh_message_deliveries + 1.0).min(params.mesh_message_deliveries_cap);
+        }
+    }
+
+    /// The peer delivered a message that was rejected by the application.
+    pub fn message_rejected(&mut self, kind: GossipKind) {
+        self.topics
+            .entry(kind)
+            .or_default()
+            .invalid_message_deliveries += 1.0;
+    }
+
+    /// Decays all counters. This should be called once per peer manager heartbeat.
+    pub fn decay(&mut self) {
+        for (kind, stats) in self.topics.iter_mut() {
+            stats.decay(&TopicScoreParams::for_kind(kind));
+        }
+        self.topics.retain(|_, stats| !stats.is_empty());
+    }
+
+    /// Returns the peer's score, summed over all topics.
+    pub fn score(&self) -> f64 {
+        self.topics
+            .iter()
+            .map(|(kind, stats)| stats.score(&TopicScoreParams::for_kind(kind)))
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::SubnetId;
+
+    #[test]
+    fn test_first_message_deliveries_are_capped() {
+        let mut score = GossipScore::default();
+        let params = TopicScoreParams::for_kind(&GossipKind::BeaconBlock);
+
+        for _ in 0..100 {
+            score.message_delivered(GossipKind::BeaconBlock);
+        }
+
+        assert_eq!(
+            score.score(),
+            params.topic_weight
+                * params.first_message_deliveries_weight
+                * params.first_message_deliveries_cap
+        );
+    }
+
+    #[test]
+    fn test_invalid_messages_are_penalised_quadratically() {
+        let mut score = GossipScore::default();
+        let kind = GossipKind::Attestation(SubnetId::new(3));
+        let params = TopicScoreParams::for_kind(&kind);
+
+        score.message_rejected(kind.clone());
+        let single = score.score();
+        assert_eq!(
+            single,
+            params.topic_weight * params.invalid_message_deliveries_weight
+        );
+
+        score.message_rejected(kind);
+        assert_eq!(score.score(), 4.0 * single);
+    }
+
+    #[test]
+    fn test_decay_forgets_old_deliveries() {
+        let mut score = GossipScore::default();
+        score.message_delivered(GossipKind::VoluntaryExit);
+        score.message_rejected(GossipKind::BeaconBlock);
+
+        let before = score.score();
+        score.decay();
+        assert!(
+            score.score() > before,
+            "penalties should decay towards zero"
+        );
+
+        for _ in 0..10_000 {
+            score.decay();
+        }
+        assert_eq!(score.score(), 0.0);
+        assert!(score.topics.is_empty());
+    }
+
+    #[test]
+    fn test_mesh_deficit_requires_activation() {
+        let mut params = TopicScoreParams::for_kind(&GossipKind::BeaconBlock);
+        let mut stats = TopicStats::default();
+        stats.subscribed_since = Some(Instant::now());
+
+        // The peer has only just subscribed, so there is no deficit penalty yet.
+        assert_eq!(stats.score(&params), 0.0);
+
+        params.mesh_message_deliveries_activation = Duration::from_secs(0);
+        assert!(stats.score(&params) < 0.0);
+
+        // Delivering enough messages clears the deficit.
+        stats.mesh_message_deliveries = params.mesh_message_deliveries_threshold;
+        assert_eq!(stats.score(&params), 0.0);
+    }
+}
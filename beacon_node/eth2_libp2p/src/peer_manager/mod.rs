@@ -3,7 +3,8 @@
 pub use self::peerdb::*;
 use crate::discovery::{Discovery, DiscoveryEvent};
 use crate::rpc::{GoodbyeReason, MetaData, Protocol, RPCError, RPCResponseErrorCode};
-use crate::{error, metrics};
+use crate::types::GossipKind;
+use crate::{error, metrics, MessageAcceptance};
 use crate::{Enr, EnrExt, NetworkConfig, NetworkGlobals, PeerId};
 use futures::prelude::*;
 use futures::Stream;
@@ -24,14 +25,18 @@ use types::{EthSpec, SubnetId};
 pub use libp2p::core::{identity::Keypair, Multiaddr};
 
 pub mod client;
+mod gossip_score;
 mod peer_info;
 mod peer_sync_status;
 mod peerdb;
 pub(crate) mod score;
+mod sync_throughput;
 
+use gossip_score::GossipScore;
 pub use peer_info::{PeerConnectionStatus::*, PeerInfo};
 pub use peer_sync_status::{PeerSyncStatus, SyncInfo};
-use score::{PeerAction, ScoreState};
+use score::{PeerAction, Score, ScoreState};
+pub use sync_throughput::SyncThroughput;
 /// The time in seconds between re-status's peers.
 const STATUS_INTERVAL: u64 = 300;
 /// The time in seconds between PING events. We do not send a ping if the other peer as PING'd us within
@@ -134,10 +139,108 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
         }
     }
 
+    /// The application layer wants to disconnect from a peer without penalising it.
+    ///
+    /// Unlike `goodbye_peer`, the peer's score is left untouched so it may reconnect later.
+    pub fn disconnect_peer(&mut self, peer_id: &PeerId, reason: GoodbyeReason) {
+        if self
+            .network_globals
+            .peers
+            .read()
+            .is_connected_or_dialing(peer_id)
+        {
+            debug!(self.log, "Disconnecting peer"; "peer_id" => peer_id.to_string(), "reason" => reason.to_string());
+            self.events
+                .push(PeerManagerEvent::DisconnectPeer(peer_id.clone(), reason));
+        }
+    }
+
+    /// Bans a peer regardless of its score, disconnecting it if it is connected or dialing.
+    ///
+    /// The ban persists until `unban_peer` is called.
+    pub fn ban_peer(&mut self, peer_id: &PeerId) {
+        let is_connected_or_dialing = {
+            let mut pdb = self.network_globals.peers.write();
+            let is_connected_or_dialing = pdb.is_connected_or_dialing(peer_id);
+            pdb.ban_explicitly(peer_id);
+            is_connected_or_dialing
+        };
+
+        debug!(self.log, "Banning peer"; "peer_id" => peer_id.to_string());
+        if is_connected_or_dialing {
+            self.events.push(PeerManagerEvent::DisconnectPeer(
+                peer_id.clone(),
+                GoodbyeReason::Banned,
+            ));
+        }
+    }
+
+    /// Unbans a peer and resets its scores, allowing it to connect again.
+    pub fn unban_peer(&mut self, peer_id: &PeerId) {
+        let mut pdb = self.network_globals.peers.write();
+        if let Some(info) = pdb.peer_info_mut(peer_id) {
+            // The gossipsub score is folded back into the score on every heartbeat, so it must
+            // be reset as well or the peer would be banned again straight away.
+            info.score = Score::default();
+            info.gossip_score = GossipScore::default();
+        }
+        pdb.unban_explicitly(peer_id);
+        debug!(self.log, "Unbanned peer"; "peer_id" => peer_id.to_string());
+    }
+
     /// Reports a peer for some action.
     ///
     /// If the peer doesn't exist, log a warning and insert defaults.
     pub fn report_peer(&mut self, peer_id: &PeerId, action: PeerAction) {
+        self.update_peer_score(peer_id, |info| info.score.apply_peer_action(action));
+    }
+
+    /* Gossipsub Scoring */
+
+    /// Records the application's validation result of a gossip message received from a peer.
+    ///
+    /// Rejected messages are penalised immediately. Accepted messages are credited to the peer's
+    /// score at the next heartbeat.
+    pub fn gossip_validation_result(
+        &mut self,
+        peer_id: &PeerId,
+        kind: GossipKind,
+        acceptance: MessageAcceptance,
+    ) {
+        match acceptance {
+            MessageAcceptance::Accept => {
+                if let Some(info) = self.network_globals.peers.write().peer_info_mut(peer_id) {
+                    info.gossip_score.message_delivered(kind);
+                }
+            }
+            MessageAcceptance::Reject => {
+                self.update_peer_score(peer_id, |info| {
+                    info.gossip_score.message_rejected(kind);
+                    let gossipsub_score = info.gossip_score.score();
+                    info.score.update_gossipsub_score(gossipsub_score);
+                });
+            }
+            MessageAcceptance::Ignore => {}
+        }
+    }
+
+    /// A peer has subscribed to a gossipsub topic.
+    pub fn gossip_subscribed(&mut self, peer_id: &PeerId, kind: GossipKind) {
+        if let Some(info) = self.network_globals.peers.write().peer_info_mut(peer_id) {
+            info.gossip_score.subscribed(kind);
+        }
+    }
+
+    /// A peer has unsubscribed from a gossipsub topic.
+    pub fn gossip_unsubscribed(&mut self, peer_id: &PeerId, kind: &GossipKind) {
+        if let Some(info) = self.network_globals.peers.write().peer_info_mut(peer_id) {
+            info.gossip_score.unsubscribed(kind);
+        }
+    }
+
+    /// Applies `update` to a peer's info and handles any resulting change in the state of its
+    /// score.
+    fn update_peer_score<F: FnOnce(&mut PeerInfo<TSpec>)>(&mut self, peer_id: &PeerId, update: F) {
         // TODO: Remove duplicate code  - This is duplicated in the update_peer_scores()
         // function.
 
@@ -147,7 +250,7 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
 
         if let Some(info) = self.network_globals.peers.write().peer_info_mut(peer_id) {
             let previous_state = info.score.state();
-            info.score.apply_peer_action(action);
+            update(info);
             if previous_state != info.score.state() {
                 match info.score.state() {
                     ScoreState::Ban => {
@@ -184,9 +287,11 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
         // Update the PeerDB state.
         if let Some(peer_id) = ban_peer.take() {
             self.network_globals.peers.write().ban(&peer_id);
-        } else {
-            if let Some(peer_id) = unban_peer.take() {
-                self.network_globals.peers.write().unban(&peer_id);
+        } else if let Some(peer_id) = unban_peer.take() {
+            // Explicit bans are only lifted by `unban_peer`.
+            let mut pdb = self.network_globals.peers.write();
+            if !pdb.is_explicitly_banned(&peer_id) {
+                pdb.unban(&peer_id);
             }
         }
     }
@@ -203,6 +308,11 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
         &mut self.discovery
     }
 
+    /// Queues a discovery query for new peers, if one isn't already queued.
+    pub fn discover_peers(&mut self) {
+        self.discovery.discover_peers();
+    }
+
     /// A request to find peers on a given subnet.
     pub fn discover_subnet_peers(&mut self, subnet_id: SubnetId, min_ttl: Option<Instant>) {
         // Extend the time to maintain peers if required.
@@ -557,6 +667,8 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
         for (peer_id, info) in pdb.peers_mut() {
             let previous_state = info.score.state();
             // Update scores
+            info.gossip_score.decay();
+            info.score.update_gossipsub_score(info.gossip_score.score());
             info.score.update();
 
             /* TODO: Implement logic about connection lifetimes
@@ -648,9 +760,11 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
         for peer_id in to_ban_peers {
             pdb.ban(&peer_id);
         }
-        // process unbanning peers
+        // process unbanning peers, except those which were banned explicitly
         for peer_id in to_unban_peers {
-            pdb.unban(&peer_id);
+            if !pdb.is_explicitly_banned(&peer_id) {
+                pdb.unban(&peer_id);
+            }
         }
     }
 
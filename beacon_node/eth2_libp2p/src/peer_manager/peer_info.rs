@@ -1,5 +1,7 @@
 use super::client::Client;
+use super::gossip_score::GossipScore;
 use super::score::Score;
+use super::sync_throughput::SyncThroughput;
 use super::PeerSyncStatus;
 use crate::rpc::MetaData;
 use crate::Multiaddr;
@@ -19,6 +21,13 @@ pub struct PeerInfo<T: EthSpec> {
     _status: PeerStatus,
     /// The peers reputation
     pub score: Score,
+    /// The peer's gossipsub delivery statistics, from which the gossipsub component of its score
+    /// is derived.
+    #[serde(skip)]
+    pub gossip_score: GossipScore,
+    /// The latency and throughput of the peer's responses to our range sync requests.
+    #[serde(skip)]
+    pub sync_throughput: SyncThroughput,
     /// Client managing this peer
     pub client: Client,
     /// Connection status of this peer
@@ -42,6 +51,8 @@ impl<TSpec: EthSpec> Default for PeerInfo<TSpec> {
         PeerInfo {
             _status: Default::default(),
             score: Score::default(),
+            gossip_score: GossipScore::default(),
+            sync_throughput: SyncThroughput::default(),
             client: Client::default(),
             connection_status: Default::default(),
             listening_addresses: vec![],
@@ -4,7 +4,7 @@ use super::score::Score;
 use crate::rpc::methods::MetaData;
 use crate::PeerId;
 use slog::{crit, debug, trace, warn};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::time::Instant;
 use types::{EthSpec, SubnetId};
 
@@ -21,6 +21,9 @@ pub struct PeerDB<TSpec: EthSpec> {
     disconnected_peers: usize,
     /// The number of banned peers in the database.
     banned_peers: usize,
+    /// Peers banned via `ban_explicitly`. These remain banned until `unban_explicitly` is called,
+    /// regardless of any change in their score.
+    explicitly_banned_peers: HashSet<PeerId>,
     /// PeerDB's logger
     log: slog::Logger,
 }
@@ -31,6 +34,7 @@ impl<TSpec: EthSpec> PeerDB<TSpec> {
             log: log.clone(),
             disconnected_peers: 0,
             banned_peers: 0,
+            explicitly_banned_peers: HashSet::new(),
             peers: HashMap::new(),
         }
     }
@@ -44,6 +48,11 @@ impl<TSpec: EthSpec> PeerDB<TSpec> {
             .map_or(Score::default(), |info| info.score)
     }
 
+    /// Returns true if the peer was banned via `ban_explicitly` and has not been unbanned since.
+    pub fn is_explicitly_banned(&self, peer_id: &PeerId) -> bool {
+        self.explicitly_banned_peers.contains(peer_id)
+    }
+
     /// Returns an iterator over all peers in the db.
     pub fn peers(&self) -> impl Iterator<Item = (&PeerId, &PeerInfo<TSpec>)> {
         self.peers.iter()
@@ -314,6 +323,18 @@ impl<TSpec: EthSpec> PeerDB<TSpec> {
         self.shrink_to_fit();
     }
 
+    /// Marks a peer as banned until `unban_explicitly` is called.
+    pub fn ban_explicitly(&mut self, peer_id: &PeerId) {
+        self.explicitly_banned_peers.insert(peer_id.clone());
+        self.ban(peer_id);
+    }
+
+    /// Lifts a ban made by `ban_explicitly` and unbans the peer.
+    pub fn unban_explicitly(&mut self, peer_id: &PeerId) {
+        self.explicitly_banned_peers.remove(peer_id);
+        self.unban(peer_id);
+    }
+
     /// Unbans a peer.
     pub fn unban(&mut self, peer_id: &PeerId) {
         let log_ref = &self.log;
@@ -350,6 +371,7 @@ impl<TSpec: EthSpec> PeerDB<TSpec> {
             {
                 debug!(self.log, "Removing old banned peer"; "peer_id" => to_drop.to_string());
                 self.peers.remove(&to_drop);
+                self.explicitly_banned_peers.remove(&to_drop);
             }
             // If there is no minimum, this is a coding error. For safety we decrease
             // the count to avoid a potential infinite loop.
@@ -491,6 +513,42 @@ mod tests {
         assert_eq!(pdb.banned_peers, MAX_BANNED_PEERS);
     }
 
+    #[test]
+    fn test_explicitly_banned_are_bounded() {
+        let mut pdb = get_db();
+
+        for _ in 0..MAX_BANNED_PEERS + 1 {
+            let p = PeerId::random();
+            pdb.connect_ingoing(&p);
+        }
+
+        for p in pdb.connected_peer_ids().cloned().collect::<Vec<_>>() {
+            pdb.ban_explicitly(&p);
+        }
+
+        assert_eq!(pdb.banned_peers, MAX_BANNED_PEERS);
+        assert_eq!(pdb.explicitly_banned_peers.len(), MAX_BANNED_PEERS);
+        assert!(pdb
+            .explicitly_banned_peers
+            .iter()
+            .all(|peer_id| pdb.is_banned(peer_id)));
+    }
+
+    #[test]
+    fn test_unban_explicitly() {
+        let mut pdb = get_db();
+
+        let p = PeerId::random();
+        pdb.connect_ingoing(&p);
+        pdb.ban_explicitly(&p);
+        assert!(pdb.is_banned(&p));
+        assert!(pdb.is_explicitly_banned(&p));
+
+        pdb.unban_explicitly(&p);
+        assert!(!pdb.is_banned(&p));
+        assert!(!pdb.is_explicitly_banned(&p));
+    }
+
     #[test]
     fn test_best_peers() {
         let mut pdb = get_db();
@@ -68,14 +68,17 @@ pub(crate) enum ScoreState {
 
 /// A peer's score (perceived potential usefulness).
 ///
-/// This simplistic version consists of a global score per peer which decays to 0 over time. The
-/// decay rate applies equally to positive and negative scores.
+/// The score is the sum of a lighthouse score, adjusted by `PeerAction`s and decaying to 0 over
+/// time, and a gossipsub score derived from the peer's gossip message deliveries. The decay rate
+/// applies equally to positive and negative lighthouse scores.
 #[derive(Copy, PartialEq, Clone, Debug, Serialize)]
 pub struct Score {
-    /// The global score.
-    // NOTE: In the future we may separate this into sub-scores involving the RPC, Gossipsub and
-    // lighthouse.
+    /// The global score, combining the lighthouse and gossipsub scores.
     score: f64,
+    /// The score derived from the peer's actions, as reported by lighthouse.
+    lighthouse_score: f64,
+    /// The score derived from the peer's gossipsub message deliveries.
+    gossipsub_score: f64,
     /// The time the score was last updated to perform time-based adjustments such as score-decay.
     #[serde(skip)]
     last_updated: Instant,
@@ -85,6 +88,8 @@ impl Default for Score {
     fn default() -> Self {
         Score {
             score: DEFAULT_SCORE,
+            lighthouse_score: DEFAULT_SCORE,
+            gossipsub_score: DEFAULT_SCORE,
             last_updated: Instant::now(),
         }
     }
@@ -109,10 +114,10 @@ impl Ord for Score {
 
 impl From<f64> for Score {
     fn from(f: f64) -> Self {
-        Score {
-            score: f,
-            last_updated: Instant::now(),
-        }
+        let mut score = Score::default();
+        score.lighthouse_score = f;
+        score.update_global_score();
+        score
     }
 }
 
@@ -150,10 +155,19 @@ impl Score {
         self.score
     }
 
+    /// Access to the gossipsub component of the score.
+    pub fn gossipsub_score(&self) -> f64 {
+        self.gossipsub_score
+    }
+
     /// Modifies the score based on a peer's action.
     pub fn apply_peer_action(&mut self, peer_action: PeerAction) {
         match peer_action {
-            PeerAction::Fatal => self.score = MIN_SCORE, // The worst possible score
+            PeerAction::Fatal => {
+                // The worst possible score
+                self.lighthouse_score = MIN_SCORE;
+                self.update_global_score();
+            }
             PeerAction::LowToleranceError => self.add(-10.0),
             PeerAction::MidToleranceError => self.add(-5.0),
             PeerAction::HighToleranceError => self.add(-1.0),
@@ -170,17 +184,21 @@ impl Score {
         }
     }
 
-    /// Add an f64 to the score abiding by the limits.
+    /// Add an f64 to the lighthouse score abiding by the limits.
     pub fn add(&mut self, score: f64) {
-        let mut new_score = self.score + score;
-        if new_score > MAX_SCORE {
-            new_score = MAX_SCORE;
-        }
-        if new_score < MIN_SCORE {
-            new_score = MIN_SCORE;
-        }
+        self.lighthouse_score = clamp_score(self.lighthouse_score + score);
+        self.update_global_score();
+    }
+
+    /// Replaces the gossipsub component of the score.
+    pub fn update_gossipsub_score(&mut self, gossipsub_score: f64) {
+        self.gossipsub_score = clamp_score(gossipsub_score);
+        self.update_global_score();
+    }
 
-        self.score = new_score;
+    /// Recomputes the global score from its components.
+    fn update_global_score(&mut self) {
+        self.score = clamp_score(self.lighthouse_score + self.gossipsub_score);
     }
 
     /// Applies time-based logic such as decay rates to the score.
@@ -210,12 +228,24 @@ impl Score {
         {
             // e^(-ln(2)/HL*t)
             let decay_factor = (*HALFLIFE_DECAY * secs_since_update as f64).exp();
-            self.score *= decay_factor;
+            self.lighthouse_score *= decay_factor;
+            self.update_global_score();
             self.last_updated = now;
         }
     }
 }
 
+/// Restricts a score to the range `[MIN_SCORE, MAX_SCORE]`.
+fn clamp_score(score: f64) -> f64 {
+    if score > MAX_SCORE {
+        MAX_SCORE
+    } else if score < MIN_SCORE {
+        MIN_SCORE
+    } else {
+        score
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -248,4 +278,23 @@ mod tests {
         score.add(change);
         assert_eq!(score.score(), DEFAULT_SCORE + change);
     }
+
+    #[test]
+    fn test_gossipsub_score_is_combined() {
+        let mut score = Score::default();
+        score.add(-10.0);
+        score.update_gossipsub_score(-15.0);
+        assert_eq!(score.score(), -25.0);
+        assert_eq!(score.state(), ScoreState::Disconnect);
+
+        // Replacing the gossipsub score does not accumulate.
+        score.update_gossipsub_score(5.0);
+        assert_eq!(score.score(), -5.0);
+        assert_eq!(score.state(), ScoreState::Healthy);
+
+        // The combined score is capped.
+        score.apply_peer_action(PeerAction::Fatal);
+        score.update_gossipsub_score(-50.0);
+        assert_eq!(score.score(), MIN_SCORE);
+    }
 }
@@ -0,0 +1,135 @@
+//! Tracks how quickly a peer serves the block ranges we request whilst syncing.
+//!
+//! Both estimates are exponential moving averages, so they follow changes in a peer's bandwidth
+//! without being dominated by a single slow or fast response.
+use std::time::Duration;
+
+/// The weight given to the most recent response when updating an average.
+const SMOOTHING_FACTOR: f64 = 0.3;
+/// A failed request multiplies the peer's throughput estimate by this factor.
+const FAILURE_PENALTY: f64 = 0.5;
+/// Responses which complete faster than this are measured as though they took this long, to
+/// avoid wildly over-estimating the throughput of tiny responses.
+const MIN_MEASURED_DURATION: Duration = Duration::from_millis(10);
+
+/// The observed response latency and throughput of a peer's `BlocksByRange` responses.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SyncThroughput {
+    /// The average time between sending a request and receiving the first block.
+    latency: Option<Duration>,
+    /// The average rate at which blocks are received, in SSZ bytes per second.
+    bytes_per_second: Option<f64>,
+    /// The number of responses received.
+    responses: u64,
+    /// The number of requests which failed or timed out.
+    failures: u64,
+}
+
+impl SyncThroughput {
+    /// Records a response which delivered `bytes` of blocks, where `latency` is the time until
+    /// the first block arrived and `duration` is the time until the response stream ended.
+    ///
+    /// Empty responses only update the latency, since they say nothing about bandwidth.
+    pub fn response_received(&mut self, latency: Duration, bytes: usize, duration: Duration) {
+        self.responses += 1;
+        self.latency = Some(match self.latency {
+            Some(average) => average
+                .mul_f64(1.0 - SMOOTHING_FACTOR)
+                .checked_add(latency.mul_f64(SMOOTHING_FACTOR))
+                .unwrap_or(latency),
+            None => latency,
+        });
+
+        if bytes > 0 {
+            let rate = bytes as f64 / std::cmp::max(duration, MIN_MEASURED_DURATION).as_secs_f64();
+            self.bytes_per_second = Some(match self.bytes_per_second {
+                Some(average) => average * (1.0 - SMOOTHING_FACTOR) + rate * SMOOTHING_FACTOR,
+                None => rate,
+            });
+        }
+    }
+
+    /// Records a request which errored or timed out before completing.
+    pub fn request_failed(&mut self) {
+        self.failures += 1;
+        if let Some(average) = self.bytes_per_second.as_mut() {
+            *average *= FAILURE_PENALTY;
+        }
+    }
+
+    /// The average time taken for the peer to start responding, if known.
+    pub fn latency(&self) -> Option<Duration> {
+        self.latency
+    }
+
+    /// The average rate at which the peer sends us blocks, in bytes per second, if known.
+    pub fn bytes_per_second(&self) -> Option<f64> {
+        self.bytes_per_second
+    }
+
+    /// The number of responses received from the peer.
+    pub fn responses(&self) -> u64 {
+        self.responses
+    }
+
+    /// The number of requests to the peer which failed.
+    pub fn failures(&self) -> u64 {
+        self.failures
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_response_sets_estimates() {
+        let mut throughput = SyncThroughput::default();
+        assert_eq!(throughput.bytes_per_second(), None);
+
+        throughput.response_received(Duration::from_millis(100), 1_000, Duration::from_secs(2));
+
+        assert_eq!(throughput.latency(), Some(Duration::from_millis(100)));
+        assert_eq!(throughput.bytes_per_second(), Some(500.0));
+        assert_eq!(throughput.responses(), 1);
+    }
+
+    #[test]
+    fn test_estimates_are_smoothed() {
+        let mut throughput = SyncThroughput::default();
+        throughput.response_received(Duration::from_millis(100), 1_000, Duration::from_secs(1));
+        throughput.response_received(Duration::from_millis(200), 2_000, Duration::from_secs(1));
+
+        let bytes_per_second = throughput.bytes_per_second().unwrap();
+        assert!(bytes_per_second > 1_000.0 && bytes_per_second < 2_000.0);
+
+        let latency = throughput.latency().unwrap();
+        assert!(latency > Duration::from_millis(100) && latency < Duration::from_millis(200));
+    }
+
+    #[test]
+    fn test_empty_responses_do_not_affect_throughput() {
+        let mut throughput = SyncThroughput::default();
+        throughput.response_received(Duration::from_millis(100), 1_000, Duration::from_secs(1));
+        throughput.response_received(Duration::from_millis(100), 0, Duration::from_secs(1));
+
+        assert_eq!(throughput.bytes_per_second(), Some(1_000.0));
+        assert_eq!(throughput.responses(), 2);
+    }
+
+    #[test]
+    fn test_failures_are_penalised() {
+        let mut throughput = SyncThroughput::default();
+        throughput.request_failed();
+        assert_eq!(throughput.bytes_per_second(), None);
+
+        throughput.response_received(Duration::from_millis(100), 1_000, Duration::from_secs(1));
+        throughput.request_failed();
+
+        assert_eq!(
+            throughput.bytes_per_second(),
+            Some(1_000.0 * FAILURE_PENALTY)
+        );
+        assert_eq!(throughput.failures(), 2);
+    }
+}
@@ -10,7 +10,7 @@ use libp2p::core::{
     identity::Keypair,
     multiaddr::Multiaddr,
     muxing::StreamMuxerBox,
-    transport::boxed::Boxed,
+    transport::{boxed::Boxed, MemoryTransport, OptionalTransport},
     upgrade::{InboundUpgradeExt, OutboundUpgradeExt},
 };
 use libp2p::{
@@ -90,7 +90,7 @@ impl<TSpec: EthSpec> Service<TSpec> {
 
         let mut swarm = {
             // Set up the transport - tcp/ws with noise and yamux/mplex
-            let transport = build_transport(local_keypair.clone())
+            let transport = build_transport(local_keypair.clone(), config.in_memory_transport)
                 .map_err(|e| format!("Failed to build transport: {:?}", e))?;
             // Lighthouse network behaviour
             let behaviour = Behaviour::new(&local_keypair, config, network_globals.clone(), &log)?;
@@ -109,7 +109,10 @@ impl<TSpec: EthSpec> Service<TSpec> {
         };
 
         // listen on the specified address
-        let listen_multiaddr = {
+        let listen_multiaddr = if config.in_memory_transport {
+            // A memory port of zero is substituted with a random, unused port.
+            Multiaddr::from(Protocol::Memory(0))
+        } else {
             let mut m = Multiaddr::from(config.listen_address);
             m.push(Protocol::Tcp(config.libp2p_port));
             m
@@ -217,6 +220,20 @@ impl<TSpec: EthSpec> Service<TSpec> {
         self.swarm.goodbye_peer(peer_id, reason);
     }
 
+    /// Dials a multiaddr directly, stripping the `/p2p` protocol if it exists.
+    pub fn dial_multiaddr(&mut self, mut multiaddr: Multiaddr) {
+        strip_peer_id(&mut multiaddr);
+        match Swarm::dial_addr(&mut self.swarm, multiaddr.clone()) {
+            Ok(()) => {
+                debug!(self.log, "Dialing libp2p peer"; "address" => format!("{}", multiaddr))
+            }
+            Err(err) => debug!(
+                self.log,
+                "Could not connect to peer"; "address" => format!("{}", multiaddr), "error" => format!("{:?}", err)
+            ),
+        };
+    }
+
     /// Sends a response to a peer's request.
     pub fn send_response(&mut self, peer_id: PeerId, id: PeerRequestId, response: Response<TSpec>) {
         self.swarm.send_successful_response(peer_id, id, response);
@@ -290,6 +307,7 @@ impl<TSpec: EthSpec> Service<TSpec> {
 
 fn build_transport(
     local_private_key: Keypair,
+    in_memory: bool,
 ) -> Result<Boxed<(PeerId, StreamMuxerBox), Error>, Error> {
     let transport = libp2p::tcp::TokioTcpConfig::new().nodelay(true);
     let transport = libp2p::dns::DnsConfig::new(transport)?;
@@ -298,6 +316,12 @@ fn build_transport(
         let trans_clone = transport.clone();
         transport.or_transport(libp2p::websocket::WsConfig::new(trans_clone))
     };
+    // The memory transport only connects peers within this process
+    let transport = transport.or_transport(if in_memory {
+        OptionalTransport::some(MemoryTransport::default())
+    } else {
+        OptionalTransport::none()
+    });
     // Authentication
     let transport = transport
         .and_then(move |stream, endpoint| {
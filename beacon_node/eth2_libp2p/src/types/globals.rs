@@ -1,7 +1,7 @@
 //! A collection of variables that are accessible outside of the network thread itself.
 use crate::peer_manager::PeerDB;
 use crate::rpc::methods::MetaData;
-use crate::types::SyncState;
+use crate::types::{SyncChainInfo, SyncState};
 use crate::Client;
 use crate::EnrExt;
 use crate::{Enr, Eth2Enr, GossipTopic, Multiaddr, PeerId};
@@ -29,6 +29,8 @@ pub struct NetworkGlobals<TSpec: EthSpec> {
     pub gossipsub_subscriptions: RwLock<HashSet<GossipTopic>>,
     /// The current sync status of the node.
     pub sync_state: RwLock<SyncState>,
+    /// The progress of each range sync chain.
+    pub sync_chains: RwLock<Vec<SyncChainInfo>>,
 }
 
 impl<TSpec: EthSpec> NetworkGlobals<TSpec> {
@@ -51,6 +53,7 @@ impl<TSpec: EthSpec> NetworkGlobals<TSpec> {
             peers: RwLock::new(PeerDB::new(log)),
             gossipsub_subscriptions: RwLock::new(HashSet::new()),
             sync_state: RwLock::new(SyncState::Stalled),
+            sync_chains: RwLock::new(Vec::new()),
         }
     }
 
@@ -100,6 +103,11 @@ impl<TSpec: EthSpec> NetworkGlobals<TSpec> {
         self.sync_state.read().clone()
     }
 
+    /// Returns the progress of each range sync chain.
+    pub fn sync_chains(&self) -> Vec<SyncChainInfo> {
+        self.sync_chains.read().clone()
+    }
+
     /// Returns a `Client` type if one is known for the `PeerId`.
     pub fn client(&self, peer_id: &PeerId) -> Client {
         self.peers
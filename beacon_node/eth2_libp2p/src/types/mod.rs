@@ -13,5 +13,5 @@ pub type Enr = discv5::enr::Enr<discv5::enr::CombinedKey>;
 
 pub use globals::NetworkGlobals;
 pub use pubsub::PubsubMessage;
-pub use sync_state::SyncState;
+pub use sync_state::{SyncChainInfo, SyncChainType, SyncState};
 pub use topics::{GossipEncoding, GossipKind, GossipTopic};
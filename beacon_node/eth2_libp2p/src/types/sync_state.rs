@@ -22,6 +22,42 @@ pub enum SyncState {
     Stalled,
 }
 
+/// The kind of chain being downloaded by range sync.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum SyncChainType {
+    /// A chain leading to a finalized checkpoint ahead of our own.
+    Finalized,
+    /// A chain leading to a peer's head, beyond our finalized checkpoint.
+    Head,
+}
+
+/// The download progress of a single range sync chain.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SyncChainInfo {
+    /// The identifier of the chain.
+    pub id: u64,
+    /// Whether this is a finalized or head chain.
+    pub chain_type: SyncChainType,
+    /// Whether the chain is currently being downloaded, rather than waiting on another chain.
+    pub syncing: bool,
+    /// The slot the chain started downloading from.
+    pub start_slot: Slot,
+    /// The target head slot of the chain.
+    pub target_head_slot: Slot,
+    /// The target head root of the chain.
+    pub target_head_root: Hash256,
+    /// All blocks prior to this slot have been processed.
+    pub processed_slot: Slot,
+    /// The number of batches processed since the chain started.
+    pub processed_batches: u64,
+    /// The number of batches awaiting a response from a peer.
+    pub pending_batches: usize,
+    /// The number of batches downloaded and awaiting processing.
+    pub completed_batches: usize,
+    /// The number of peers the chain is downloading from.
+    pub peers: usize,
+}
+
 impl PartialEq for SyncState {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
@@ -65,7 +65,11 @@ async fn test_gossipsub_forward() {
                             assert_eq!(message, pubsub_message.clone());
                             received_count += 1;
                             // Since `propagate_message` is false, need to propagate manually
-                            node.swarm.propagate_message(&source, id);
+                            node.swarm.report_message_validation_result(
+                                &source,
+                                id,
+                                MessageAcceptance::Accept,
+                            );
                             // Test should succeed if all nodes except the publisher receive the message
                             if received_count == num_nodes - 1 {
                                 debug!(log.clone(), "Received message at {} nodes", num_nodes - 1);
@@ -48,4 +48,29 @@ lazy_static! {
         "network_subnet_subscriptions_aggregator_total",
         "Count of validator subscription requests where the subscriber is an aggregator."
     );
+
+    /*
+     * Range sync
+     */
+    pub static ref SYNC_DOWNLOADED_BYTES: Result<IntCounter> = try_create_int_counter(
+        "network_sync_downloaded_bytes_total",
+        "Total SSZ bytes of blocks received in BlocksByRange responses whilst syncing"
+    );
+    pub static ref SYNC_BATCH_RESPONSE_LATENCY: Result<Histogram> = try_create_histogram(
+        "network_sync_batch_response_latency_seconds",
+        "Time between requesting a batch and receiving its first block"
+    );
+    pub static ref SYNC_BATCH_DOWNLOAD_TIMES: Result<Histogram> = try_create_histogram(
+        "network_sync_batch_download_seconds",
+        "Time between requesting a batch and receiving all of its blocks"
+    );
+    pub static ref SYNC_BATCH_FAILURES: Result<IntCounter> = try_create_int_counter(
+        "network_sync_batch_failures_total",
+        "Count of batch requests which errored or timed out"
+    );
+    pub static ref SYNC_BATCH_EPOCHS: Result<Histogram> = try_create_histogram_with_buckets(
+        "network_sync_batch_epochs",
+        "Number of epochs requested in each batch",
+        vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0]
+    );
 }
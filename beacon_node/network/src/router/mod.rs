@@ -6,13 +6,15 @@
 #![allow(clippy::unit_arg)]
 
 pub mod processor;
+pub(crate) mod reprocess_queue;
 
 use crate::error;
 use crate::service::NetworkMessage;
-use beacon_chain::{BeaconChain, BeaconChainTypes, BlockError};
+use beacon_chain::{BeaconChain, BeaconChainTypes};
 use eth2_libp2p::{
     rpc::{RPCError, RequestId},
-    MessageId, NetworkGlobals, PeerId, PeerRequestId, PubsubMessage, Request, Response,
+    MessageAcceptance, MessageId, NetworkGlobals, PeerId, PeerRequestId, PubsubMessage, Request,
+    Response,
 };
 use futures::prelude::*;
 use processor::Processor;
@@ -65,6 +67,10 @@ pub enum RouterMessage<T: EthSpec> {
     /// A gossip message has been received. The fields are: message id, the peer that sent us this
     /// message and the message itself.
     PubsubMessage(MessageId, PeerId, PubsubMessage<T>),
+    /// A gossip message that was queued for re-processing is ready to be processed again.
+    ReprocessPubsubMessage(MessageId, PeerId, PubsubMessage<T>),
+    /// A gossip message could not be queued for re-processing because the queue is full.
+    DroppedPubsubMessage(MessageId, PeerId),
     /// The peer manager has requested we re-status a peer.
     StatusPeer(PeerId),
 }
@@ -89,6 +95,7 @@ impl<T: BeaconChainTypes> Router<T> {
             beacon_chain,
             network_globals.clone(),
             network_send.clone(),
+            handler_send.clone(),
             &log,
         );
 
@@ -153,7 +160,13 @@ impl<T: BeaconChainTypes> Router<T> {
                 self.processor.on_rpc_error(peer_id, request_id);
             }
             RouterMessage::PubsubMessage(id, peer_id, gossip) => {
-                self.handle_gossip(id, peer_id, gossip);
+                self.handle_gossip(id, peer_id, gossip, true);
+            }
+            RouterMessage::ReprocessPubsubMessage(id, peer_id, gossip) => {
+                self.handle_gossip(id, peer_id, gossip, false);
+            }
+            RouterMessage::DroppedPubsubMessage(id, peer_id) => {
+                self.report_validation_result(id, peer_id, MessageAcceptance::Ignore);
             }
         }
     }
@@ -201,60 +214,94 @@ impl<T: BeaconChainTypes> Router<T> {
     }
 
     /// Handle RPC messages
+    ///
+    /// Messages which cannot be verified yet are queued for re-processing if `allow_reprocess` is
+    /// `true`.
     fn handle_gossip(
         &mut self,
         id: MessageId,
         peer_id: PeerId,
         gossip_message: PubsubMessage<T::EthSpec>,
+        allow_reprocess: bool,
     ) {
         match gossip_message {
             // Attestations should never reach the router.
             PubsubMessage::AggregateAndProofAttestation(aggregate_and_proof) => {
-                if let Some(gossip_verified) = self
-                    .processor
-                    .verify_aggregated_attestation_for_gossip(peer_id.clone(), *aggregate_and_proof)
-                {
-                    self.propagate_message(id, peer_id.clone());
-                    self.processor
-                        .import_aggregated_attestation(peer_id, gossip_verified);
+                match self.processor.verify_aggregated_attestation_for_gossip(
+                    peer_id.clone(),
+                    id.clone(),
+                    *aggregate_and_proof,
+                    allow_reprocess,
+                ) {
+                    Ok(gossip_verified) => {
+                        self.report_validation_result(
+                            id,
+                            peer_id.clone(),
+                            MessageAcceptance::Accept,
+                        );
+                        self.processor
+                            .import_aggregated_attestation(peer_id, gossip_verified);
+                    }
+                    Err(Some(acceptance)) => self.report_validation_result(id, peer_id, acceptance),
+                    // The aggregate has been queued for re-processing.
+                    Err(None) => {}
                 }
             }
             PubsubMessage::Attestation(subnet_attestation) => {
-                if let Some(gossip_verified) =
-                    self.processor.verify_unaggregated_attestation_for_gossip(
-                        peer_id.clone(),
-                        subnet_attestation.1.clone(),
-                        subnet_attestation.0,
-                    )
-                {
-                    self.propagate_message(id, peer_id.clone());
-                    self.processor
-                        .import_unaggregated_attestation(peer_id, gossip_verified);
+                let (subnet_id, attestation) = *subnet_attestation;
+                match self.processor.verify_unaggregated_attestation_for_gossip(
+                    peer_id.clone(),
+                    id.clone(),
+                    attestation,
+                    subnet_id,
+                    allow_reprocess,
+                ) {
+                    Ok(gossip_verified) => {
+                        self.report_validation_result(
+                            id,
+                            peer_id.clone(),
+                            MessageAcceptance::Accept,
+                        );
+                        self.processor
+                            .import_unaggregated_attestation(peer_id, gossip_verified);
+                    }
+                    Err(Some(acceptance)) => self.report_validation_result(id, peer_id, acceptance),
+                    // The attestation has been queued for re-processing.
+                    Err(None) => {}
                 }
             }
             PubsubMessage::BeaconBlock(block) => {
-                match self.processor.should_forward_block(&peer_id, block) {
+                match self.processor.should_forward_block(
+                    &peer_id,
+                    id.clone(),
+                    block,
+                    allow_reprocess,
+                ) {
                     Ok(verified_block) => {
                         info!(self.log, "New block received"; "slot" => verified_block.block.slot(), "hash" => verified_block.block_root.to_string());
-                        self.propagate_message(id, peer_id.clone());
+                        self.report_validation_result(
+                            id,
+                            peer_id.clone(),
+                            MessageAcceptance::Accept,
+                        );
                         self.processor.on_block_gossip(peer_id, verified_block);
                     }
-                    Err(BlockError::ParentUnknown { .. }) => {} // performing a parent lookup
-                    Err(e) => {
-                        // performing a parent lookup
-                        warn!(self.log, "Could not verify block for gossip";
-                            "error" => format!("{:?}", e));
-                    }
+                    Err(Some(acceptance)) => self.report_validation_result(id, peer_id, acceptance),
+                    // The block has been queued until its slot starts.
+                    Err(None) => {}
                 }
             }
             PubsubMessage::VoluntaryExit(exit) => {
                 debug!(self.log, "Received a voluntary exit"; "peer_id" => format!("{}", peer_id));
-                if let Some(verified_exit) = self
+                match self
                     .processor
                     .verify_voluntary_exit_for_gossip(&peer_id, *exit)
                 {
-                    self.propagate_message(id, peer_id.clone());
-                    self.processor.import_verified_voluntary_exit(verified_exit);
+                    Ok(verified_exit) => {
+                        self.report_validation_result(id, peer_id, MessageAcceptance::Accept);
+                        self.processor.import_verified_voluntary_exit(verified_exit);
+                    }
+                    Err(acceptance) => self.report_validation_result(id, peer_id, acceptance),
                 }
             }
             PubsubMessage::ProposerSlashing(proposer_slashing) => {
@@ -263,13 +310,16 @@ impl<T: BeaconChainTypes> Router<T> {
                     "Received a proposer slashing";
                     "peer_id" => format!("{}", peer_id)
                 );
-                if let Some(verified_proposer_slashing) = self
+                match self
                     .processor
                     .verify_proposer_slashing_for_gossip(&peer_id, *proposer_slashing)
                 {
-                    self.propagate_message(id, peer_id.clone());
-                    self.processor
-                        .import_verified_proposer_slashing(verified_proposer_slashing);
+                    Ok(verified_proposer_slashing) => {
+                        self.report_validation_result(id, peer_id, MessageAcceptance::Accept);
+                        self.processor
+                            .import_verified_proposer_slashing(verified_proposer_slashing);
+                    }
+                    Err(acceptance) => self.report_validation_result(id, peer_id, acceptance),
                 }
             }
             PubsubMessage::AttesterSlashing(attester_slashing) => {
@@ -278,29 +328,39 @@ impl<T: BeaconChainTypes> Router<T> {
                     "Received a attester slashing";
                     "peer_id" => format!("{}", peer_id)
                 );
-                if let Some(verified_attester_slashing) = self
+                match self
                     .processor
                     .verify_attester_slashing_for_gossip(&peer_id, *attester_slashing)
                 {
-                    self.propagate_message(id, peer_id.clone());
-                    self.processor
-                        .import_verified_attester_slashing(verified_attester_slashing);
+                    Ok(verified_attester_slashing) => {
+                        self.report_validation_result(id, peer_id, MessageAcceptance::Accept);
+                        self.processor
+                            .import_verified_attester_slashing(verified_attester_slashing);
+                    }
+                    Err(acceptance) => self.report_validation_result(id, peer_id, acceptance),
                 }
             }
         }
     }
 
-    /// Informs the network service that the message should be forwarded to other peers.
-    fn propagate_message(&mut self, message_id: MessageId, propagation_source: PeerId) {
+    /// Informs the network service of the validation result of a gossip message. Accepted
+    /// messages are forwarded to other peers.
+    fn report_validation_result(
+        &mut self,
+        message_id: MessageId,
+        propagation_source: PeerId,
+        validation_result: MessageAcceptance,
+    ) {
         self.network_send
-            .send(NetworkMessage::Propagate {
+            .send(NetworkMessage::Validate {
                 propagation_source,
                 message_id,
+                validation_result,
             })
             .unwrap_or_else(|_| {
                 warn!(
                     self.log,
-                    "Could not send propagation request to the network service"
+                    "Could not send validation result to the network service"
                 )
             });
     }
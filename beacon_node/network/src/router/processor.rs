@@ -1,3 +1,5 @@
+use super::reprocess_queue::{self, ReprocessQueueMessage};
+use super::RouterMessage;
 use crate::service::NetworkMessage;
 use crate::sync::{PeerSyncInfo, SyncMessage};
 use beacon_chain::{
@@ -10,9 +12,13 @@ use beacon_chain::{
     GossipVerifiedBlock,
 };
 use eth2_libp2p::rpc::*;
-use eth2_libp2p::{NetworkGlobals, PeerAction, PeerId, PeerRequestId, Request, Response};
+use eth2_libp2p::{
+    MessageAcceptance, MessageId, NetworkGlobals, PeerAction, PeerId, PeerRequestId, PubsubMessage,
+    Request, Response,
+};
 use itertools::process_results;
 use slog::{debug, error, o, trace, warn};
+use slot_clock::SlotClock;
 use ssz::Encode;
 use state_processing::SigVerifiedOp;
 use std::sync::Arc;
@@ -35,6 +41,8 @@ pub struct Processor<T: BeaconChainTypes> {
     chain: Arc<BeaconChain<T>>,
     /// A channel to the syncing thread.
     sync_send: mpsc::UnboundedSender<SyncMessage<T::EthSpec>>,
+    /// A channel to the queue of gossip messages awaiting re-processing.
+    reprocess_send: mpsc::UnboundedSender<ReprocessQueueMessage<T::EthSpec>>,
     /// A network context to return and handle RPC requests.
     network: HandlerNetworkContext<T::EthSpec>,
     /// The `RPCHandler` logger.
@@ -48,27 +56,46 @@ impl<T: BeaconChainTypes> Processor<T> {
         beacon_chain: Arc<BeaconChain<T>>,
         network_globals: Arc<NetworkGlobals<T::EthSpec>>,
         network_send: mpsc::UnboundedSender<NetworkMessage<T::EthSpec>>,
+        router_send: mpsc::UnboundedSender<RouterMessage<T::EthSpec>>,
         log: &slog::Logger,
     ) -> Self {
         let sync_logger = log.new(o!("service"=> "sync"));
 
+        // spawn the queue of gossip messages awaiting re-processing
+        let reprocess_send = reprocess_queue::spawn(
+            executor.clone(),
+            router_send,
+            log.new(o!("service" => "reprocess_queue")),
+        );
+
         // spawn the sync thread
         let sync_send = crate::sync::manager::spawn(
             executor,
             beacon_chain.clone(),
             network_globals,
             network_send.clone(),
+            reprocess_send.clone(),
             sync_logger,
         );
 
         Processor {
             chain: beacon_chain,
             sync_send,
+            reprocess_send,
             network: HandlerNetworkContext::new(network_send, log.clone()),
             log: log.clone(),
         }
     }
 
+    fn send_to_reprocess_queue(&mut self, message: ReprocessQueueMessage<T::EthSpec>) {
+        self.reprocess_send.send(message).unwrap_or_else(|_| {
+            warn!(
+                self.log,
+                "Could not send message to the reprocess queue";
+            )
+        });
+    }
+
     fn send_to_sync(&mut self, message: SyncMessage<T::EthSpec>) {
         self.sync_send.send(message).unwrap_or_else(|_| {
             warn!(
@@ -501,19 +528,61 @@ impl<T: BeaconChainTypes> Processor<T> {
 
     /// Template function to be called on a block to determine if the block should be propagated
     /// across the network.
+    ///
+    /// If the block is for a slot slightly in the future and `allow_reprocess` is `true`, the
+    /// block is queued until its slot starts and `Err(None)` is returned. Its validation result is
+    /// reported once it has been re-processed. Otherwise, errors are returned as the gossipsub
+    /// validation result.
     pub fn should_forward_block(
         &mut self,
         peer_id: &PeerId,
+        message_id: MessageId,
         block: Box<SignedBeaconBlock<T::EthSpec>>,
-    ) -> Result<GossipVerifiedBlock<T>, BlockError> {
-        let result = self.chain.verify_block_for_gossip(*block.clone());
+        allow_reprocess: bool,
+    ) -> Result<GossipVerifiedBlock<T>, Option<MessageAcceptance>> {
+        // TODO: Modify the return to avoid the block clone.
+        let error = match self.chain.verify_block_for_gossip(*block.clone()) {
+            Ok(verified_block) => return Ok(verified_block),
+            Err(e) => e,
+        };
 
-        if let Err(BlockError::ParentUnknown(_)) = result {
-            // if we don't know the parent, start a parent lookup
-            // TODO: Modify the return to avoid the block clone.
-            self.send_to_sync(SyncMessage::UnknownBlock(peer_id.clone(), block));
+        match error {
+            BlockError::ParentUnknown(_) => {
+                // if we don't know the parent, start a parent lookup
+                self.send_to_sync(SyncMessage::UnknownBlock(peer_id.clone(), block));
+                Err(Some(MessageAcceptance::Ignore))
+            }
+            BlockError::FutureSlot {
+                present_slot,
+                block_slot,
+            } if allow_reprocess && block_slot <= present_slot + FUTURE_SLOT_TOLERANCE => {
+                match self.chain.slot_clock.duration_to_slot(block_slot) {
+                    Some(delay) => {
+                        self.send_to_reprocess_queue(ReprocessQueueMessage::EarlyBlock {
+                            message_id,
+                            peer_id: peer_id.clone(),
+                            block,
+                            delay,
+                        });
+                        Err(None)
+                    }
+                    None => Err(Some(MessageAcceptance::Ignore)),
+                }
+            }
+            e => {
+                warn!(self.log, "Could not verify block for gossip";
+                    "error" => format!("{:?}", e));
+                match e {
+                    // These errors do not indicate a faulty peer.
+                    BlockError::FutureSlot { .. }
+                    | BlockError::WouldRevertFinalizedSlot { .. }
+                    | BlockError::BlockIsAlreadyKnown
+                    | BlockError::RepeatProposal { .. }
+                    | BlockError::BeaconChainError(_) => Err(Some(MessageAcceptance::Ignore)),
+                    _ => Err(Some(MessageAcceptance::Reject)),
+                }
+            }
         }
-        result
     }
 
     /// Process a gossip message declaring a new block.
@@ -528,13 +597,16 @@ impl<T: BeaconChainTypes> Processor<T> {
     ) -> bool {
         let block = Box::new(verified_block.block.clone());
         match self.chain.process_block(verified_block) {
-            Ok(_block_root) => {
+            Ok(block_root) => {
                 trace!(
                     self.log,
                     "Gossipsub block processed";
                     "peer_id" => peer_id.to_string()
                 );
 
+                // Re-process any attestations that were waiting for this block.
+                self.send_to_reprocess_queue(ReprocessQueueMessage::BlockImported(block_root));
+
                 // TODO: It would be better if we can run this _after_ we publish the block to
                 // reduce block propagation latency.
                 //
@@ -586,13 +658,15 @@ impl<T: BeaconChainTypes> Processor<T> {
 
     /// Handle an error whilst verifying an `Attestation` or `SignedAggregateAndProof` from the
     /// network.
+    ///
+    /// Returns whether the message should be ignored or rejected by gossipsub.
     pub fn handle_attestation_verification_failure(
         &mut self,
         peer_id: PeerId,
         beacon_block_root: Hash256,
         attestation_type: &str,
         error: AttnError,
-    ) {
+    ) -> MessageAcceptance {
         debug!(
             self.log,
             "Invalid attestation from network";
@@ -612,6 +686,7 @@ impl<T: BeaconChainTypes> Processor<T> {
                  *
                  * The peer has published an invalid consensus message, _only_ if we trust our own clock.
                  */
+                MessageAcceptance::Ignore
             }
             AttnError::InvalidSelectionProof { .. } | AttnError::InvalidSignature => {
                 /*
@@ -619,6 +694,7 @@ impl<T: BeaconChainTypes> Processor<T> {
                  *
                  * The peer has published an invalid consensus message.
                  */
+                MessageAcceptance::Reject
             }
             AttnError::EmptyAggregationBitfield => {
                 /*
@@ -631,6 +707,7 @@ impl<T: BeaconChainTypes> Processor<T> {
                  *
                  * https://github.com/ethereum/eth2.0-specs/pull/1732
                  */
+                MessageAcceptance::Ignore
             }
             AttnError::AggregatorPubkeyUnknown(_) => {
                 /*
@@ -646,6 +723,7 @@ impl<T: BeaconChainTypes> Processor<T> {
                  *
                  * The peer has published an invalid consensus message.
                  */
+                MessageAcceptance::Reject
             }
             AttnError::AggregatorNotInCommittee { .. } => {
                 /*
@@ -661,6 +739,7 @@ impl<T: BeaconChainTypes> Processor<T> {
                  *
                  * The peer has published an invalid consensus message.
                  */
+                MessageAcceptance::Reject
             }
             AttnError::AttestationAlreadyKnown { .. } => {
                 /*
@@ -669,6 +748,7 @@ impl<T: BeaconChainTypes> Processor<T> {
                  *
                  * The peer is not necessarily faulty.
                  */
+                MessageAcceptance::Ignore
             }
             AttnError::AggregatorAlreadyKnown(_) => {
                 /*
@@ -677,6 +757,7 @@ impl<T: BeaconChainTypes> Processor<T> {
                  *
                  * The peer is not necessarily faulty.
                  */
+                MessageAcceptance::Ignore
             }
             AttnError::PriorAttestationKnown { .. } => {
                 /*
@@ -684,6 +765,7 @@ impl<T: BeaconChainTypes> Processor<T> {
                  *
                  * The peer is not necessarily faulty.
                  */
+                MessageAcceptance::Ignore
             }
             AttnError::ValidatorIndexTooHigh(_) => {
                 /*
@@ -692,6 +774,7 @@ impl<T: BeaconChainTypes> Processor<T> {
                  *
                  * The peer has published an invalid consensus message.
                  */
+                MessageAcceptance::Reject
             }
             AttnError::UnknownHeadBlock { beacon_block_root } => {
                 // Note: its a little bit unclear as to whether or not this block is unknown or
@@ -708,6 +791,7 @@ impl<T: BeaconChainTypes> Processor<T> {
                 );
                 // we don't know the block, get the sync manager to handle the block lookup
                 self.send_to_sync(SyncMessage::UnknownBlockHash(peer_id, beacon_block_root));
+                MessageAcceptance::Ignore
             }
             AttnError::UnknownTargetRoot(_) => {
                 /*
@@ -726,6 +810,7 @@ impl<T: BeaconChainTypes> Processor<T> {
                  *
                  * The peer has published an invalid consensus message.
                  */
+                MessageAcceptance::Reject
             }
             AttnError::BadTargetEpoch => {
                 /*
@@ -734,6 +819,7 @@ impl<T: BeaconChainTypes> Processor<T> {
                  *
                  * The peer has published an invalid consensus message.
                  */
+                MessageAcceptance::Reject
             }
             AttnError::NoCommitteeForSlotAndIndex { .. } => {
                 /*
@@ -741,6 +827,7 @@ impl<T: BeaconChainTypes> Processor<T> {
                  *
                  * The peer has published an invalid consensus message.
                  */
+                MessageAcceptance::Reject
             }
             AttnError::NotExactlyOneAggregationBitSet(_) => {
                 /*
@@ -748,6 +835,7 @@ impl<T: BeaconChainTypes> Processor<T> {
                  *
                  * The peer has published an invalid consensus message.
                  */
+                MessageAcceptance::Reject
             }
             AttnError::AttestsToFutureBlock { .. } => {
                 /*
@@ -755,6 +843,7 @@ impl<T: BeaconChainTypes> Processor<T> {
                  *
                  * The peer has published an invalid consensus message.
                  */
+                MessageAcceptance::Reject
             }
 
             AttnError::InvalidSubnetId { received, expected } => {
@@ -766,7 +855,8 @@ impl<T: BeaconChainTypes> Processor<T> {
                     "Received attestation on incorrect subnet";
                     "expected" => format!("{:?}", expected),
                     "received" => format!("{:?}", received),
-                )
+                );
+                MessageAcceptance::Reject
             }
             AttnError::Invalid(_) => {
                 /*
@@ -774,6 +864,7 @@ impl<T: BeaconChainTypes> Processor<T> {
                  *
                  * The peer has published an invalid consensus message.
                  */
+                MessageAcceptance::Reject
             }
             AttnError::BeaconChainError(e) => {
                 /*
@@ -789,29 +880,45 @@ impl<T: BeaconChainTypes> Processor<T> {
                     "peer_id" => peer_id.to_string(),
                     "error" => format!("{:?}", e),
                 );
+                MessageAcceptance::Ignore
             }
         }
     }
 
+    /// Verify an aggregate before gossiping or processing it.
+    ///
+    /// If the aggregate references an unknown block and `allow_reprocess` is `true`, the aggregate
+    /// is queued until the block is imported and `Err(None)` is returned.
     pub fn verify_aggregated_attestation_for_gossip(
         &mut self,
         peer_id: PeerId,
+        message_id: MessageId,
         aggregate_and_proof: SignedAggregateAndProof<T::EthSpec>,
-    ) -> Option<VerifiedAggregatedAttestation<T>> {
+        allow_reprocess: bool,
+    ) -> Result<VerifiedAggregatedAttestation<T>, Option<MessageAcceptance>> {
         // This is provided to the error handling function to assist with debugging.
         let beacon_block_root = aggregate_and_proof.message.aggregate.data.beacon_block_root;
+        // Keep a copy of the aggregate in case it needs to be queued.
+        let queued = if allow_reprocess {
+            Some(PubsubMessage::AggregateAndProofAttestation(Box::new(
+                aggregate_and_proof.clone(),
+            )))
+        } else {
+            None
+        };
 
         self.chain
             .verify_aggregated_attestation_for_gossip(aggregate_and_proof)
             .map_err(|e| {
-                self.handle_attestation_verification_failure(
+                self.handle_gossip_attestation_failure(
                     peer_id,
+                    message_id,
                     beacon_block_root,
                     "aggregated",
                     e,
+                    queued,
                 )
             })
-            .ok()
     }
 
     pub fn import_aggregated_attestation(
@@ -839,26 +946,81 @@ impl<T: BeaconChainTypes> Processor<T> {
         }
     }
 
+    /// Verify an unaggregated attestation before gossiping or processing it.
+    ///
+    /// If the attestation references an unknown block and `allow_reprocess` is `true`, the
+    /// attestation is queued until the block is imported and `Err(None)` is returned.
     pub fn verify_unaggregated_attestation_for_gossip(
         &mut self,
         peer_id: PeerId,
+        message_id: MessageId,
         unaggregated_attestation: Attestation<T::EthSpec>,
         subnet_id: SubnetId,
-    ) -> Option<VerifiedUnaggregatedAttestation<T>> {
+        allow_reprocess: bool,
+    ) -> Result<VerifiedUnaggregatedAttestation<T>, Option<MessageAcceptance>> {
         // This is provided to the error handling function to assist with debugging.
         let beacon_block_root = unaggregated_attestation.data.beacon_block_root;
+        // Keep a copy of the attestation in case it needs to be queued.
+        let queued = if allow_reprocess {
+            Some(PubsubMessage::Attestation(Box::new((
+                subnet_id,
+                unaggregated_attestation.clone(),
+            ))))
+        } else {
+            None
+        };
 
         self.chain
             .verify_unaggregated_attestation_for_gossip(unaggregated_attestation, subnet_id)
             .map_err(|e| {
-                self.handle_attestation_verification_failure(
+                self.handle_gossip_attestation_failure(
                     peer_id,
+                    message_id,
                     beacon_block_root,
                     "unaggregated",
                     e,
+                    queued,
                 )
             })
-            .ok()
+    }
+
+    /// Handles an error whilst verifying a gossip attestation or aggregate.
+    ///
+    /// If the attestation references an unknown block and a copy of the message is provided in
+    /// `queued`, it is sent to the reprocess queue and `None` is returned. Otherwise, the
+    /// gossipsub validation result is returned.
+    fn handle_gossip_attestation_failure(
+        &mut self,
+        peer_id: PeerId,
+        message_id: MessageId,
+        beacon_block_root: Hash256,
+        attestation_type: &str,
+        error: AttnError,
+        queued: Option<PubsubMessage<T::EthSpec>>,
+    ) -> Option<MessageAcceptance> {
+        let unknown_block = match error {
+            AttnError::UnknownHeadBlock { beacon_block_root } => Some(beacon_block_root),
+            _ => None,
+        };
+        let acceptance = self.handle_attestation_verification_failure(
+            peer_id.clone(),
+            beacon_block_root,
+            attestation_type,
+            error,
+        );
+
+        match (unknown_block, queued) {
+            (Some(beacon_block_root), Some(attestation)) => {
+                self.send_to_reprocess_queue(ReprocessQueueMessage::UnknownBlockAttestation {
+                    message_id,
+                    peer_id,
+                    attestation,
+                    beacon_block_root,
+                });
+                None
+            }
+            _ => Some(acceptance),
+        }
     }
 
     pub fn import_unaggregated_attestation(
@@ -928,16 +1090,16 @@ impl<T: BeaconChainTypes> Processor<T> {
 
     /// Verify a voluntary exit before gossiping or processing it.
     ///
-    /// Errors are logged at debug level.
+    /// Errors are logged at debug level and returned as the gossipsub validation result.
     pub fn verify_voluntary_exit_for_gossip(
         &self,
         peer_id: &PeerId,
         voluntary_exit: SignedVoluntaryExit,
-    ) -> Option<SigVerifiedOp<SignedVoluntaryExit>> {
+    ) -> Result<SigVerifiedOp<SignedVoluntaryExit>, MessageAcceptance> {
         let validator_index = voluntary_exit.message.validator_index;
 
         match self.chain.verify_voluntary_exit_for_gossip(voluntary_exit) {
-            Ok(ObservationOutcome::New(sig_verified_exit)) => Some(sig_verified_exit),
+            Ok(ObservationOutcome::New(sig_verified_exit)) => Ok(sig_verified_exit),
             Ok(ObservationOutcome::AlreadyKnown) => {
                 debug!(
                     self.log,
@@ -945,7 +1107,7 @@ impl<T: BeaconChainTypes> Processor<T> {
                     "validator_index" => validator_index,
                     "peer" => peer_id.to_string()
                 );
-                None
+                Err(MessageAcceptance::Ignore)
             }
             Err(e) => {
                 debug!(
@@ -955,7 +1117,7 @@ impl<T: BeaconChainTypes> Processor<T> {
                     "peer" => peer_id.to_string(),
                     "error" => format!("{:?}", e)
                 );
-                None
+                Err(MessageAcceptance::Reject)
             }
         }
     }
@@ -971,19 +1133,19 @@ impl<T: BeaconChainTypes> Processor<T> {
 
     /// Verify a proposer slashing before gossiping or processing it.
     ///
-    /// Errors are logged at debug level.
+    /// Errors are logged at debug level and returned as the gossipsub validation result.
     pub fn verify_proposer_slashing_for_gossip(
         &self,
         peer_id: &PeerId,
         proposer_slashing: ProposerSlashing,
-    ) -> Option<SigVerifiedOp<ProposerSlashing>> {
+    ) -> Result<SigVerifiedOp<ProposerSlashing>, MessageAcceptance> {
         let validator_index = proposer_slashing.signed_header_1.message.proposer_index;
 
         match self
             .chain
             .verify_proposer_slashing_for_gossip(proposer_slashing)
         {
-            Ok(ObservationOutcome::New(verified_slashing)) => Some(verified_slashing),
+            Ok(ObservationOutcome::New(verified_slashing)) => Ok(verified_slashing),
             Ok(ObservationOutcome::AlreadyKnown) => {
                 debug!(
                     self.log,
@@ -992,7 +1154,7 @@ impl<T: BeaconChainTypes> Processor<T> {
                     "validator_index" => validator_index,
                     "peer" => peer_id.to_string()
                 );
-                None
+                Err(MessageAcceptance::Ignore)
             }
             Err(e) => {
                 debug!(
@@ -1002,7 +1164,7 @@ impl<T: BeaconChainTypes> Processor<T> {
                     "peer" => peer_id.to_string(),
                     "error" => format!("{:?}", e)
                 );
-                None
+                Err(MessageAcceptance::Reject)
             }
         }
     }
@@ -1018,17 +1180,17 @@ impl<T: BeaconChainTypes> Processor<T> {
 
     /// Verify an attester slashing before gossiping or processing it.
     ///
-    /// Errors are logged at debug level.
+    /// Errors are logged at debug level and returned as the gossipsub validation result.
     pub fn verify_attester_slashing_for_gossip(
         &self,
         peer_id: &PeerId,
         attester_slashing: AttesterSlashing<T::EthSpec>,
-    ) -> Option<SigVerifiedOp<AttesterSlashing<T::EthSpec>>> {
+    ) -> Result<SigVerifiedOp<AttesterSlashing<T::EthSpec>>, MessageAcceptance> {
         match self
             .chain
             .verify_attester_slashing_for_gossip(attester_slashing)
         {
-            Ok(ObservationOutcome::New(verified_slashing)) => Some(verified_slashing),
+            Ok(ObservationOutcome::New(verified_slashing)) => Ok(verified_slashing),
             Ok(ObservationOutcome::AlreadyKnown) => {
                 debug!(
                     self.log,
@@ -1036,7 +1198,7 @@ impl<T: BeaconChainTypes> Processor<T> {
                     "reason" => "Slashings already known for all slashed validators",
                     "peer" => peer_id.to_string()
                 );
-                None
+                Err(MessageAcceptance::Ignore)
             }
             Err(e) => {
                 debug!(
@@ -1045,7 +1207,7 @@ impl<T: BeaconChainTypes> Processor<T> {
                     "peer" => peer_id.to_string(),
                     "error" => format!("{:?}", e)
                 );
-                None
+                Err(MessageAcceptance::Reject)
             }
         }
     }
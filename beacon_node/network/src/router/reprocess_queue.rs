@@ -0,0 +1,414 @@
+//! Provides a queue for gossip messages which cannot be processed when they arrive, but which are
+//! likely to become valid shortly afterwards:
+//!
+//! - Blocks from a slot slightly in the future are held until their slot starts.
+//! - Attestations and aggregates that reference an unknown block are held until the block is
+//!   imported, or until `QUEUED_ATTESTATION_DELAY` elapses.
+//!
+//! Once ready, messages are sent back to the `Router` to be verified again. A message is only
+//! queued once; if it still cannot be processed on the second attempt it is dropped. Messages
+//! which arrive whilst the queue is full are sent back to the `Router` to be ignored.
+//!
+//! The queue runs in its own task and uses `HashSetDelay`s to track the deadlines of the queued
+//! messages.
+use super::RouterMessage;
+use eth2_libp2p::{MessageId, PeerId, PubsubMessage};
+use futures::prelude::*;
+use hashset_delay::HashSetDelay;
+use slog::{debug, trace, warn};
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use types::{EthSpec, Hash256, SignedBeaconBlock};
+
+/// The maximum time an attestation waits for the block it references to be imported.
+const QUEUED_ATTESTATION_DELAY: Duration = Duration::from_secs(12);
+
+/// The maximum number of blocks that can be queued at any one time.
+const MAXIMUM_QUEUED_BLOCKS: usize = 16;
+
+/// The maximum number of attestations that can be queued at any one time.
+const MAXIMUM_QUEUED_ATTESTATIONS: usize = 1_024;
+
+/// Messages that the reprocess queue can receive.
+#[derive(Debug)]
+pub enum ReprocessQueueMessage<T: EthSpec> {
+    /// A gossip block for a future slot. It is re-processed once `delay` has elapsed.
+    EarlyBlock {
+        message_id: MessageId,
+        peer_id: PeerId,
+        block: Box<SignedBeaconBlock<T>>,
+        delay: Duration,
+    },
+    /// A gossip attestation or aggregate which references the unknown block `beacon_block_root`.
+    UnknownBlockAttestation {
+        message_id: MessageId,
+        peer_id: PeerId,
+        attestation: PubsubMessage<T>,
+        beacon_block_root: Hash256,
+    },
+    /// A block has been imported. Any attestations waiting for it are re-processed.
+    BlockImported(Hash256),
+}
+
+/// A gossip message waiting in the queue.
+struct QueuedMessage<T: EthSpec> {
+    message_id: MessageId,
+    peer_id: PeerId,
+    message: PubsubMessage<T>,
+}
+
+/// A queued attestation along with the root of the block it is waiting for.
+struct QueuedAttestation<T: EthSpec> {
+    queued: QueuedMessage<T>,
+    beacon_block_root: Hash256,
+}
+
+pub struct ReprocessQueue<T: EthSpec> {
+    /// Receives messages to queue.
+    input_channel: mpsc::UnboundedReceiver<ReprocessQueueMessage<T>>,
+    /// Sends messages that are ready to be re-processed back to the router.
+    router_send: mpsc::UnboundedSender<RouterMessage<T>>,
+    /// The deadlines of the queued blocks, by block root.
+    block_delays: HashSetDelay<Hash256>,
+    /// The queued blocks, by block root.
+    queued_blocks: HashMap<Hash256, QueuedMessage<T>>,
+    /// The deadlines of the queued attestations.
+    attestation_delays: HashSetDelay<MessageId>,
+    /// The queued attestations.
+    queued_attestations: HashMap<MessageId, QueuedAttestation<T>>,
+    /// The queued attestations, by the root of the block they are waiting for.
+    awaiting_block: HashMap<Hash256, Vec<MessageId>>,
+    /// The logger for the reprocess queue.
+    log: slog::Logger,
+}
+
+/// Spawns a new `ReprocessQueue` task, returning the channel to queue messages on.
+pub fn spawn<T: EthSpec>(
+    executor: environment::TaskExecutor,
+    router_send: mpsc::UnboundedSender<RouterMessage<T>>,
+    log: slog::Logger,
+) -> mpsc::UnboundedSender<ReprocessQueueMessage<T>> {
+    let (reprocess_send, reprocess_recv) = mpsc::unbounded_channel();
+
+    let queue = ReprocessQueue::new(reprocess_recv, router_send, log);
+    executor.spawn(queue.main(), "reprocess_queue");
+
+    reprocess_send
+}
+
+impl<T: EthSpec> ReprocessQueue<T> {
+    fn new(
+        input_channel: mpsc::UnboundedReceiver<ReprocessQueueMessage<T>>,
+        router_send: mpsc::UnboundedSender<RouterMessage<T>>,
+        log: slog::Logger,
+    ) -> Self {
+        ReprocessQueue {
+            input_channel,
+            router_send,
+            block_delays: HashSetDelay::default(),
+            queued_blocks: HashMap::new(),
+            attestation_delays: HashSetDelay::new(QUEUED_ATTESTATION_DELAY),
+            queued_attestations: HashMap::new(),
+            awaiting_block: HashMap::new(),
+            log,
+        }
+    }
+
+    /// The main driving future for the reprocess queue.
+    async fn main(mut self) {
+        loop {
+            tokio::select! {
+                message = self.input_channel.recv() => match message {
+                    Some(message) => self.handle_message(message),
+                    None => {
+                        debug!(self.log, "Reprocess queue shutting down");
+                        return;
+                    }
+                },
+                // The delay queues yield `None` while they are empty, which disables their
+                // branches until the next loop iteration.
+                Some(Ok(block_root)) = self.block_delays.next() => {
+                    self.release_block(&block_root);
+                }
+                Some(Ok(message_id)) = self.attestation_delays.next() => {
+                    trace!(self.log, "Queued attestation timed out"; "message_id" => message_id.to_string());
+                    self.release_attestation(&message_id);
+                }
+            }
+        }
+    }
+
+    fn handle_message(&mut self, message: ReprocessQueueMessage<T>) {
+        match message {
+            ReprocessQueueMessage::EarlyBlock {
+                message_id,
+                peer_id,
+                block,
+                delay,
+            } => {
+                let block_root = block.canonical_root();
+                if self.queued_blocks.contains_key(&block_root) {
+                    return;
+                }
+                if self.queued_blocks.len() >= MAXIMUM_QUEUED_BLOCKS {
+                    warn!(self.log, "Early block queue is full"; "block_root" => block_root.to_string());
+                    self.send_dropped_to_router(message_id, peer_id);
+                    return;
+                }
+
+                debug!(
+                    self.log,
+                    "Queued early block";
+                    "block_root" => block_root.to_string(),
+                    "slot" => block.slot(),
+                    "delay_ms" => delay.as_millis()
+                );
+                self.block_delays.insert_at(block_root, delay);
+                self.queued_blocks.insert(
+                    block_root,
+                    QueuedMessage {
+                        message_id,
+                        peer_id,
+                        message: PubsubMessage::BeaconBlock(block),
+                    },
+                );
+            }
+            ReprocessQueueMessage::UnknownBlockAttestation {
+                message_id,
+                peer_id,
+                attestation,
+                beacon_block_root,
+            } => {
+                if self.queued_attestations.contains_key(&message_id) {
+                    return;
+                }
+                if self.queued_attestations.len() >= MAXIMUM_QUEUED_ATTESTATIONS {
+                    debug!(self.log, "Attestation queue is full"; "block_root" => beacon_block_root.to_string());
+                    self.send_dropped_to_router(message_id, peer_id);
+                    return;
+                }
+
+                self.attestation_delays.insert(message_id.clone());
+                self.awaiting_block
+                    .entry(beacon_block_root)
+                    .or_default()
+                    .push(message_id.clone());
+                self.queued_attestations.insert(
+                    message_id.clone(),
+                    QueuedAttestation {
+                        queued: QueuedMessage {
+                            message_id,
+                            peer_id,
+                            message: attestation,
+                        },
+                        beacon_block_root,
+                    },
+                );
+            }
+            ReprocessQueueMessage::BlockImported(block_root) => {
+                if let Some(message_ids) = self.awaiting_block.remove(&block_root) {
+                    debug!(
+                        self.log,
+                        "Releasing attestations for imported block";
+                        "block_root" => block_root.to_string(),
+                        "count" => message_ids.len()
+                    );
+                    for message_id in message_ids {
+                        self.attestation_delays.remove(&message_id);
+                        self.release_attestation(&message_id);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Removes a block from the queue and sends it to the router.
+    fn release_block(&mut self, block_root: &Hash256) {
+        if let Some(queued) = self.queued_blocks.remove(block_root) {
+            self.send_to_router(queued);
+        }
+    }
+
+    /// Removes an attestation from the queue and sends it to the router.
+    fn release_attestation(&mut self, message_id: &MessageId) {
+        if let Some(QueuedAttestation {
+            queued,
+            beacon_block_root,
+        }) = self.queued_attestations.remove(message_id)
+        {
+            if let Some(message_ids) = self.awaiting_block.get_mut(&beacon_block_root) {
+                message_ids.retain(|id| id != message_id);
+                if message_ids.is_empty() {
+                    self.awaiting_block.remove(&beacon_block_root);
+                }
+            }
+            self.send_to_router(queued);
+        }
+    }
+
+    fn send_to_router(&mut self, queued: QueuedMessage<T>) {
+        self.router_send
+            .send(RouterMessage::ReprocessPubsubMessage(
+                queued.message_id,
+                queued.peer_id,
+                queued.message,
+            ))
+            .unwrap_or_else(|_| {
+                debug!(
+                    self.log,
+                    "Could not send queued message to the router";
+                )
+            });
+    }
+
+    /// Informs the router that a message could not be queued, so that its validation result can
+    /// be reported.
+    fn send_dropped_to_router(&mut self, message_id: MessageId, peer_id: PeerId) {
+        self.router_send
+            .send(RouterMessage::DroppedPubsubMessage(message_id, peer_id))
+            .unwrap_or_else(|_| {
+                debug!(
+                    self.log,
+                    "Could not send dropped message to the router";
+                )
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sloggers::{null::NullLoggerBuilder, Build};
+    use types::{
+        AggregateSignature, Attestation, AttestationData, BeaconBlock, BitList, EthSpec,
+        MinimalEthSpec, Signature, SubnetId,
+    };
+
+    type E = MinimalEthSpec;
+
+    fn queue() -> (
+        mpsc::UnboundedSender<ReprocessQueueMessage<E>>,
+        mpsc::UnboundedReceiver<RouterMessage<E>>,
+    ) {
+        let (reprocess_send, reprocess_recv) = mpsc::unbounded_channel();
+        let (router_send, router_recv) = mpsc::unbounded_channel();
+        let log = NullLoggerBuilder.build().expect("logger should build");
+        tokio::spawn(ReprocessQueue::new(reprocess_recv, router_send, log).main());
+        (reprocess_send, router_recv)
+    }
+
+    fn early_block(slot: u64, delay: Duration) -> ReprocessQueueMessage<E> {
+        let mut block = BeaconBlock::empty(&E::default_spec());
+        block.slot = slot.into();
+        ReprocessQueueMessage::EarlyBlock {
+            message_id: MessageId(format!("block-{}", slot)),
+            peer_id: PeerId::random(),
+            block: Box::new(SignedBeaconBlock {
+                message: block,
+                signature: Signature::empty_signature(),
+            }),
+            delay,
+        }
+    }
+
+    fn unknown_block_attestation(id: &str, beacon_block_root: Hash256) -> ReprocessQueueMessage<E> {
+        let attestation = Attestation {
+            aggregation_bits: BitList::with_capacity(1).expect("should create bitlist"),
+            data: AttestationData {
+                beacon_block_root,
+                ..AttestationData::default()
+            },
+            signature: AggregateSignature::new(),
+        };
+        ReprocessQueueMessage::UnknownBlockAttestation {
+            message_id: MessageId(id.to_string()),
+            peer_id: PeerId::random(),
+            attestation: PubsubMessage::Attestation(Box::new((SubnetId::new(0), attestation))),
+            beacon_block_root,
+        }
+    }
+
+    fn released_id(message: RouterMessage<E>) -> String {
+        match message {
+            RouterMessage::ReprocessPubsubMessage(message_id, _, _) => message_id.0,
+            other => panic!("unexpected router message: {:?}", other),
+        }
+    }
+
+    fn dropped_id(message: RouterMessage<E>) -> String {
+        match message {
+            RouterMessage::DroppedPubsubMessage(message_id, _) => message_id.0,
+            other => panic!("unexpected router message: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn early_blocks_are_released_in_slot_order() {
+        let (reprocess_send, mut router_recv) = queue();
+
+        reprocess_send
+            .send(early_block(2, Duration::from_millis(200)))
+            .unwrap();
+        reprocess_send
+            .send(early_block(1, Duration::from_millis(50)))
+            .unwrap();
+
+        let first = router_recv.recv().await.expect("should release block");
+        let second = router_recv.recv().await.expect("should release block");
+        assert_eq!(released_id(first), "block-1");
+        assert_eq!(released_id(second), "block-2");
+    }
+
+    #[tokio::test]
+    async fn attestations_are_released_on_block_import() {
+        let (reprocess_send, mut router_recv) = queue();
+        let awaited_root = Hash256::repeat_byte(1);
+
+        reprocess_send
+            .send(unknown_block_attestation("awaited", awaited_root))
+            .unwrap();
+        reprocess_send
+            .send(unknown_block_attestation("other", Hash256::repeat_byte(2)))
+            .unwrap();
+        reprocess_send
+            .send(ReprocessQueueMessage::BlockImported(awaited_root))
+            .unwrap();
+
+        let released = tokio::time::timeout(Duration::from_secs(1), router_recv.recv())
+            .await
+            .expect("should release before the attestation delay")
+            .expect("channel should be open");
+        assert_eq!(released_id(released), "awaited");
+
+        // The other attestation is still waiting for its block.
+        assert!(
+            tokio::time::timeout(Duration::from_millis(100), router_recv.recv())
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn attestations_are_dropped_when_the_queue_is_full() {
+        let (reprocess_send, mut router_recv) = queue();
+        let awaited_root = Hash256::repeat_byte(1);
+
+        for i in 0..MAXIMUM_QUEUED_ATTESTATIONS {
+            reprocess_send
+                .send(unknown_block_attestation(&i.to_string(), awaited_root))
+                .unwrap();
+        }
+        reprocess_send
+            .send(unknown_block_attestation("overflow", awaited_root))
+            .unwrap();
+
+        // The attestation which does not fit is returned straight away, so that it can be
+        // ignored.
+        let dropped = tokio::time::timeout(Duration::from_secs(1), router_recv.recv())
+            .await
+            .expect("should drop before the attestation delay")
+            .expect("channel should be open");
+        assert_eq!(dropped_id(dropped), "overflow");
+    }
+}
@@ -9,9 +9,9 @@ use beacon_chain::{BeaconChain, BeaconChainTypes};
 use eth2_libp2p::Service as LibP2PService;
 use eth2_libp2p::{
     rpc::{GoodbyeReason, RPCResponseErrorCode, RequestId},
-    Libp2pEvent, PeerAction, PeerRequestId, PubsubMessage, Request, Response,
+    Libp2pEvent, MessageAcceptance, PeerAction, PeerRequestId, PubsubMessage, Request, Response,
 };
-use eth2_libp2p::{BehaviourEvent, MessageId, NetworkGlobals, PeerId};
+use eth2_libp2p::{BehaviourEvent, Enr, MessageId, Multiaddr, NetworkGlobals, PeerId};
 use futures::prelude::*;
 use rest_types::ValidatorSubscription;
 use slog::{debug, error, info, o, trace, warn};
@@ -54,10 +54,12 @@ pub enum NetworkMessage<T: EthSpec> {
     },
     /// Publish a list of messages to the gossipsub protocol.
     Publish { messages: Vec<PubsubMessage<T>> },
-    /// Propagate a received gossipsub message.
-    Propagate {
+    /// Report the validation result of a received gossipsub message. Accepted messages are
+    /// propagated.
+    Validate {
         propagation_source: PeerId,
         message_id: MessageId,
+        validation_result: MessageAcceptance,
     },
     /// Reports a peer to the peer manager for performing an action.
     ReportPeer { peer_id: PeerId, action: PeerAction },
@@ -66,6 +68,21 @@ pub enum NetworkMessage<T: EthSpec> {
         peer_id: PeerId,
         reason: GoodbyeReason,
     },
+    /// Dial a peer at the given multiaddr.
+    DialMultiaddr { multiaddr: Multiaddr },
+    /// Add an ENR to the routing table and dial the peer it describes.
+    DialEnr { enr: Enr },
+    /// Disconnect a peer without banning it, providing a reason.
+    DisconnectPeer {
+        peer_id: PeerId,
+        reason: GoodbyeReason,
+    },
+    /// Ban a peer until it is unbanned, disconnecting it if required.
+    BanPeer { peer_id: PeerId },
+    /// Remove the ban on a peer.
+    UnbanPeer { peer_id: PeerId },
+    /// Start a discovery query for new peers.
+    DiscoverPeers,
 }
 
 /// Service that handles communication between internal services and the `eth2_libp2p` network service.
@@ -212,18 +229,24 @@ fn spawn_service<T: BeaconChainTypes>(
                         NetworkMessage::SendError{ peer_id, error, id, reason } => {
                             service.libp2p.respond_with_error(peer_id, id, error, reason);
                         }
-                        NetworkMessage::Propagate {
+                        NetworkMessage::Validate {
                             propagation_source,
                             message_id,
+                            validation_result,
                         } => {
-                                trace!(service.log, "Propagating gossipsub message";
+                                trace!(service.log, "Validated gossipsub message";
                                     "propagation_peer" => format!("{:?}", propagation_source),
                                     "message_id" => message_id.to_string(),
+                                    "validation_result" => format!("{:?}", validation_result),
                                 );
                                 service
                                     .libp2p
                                     .swarm
-                                    .propagate_message(&propagation_source, message_id);
+                                    .report_message_validation_result(
+                                        &propagation_source,
+                                        message_id,
+                                        validation_result,
+                                    );
                         }
                         NetworkMessage::Publish { messages } => {
                                 let mut topic_kinds = Vec::new();
@@ -243,6 +266,12 @@ fn spawn_service<T: BeaconChainTypes>(
                         }
                         NetworkMessage::ReportPeer { peer_id, action } => service.libp2p.report_peer(&peer_id, action),
                         NetworkMessage::GoodbyePeer { peer_id, reason } => service.libp2p.goodbye_peer(&peer_id, reason),
+                        NetworkMessage::DialMultiaddr { multiaddr } => service.libp2p.dial_multiaddr(multiaddr),
+                        NetworkMessage::DialEnr { enr } => service.libp2p.swarm.dial_enr(enr),
+                        NetworkMessage::DisconnectPeer { peer_id, reason } => service.libp2p.swarm.disconnect_peer(&peer_id, reason),
+                        NetworkMessage::BanPeer { peer_id } => service.libp2p.swarm.ban_peer(&peer_id),
+                        NetworkMessage::UnbanPeer { peer_id } => service.libp2p.swarm.unban_peer(&peer_id),
+                        NetworkMessage::DiscoverPeers => service.libp2p.swarm.discover_peers(),
                         NetworkMessage::Subscribe { subscriptions } => {
                             if let Err(e) = service
                                 .attestation_service
@@ -357,7 +386,12 @@ fn spawn_service<T: BeaconChainTypes>(
                                                     debug!(service.log, "Failed to send pubsub message to router");
                                                 });
                                         } else {
-                                            metrics::inc_counter(&metrics::GOSSIP_UNAGGREGATED_ATTESTATIONS_IGNORED)
+                                            metrics::inc_counter(&metrics::GOSSIP_UNAGGREGATED_ATTESTATIONS_IGNORED);
+                                            service.libp2p.swarm.report_message_validation_result(
+                                                &source,
+                                                id,
+                                                MessageAcceptance::Ignore,
+                                            );
                                         }
                                     }
                                     _ => {
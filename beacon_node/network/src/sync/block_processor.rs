@@ -54,7 +54,8 @@ pub fn spawn_block_processor<T: BeaconChainTypes>(
                 };
 
                 debug!(log, "Processing batch"; "id" => *batch_id, "blocks" => downloaded_blocks.len(),  "start_slot" => start_slot, "end_slot" => end_slot);
-                let result = match process_blocks(chain, downloaded_blocks.iter(), &log) {
+                let result = match process_blocks(chain, downloaded_blocks.iter(), &sync_send, &log)
+                {
                     (_, Ok(_)) => {
                         debug!(log, "Batch processed"; "id" => *batch_id , "start_slot" => start_slot, "end_slot" => end_slot);
                         BatchProcessResult::Success
@@ -92,7 +93,7 @@ pub fn spawn_block_processor<T: BeaconChainTypes>(
                 );
                 // parent blocks are ordered from highest slot to lowest, so we need to process in
                 // reverse
-                match process_blocks(chain, downloaded_blocks.iter().rev(), &log) {
+                match process_blocks(chain, downloaded_blocks.iter().rev(), &sync_send, &log) {
                     (_, Err(e)) => {
                         warn!(log, "Parent lookup failed"; "last_peer_id" => format!("{}", peer_id), "error" => e);
                         sync_send
@@ -115,6 +116,9 @@ pub fn spawn_block_processor<T: BeaconChainTypes>(
 }
 
 /// Helper function to process blocks batches which only consumes the chain and blocks to process.
+///
+/// The roots of any imported blocks are sent to the sync manager, so that gossip messages which
+/// were waiting for them can be re-processed.
 fn process_blocks<
     'a,
     T: BeaconChainTypes,
@@ -122,10 +126,15 @@ fn process_blocks<
 >(
     chain: Weak<BeaconChain<T>>,
     downloaded_blocks: I,
+    sync_send: &mpsc::UnboundedSender<SyncMessage<T::EthSpec>>,
     log: &slog::Logger,
 ) -> (usize, Result<(), String>) {
     if let Some(chain) = chain.upgrade() {
         let blocks = downloaded_blocks.cloned().collect::<Vec<_>>();
+        let block_roots = blocks
+            .iter()
+            .map(|block| block.canonical_root())
+            .collect::<Vec<_>>();
         let (imported_blocks, r) = match chain.process_chain_segment(blocks) {
             ChainSegmentResult::Successful { imported_blocks } => {
                 if imported_blocks == 0 {
@@ -153,6 +162,24 @@ fn process_blocks<
             }
         };
 
+        if imported_blocks > 0 {
+            let imported_roots = {
+                let fork_choice = chain.fork_choice.read();
+                block_roots
+                    .into_iter()
+                    .filter(|root| fork_choice.contains_block(root))
+                    .collect()
+            };
+            sync_send
+                .send(SyncMessage::BlocksImported(imported_roots))
+                .unwrap_or_else(|_| {
+                    debug!(
+                        log,
+                        "Block processor could not inform imported blocks. Likely shutting down."
+                    );
+                });
+        }
+
         return (imported_blocks, r);
     }
 
@@ -36,8 +36,9 @@
 use super::block_processor::{spawn_block_processor, BatchProcessResult, ProcessId};
 use super::network_context::SyncNetworkContext;
 use super::peer_sync_info::{PeerSyncInfo, PeerSyncType};
-use super::range_sync::{BatchId, ChainId, RangeSync, EPOCHS_PER_BATCH};
+use super::range_sync::{BatchId, ChainId, RangeSync, MAX_EPOCHS_PER_BATCH};
 use super::RequestId;
+use crate::router::reprocess_queue::ReprocessQueueMessage;
 use crate::service::NetworkMessage;
 use beacon_chain::{BeaconChain, BeaconChainTypes, BlockError};
 use eth2_libp2p::rpc::{methods::MAX_REQUEST_BLOCKS, BlocksByRootRequest, GoodbyeReason};
@@ -107,6 +108,9 @@ pub enum SyncMessage<T: EthSpec> {
 
     /// A parent lookup has failed for a block given by this `peer_id`.
     ParentLookupFailed(PeerId),
+
+    /// Blocks with the given roots have been imported by the block processor thread.
+    BlocksImported(Vec<Hash256>),
 }
 
 /// Maintains a sequential list of parents to lookup and the lookup's current state.
@@ -160,6 +164,10 @@ pub struct SyncManager<T: BeaconChainTypes> {
 
     /// The sending part of input_channel
     sync_send: mpsc::UnboundedSender<SyncMessage<T::EthSpec>>,
+
+    /// A channel to the queue of gossip messages awaiting re-processing, which is notified of
+    /// imported blocks.
+    reprocess_send: mpsc::UnboundedSender<ReprocessQueueMessage<T::EthSpec>>,
 }
 
 /// Object representing a single block lookup request.
@@ -187,10 +195,11 @@ pub fn spawn<T: BeaconChainTypes>(
     beacon_chain: Arc<BeaconChain<T>>,
     network_globals: Arc<NetworkGlobals<T::EthSpec>>,
     network_send: mpsc::UnboundedSender<NetworkMessage<T::EthSpec>>,
+    reprocess_send: mpsc::UnboundedSender<ReprocessQueueMessage<T::EthSpec>>,
     log: slog::Logger,
 ) -> mpsc::UnboundedSender<SyncMessage<T::EthSpec>> {
     assert!(
-        MAX_REQUEST_BLOCKS >= T::EthSpec::slots_per_epoch() * EPOCHS_PER_BATCH,
+        MAX_REQUEST_BLOCKS >= T::EthSpec::slots_per_epoch() * MAX_EPOCHS_PER_BATCH,
         "Max blocks that can be requested in a single batch greater than max allowed blocks in a single request"
     );
     // generate the message channel
@@ -212,6 +221,7 @@ pub fn spawn<T: BeaconChainTypes>(
         single_block_lookups: FnvHashMap::default(),
         log: log.clone(),
         sync_send: sync_send.clone(),
+        reprocess_send,
     };
 
     // spawn the sync manager thread
@@ -221,6 +231,19 @@ pub fn spawn<T: BeaconChainTypes>(
 }
 
 impl<T: BeaconChainTypes> SyncManager<T> {
+    /// Informs the reprocess queue that a block has been imported, releasing any attestations
+    /// which were waiting for it.
+    fn notify_block_imported(&mut self, block_root: Hash256) {
+        self.reprocess_send
+            .send(ReprocessQueueMessage::BlockImported(block_root))
+            .unwrap_or_else(|_| {
+                debug!(
+                    self.log,
+                    "Could not send imported block to the reprocess queue";
+                )
+            });
+    }
+
     /* Input Handling Functions */
 
     /// A peer has connected which has blocks that are unknown to us.
@@ -403,6 +426,7 @@ impl<T: BeaconChainTypes> SyncManager<T> {
         match self.chain.process_block(block.clone()) {
             Ok(block_root) => {
                 info!(self.log, "Processed block"; "block" => format!("{}", block_root));
+                self.notify_block_imported(block_root);
 
                 match self.chain.fork_choice() {
                     Ok(()) => trace!(
@@ -661,6 +685,7 @@ impl<T: BeaconChainTypes> SyncManager<T> {
                     return;
                 }
                 Ok(_) | Err(BlockError::BlockIsAlreadyKnown { .. }) => {
+                    self.notify_block_imported(newest_block.canonical_root());
                     spawn_block_processor(
                         Arc::downgrade(&self.chain),
                         ProcessId::ParentLookup(parent_request.last_submitted_peer.clone()),
@@ -799,6 +824,11 @@ impl<T: BeaconChainTypes> SyncManager<T> {
                         self.network
                             .report_peer(peer_id, PeerAction::MidToleranceError);
                     }
+                    SyncMessage::BlocksImported(block_roots) => {
+                        for block_root in block_roots {
+                            self.notify_block_imported(block_root);
+                        }
+                    }
                 }
             }
         }
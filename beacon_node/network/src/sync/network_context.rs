@@ -1,6 +1,7 @@
 //! Provides network functionality for the Syncing thread. This fundamentally wraps a network
 //! channel and stores a global RPC ID to perform requests.
 
+use crate::metrics;
 use crate::router::processor::status_message;
 use crate::service::NetworkMessage;
 use beacon_chain::{BeaconChain, BeaconChainTypes};
@@ -8,6 +9,7 @@ use eth2_libp2p::rpc::{BlocksByRangeRequest, BlocksByRootRequest, GoodbyeReason,
 use eth2_libp2p::{Client, NetworkGlobals, PeerAction, PeerId, Request};
 use slog::{debug, trace, warn};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::mpsc;
 use types::EthSpec;
 
@@ -50,6 +52,41 @@ impl<T: EthSpec> SyncNetworkContext<T> {
             .unwrap_or_default()
     }
 
+    /// Returns the rate at which the peer has served our range requests, in bytes per second, if
+    /// known.
+    pub fn peer_bytes_per_second(&self, peer_id: &PeerId) -> Option<f64> {
+        self.network_globals
+            .peers
+            .read()
+            .peer_info(peer_id)
+            .and_then(|info| info.sync_throughput.bytes_per_second())
+    }
+
+    /// Records the response times of a completed `BlocksByRange` request against the peer.
+    pub fn batch_response_received(
+        &self,
+        peer_id: &PeerId,
+        latency: Duration,
+        bytes: usize,
+        duration: Duration,
+    ) {
+        metrics::inc_counter_by(&metrics::SYNC_DOWNLOADED_BYTES, bytes as i64);
+        metrics::observe(&metrics::SYNC_BATCH_RESPONSE_LATENCY, latency.as_secs_f64());
+        metrics::observe(&metrics::SYNC_BATCH_DOWNLOAD_TIMES, duration.as_secs_f64());
+        if let Some(info) = self.network_globals.peers.write().peer_info_mut(peer_id) {
+            info.sync_throughput
+                .response_received(latency, bytes, duration);
+        }
+    }
+
+    /// Records a `BlocksByRange` request to the peer which failed to complete.
+    pub fn batch_request_failed(&self, peer_id: &PeerId) {
+        metrics::inc_counter(&metrics::SYNC_BATCH_FAILURES);
+        if let Some(info) = self.network_globals.peers.write().peer_info_mut(peer_id) {
+            info.sync_throughput.request_failed();
+        }
+    }
+
     pub fn status_peer<U: BeaconChainTypes>(
         &mut self,
         chain: Arc<BeaconChain<U>>,
@@ -1,14 +1,13 @@
-use super::chain::EPOCHS_PER_BATCH;
 use eth2_libp2p::rpc::methods::*;
 use eth2_libp2p::PeerId;
 use fnv::FnvHashMap;
 use ssz::Encode;
-use std::cmp::min;
 use std::cmp::Ordering;
 use std::collections::hash_map::Entry;
 use std::collections::{HashMap, HashSet};
 use std::hash::{Hash, Hasher};
 use std::ops::Sub;
+use std::time::Instant;
 use types::{EthSpec, SignedBeaconBlock, Slot};
 
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -54,6 +53,15 @@ pub struct Batch<T: EthSpec> {
     pub original_hash: Option<u64>,
     /// The blocks that have been downloaded.
     pub downloaded_blocks: Vec<SignedBeaconBlock<T>>,
+    /// The time at which the batch was last requested from a peer.
+    pub requested_at: Instant,
+    /// The time at which the first block of the current request was received, if any.
+    pub first_block_at: Option<Instant>,
+    /// The SSZ-encoded size of the blocks received for the current request.
+    pub downloaded_bytes: usize,
+    /// Whether the downloaded blocks were loaded from the store after a restart, rather than
+    /// received from `current_peer`.
+    pub from_store: bool,
 }
 
 impl<T: EthSpec> Eq for Batch<T> {}
@@ -70,16 +78,17 @@ impl<T: EthSpec> Batch<T> {
             reprocess_retries: 0,
             original_hash: None,
             downloaded_blocks: Vec::new(),
+            requested_at: Instant::now(),
+            first_block_at: None,
+            downloaded_bytes: 0,
+            from_store: false,
         }
     }
 
     pub fn to_blocks_by_range_request(&self) -> BlocksByRangeRequest {
         BlocksByRangeRequest {
             start_slot: self.start_slot.into(),
-            count: min(
-                T::slots_per_epoch() * EPOCHS_PER_BATCH,
-                self.end_slot.sub(self.start_slot).into(),
-            ),
+            count: self.end_slot.sub(self.start_slot).into(),
             step: 1,
         }
     }
@@ -109,7 +118,7 @@ impl<T: EthSpec> PartialOrd for Batch<T> {
 /// A structure that contains a mapping of pending batch requests, that also keeps track of which
 /// peers are currently making batch requests.
 ///
-/// This is used to optimise searches for idle peers (peers that have no outbound batch requests).
+/// This is used to limit the number of outbound batch requests to each peer.
 pub struct PendingBatches<T: EthSpec> {
     /// The current pending batches.
     batches: FnvHashMap<usize, Batch<T>>,
@@ -159,14 +168,15 @@ impl<T: EthSpec> PendingBatches<T> {
     /// matching the request id.
     pub fn add_block(&mut self, request_id: usize, block: SignedBeaconBlock<T>) -> Option<()> {
         let batch = self.batches.get_mut(&request_id)?;
+        batch.first_block_at.get_or_insert_with(Instant::now);
+        batch.downloaded_bytes += block.ssz_bytes_len();
         batch.downloaded_blocks.push(block);
         Some(())
     }
 
-    /// Returns true if there the peer does not exist in the peer_requests mapping. Indicating it
-    /// has no pending outgoing requests.
-    pub fn peer_is_idle(&self, peer_id: &PeerId) -> bool {
-        self.peer_requests.get(peer_id).is_none()
+    /// The number of pending batch requests assigned to the peer.
+    pub fn peer_request_count(&self, peer_id: &PeerId) -> usize {
+        self.peer_requests.get(peer_id).map_or(0, HashSet::len)
     }
 
     /// Removes a batch for a given peer.
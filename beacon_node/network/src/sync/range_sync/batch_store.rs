@@ -0,0 +1,483 @@
+//! Persists range sync batches which have been downloaded but not yet processed.
+//!
+//! If the node restarts mid-sync, these batches are used in place of requesting the same blocks
+//! from peers again. They are validated and imported as though they were freshly downloaded.
+//!
+//! Each batch is stored under a key derived from its slot range and the parent root of its first
+//! block, alongside an index of all stored batch ranges so they may be found after a restart.
+//! Batches are shared between all chains: a chain which requests the same slots uses the stored
+//! blocks, and if they do not build upon its blocks they fail import validation and the batch is
+//! downloaded from a peer instead.
+//!
+//! Writes to the database are performed by a dedicated thread, so that range sync is not blocked
+//! on them. The thread coalesces queued writes, only writing the most recent index.
+
+use super::batch::Batch;
+use beacon_chain::BeaconChainTypes;
+use parking_lot::Mutex;
+use slog::{debug, warn};
+use ssz::{Decode, Encode};
+use ssz_derive::{Decode, Encode};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{mpsc, Arc};
+use std::thread::JoinHandle;
+use store::{DBColumn, Error as StoreError, HotColdDB, ItemStore, StoreItem};
+use types::{EthSpec, Hash256, SignedBeaconBlock, Slot};
+
+/// 32-byte key for accessing the `PersistedBatchIndex`.
+pub const SYNC_BATCHES_DB_KEY: &str = "PERSISTEDSYNCBATCHESPERSISTEDSYN";
+
+/// The slot range of a stored batch, and the parent root of its first block.
+#[derive(Clone, Copy, Debug, PartialEq, Encode, Decode)]
+struct BatchRange {
+    start_slot: Slot,
+    end_slot: Slot,
+    parent_root: Hash256,
+}
+
+impl BatchRange {
+    /// Returns the key under which the blocks of the batch are stored.
+    fn key(&self) -> Hash256 {
+        let mut bytes = self.start_slot.as_u64().to_le_bytes().to_vec();
+        bytes.extend_from_slice(&self.end_slot.as_u64().to_le_bytes());
+        bytes.extend_from_slice(self.parent_root.as_bytes());
+        tree_hash::merkle_root(&bytes, 0)
+    }
+}
+
+/// The ranges of all stored batches.
+#[derive(Encode, Decode)]
+struct PersistedBatchIndex {
+    batches: Vec<BatchRange>,
+}
+
+impl StoreItem for PersistedBatchIndex {
+    fn db_column() -> DBColumn {
+        DBColumn::SyncBatches
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, StoreError> {
+        Self::from_ssz_bytes(bytes).map_err(Into::into)
+    }
+}
+
+/// The blocks of a downloaded batch.
+#[derive(Encode, Decode)]
+struct PersistedBatch<E: EthSpec> {
+    range: BatchRange,
+    blocks: Vec<SignedBeaconBlock<E>>,
+}
+
+impl<E: EthSpec> StoreItem for PersistedBatch<E> {
+    fn db_column() -> DBColumn {
+        DBColumn::SyncBatches
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, StoreError> {
+        Self::from_ssz_bytes(bytes).map_err(Into::into)
+    }
+}
+
+/// A write to be performed by the writer thread.
+enum WriteOp<E: EthSpec> {
+    PutBatch(Arc<PersistedBatch<E>>),
+    DeleteBatch(BatchRange),
+    PutIndex(PersistedBatchIndex),
+}
+
+/// Batches which have been queued for writing, but not yet written.
+type UnwrittenBatches<E> = Arc<Mutex<HashMap<Hash256, Arc<PersistedBatch<E>>>>>;
+
+/// The downloaded but unprocessed batches of all chains, shared between the chains of range sync.
+///
+/// Storage errors are logged rather than returned, as a failure to persist a batch only means it
+/// must be downloaded again after a restart.
+pub struct BatchStore<T: BeaconChainTypes> {
+    /// The database the batches are stored in.
+    store: Arc<HotColdDB<T::EthSpec, T::HotStore, T::ColdStore>>,
+    /// A mapping of the start slot of each stored batch to its range.
+    batches: BTreeMap<Slot, BatchRange>,
+    /// Batches which are yet to be written by the writer thread.
+    unwritten: UnwrittenBatches<T::EthSpec>,
+    /// The channel to the writer thread.
+    writer_send: Option<mpsc::Sender<WriteOp<T::EthSpec>>>,
+    /// The handle of the writer thread, joined on drop so all queued writes complete.
+    writer_handle: Option<JoinHandle<()>>,
+    /// The syncing logger.
+    log: slog::Logger,
+}
+
+impl<T: BeaconChainTypes> BatchStore<T> {
+    /// Loads the index of batches persisted prior to a restart and spawns the writer thread.
+    pub fn open(
+        store: Arc<HotColdDB<T::EthSpec, T::HotStore, T::ColdStore>>,
+        log: slog::Logger,
+    ) -> Self {
+        let key = Hash256::from_slice(&SYNC_BATCHES_DB_KEY.as_bytes());
+        let batches = match store.get_item::<PersistedBatchIndex>(&key) {
+            Ok(Some(index)) => index
+                .batches
+                .into_iter()
+                .map(|range| (range.start_slot, range))
+                .collect(),
+            Ok(None) => BTreeMap::new(),
+            Err(e) => {
+                warn!(log, "Failed to load persisted sync batches"; "error" => format!("{:?}", e));
+                BTreeMap::new()
+            }
+        };
+
+        if !batches.is_empty() {
+            debug!(log, "Loaded persisted sync batches"; "count" => batches.len());
+        }
+
+        let unwritten = UnwrittenBatches::default();
+        let (writer_send, writer_recv) = mpsc::channel();
+        let writer_handle = {
+            let store = store.clone();
+            let unwritten = unwritten.clone();
+            let log = log.clone();
+            std::thread::spawn(move || run_writer(store, writer_recv, unwritten, log))
+        };
+
+        BatchStore {
+            store,
+            batches,
+            unwritten,
+            writer_send: Some(writer_send),
+            writer_handle: Some(writer_handle),
+            log,
+        }
+    }
+
+    /// Returns the end slot of the stored batch which starts at `start_slot`, if any.
+    pub fn end_slot(&self, start_slot: Slot) -> Option<Slot> {
+        self.batches.get(&start_slot).map(|range| range.end_slot)
+    }
+
+    /// Returns the start slot of the first stored batch which starts after `slot`, if any.
+    pub fn next_start_slot(&self, slot: Slot) -> Option<Slot> {
+        self.batches
+            .range(slot + 1..)
+            .next()
+            .map(|(start_slot, _)| *start_slot)
+    }
+
+    /// Persists the blocks of a downloaded batch, replacing any stored batches which it overlaps.
+    pub fn put(&mut self, batch: &Batch<T::EthSpec>) {
+        let overlapping = self
+            .batches
+            .range(..batch.end_slot)
+            .filter(|(_, range)| range.end_slot > batch.start_slot)
+            .map(|(start_slot, _)| *start_slot)
+            .collect::<Vec<_>>();
+        for start_slot in overlapping {
+            self.delete(start_slot);
+        }
+
+        let range = BatchRange {
+            start_slot: batch.start_slot,
+            end_slot: batch.end_slot,
+            parent_root: batch
+                .downloaded_blocks
+                .first()
+                .map_or_else(Hash256::zero, |block| block.message.parent_root),
+        };
+        let persisted = Arc::new(PersistedBatch {
+            range,
+            blocks: batch.downloaded_blocks.clone(),
+        });
+        self.unwritten.lock().insert(range.key(), persisted.clone());
+        self.write(WriteOp::PutBatch(persisted));
+        self.batches.insert(batch.start_slot, range);
+        self.persist_index();
+    }
+
+    /// Loads the blocks of the stored batch which matches the range of `batch`, if any.
+    pub fn get(&self, batch: &Batch<T::EthSpec>) -> Option<Vec<SignedBeaconBlock<T::EthSpec>>> {
+        let range = self.batches.get(&batch.start_slot)?;
+        if range.end_slot != batch.end_slot {
+            return None;
+        }
+
+        if let Some(persisted) = self.unwritten.lock().get(&range.key()) {
+            return Some(persisted.blocks.clone());
+        }
+
+        match self
+            .store
+            .get_item::<PersistedBatch<T::EthSpec>>(&range.key())
+        {
+            Ok(Some(persisted)) if persisted.range == *range => Some(persisted.blocks),
+            Ok(_) => None,
+            Err(e) => {
+                warn!(self.log, "Failed to load persisted sync batch";
+                    "start_slot" => batch.start_slot, "error" => format!("{:?}", e));
+                None
+            }
+        }
+    }
+
+    /// Removes the stored batch which starts at `start_slot`, if any.
+    pub fn remove(&mut self, start_slot: Slot) {
+        if self.delete(start_slot) {
+            self.persist_index();
+        }
+    }
+
+    /// Removes the stored batches which end at or before `slot`.
+    pub fn prune(&mut self, slot: Slot) {
+        let outdated = self
+            .batches
+            .values()
+            .filter(|range| range.end_slot <= slot)
+            .map(|range| range.start_slot)
+            .collect::<Vec<_>>();
+        if !outdated.is_empty() {
+            for start_slot in outdated {
+                self.delete(start_slot);
+            }
+            self.persist_index();
+        }
+    }
+
+    /// Deletes the batch which starts at `start_slot` without updating the persisted index.
+    /// Returns `true` if the batch existed.
+    fn delete(&mut self, start_slot: Slot) -> bool {
+        match self.batches.remove(&start_slot) {
+            Some(range) => {
+                self.unwritten.lock().remove(&range.key());
+                self.write(WriteOp::DeleteBatch(range));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Queues a write of the index of stored batches.
+    fn persist_index(&self) {
+        let index = PersistedBatchIndex {
+            batches: self.batches.values().copied().collect(),
+        };
+        self.write(WriteOp::PutIndex(index));
+    }
+
+    /// Queues a write to be performed by the writer thread.
+    fn write(&self, op: WriteOp<T::EthSpec>) {
+        let sent = self
+            .writer_send
+            .as_ref()
+            .map_or(false, |writer_send| writer_send.send(op).is_ok());
+        if !sent {
+            warn!(self.log, "Sync batch writer has stopped");
+        }
+    }
+}
+
+impl<T: BeaconChainTypes> Drop for BatchStore<T> {
+    /// Waits for all queued writes to complete.
+    fn drop(&mut self) {
+        self.writer_send = None;
+        if let Some(handle) = self.writer_handle.take() {
+            if handle.join().is_err() {
+                warn!(self.log, "Sync batch writer panicked");
+            }
+        }
+    }
+}
+
+/// Performs the writes queued by a `BatchStore` until it is dropped.
+///
+/// Writes are performed in the order they were queued, except that only the most recent of the
+/// queued index writes is performed.
+fn run_writer<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
+    store: Arc<HotColdDB<E, Hot, Cold>>,
+    writer_recv: mpsc::Receiver<WriteOp<E>>,
+    unwritten: UnwrittenBatches<E>,
+    log: slog::Logger,
+) {
+    while let Ok(op) = writer_recv.recv() {
+        let mut index = None;
+        for op in std::iter::once(op).chain(writer_recv.try_iter()) {
+            match op {
+                WriteOp::PutBatch(persisted) => {
+                    let key = persisted.range.key();
+                    if let Err(e) = store.put_item(&key, persisted.as_ref()) {
+                        warn!(log, "Failed to persist sync batch";
+                            "start_slot" => persisted.range.start_slot, "error" => format!("{:?}", e));
+                    }
+                    let mut unwritten = unwritten.lock();
+                    if unwritten
+                        .get(&key)
+                        .map_or(false, |queued| Arc::ptr_eq(queued, &persisted))
+                    {
+                        unwritten.remove(&key);
+                    }
+                }
+                WriteOp::DeleteBatch(range) => {
+                    if let Err(e) = store.delete_item::<PersistedBatch<E>>(&range.key()) {
+                        warn!(log, "Failed to delete persisted sync batch";
+                            "start_slot" => range.start_slot, "error" => format!("{:?}", e));
+                    }
+                }
+                WriteOp::PutIndex(new_index) => index = Some(new_index),
+            }
+        }
+
+        if let Some(index) = index {
+            let key = Hash256::from_slice(&SYNC_BATCHES_DB_KEY.as_bytes());
+            if let Err(e) = store.put_item(&key, &index) {
+                warn!(log, "Failed to persist sync batch index"; "error" => format!("{:?}", e));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::range_sync::batch::BatchId;
+    use beacon_chain::test_utils::HarnessType;
+    use eth2_libp2p::PeerId;
+    use sloggers::{null::NullLoggerBuilder, Build};
+    use store::config::StoreConfig;
+    use store::MemoryStore;
+    use types::{BeaconBlock, ChainSpec, MinimalEthSpec, Signature};
+
+    type E = MinimalEthSpec;
+    type Store = HotColdDB<E, MemoryStore<E>, MemoryStore<E>>;
+
+    fn get_store() -> Arc<Store> {
+        let log = NullLoggerBuilder.build().unwrap();
+        Arc::new(
+            HotColdDB::open_ephemeral(StoreConfig::default(), ChainSpec::minimal(), log).unwrap(),
+        )
+    }
+
+    fn open(store: Arc<Store>) -> BatchStore<HarnessType<E>> {
+        BatchStore::open(store, NullLoggerBuilder.build().unwrap())
+    }
+
+    /// Returns a downloaded batch over the given slots, with a block at `start_slot`.
+    fn batch(start_slot: u64, end_slot: u64) -> Batch<E> {
+        let mut batch = Batch::new(
+            BatchId(1),
+            Slot::new(start_slot),
+            Slot::new(end_slot),
+            PeerId::random(),
+        );
+        let mut block = BeaconBlock::empty(&ChainSpec::minimal());
+        block.slot = Slot::new(start_slot);
+        batch.downloaded_blocks = vec![SignedBeaconBlock {
+            message: block,
+            signature: Signature::empty_signature(),
+        }];
+        batch
+    }
+
+    /// Returns a downloaded batch over the given slots, whose block builds upon `parent_root`.
+    fn fork_batch(start_slot: u64, end_slot: u64, parent_root: Hash256) -> Batch<E> {
+        let mut batch = batch(start_slot, end_slot);
+        batch.downloaded_blocks[0].message.parent_root = parent_root;
+        batch
+    }
+
+    #[test]
+    fn put_and_get() {
+        let mut batch_store = open(get_store());
+        let batch = batch(8, 16);
+
+        assert_eq!(batch_store.get(&batch), None);
+
+        batch_store.put(&batch);
+        assert_eq!(batch_store.end_slot(Slot::new(8)), Some(Slot::new(16)));
+        assert_eq!(
+            batch_store.get(&batch),
+            Some(batch.downloaded_blocks.clone())
+        );
+
+        // A batch over different slots does not match.
+        assert_eq!(batch_store.get(&self::batch(8, 24)), None);
+
+        batch_store.remove(Slot::new(8));
+        assert_eq!(batch_store.end_slot(Slot::new(8)), None);
+        assert_eq!(batch_store.get(&batch), None);
+    }
+
+    #[test]
+    fn batches_of_other_forks_replace_each_other() {
+        let mut batch_store = open(get_store());
+        let first = fork_batch(8, 16, Hash256::from_low_u64_be(1));
+        let second = fork_batch(8, 16, Hash256::from_low_u64_be(2));
+
+        batch_store.put(&first);
+        batch_store.put(&second);
+        assert_eq!(batch_store.get(&first), Some(second.downloaded_blocks));
+    }
+
+    #[test]
+    fn put_replaces_overlapping_batches() {
+        let mut batch_store = open(get_store());
+
+        batch_store.put(&batch(0, 8));
+        batch_store.put(&batch(8, 16));
+        batch_store.put(&batch(16, 24));
+        assert_eq!(
+            batch_store.next_start_slot(Slot::new(0)),
+            Some(Slot::new(8))
+        );
+
+        batch_store.put(&batch(4, 12));
+        assert_eq!(batch_store.end_slot(Slot::new(0)), None);
+        assert_eq!(batch_store.end_slot(Slot::new(8)), None);
+        assert_eq!(batch_store.end_slot(Slot::new(4)), Some(Slot::new(12)));
+        assert_eq!(batch_store.end_slot(Slot::new(16)), Some(Slot::new(24)));
+        assert_eq!(
+            batch_store.next_start_slot(Slot::new(4)),
+            Some(Slot::new(16))
+        );
+    }
+
+    #[test]
+    fn prune_removes_outdated_batches() {
+        let mut batch_store = open(get_store());
+
+        batch_store.put(&batch(0, 8));
+        batch_store.put(&batch(8, 16));
+        batch_store.put(&batch(16, 32));
+
+        batch_store.prune(Slot::new(16));
+        assert_eq!(batch_store.end_slot(Slot::new(0)), None);
+        assert_eq!(batch_store.end_slot(Slot::new(8)), None);
+        assert_eq!(batch_store.end_slot(Slot::new(16)), Some(Slot::new(32)));
+        assert_eq!(batch_store.get(&batch(8, 16)), None);
+    }
+
+    #[test]
+    fn batches_are_reloaded() {
+        let store = get_store();
+        let mut batch_store = open(store.clone());
+
+        let first = batch(0, 8);
+        let second = fork_batch(8, 16, Hash256::from_low_u64_be(1));
+        batch_store.put(&first);
+        batch_store.put(&second);
+        batch_store.put(&batch(16, 32));
+        batch_store.remove(Slot::new(16));
+        // Waits for the writer thread to complete all queued writes.
+        drop(batch_store);
+
+        let batch_store = open(store);
+        assert_eq!(batch_store.get(&first), Some(first.downloaded_blocks));
+        assert_eq!(batch_store.get(&second), Some(second.downloaded_blocks));
+        assert_eq!(batch_store.end_slot(Slot::new(16)), None);
+    }
+}
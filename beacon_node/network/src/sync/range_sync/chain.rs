@@ -1,23 +1,45 @@
 use super::batch::{Batch, BatchId, PendingBatches};
+use super::batch_store::BatchStore;
+use crate::metrics;
 use crate::sync::block_processor::{spawn_block_processor, BatchProcessResult, ProcessId};
 use crate::sync::network_context::SyncNetworkContext;
 use crate::sync::{RequestId, SyncMessage};
 use beacon_chain::{BeaconChain, BeaconChainTypes};
+use eth2_libp2p::types::{SyncChainInfo, SyncChainType};
 use eth2_libp2p::{PeerAction, PeerId};
+use parking_lot::Mutex;
 use rand::prelude::*;
 use slog::{crit, debug, warn};
+use std::cmp::Ordering;
 use std::collections::HashSet;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 use types::{Epoch, EthSpec, Hash256, SignedBeaconBlock, Slot};
 
 /// Blocks are downloaded in batches from peers. This constant specifies how many epochs worth of
-/// blocks per batch are requested _at most_. A batch may request less blocks to account for
-/// already requested slots. There is a timeout for each batch request. If this value is too high,
-/// we will negatively report peers with poor bandwidth. This can be set arbitrarily high, in which case the
-/// responder will fill the response up to the max request size, assuming they have the bandwidth
-/// to do so.
-pub const EPOCHS_PER_BATCH: u64 = 2;
+/// blocks per batch are requested until the size of blocks and the bandwidth of the peer are
+/// known. A batch may request less blocks to account for already requested slots.
+const EPOCHS_PER_BATCH: u64 = 2;
+
+/// The fewest epochs worth of blocks requested in a single batch.
+const MIN_EPOCHS_PER_BATCH: u64 = 1;
+
+/// The most epochs worth of blocks requested in a single batch, regardless of the bandwidth of the
+/// peer. There is a timeout for each batch request, so this bounds the cost of a peer whose
+/// bandwidth suddenly drops.
+pub const MAX_EPOCHS_PER_BATCH: u64 = 8;
+
+/// Batches are sized such that a peer is expected to serve them within this time. This is well
+/// within the RPC timeout, so peers with poor bandwidth are not negatively reported.
+const TARGET_BATCH_DOWNLOAD_TIME: Duration = Duration::from_secs(4);
+
+/// A peer may have one additional batch request in flight for each multiple of this throughput
+/// (in bytes per second) it has shown.
+const FAST_PEER_BYTES_PER_SECOND: f64 = 1_048_576.0;
+
+/// The maximum number of batch requests in flight to a single peer.
+const MAX_REQUESTS_PER_PEER: usize = 3;
 
 /// The number of times to retry a batch before the chain is considered failed and removed.
 const MAX_BATCH_RETRIES: u8 = 5;
@@ -25,6 +47,9 @@ const MAX_BATCH_RETRIES: u8 = 5;
 /// The maximum number of batches to queue before requesting more.
 const BATCH_BUFFER_SIZE: u8 = 5;
 
+/// The maximum number of batches to queue when fast peers can serve additional requests.
+const MAX_BATCH_BUFFER_SIZE: usize = 10;
+
 /// Invalid batches are attempted to be re-downloaded from other peers. If they cannot be processed
 /// after `INVALID_BATCH_LOOKUP_ATTEMPTS` times, the chain is considered faulty and all peers will
 /// be reported negatively.
@@ -78,6 +103,19 @@ pub struct SyncingChain<T: BeaconChainTypes> {
     /// The next batch id that needs to be processed.
     to_be_processed_id: BatchId,
 
+    /// The start slot of the next batch that needs to be downloaded.
+    to_be_downloaded_slot: Slot,
+
+    /// The start slot of the next batch that needs to be processed. All prior slots have been
+    /// processed.
+    to_be_processed_slot: Slot,
+
+    /// The total SSZ size of the blocks in all downloaded batches, used to size future batches.
+    downloaded_bytes: usize,
+
+    /// The total number of slots spanned by all downloaded batches.
+    downloaded_slots: u64,
+
     /// The current state of the chain.
     pub state: ChainSyncingState,
 
@@ -91,6 +129,9 @@ pub struct SyncingChain<T: BeaconChainTypes> {
     /// A reference to the underlying beacon chain.
     chain: Arc<BeaconChain<T>>,
 
+    /// The store of downloaded but unprocessed batches, shared by all chains.
+    batch_store: Arc<Mutex<BatchStore<T>>>,
+
     /// A reference to the sync logger.
     log: slog::Logger,
 }
@@ -104,6 +145,7 @@ pub enum ChainSyncingState {
 }
 
 impl<T: BeaconChainTypes> SyncingChain<T> {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         id: u64,
         start_epoch: Epoch,
@@ -112,10 +154,12 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
         peer_id: PeerId,
         sync_send: mpsc::UnboundedSender<SyncMessage<T::EthSpec>>,
         chain: Arc<BeaconChain<T>>,
+        batch_store: Arc<Mutex<BatchStore<T>>>,
         log: slog::Logger,
     ) -> Self {
         let mut peer_pool = HashSet::new();
         peer_pool.insert(peer_id);
+        let start_slot = start_epoch.start_slot(T::EthSpec::slots_per_epoch());
 
         SyncingChain {
             id,
@@ -128,23 +172,39 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
             peer_pool,
             to_be_downloaded_id: BatchId(1),
             to_be_processed_id: BatchId(1),
+            to_be_downloaded_slot: start_slot,
+            to_be_processed_slot: start_slot,
+            downloaded_bytes: 0,
+            downloaded_slots: 0,
             state: ChainSyncingState::Stopped,
             current_processing_batch: None,
             sync_send,
             chain,
+            batch_store,
             log,
         }
     }
 
     /// Returns the latest slot number that has been processed.
     fn current_processed_slot(&self) -> Slot {
-        self.start_epoch
-            .start_slot(T::EthSpec::slots_per_epoch())
-            .saturating_add(
-                self.to_be_processed_id.saturating_sub(1u64)
-                    * T::EthSpec::slots_per_epoch()
-                    * EPOCHS_PER_BATCH,
-            )
+        self.to_be_processed_slot
+    }
+
+    /// Returns the download progress of the chain.
+    pub fn info(&self, chain_type: SyncChainType) -> SyncChainInfo {
+        SyncChainInfo {
+            id: self.id,
+            chain_type,
+            syncing: self.state == ChainSyncingState::Syncing,
+            start_slot: self.start_epoch.start_slot(T::EthSpec::slots_per_epoch()),
+            target_head_slot: self.target_head_slot,
+            target_head_root: self.target_head_root,
+            processed_slot: self.current_processed_slot(),
+            processed_batches: self.to_be_processed_id.saturating_sub(1),
+            pending_batches: self.pending_batches.len(),
+            completed_batches: self.completed_batches.len(),
+            peers: self.peer_pool.len(),
+        }
     }
 
     /// A batch of blocks has been received. This function gets run on all chains and should
@@ -165,6 +225,16 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
         } else {
             // A stream termination has been sent. This batch has ended. Process a completed batch.
             let batch = self.pending_batches.remove(request_id)?;
+            let now = Instant::now();
+            network.batch_response_received(
+                &batch.current_peer,
+                batch
+                    .first_block_at
+                    .unwrap_or(now)
+                    .duration_since(batch.requested_at),
+                batch.downloaded_bytes,
+                now.duration_since(batch.requested_at),
+            );
             self.handle_completed_batch(network, batch);
             Some(())
         }
@@ -196,20 +266,27 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
                 // this much either.
                 network.report_peer(batch.current_peer, PeerAction::LowToleranceError);
                 self.to_be_processed_id = batch.id; // reset the id back to here, when incrementing, it will check against completed batches
+                self.to_be_processed_slot = batch.start_slot;
                 return;
             }
         }
 
+        // Track the size of downloaded blocks, to size future batches to the bandwidth of peers.
+        // Empty batches are ignored, as they may be erroneously empty.
+        if batch.downloaded_bytes > 0 {
+            self.downloaded_bytes += batch.downloaded_bytes;
+            self.downloaded_slots += batch.end_slot.saturating_sub(batch.start_slot).as_u64();
+        }
+
+        // Persist the batch, so it need not be downloaded again if the node restarts before it is
+        // processed.
+        self.batch_store.lock().put(&batch);
+
         // Add this completed batch to the list of completed batches. This list will then need to
         // be checked if any batches can be processed and verified for errors or invalid responses
         // from peers. The logic is simpler to create this ordered batch list and to then process
         // the list.
-
-        let insert_index = self
-            .completed_batches
-            .binary_search(&batch)
-            .unwrap_or_else(|index| index);
-        self.completed_batches.insert(insert_index, batch);
+        self.insert_completed_batch(batch);
 
         // We have a list of completed batches. It is not sufficient to process batch successfully
         // to consider the batch correct. This is because batches could be erroneously empty, or
@@ -226,6 +303,15 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
         self.process_completed_batches();
     }
 
+    /// Adds a batch to the ordered list of completed batches.
+    fn insert_completed_batch(&mut self, batch: Batch<T::EthSpec>) {
+        let insert_index = self
+            .completed_batches
+            .binary_search(&batch)
+            .unwrap_or_else(|index| index);
+        self.completed_batches.insert(insert_index, batch);
+    }
+
     /// Tries to process any batches if there are any available and we are not currently processing
     /// other batches.
     fn process_completed_batches(&mut self) {
@@ -311,6 +397,10 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
         // These are the blocks of this batch
         batch.downloaded_blocks = downloaded_blocks;
 
+        // The batch no longer needs to be stored. Its blocks have either been imported, or it will
+        // be downloaded again.
+        self.batch_store.lock().remove(batch.start_slot);
+
         // double check batches are processed in order TODO: Remove for prod
         if batch.id != self.to_be_processed_id {
             crit!(self.log, "Batch processed out of order";
@@ -322,6 +412,7 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
         let res = match result {
             BatchProcessResult::Success => {
                 *self.to_be_processed_id += 1;
+                self.to_be_processed_slot = batch.end_slot;
 
                 // If the processed batch was not empty, we can validate previous invalidated
                 // blocks
@@ -505,7 +596,10 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
         // Find any pre-processed batches awaiting validation
         while !self.processed_batches.is_empty() {
             let past_batch = self.processed_batches.remove(0);
-            *self.to_be_processed_id = std::cmp::min(*self.to_be_processed_id, *past_batch.id);
+            if *past_batch.id < *self.to_be_processed_id {
+                self.to_be_processed_id = past_batch.id;
+                self.to_be_processed_slot = past_batch.start_slot;
+            }
             self.reprocess_batch(network, past_batch);
         }
 
@@ -522,8 +616,14 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
         network: &mut SyncNetworkContext<T::EthSpec>,
         mut batch: Batch<T::EthSpec>,
     ) {
-        // marks the batch as attempting to be reprocessed by hashing the downloaded blocks
-        batch.original_hash = Some(batch.hash());
+        // marks the batch as attempting to be reprocessed by hashing the downloaded blocks. Blocks
+        // loaded from the store were not sent by the original peer, so it cannot be blamed for them.
+        batch.original_hash = if batch.from_store {
+            None
+        } else {
+            Some(batch.hash())
+        };
+        batch.from_store = false;
 
         // remove previously downloaded blocks
         batch.downloaded_blocks.clear();
@@ -533,14 +633,7 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
 
         // attempt to find another peer to download the batch from (this potentially doubles up
         // requests on a single peer)
-        let current_peer = &batch.current_peer;
-        let new_peer = self
-            .peer_pool
-            .iter()
-            .find(|peer| *peer != current_peer)
-            .unwrap_or_else(|| current_peer);
-
-        batch.current_peer = new_peer.clone();
+        batch.current_peer = self.get_retry_peer(network, &batch.current_peer);
 
         debug!(self.log, "Re-requesting batch";
             "chain_id" => self.id,
@@ -578,6 +671,9 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
         let local_finalized_slot = local_finalized_epoch.start_slot(T::EthSpec::slots_per_epoch());
         let current_processed_slot = self.current_processed_slot();
 
+        // stored batches prior to the finalized slot will never be processed
+        self.batch_store.lock().prune(local_finalized_slot);
+
         if local_finalized_slot > current_processed_slot {
             // Advance the chain to account for already downloaded blocks.
             self.start_epoch = local_finalized_epoch;
@@ -585,10 +681,12 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
             debug!(self.log, "Updating chain's progress";
                 "chain_id" => self.id,
                 "prev_completed_slot" => current_processed_slot,
-                "new_completed_slot" => self.current_processed_slot());
+                "new_completed_slot" => local_finalized_slot);
             // Re-index batches
             *self.to_be_downloaded_id = 1;
             *self.to_be_processed_id = 1;
+            self.to_be_downloaded_slot = local_finalized_slot;
+            self.to_be_processed_slot = local_finalized_slot;
 
             // remove any completed or processed batches
             self.completed_batches.clear();
@@ -646,6 +744,7 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
                 "retries" => batch.retries,
                 "peer" => format!("{:?}", peer_id));
 
+            network.batch_request_failed(&batch.current_peer);
             Some(self.failed_batch(network, batch))
         } else {
             None
@@ -670,14 +769,7 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
             ProcessingResult::RemoveChain
         } else {
             // try to re-process the request using a different peer, if possible
-            let current_peer = &batch.current_peer;
-            let new_peer = self
-                .peer_pool
-                .iter()
-                .find(|peer| *peer != current_peer)
-                .unwrap_or_else(|| current_peer);
-
-            batch.current_peer = new_peer.clone();
+            batch.current_peer = self.get_retry_peer(network, &batch.current_peer);
             debug!(self.log, "Re-Requesting batch";
                 "chain_id" => self.id,
                 "start_slot" => batch.start_slot,
@@ -701,14 +793,37 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
     /// to send a request and there are batches to request, false otherwise.
     fn send_range_request(&mut self, network: &mut SyncNetworkContext<T::EthSpec>) -> bool {
         // find the next pending batch and request it from the peer
-        if let Some(peer_id) = self.get_next_peer() {
-            if let Some(batch) = self.get_next_batch(peer_id) {
+        if let Some((peer_id, bytes_per_second)) = self.get_next_peer(network) {
+            let epochs_per_batch = epochs_per_batch(
+                bytes_per_second,
+                self.bytes_per_slot()
+                    .map(|bytes| bytes * T::EthSpec::slots_per_epoch() as f64),
+            );
+            let buffer_size = self.batch_buffer_size(network);
+            if let Some(mut batch) = self.get_next_batch(peer_id, epochs_per_batch, buffer_size) {
+                // use the blocks of a batch stored prior to a restart, if there is one
+                let stored_blocks = self.batch_store.lock().get(&batch);
+                if let Some(blocks) = stored_blocks {
+                    debug!(self.log, "Loaded stored batch";
+                        "chain_id" => self.id,
+                        "start_slot" => batch.start_slot,
+                        "end_slot" => batch.end_slot,
+                        "id" => *batch.id,
+                        "blocks" => blocks.len());
+                    batch.downloaded_blocks = blocks;
+                    batch.from_store = true;
+                    self.insert_completed_batch(batch);
+                    self.process_completed_batches();
+                    return true;
+                }
+
                 debug!(self.log, "Requesting batch";
                     "chain_id" => self.id,
                     "start_slot" => batch.start_slot,
                     "end_slot" => batch.end_slot,
                     "id" => *batch.id,
                     "peer" => format!("{}", batch.current_peer));
+                metrics::observe(&metrics::SYNC_BATCH_EPOCHS, epochs_per_batch as f64);
                 // send the batch
                 self.send_batch(network, batch);
                 return true;
@@ -717,57 +832,150 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
         false
     }
 
-    /// Returns a peer if there exists a peer which does not currently have a pending request.
+    /// Returns the average SSZ size of the blocks downloaded per slot, if any have been
+    /// downloaded.
+    fn bytes_per_slot(&self) -> Option<f64> {
+        if self.downloaded_slots > 0 {
+            Some(self.downloaded_bytes as f64 / self.downloaded_slots as f64)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the number of batches that may be queued before requesting more. This grows with
+    /// the number of additional requests the peer pool is able to serve.
+    fn batch_buffer_size(&self, network: &SyncNetworkContext<T::EthSpec>) -> usize {
+        let extra_requests: usize = self
+            .peer_pool
+            .iter()
+            .map(|peer| max_requests_per_peer(network.peer_bytes_per_second(peer)) - 1)
+            .sum();
+        std::cmp::min(
+            (BATCH_BUFFER_SIZE as usize).saturating_add(extra_requests),
+            MAX_BATCH_BUFFER_SIZE,
+        )
+    }
+
+    /// Returns a peer, along with its known throughput, if there exists a peer which can accept
+    /// another request.
     ///
-    /// This is used to create the next request.
-    fn get_next_peer(&self) -> Option<PeerId> {
-        // TODO: Optimize this by combining with above two functions.
-        // randomize the peers for load balancing
+    /// Peers whose throughput is unknown are preferred so that it can be measured, followed by
+    /// the fastest peers.
+    fn get_next_peer(
+        &self,
+        network: &SyncNetworkContext<T::EthSpec>,
+    ) -> Option<(PeerId, Option<f64>)> {
+        self.ranked_peers(network)
+            .into_iter()
+            .find(|(peer, bytes_per_second)| self.can_accept_request(peer, *bytes_per_second))
+    }
+
+    /// Returns the peer to re-request a failed or invalid batch from, given the peer which
+    /// served it last.
+    ///
+    /// The other peers are ranked as per `Self::get_next_peer`, preferring those which can accept
+    /// another request. The last peer is only used again if it is the only peer in the pool.
+    fn get_retry_peer(
+        &self,
+        network: &SyncNetworkContext<T::EthSpec>,
+        last_peer: &PeerId,
+    ) -> PeerId {
+        let peers = self
+            .ranked_peers(network)
+            .into_iter()
+            .filter(|(peer, _)| peer != last_peer)
+            .collect::<Vec<_>>();
+
+        peers
+            .iter()
+            .find(|(peer, bytes_per_second)| self.can_accept_request(peer, *bytes_per_second))
+            .or_else(|| peers.first())
+            .map(|(peer, _)| peer.clone())
+            .unwrap_or_else(|| last_peer.clone())
+    }
+
+    /// Returns the peers of the peer pool along with their known throughput, ordered by
+    /// preference. Peers of equal throughput are shuffled, to balance the load amongst them.
+    fn ranked_peers(&self, network: &SyncNetworkContext<T::EthSpec>) -> Vec<(PeerId, Option<f64>)> {
         let mut rng = rand::thread_rng();
-        let mut peers = self.peer_pool.iter().collect::<Vec<_>>();
+        let mut peers = self
+            .peer_pool
+            .iter()
+            .map(|peer| (peer.clone(), network.peer_bytes_per_second(peer)))
+            .collect::<Vec<_>>();
         peers.shuffle(&mut rng);
-        for peer in peers {
-            if self.pending_batches.peer_is_idle(peer) {
-                return Some(peer.clone());
-            }
-        }
-        None
+        // the sort is stable, so the shuffled order is kept amongst equal peers
+        peers.sort_by(|(_, a), (_, b)| match (a, b) {
+            (None, None) => Ordering::Equal,
+            (None, Some(_)) => Ordering::Less,
+            (Some(_), None) => Ordering::Greater,
+            (Some(a), Some(b)) => b.partial_cmp(a).unwrap_or(Ordering::Equal),
+        });
+        peers
+    }
+
+    /// Returns `true` if `peer`, serving `bytes_per_second`, may be sent another batch request.
+    fn can_accept_request(&self, peer: &PeerId, bytes_per_second: Option<f64>) -> bool {
+        self.pending_batches.peer_request_count(peer) < max_requests_per_peer(bytes_per_second)
     }
 
     /// Returns the next required batch from the chain if it exists. If there are no more batches
     /// required, `None` is returned.
-    fn get_next_batch(&mut self, peer_id: PeerId) -> Option<Batch<T::EthSpec>> {
+    fn get_next_batch(
+        &mut self,
+        peer_id: PeerId,
+        epochs_per_batch: u64,
+        buffer_size: usize,
+    ) -> Option<Batch<T::EthSpec>> {
         let slots_per_epoch = T::EthSpec::slots_per_epoch();
-        let blocks_per_batch = slots_per_epoch * EPOCHS_PER_BATCH;
+        let blocks_per_batch = slots_per_epoch * epochs_per_batch;
 
         // only request batches up to the buffer size limit
         if self
             .completed_batches
             .len()
             .saturating_add(self.pending_batches.len())
-            > BATCH_BUFFER_SIZE as usize
+            > buffer_size
         {
             return None;
         }
 
-        let batch_start_slot = self.start_epoch.start_slot(slots_per_epoch)
-            + self.to_be_downloaded_id.saturating_sub(1) * blocks_per_batch;
+        let batch_start_slot = self.to_be_downloaded_slot;
 
         // don't request batches beyond the target head slot
         if batch_start_slot > self.target_head_slot {
             return None;
         }
 
-        // truncate the batch to the epoch containing the target head of the chain
-        let batch_end_slot = std::cmp::min(
-            // request either a batch containing the max number of blocks per batch
-            batch_start_slot + blocks_per_batch,
-            // or a batch of one epoch of blocks, which contains the `target_head_slot`
-            self.target_head_slot
-                .saturating_add(slots_per_epoch)
-                .epoch(slots_per_epoch)
-                .start_slot(slots_per_epoch),
-        );
+        // the end of the epoch which contains the `target_head_slot`
+        let target_end_slot = self
+            .target_head_slot
+            .saturating_add(slots_per_epoch)
+            .epoch(slots_per_epoch)
+            .start_slot(slots_per_epoch);
+
+        // Align the batch with any batch stored prior to a restart, so that the stored blocks may
+        // be used rather than downloading them again.
+        let batch_store = self.batch_store.lock();
+        let batch_end_slot = match batch_store.end_slot(batch_start_slot) {
+            Some(stored_end_slot) if stored_end_slot <= target_end_slot => stored_end_slot,
+            _ => {
+                // truncate the batch to the epoch containing the target head of the chain
+                let batch_end_slot = std::cmp::min(
+                    // request either a batch containing the max number of blocks per batch
+                    batch_start_slot + blocks_per_batch,
+                    // or a batch of one epoch of blocks, which contains the `target_head_slot`
+                    target_end_slot,
+                );
+                // or up to the next stored batch
+                batch_store
+                    .next_start_slot(batch_start_slot)
+                    .map_or(batch_end_slot, |next_start_slot| {
+                        std::cmp::min(batch_end_slot, next_start_slot)
+                    })
+            }
+        };
+        drop(batch_store);
 
         let batch_id = self.to_be_downloaded_id;
 
@@ -784,6 +992,7 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
             self.to_be_downloaded_id.0 + 1,
             max_completed_id + 1,
         ));
+        self.to_be_downloaded_slot = batch_end_slot;
 
         Some(Batch::new(
             batch_id,
@@ -797,8 +1006,13 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
     fn send_batch(
         &mut self,
         network: &mut SyncNetworkContext<T::EthSpec>,
-        batch: Batch<T::EthSpec>,
+        mut batch: Batch<T::EthSpec>,
     ) {
+        // restart the response time measurements for this request
+        batch.requested_at = Instant::now();
+        batch.first_block_at = None;
+        batch.downloaded_bytes = 0;
+
         let request = batch.to_blocks_by_range_request();
         if let Ok(request_id) = network.blocks_by_range_request(batch.current_peer.clone(), request)
         {
@@ -807,3 +1021,289 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
         }
     }
 }
+
+/// Returns the number of epochs worth of blocks to request in a single batch from a peer serving
+/// `bytes_per_second`, given the average SSZ size of an epoch of blocks.
+///
+/// Batches are sized such that the peer is expected to serve them in `TARGET_BATCH_DOWNLOAD_TIME`.
+fn epochs_per_batch(bytes_per_second: Option<f64>, bytes_per_epoch: Option<f64>) -> u64 {
+    match (bytes_per_second, bytes_per_epoch) {
+        (Some(bytes_per_second), Some(bytes_per_epoch)) if bytes_per_epoch > 0.0 => {
+            // float to integer casts saturate, so this cannot overflow
+            let epochs = (bytes_per_second * TARGET_BATCH_DOWNLOAD_TIME.as_secs_f64()
+                / bytes_per_epoch) as u64;
+            std::cmp::min(
+                std::cmp::max(epochs, MIN_EPOCHS_PER_BATCH),
+                MAX_EPOCHS_PER_BATCH,
+            )
+        }
+        _ => EPOCHS_PER_BATCH,
+    }
+}
+
+/// Returns the number of batch requests that may be in flight to a peer serving
+/// `bytes_per_second`.
+fn max_requests_per_peer(bytes_per_second: Option<f64>) -> usize {
+    match bytes_per_second {
+        Some(bytes_per_second) => std::cmp::min(
+            1 + (bytes_per_second / FAST_PEER_BYTES_PER_SECOND) as usize,
+            MAX_REQUESTS_PER_PEER,
+        ),
+        None => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::service::NetworkMessage;
+    use beacon_chain::test_utils::{
+        AttestationStrategy, BeaconChainHarness, BlockStrategy, HarnessType,
+    };
+    use eth2_libp2p::discovery::{build_enr, CombinedKey, Keypair};
+    use eth2_libp2p::{CombinedKeyExt, NetworkConfig, NetworkGlobals, Request};
+    use sloggers::{null::NullLoggerBuilder, Build};
+    use store::config::StoreConfig;
+    use types::{test_utils::generate_deterministic_keypairs, EnrForkId, MinimalEthSpec};
+
+    type E = MinimalEthSpec;
+
+    const VALIDATOR_COUNT: usize = 8;
+
+    fn get_logger() -> slog::Logger {
+        NullLoggerBuilder.build().expect("logger should build")
+    }
+
+    fn get_harness() -> BeaconChainHarness<HarnessType<E>> {
+        BeaconChainHarness::new(
+            MinimalEthSpec,
+            generate_deterministic_keypairs(VALIDATOR_COUNT),
+            StoreConfig::default(),
+        )
+    }
+
+    /// Returns the blocks of a chain of `num_blocks` blocks built from genesis, in slot order.
+    fn get_blocks(num_blocks: usize) -> Vec<SignedBeaconBlock<E>> {
+        let harness = get_harness();
+        harness.extend_chain(
+            num_blocks,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        );
+        let mut blocks = harness
+            .chain
+            .chain_dump()
+            .expect("should dump chain")
+            .into_iter()
+            .map(|snapshot| snapshot.beacon_block)
+            .filter(|block| block.slot() > 0)
+            .collect::<Vec<_>>();
+        blocks.sort_by_key(|block| block.slot());
+        blocks
+    }
+
+    fn get_network_context() -> (
+        SyncNetworkContext<E>,
+        mpsc::UnboundedReceiver<NetworkMessage<E>>,
+        Arc<NetworkGlobals<E>>,
+    ) {
+        let log = get_logger();
+        let config = NetworkConfig::default();
+        let enr_key = CombinedKey::from_libp2p(&Keypair::generate_secp256k1()).unwrap();
+        let enr = build_enr::<E>(&enr_key, &config, EnrForkId::default()).unwrap();
+        let network_globals = Arc::new(NetworkGlobals::new(enr, 0, 0, &log));
+        let (network_send, network_recv) = mpsc::unbounded_channel();
+        (
+            SyncNetworkContext::new(network_send, network_globals.clone(), log),
+            network_recv,
+            network_globals,
+        )
+    }
+
+    /// Connects a new peer which has served one range request at `bytes_per_second`.
+    fn connect_peer(
+        network: &SyncNetworkContext<E>,
+        network_globals: &NetworkGlobals<E>,
+        bytes_per_second: usize,
+    ) -> PeerId {
+        let peer_id = PeerId::random();
+        network_globals.peers.write().connect_ingoing(&peer_id);
+        network.batch_response_received(
+            &peer_id,
+            Duration::from_millis(100),
+            bytes_per_second,
+            Duration::from_secs(1),
+        );
+        peer_id
+    }
+
+    /// Returns the peer of the next blocks by range request sent to the network.
+    fn next_requested_peer(
+        network_recv: &mut mpsc::UnboundedReceiver<NetworkMessage<E>>,
+    ) -> PeerId {
+        match network_recv.try_recv() {
+            Ok(NetworkMessage::SendRequest {
+                peer_id,
+                request: Request::BlocksByRange(_),
+                ..
+            }) => peer_id,
+            other => panic!("expected a blocks by range request, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_resumes_from_stored_batch_after_restart() {
+        let blocks = get_blocks(24);
+        let head = blocks.last().unwrap().clone();
+
+        let harness = get_harness();
+        harness.chain.slot_clock.set_slot(head.slot().as_u64());
+        let beacon_chain = Arc::new(harness.chain);
+
+        // Store the first batch of a chain which is interrupted by a restart.
+        let mut batch = Batch::new(BatchId(1), Slot::new(0), Slot::new(16), PeerId::random());
+        batch.downloaded_blocks = blocks
+            .iter()
+            .filter(|block| block.slot() < 16)
+            .cloned()
+            .collect();
+        let mut batch_store = BatchStore::open(beacon_chain.store.clone(), get_logger());
+        batch_store.put(&batch);
+        drop(batch_store);
+
+        // After the restart, a chain to a different target head uses the stored batch.
+        let batch_store = BatchStore::open(beacon_chain.store.clone(), get_logger());
+        let (mut network, mut network_recv, _network_globals) = get_network_context();
+        let (sync_send, mut sync_recv) = mpsc::unbounded_channel();
+        let mut chain = SyncingChain::new(
+            1,
+            Epoch::new(0),
+            head.slot(),
+            head.canonical_root(),
+            PeerId::random(),
+            sync_send,
+            beacon_chain.clone(),
+            Arc::new(Mutex::new(batch_store)),
+            get_logger(),
+        );
+        chain.start_syncing(&mut network, Epoch::new(0));
+
+        // Only the blocks after the stored batch are requested from the peer.
+        match network_recv.try_recv() {
+            Ok(NetworkMessage::SendRequest {
+                request: Request::BlocksByRange(request),
+                ..
+            }) => assert_eq!(request.start_slot, 16),
+            other => panic!("expected a blocks by range request, got {:?}", other),
+        }
+
+        // The stored blocks are imported.
+        let result = futures::executor::block_on(async {
+            loop {
+                match sync_recv.recv().await {
+                    Some(SyncMessage::BatchProcessed {
+                        batch_id, result, ..
+                    }) => break (batch_id, result),
+                    Some(_) => {}
+                    None => panic!("sync channel closed"),
+                }
+            }
+        });
+        assert_eq!(result.0, BatchId(1));
+        assert!(matches!(result.1, BatchProcessResult::Success));
+        assert!(beacon_chain
+            .fork_choice
+            .read()
+            .contains_block(&batch.downloaded_blocks.last().unwrap().canonical_root()));
+    }
+
+    #[test]
+    fn test_retries_batches_from_the_fastest_other_peer() {
+        let harness = get_harness();
+        let (mut network, mut network_recv, network_globals) = get_network_context();
+        let batch_store = BatchStore::open(harness.chain.store.clone(), get_logger());
+        let (sync_send, _sync_recv) = mpsc::unbounded_channel();
+
+        let failing_peer = connect_peer(&network, &network_globals, 100);
+        let slow_peer = connect_peer(&network, &network_globals, 1_000);
+        let fast_peer = connect_peer(&network, &network_globals, 10_000_000);
+
+        let mut chain = SyncingChain::new(
+            1,
+            Epoch::new(0),
+            Slot::new(64),
+            Hash256::zero(),
+            failing_peer.clone(),
+            sync_send,
+            Arc::new(harness.chain),
+            Arc::new(Mutex::new(batch_store)),
+            get_logger(),
+        );
+        // The chain is not syncing, so adding peers doesn't send any requests.
+        chain.add_peer(&mut network, slow_peer.clone());
+        chain.add_peer(&mut network, fast_peer.clone());
+        assert!(network_recv.try_recv().is_err());
+
+        // A batch which failed is re-requested from the fastest other peer.
+        let batch = Batch::new(
+            BatchId(1),
+            Slot::new(0),
+            Slot::new(16),
+            failing_peer.clone(),
+        );
+        assert!(chain.failed_batch(&mut network, batch) == ProcessingResult::KeepChain);
+        assert_eq!(next_requested_peer(&mut network_recv), fast_peer);
+
+        // An invalid batch is re-requested from the fastest peer other than the one which served
+        // it.
+        let batch = Batch::new(BatchId(2), Slot::new(16), Slot::new(32), fast_peer.clone());
+        chain.reprocess_batch(&mut network, batch);
+        assert_eq!(next_requested_peer(&mut network_recv), slow_peer);
+
+        // Peers which can't accept another request are only used if there's no alternative.
+        let batch = Batch::new(BatchId(3), Slot::new(32), Slot::new(48), fast_peer.clone());
+        chain.reprocess_batch(&mut network, batch);
+        assert_eq!(next_requested_peer(&mut network_recv), failing_peer);
+
+        let batch = Batch::new(BatchId(4), Slot::new(48), Slot::new(64), fast_peer);
+        chain.reprocess_batch(&mut network, batch);
+        assert_eq!(next_requested_peer(&mut network_recv), slow_peer);
+    }
+
+    #[test]
+    fn test_epochs_per_batch_defaults_when_unknown() {
+        assert_eq!(epochs_per_batch(None, None), EPOCHS_PER_BATCH);
+        assert_eq!(epochs_per_batch(Some(1_000.0), None), EPOCHS_PER_BATCH);
+        assert_eq!(epochs_per_batch(None, Some(1_000.0)), EPOCHS_PER_BATCH);
+    }
+
+    #[test]
+    fn test_epochs_per_batch_follows_bandwidth() {
+        let bytes_per_epoch = 100_000.0;
+        let target = TARGET_BATCH_DOWNLOAD_TIME.as_secs_f64();
+
+        assert_eq!(
+            epochs_per_batch(Some(3.0 * bytes_per_epoch / target), Some(bytes_per_epoch)),
+            3
+        );
+        assert_eq!(
+            epochs_per_batch(Some(1.0), Some(bytes_per_epoch)),
+            MIN_EPOCHS_PER_BATCH
+        );
+        assert_eq!(
+            epochs_per_batch(Some(f64::MAX), Some(bytes_per_epoch)),
+            MAX_EPOCHS_PER_BATCH
+        );
+    }
+
+    #[test]
+    fn test_max_requests_per_peer() {
+        assert_eq!(max_requests_per_peer(None), 1);
+        assert_eq!(max_requests_per_peer(Some(1_000.0)), 1);
+        assert_eq!(max_requests_per_peer(Some(FAST_PEER_BYTES_PER_SECOND)), 2);
+        assert_eq!(
+            max_requests_per_peer(Some(100.0 * FAST_PEER_BYTES_PER_SECOND)),
+            MAX_REQUESTS_PER_PEER
+        );
+    }
+}
@@ -3,12 +3,15 @@
 //! Each chain type is stored in it's own vector. A variety of helper functions are given along
 //! with this struct to to simplify the logic of the other layers of sync.
 
+use super::batch_store::BatchStore;
 use super::chain::{ChainSyncingState, SyncingChain};
 use crate::sync::manager::SyncMessage;
 use crate::sync::network_context::SyncNetworkContext;
 use crate::sync::PeerSyncInfo;
 use beacon_chain::{BeaconChain, BeaconChainTypes};
-use eth2_libp2p::{types::SyncState, NetworkGlobals, PeerId};
+use eth2_libp2p::types::{SyncChainType, SyncState};
+use eth2_libp2p::{NetworkGlobals, PeerId};
+use parking_lot::Mutex;
 use slog::{debug, error, info};
 use std::sync::Arc;
 use tokio::sync::mpsc;
@@ -85,6 +88,8 @@ pub struct ChainCollection<T: BeaconChainTypes> {
     head_chains: Vec<SyncingChain<T>>,
     /// The current sync state of the process.
     state: RangeSyncState,
+    /// The downloaded but unprocessed batches of all chains.
+    batch_store: Arc<Mutex<BatchStore<T>>>,
     /// Logger for the collection.
     log: slog::Logger,
 }
@@ -95,12 +100,14 @@ impl<T: BeaconChainTypes> ChainCollection<T> {
         network_globals: Arc<NetworkGlobals<T::EthSpec>>,
         log: slog::Logger,
     ) -> Self {
+        let batch_store = BatchStore::open(beacon_chain.store.clone(), log.clone());
         ChainCollection {
             beacon_chain,
             network_globals,
             finalized_chains: Vec::new(),
             head_chains: Vec::new(),
             state: RangeSyncState::Idle,
+            batch_store: Arc::new(Mutex::new(batch_store)),
             log,
         }
     }
@@ -139,6 +146,23 @@ impl<T: BeaconChainTypes> ChainCollection<T> {
             }
             *node_sync_state = new_state;
         }
+
+        self.update_chain_info();
+    }
+
+    /// Updates the globally visible progress of each chain.
+    pub fn update_chain_info(&self) {
+        let chains = self
+            .finalized_chains
+            .iter()
+            .map(|chain| chain.info(SyncChainType::Finalized))
+            .chain(
+                self.head_chains
+                    .iter()
+                    .map(|chain| chain.info(SyncChainType::Head)),
+            )
+            .collect();
+        *self.network_globals.sync_chains.write() = chains;
     }
 
     /// A fully synced peer has joined.
@@ -313,6 +337,7 @@ impl<T: BeaconChainTypes> ChainCollection<T> {
             peer_id,
             sync_send,
             self.beacon_chain.clone(),
+            self.batch_store.clone(),
             self.log.clone(),
         ));
     }
@@ -344,6 +369,7 @@ impl<T: BeaconChainTypes> ChainCollection<T> {
             peer_id,
             sync_send,
             self.beacon_chain.clone(),
+            self.batch_store.clone(),
             self.log.clone(),
         );
         // All head chains can sync simultaneously
@@ -512,6 +538,7 @@ impl<T: BeaconChainTypes> ChainCollection<T> {
 
         // update the state
         self.update_finalized(network);
+        self.update_chain_info();
     }
 
     /// Returns the index of finalized chain that is currently syncing. Returns `None` if no
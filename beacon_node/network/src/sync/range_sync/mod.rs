@@ -2,6 +2,7 @@
 //! peers.
 
 mod batch;
+mod batch_store;
 mod chain;
 mod chain_collection;
 mod range;
@@ -9,5 +10,5 @@ mod sync_type;
 
 pub use batch::Batch;
 pub use batch::BatchId;
-pub use chain::{ChainId, EPOCHS_PER_BATCH};
+pub use chain::{ChainId, MAX_EPOCHS_PER_BATCH};
 pub use range::RangeSync;
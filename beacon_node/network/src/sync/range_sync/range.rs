@@ -265,6 +265,9 @@ impl<T: BeaconChainTypes> RangeSync<T> {
             // the chain was purged due to being out of date whilst a request was pending. Log
             // and ignore.
             debug!(self.log, "Range response without matching request"; "peer" => format!("{:?}", peer_id), "request_id" => request_id);
+        } else if beacon_block.is_none() {
+            // a batch has completed
+            self.chains.update_chain_info();
         }
     }
 
@@ -314,7 +317,7 @@ impl<T: BeaconChainTypes> RangeSync<T> {
                     RangeSyncState::Finalized { .. } => {} // Have more finalized chains to complete
                 }
             }
-            Some((_, ProcessingResult::KeepChain)) => {}
+            Some((_, ProcessingResult::KeepChain)) => self.chains.update_chain_info(),
             None => {
                 match self.chains.head_request(|chain| {
                     chain.on_batch_process_result(
@@ -336,7 +339,7 @@ impl<T: BeaconChainTypes> RangeSync<T> {
                         // update the global state and log any change
                         self.chains.update_sync_state();
                     }
-                    Some((_, ProcessingResult::KeepChain)) => {}
+                    Some((_, ProcessingResult::KeepChain)) => self.chains.update_chain_info(),
                     None => {
                         // This can happen if a chain gets purged due to being out of date whilst a
                         // batch process is in progress.
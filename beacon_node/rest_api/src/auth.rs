@@ -0,0 +1,101 @@
+//! Optional bearer-token authentication for endpoints which modify the state of the node.
+
+use hyper::header::AUTHORIZATION;
+use hyper::{Body, Method, Request};
+
+/// The scheme which must prefix the token in the `Authorization` header.
+const BEARER_PREFIX: &str = "Bearer ";
+
+/// Returns `true` if the method of the request may modify the state of the node (e.g., by
+/// publishing a block or banning a peer).
+///
+/// Some `POST` routes only read from the node, using `POST` so that a large list of parameters can
+/// be supplied in the body. The router matches those routes before checking authorization, so
+/// they never reach this function.
+pub fn is_mutating(req: &Request<Body>) -> bool {
+    match *req.method() {
+        Method::GET | Method::HEAD | Method::OPTIONS => false,
+        _ => true,
+    }
+}
+
+/// Returns `true` if the request may be served. Requests which modify the node must carry an
+/// `Authorization: Bearer <token>` header which matches `api_token`, unless `api_token` is `None`.
+pub fn is_authorized(req: &Request<Body>, api_token: Option<&str>) -> bool {
+    let api_token = match api_token {
+        Some(token) if is_mutating(req) => token,
+        _ => return true,
+    };
+
+    req.headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| value.starts_with(BEARER_PREFIX))
+        .map_or(false, |value| {
+            constant_time_eq(
+                value[BEARER_PREFIX.len()..].trim().as_bytes(),
+                api_token.as_bytes(),
+            )
+        })
+}
+
+/// Compares `a` and `b` in time which depends only on their lengths, so that the token cannot be
+/// discovered byte-by-byte by timing failed requests.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(method: Method, path: &str, token: Option<&str>) -> Request<Body> {
+        let mut builder = Request::builder().method(method).uri(path);
+        if let Some(token) = token {
+            builder = builder.header(AUTHORIZATION, format!("{}{}", BEARER_PREFIX, token));
+        }
+        builder.body(Body::empty()).unwrap()
+    }
+
+    #[test]
+    fn mutating_requests() {
+        assert!(!is_mutating(&request(Method::GET, "/beacon/head", None)));
+        assert!(!is_mutating(&request(Method::HEAD, "/beacon/head", None)));
+        assert!(is_mutating(&request(
+            Method::POST,
+            "/validator/block",
+            None
+        )));
+        assert!(is_mutating(&request(
+            Method::POST,
+            "/network/peers/ban",
+            None
+        )));
+    }
+
+    #[test]
+    fn authorization() {
+        let token = Some("secret");
+
+        assert!(is_authorized(
+            &request(Method::POST, "/validator/block", None),
+            None
+        ));
+        assert!(is_authorized(
+            &request(Method::GET, "/beacon/head", None),
+            token
+        ));
+        assert!(is_authorized(
+            &request(Method::POST, "/validator/block", token),
+            token
+        ));
+        assert!(!is_authorized(
+            &request(Method::POST, "/validator/block", None),
+            token
+        ));
+        assert!(!is_authorized(
+            &request(Method::POST, "/validator/block", Some("secrets")),
+            token
+        ));
+    }
+}
@@ -2,13 +2,15 @@ use crate::helpers::*;
 use crate::response_builder::ResponseBuilder;
 use crate::validator::get_state_for_epoch;
 use crate::{ApiError, ApiResult, UrlQuery};
+use beacon_chain::events::EventKind;
 use beacon_chain::{
     observed_operations::ObservationOutcome, BeaconChain, BeaconChainTypes, StateSkipConfig,
 };
-use bus::BusReader;
-use futures::executor::block_on;
+use futures::channel::mpsc;
+use futures::future;
+use futures::stream::StreamExt;
 use hyper::body::Bytes;
-use hyper::{Body, Request, Response};
+use hyper::{Body, Request};
 use rest_types::{
     BlockResponse, CanonicalHeadResponse, Committee, HeadBeaconBlock, StateResponse,
     ValidatorRequest, ValidatorResponse,
@@ -19,7 +21,7 @@ use std::sync::Arc;
 use slog::{error, Logger};
 use types::{
     AttesterSlashing, BeaconState, EthSpec, Hash256, ProposerSlashing, PublicKeyBytes,
-    RelativeEpoch, SignedBeaconBlockHash, Slot,
+    RelativeEpoch, Slot,
 };
 
 /// HTTP handler to return a `BeaconBlock` at a given `root` or `slot`.
@@ -129,46 +131,68 @@ pub fn get_block_root<T: BeaconChainTypes>(
     ResponseBuilder::new(&req)?.body(&root)
 }
 
-fn make_sse_response_chunk(new_head_hash: SignedBeaconBlockHash) -> std::io::Result<Bytes> {
+fn make_sse_response_chunk(new_head_hash: Hash256) -> std::io::Result<Bytes> {
     let mut buffer = Vec::new();
     {
         let mut sse_message = uhttp_sse::SseMessage::new(&mut buffer);
-        let untyped_hash: Hash256 = new_head_hash.into();
-        write!(sse_message.data()?, "{:?}", untyped_hash)?;
+        write!(sse_message.data()?, "{:?}", new_head_hash)?;
     }
     let bytes: Bytes = buffer.into();
     Ok(bytes)
 }
 
+/// HTTP handler to stream the root of each new head block as a server-sent event.
 pub fn stream_forks<T: BeaconChainTypes>(
     log: Logger,
-    mut events: BusReader<SignedBeaconBlockHash>,
+    events: mpsc::Receiver<EventKind<T::EthSpec>>,
 ) -> ApiResult {
-    let (mut sender, body) = Body::channel();
-    std::thread::spawn(move || {
-        while let Ok(new_head_hash) = events.recv() {
-            let chunk = match make_sse_response_chunk(new_head_hash) {
-                Ok(chunk) => chunk,
+    let stream = events.filter_map(move |event| {
+        let chunk = match event {
+            EventKind::BeaconHeadChanged {
+                current_head_beacon_block_root,
+                ..
+            } => match make_sse_response_chunk(current_head_beacon_block_root) {
+                Ok(chunk) => Some(Ok::<_, std::io::Error>(chunk)),
                 Err(e) => {
                     error!(log, "Failed to make SSE chunk"; "error" => e.to_string());
-                    sender.abort();
-                    break;
+                    Some(Err(e))
                 }
-            };
-            if let Err(bytes) = block_on(sender.send_data(chunk)) {
-                error!(log, "Couldn't stream piece {:?}", bytes);
-            }
-        }
+            },
+            _ => None,
+        };
+        future::ready(chunk)
     });
-    let response = Response::builder()
-        .status(200)
-        .header("Content-Type", "text/event-stream")
-        .header("Connection", "Keep-Alive")
-        .header("Cache-Control", "no-cache")
-        .header("Access-Control-Allow-Origin", "*")
-        .body(body)
-        .map_err(|e| ApiError::ServerError(format!("Failed to build response: {:?}", e)))?;
-    Ok(response)
+
+    event_stream_response(Body::wrap_stream(stream))
+}
+
+/// HTTP handler to return the `LightClientFinalityProof` of the finalized block with the given
+/// `root`, or of the latest finalized block if no `root` is supplied.
+pub fn get_light_client_finality_proof<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+) -> ApiResult {
+    if !beacon_chain.light_client_server {
+        return Err(ApiError::MethodNotAllowed(
+            "Light client server is disabled, see --light-client-server".into(),
+        ));
+    }
+
+    let block_root = match UrlQuery::from_request(&req)?.first_of_opt(&["root"]) {
+        Some((_key, value)) => parse_root(&value)?,
+        None => beacon_chain.head_info()?.finalized_checkpoint.root,
+    };
+
+    let proof = beacon_chain
+        .get_light_client_finality_proof(&block_root)?
+        .ok_or_else(|| {
+            ApiError::NotFound(format!(
+                "Unable to find LightClientFinalityProof for root {:?}",
+                block_root
+            ))
+        })?;
+
+    ResponseBuilder::new(&req)?.body(&proof)
 }
 
 /// HTTP handler to return the `Fork` of the current head.
@@ -319,6 +343,26 @@ fn validator_responses_by_pubkey<T: BeaconChainTypes>(
     state_root_opt: Option<Hash256>,
     validator_pubkeys: Vec<PublicKeyBytes>,
 ) -> Result<Vec<ValidatorResponse>, ApiError> {
+    // Restore points in the freezer can serve individual validators without being decoded.
+    if let Some(state_root) = state_root_opt {
+        if beacon_chain.store.is_restore_point(&state_root)? {
+            return validator_pubkeys
+                .into_iter()
+                .map(|validator_pubkey| -> Result<_, ApiError> {
+                    let validator_opt =
+                        beacon_chain.restore_point_validator(&state_root, &validator_pubkey)?;
+
+                    Ok(ValidatorResponse {
+                        pubkey: validator_pubkey,
+                        validator_index: validator_opt.as_ref().map(|(index, _, _)| *index),
+                        balance: validator_opt.as_ref().map(|(_, _, balance)| *balance),
+                        validator: validator_opt.map(|(_, validator, _)| validator),
+                    })
+                })
+                .collect();
+        }
+    }
+
     let mut state = get_state_from_root_opt(&beacon_chain, state_root_opt)?;
     state.update_pubkey_cache()?;
 
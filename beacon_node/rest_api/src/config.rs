@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
-use std::net::Ipv4Addr;
+use std::net::{IpAddr, Ipv4Addr};
+use std::path::PathBuf;
 
 /// Defines the encoding for the API.
 #[derive(Clone, Serialize, Deserialize, Copy)]
@@ -29,23 +30,43 @@ impl From<&str> for ApiEncodingFormat {
     }
 }
 
+/// The PEM-encoded certificate chain and private key used to serve the REST API over HTTPS.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TlsConfig {
+    /// Path to the certificate chain.
+    pub cert: PathBuf,
+    /// Path to the private key, in either PKCS #8 or RSA format.
+    pub key: PathBuf,
+}
+
 /// HTTP REST API Configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     /// Enable the REST API server.
     pub enabled: bool,
-    /// The IPv4 address the REST API HTTP server will listen on.
-    pub listen_address: Ipv4Addr,
+    /// The IPv4 or IPv6 address the REST API HTTP server will listen on.
+    pub listen_address: IpAddr,
     /// The port the REST API HTTP server will listen on.
     pub port: u16,
+    /// If `Some`, the REST API is served over HTTPS instead of HTTP.
+    pub tls_config: Option<TlsConfig>,
+    /// The origins which may make cross-origin requests to the REST API. `"*"` allows any
+    /// origin. Cross-origin requests are refused if this is empty.
+    pub allow_origins: Vec<String>,
+    /// If `Some`, requests to endpoints which modify the state of the node must supply this token
+    /// in an `Authorization: Bearer <token>` header.
+    pub api_token: Option<String>,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Config {
             enabled: false,
-            listen_address: Ipv4Addr::new(127, 0, 0, 1),
+            listen_address: IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)),
             port: 5052,
+            tls_config: None,
+            allow_origins: vec![],
+            api_token: None,
         }
     }
 }
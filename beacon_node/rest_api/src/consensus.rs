@@ -3,10 +3,15 @@ use crate::response_builder::ResponseBuilder;
 use crate::{ApiError, ApiResult, UrlQuery};
 use beacon_chain::{BeaconChain, BeaconChainTypes};
 use hyper::{Body, Request};
-use rest_types::{IndividualVotesRequest, IndividualVotesResponse};
+use rest_types::{
+    IndividualRewardsRequest, IndividualRewardsResponse, IndividualVotesRequest,
+    IndividualVotesResponse,
+};
 use serde::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
-use state_processing::per_epoch_processing::{TotalBalances, ValidatorStatuses};
+use state_processing::per_epoch_processing::{
+    per_epoch_processing_with_reward_breakdown, TotalBalances, ValidatorStatuses,
+};
 use std::sync::Arc;
 use types::EthSpec;
 
@@ -137,3 +142,74 @@ pub async fn post_individual_votes<T: BeaconChainTypes>(
         })
         .and_then(|votes| response_builder?.body_no_ssz(&votes))
 }
+
+/// HTTP handler to return the rewards and penalties applied to each of the given validators for
+/// their attestations in some `Epoch`.
+///
+/// These are applied during the transition at the end of the following epoch, which is replayed
+/// from the state at its last slot.
+pub async fn post_individual_rewards<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+) -> ApiResult {
+    let response_builder = ResponseBuilder::new(&req);
+
+    let body = req.into_body();
+    let chunks = hyper::body::to_bytes(body)
+        .await
+        .map_err(|e| ApiError::ServerError(format!("Unable to get request body: {:?}", e)))?;
+
+    serde_json::from_slice::<IndividualRewardsRequest>(&chunks)
+        .map_err(|e| {
+            ApiError::BadRequest(format!(
+                "Unable to parse JSON into IndividualRewardsRequest: {:?}",
+                e
+            ))
+        })
+        .and_then(move |body| {
+            let epoch = body.epoch;
+
+            // This is the last slot of the epoch after the given epoch, where the attestations
+            // from the given epoch are rewarded.
+            let target_slot = (epoch + 2).start_slot(T::EthSpec::slots_per_epoch()) - 1;
+
+            let (_root, mut state) = state_at_slot(&beacon_chain, target_slot)?;
+            let spec = &beacon_chain.spec;
+
+            state.update_pubkey_cache().map_err(|e| {
+                ApiError::ServerError(format!("Unable to build pubkey cache: {:?}", e))
+            })?;
+
+            let validator_indices = body
+                .pubkeys
+                .iter()
+                .map(|pubkey| {
+                    state.get_validator_index(pubkey).map_err(|e| {
+                        ApiError::ServerError(format!("Unable to read pubkey cache: {:?}", e))
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let breakdowns = per_epoch_processing_with_reward_breakdown(&mut state, spec)?
+                .reward_breakdown
+                .ok_or_else(|| {
+                    ApiError::ServerError("Epoch processing omitted rewards".to_string())
+                })?;
+
+            Ok(body
+                .pubkeys
+                .into_iter()
+                .zip(validator_indices)
+                .map(|(pubkey, validator_index)| IndividualRewardsResponse {
+                    epoch,
+                    pubkey,
+                    validator_index,
+                    rewards: validator_index
+                        .and_then(|i| breakdowns.get(i))
+                        .cloned()
+                        .map(Into::into),
+                })
+                .collect::<Vec<_>>())
+        })
+        .and_then(|rewards| response_builder?.body_no_ssz(&rewards))
+}
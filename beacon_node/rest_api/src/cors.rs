@@ -0,0 +1,99 @@
+//! Cross-Origin Resource Sharing (CORS) support, allowing browser-based applications served from
+//! the configured origins to use the REST API.
+
+use hyper::header::{
+    HeaderValue, ACCESS_CONTROL_ALLOW_HEADERS, ACCESS_CONTROL_ALLOW_METHODS,
+    ACCESS_CONTROL_ALLOW_ORIGIN, ACCESS_CONTROL_MAX_AGE, ACCESS_CONTROL_REQUEST_METHOD, ORIGIN,
+    VARY,
+};
+use hyper::{Body, Method, Request, Response, StatusCode};
+
+/// The methods which may be used in a cross-origin request.
+const ALLOW_METHODS: &str = "GET, POST, OPTIONS";
+/// The headers which may be supplied in a cross-origin request.
+const ALLOW_HEADERS: &str = "Authorization, Content-Type, Accept";
+/// The number of seconds a browser may cache the response to a preflight request.
+const MAX_AGE_SECONDS: &str = "3600";
+
+/// Returns the value of the `Access-Control-Allow-Origin` header for a request, or `None` if the
+/// request has no `Origin` or its origin is not permitted by `allow_origins`.
+pub fn allowed_origin(req: &Request<Body>, allow_origins: &[String]) -> Option<HeaderValue> {
+    let origin = req.headers().get(ORIGIN)?;
+
+    if allow_origins.iter().any(|allowed| allowed == "*") {
+        Some(HeaderValue::from_static("*"))
+    } else if allow_origins
+        .iter()
+        .any(|allowed| allowed.as_bytes() == origin.as_bytes())
+    {
+        Some(origin.clone())
+    } else {
+        None
+    }
+}
+
+/// Returns `true` if the request is a CORS preflight request.
+pub fn is_preflight(req: &Request<Body>) -> bool {
+    *req.method() == Method::OPTIONS
+        && req.headers().contains_key(ORIGIN)
+        && req.headers().contains_key(ACCESS_CONTROL_REQUEST_METHOD)
+}
+
+/// Returns the response to a preflight request. The origin header is added later, by
+/// `add_allow_origin`.
+pub fn preflight_response() -> Response<Body> {
+    Response::builder()
+        .status(StatusCode::NO_CONTENT)
+        .header(ACCESS_CONTROL_ALLOW_METHODS, ALLOW_METHODS)
+        .header(ACCESS_CONTROL_ALLOW_HEADERS, ALLOW_HEADERS)
+        .header(ACCESS_CONTROL_MAX_AGE, MAX_AGE_SECONDS)
+        .body(Body::empty())
+        .expect("Response should always be created.")
+}
+
+/// Adds the `Access-Control-Allow-Origin` header to `response`, if `allowed_origin` is `Some`.
+pub fn add_allow_origin(response: &mut Response<Body>, allowed_origin: Option<HeaderValue>) {
+    if let Some(origin) = allowed_origin {
+        let headers = response.headers_mut();
+        headers.insert(ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+        // The response depends upon the origin of the request, so it must not be served from a
+        // cache to a different origin.
+        headers.append(VARY, HeaderValue::from_static("Origin"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(origin: Option<&str>) -> Request<Body> {
+        let mut builder = Request::builder().uri("/beacon/head");
+        if let Some(origin) = origin {
+            builder = builder.header(ORIGIN, origin);
+        }
+        builder.body(Body::empty()).unwrap()
+    }
+
+    #[test]
+    fn origins() {
+        let allow_origins = vec!["http://localhost:8080".to_string()];
+
+        assert_eq!(
+            allowed_origin(&request(Some("http://localhost:8080")), &allow_origins),
+            Some(HeaderValue::from_static("http://localhost:8080"))
+        );
+        assert_eq!(
+            allowed_origin(&request(Some("http://example.com")), &allow_origins),
+            None
+        );
+        assert_eq!(allowed_origin(&request(None), &allow_origins), None);
+        assert_eq!(
+            allowed_origin(&request(Some("http://localhost:8080")), &[]),
+            None
+        );
+        assert_eq!(
+            allowed_origin(&request(Some("http://example.com")), &["*".to_string()]),
+            Some(HeaderValue::from_static("*"))
+        );
+    }
+}
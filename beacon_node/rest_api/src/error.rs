@@ -1,4 +1,5 @@
 use hyper::{Body, Response, StatusCode};
+use rest_types::ErrorMessage;
 use std::error::Error as StdError;
 
 #[derive(PartialEq, Debug, Clone)]
@@ -7,6 +8,7 @@ pub enum ApiError {
     ServerError(String),
     NotImplemented(String),
     BadRequest(String),
+    Unauthorized(String),
     NotFound(String),
     UnsupportedType(String),
     ImATeapot(String),       // Just in case.
@@ -22,6 +24,7 @@ impl ApiError {
             ApiError::ServerError(desc) => (StatusCode::INTERNAL_SERVER_ERROR, desc),
             ApiError::NotImplemented(desc) => (StatusCode::NOT_IMPLEMENTED, desc),
             ApiError::BadRequest(desc) => (StatusCode::BAD_REQUEST, desc),
+            ApiError::Unauthorized(desc) => (StatusCode::UNAUTHORIZED, desc),
             ApiError::NotFound(desc) => (StatusCode::NOT_FOUND, desc),
             ApiError::UnsupportedType(desc) => (StatusCode::UNSUPPORTED_MEDIA_TYPE, desc),
             ApiError::ImATeapot(desc) => (StatusCode::IM_A_TEAPOT, desc),
@@ -30,6 +33,25 @@ impl ApiError {
     }
 }
 
+impl ApiError {
+    /// Converts the error into a response with an `ErrorMessage` body, as used by the standard
+    /// Eth2 API.
+    pub fn into_json_response(self) -> Response<Body> {
+        let (status_code, message) = self.status_code();
+        let body = serde_json::to_string(&ErrorMessage {
+            code: status_code.as_u16(),
+            message,
+        })
+        .expect("ErrorMessage should always serialize.");
+
+        Response::builder()
+            .status(status_code)
+            .header("content-type", "application/json")
+            .body(Body::from(body))
+            .expect("Response should always be created.")
+    }
+}
+
 impl Into<Response<Body>> for ApiError {
     fn into(self) -> Response<Body> {
         let (status_code, desc) = self.status_code();
@@ -65,6 +87,12 @@ impl From<state_processing::per_slot_processing::Error> for ApiError {
     }
 }
 
+impl From<state_processing::EpochProcessingError> for ApiError {
+    fn from(e: state_processing::EpochProcessingError) -> ApiError {
+        ApiError::ServerError(format!("PerEpochProcessing error: {:?}", e))
+    }
+}
+
 impl From<hyper::error::Error> for ApiError {
     fn from(e: hyper::error::Error) -> ApiError {
         ApiError::ServerError(format!("Networking error: {:?}", e))
@@ -0,0 +1,47 @@
+use crate::helpers::event_stream_response;
+use crate::{ApiResult, UrlQuery};
+use beacon_chain::events::{EventKind, EventSubscribers};
+use futures::future;
+use futures::stream::StreamExt;
+use hyper::body::Bytes;
+use hyper::{Body, Request};
+use slog::{error, Logger};
+use types::EthSpec;
+
+/// Formats `event` as a server-sent event, using the event name as the SSE `event` field and its
+/// JSON encoding as the `data` field.
+fn make_sse_event_chunk<T: EthSpec>(event: &EventKind<T>) -> Result<Bytes, serde_json::Error> {
+    // The JSON encoding is compact, so it never spans multiple `data` lines.
+    let data = serde_json::to_string(event)?;
+    Ok(format!("event: {}\ndata: {}\n\n", event.name(), data).into())
+}
+
+/// HTTP handler to stream all events matching the `topics` in the query as server-sent events.
+///
+/// E.g., `/events?topics=head,finalized_checkpoint`
+pub fn stream_events<T: EthSpec>(
+    req: Request<Body>,
+    events: EventSubscribers<T>,
+    log: Logger,
+) -> ApiResult {
+    let topics = UrlQuery::from_request(&req)?.event_topics()?;
+
+    let stream = events.subscribe(topics).filter_map(move |event| {
+        let chunk = match make_sse_event_chunk(&event) {
+            Ok(chunk) => Some(Ok::<_, std::io::Error>(chunk)),
+            // Skip events which cannot be serialized rather than closing the stream.
+            Err(e) => {
+                error!(
+                    log,
+                    "Failed to make SSE chunk";
+                    "event" => event.name(),
+                    "error" => e.to_string()
+                );
+                None
+            }
+        };
+        future::ready(chunk)
+    });
+
+    event_stream_response(Body::wrap_stream(stream))
+}
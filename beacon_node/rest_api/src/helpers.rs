@@ -4,9 +4,10 @@ use bls::PublicKeyBytes;
 use eth2_libp2p::PubsubMessage;
 use hex;
 use http::header;
-use hyper::{Body, Request};
+use hyper::{Body, Request, Response};
 use itertools::process_results;
 use network::NetworkMessage;
+use serde::de::DeserializeOwned;
 use ssz::Decode;
 use store::iter::AncestorIter;
 use types::{
@@ -235,6 +236,37 @@ pub fn implementation_pending_response(_req: Request<Body>) -> ApiResult {
     ))
 }
 
+/// Builds a `text/event-stream` response which streams `body` to the client.
+pub fn event_stream_response(body: Body) -> ApiResult {
+    Response::builder()
+        .status(200)
+        .header("Content-Type", "text/event-stream")
+        .header("Connection", "Keep-Alive")
+        .header("Cache-Control", "no-cache")
+        .body(body)
+        .map_err(|e| ApiError::ServerError(format!("Failed to build response: {:?}", e)))
+}
+
+/// Reads the body of `req` and parses it as JSON.
+pub async fn parse_json_body<T: DeserializeOwned>(req: Request<Body>) -> Result<T, ApiError> {
+    let chunks = hyper::body::to_bytes(req.into_body())
+        .await
+        .map_err(|e| ApiError::ServerError(format!("Unable to get request body: {:?}", e)))?;
+
+    serde_json::from_slice(&chunks)
+        .map_err(|e| ApiError::BadRequest(format!("Unable to parse JSON body: {:?}", e)))
+}
+
+/// Sends `message` to the network service.
+pub fn send_to_network<T: BeaconChainTypes>(
+    network_chan: &NetworkChannel<T::EthSpec>,
+    message: NetworkMessage<T::EthSpec>,
+) -> Result<(), ApiError> {
+    network_chan.send(message).map_err(|e| {
+        ApiError::ServerError(format!("Unable to send message to the network: {:?}", e))
+    })
+}
+
 pub fn publish_beacon_block_to_network<T: BeaconChainTypes + 'static>(
     chan: NetworkChannel<T::EthSpec>,
     block: SignedBeaconBlock<T::EthSpec>,
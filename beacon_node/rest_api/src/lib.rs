@@ -5,10 +5,13 @@ extern crate lazy_static;
 extern crate network as client_network;
 
 mod advanced;
+mod auth;
 mod beacon;
 pub mod config;
 mod consensus;
+mod cors;
 mod error;
+mod events;
 mod helpers;
 mod lighthouse;
 mod metrics;
@@ -17,31 +20,35 @@ mod node;
 mod response_builder;
 mod router;
 mod spec;
+mod tls;
 mod url_query;
+mod v1;
 mod validator;
 
+use beacon_chain::events::EventSubscribers;
 use beacon_chain::{BeaconChain, BeaconChainTypes};
-use bus::Bus;
 use client_network::NetworkMessage;
 pub use config::ApiEncodingFormat;
 use error::{ApiError, ApiResult};
 use eth2_config::Eth2Config;
 use eth2_libp2p::NetworkGlobals;
-use futures::future::TryFutureExt;
+use futures::future::{Future, TryFutureExt};
+use hyper::server::accept;
 use hyper::server::conn::AddrStream;
 use hyper::service::{make_service_fn, service_fn};
 use hyper::{Body, Request, Server};
-use parking_lot::Mutex;
 use slog::{info, warn};
 use std::net::SocketAddr;
 use std::path::PathBuf;
+use std::pin::Pin;
 use std::sync::Arc;
+use tokio::net::{TcpListener, TcpStream};
 use tokio::sync::mpsc;
-use types::SignedBeaconBlockHash;
+use tokio_rustls::server::TlsStream;
 use url_query::UrlQuery;
 
 pub use crate::helpers::parse_pubkey_bytes;
-pub use config::Config;
+pub use config::{Config, TlsConfig};
 
 pub type NetworkChannel<T> = mpsc::UnboundedSender<NetworkMessage<T>>;
 
@@ -60,48 +67,32 @@ pub fn start_server<T: BeaconChainTypes>(
     db_path: PathBuf,
     freezer_db_path: PathBuf,
     eth2_config: Eth2Config,
-    events: Arc<Mutex<Bus<SignedBeaconBlockHash>>>,
-) -> Result<SocketAddr, hyper::Error> {
+    events: EventSubscribers<T::EthSpec>,
+) -> Result<SocketAddr, String> {
     let log = executor.log();
     let inner_log = log.clone();
     let eth2_config = Arc::new(eth2_config);
+    let api_config = Arc::new(config.clone());
+    let network_globals = network_info.network_globals;
+    let network_channel = network_info.network_chan;
 
-    // Define the function that will build the request handler.
-    let make_service = make_service_fn(move |_socket: &AddrStream| {
-        let beacon_chain = beacon_chain.clone();
-        let log = inner_log.clone();
-        let eth2_config = eth2_config.clone();
-        let network_globals = network_info.network_globals.clone();
-        let network_channel = network_info.network_chan.clone();
-        let db_path = db_path.clone();
-        let freezer_db_path = freezer_db_path.clone();
-        let events = events.clone();
-
-        async move {
-            Ok::<_, hyper::Error>(service_fn(move |req: Request<Body>| {
-                router::route(
-                    req,
-                    beacon_chain.clone(),
-                    network_globals.clone(),
-                    network_channel.clone(),
-                    eth2_config.clone(),
-                    log.clone(),
-                    db_path.clone(),
-                    freezer_db_path.clone(),
-                    events.clone(),
-                )
-            }))
-        }
-    });
-
-    let bind_addr = (config.listen_address, config.port).into();
-    let server = Server::bind(&bind_addr).serve(make_service);
-
-    // Determine the address the server is actually listening on.
-    //
-    // This may be different to `bind_addr` if bind port was 0 (this allows the OS to choose a free
-    // port).
-    let actual_listen_addr = server.local_addr();
+    // Define the function that will handle each request. It is cloned for each connection.
+    let handle_request = move |req: Request<Body>| {
+        router::route(
+            req,
+            beacon_chain.clone(),
+            network_globals.clone(),
+            network_channel.clone(),
+            eth2_config.clone(),
+            api_config.clone(),
+            inner_log.clone(),
+            db_path.clone(),
+            freezer_db_path.clone(),
+            events.clone(),
+        )
+    };
+
+    let bind_addr = SocketAddr::new(config.listen_address, config.port);
 
     // Build a channel to kill the HTTP server.
     let exit = executor.exit();
@@ -112,11 +103,60 @@ pub fn start_server<T: BeaconChainTypes>(
     };
 
     // Configure the `hyper` server to gracefully shutdown when the shutdown channel is triggered.
+    //
+    // The server is boxed since its type depends upon whether or not TLS is enabled.
+    let (actual_listen_addr, server): (
+        SocketAddr,
+        Pin<Box<dyn Future<Output = Result<(), hyper::Error>> + Send>>,
+    ) = if let Some(tls_config) = &config.tls_config {
+        let acceptor = tls::tls_acceptor(tls_config)?;
+
+        let listener = std::net::TcpListener::bind(bind_addr)
+            .map_err(|e| format!("Unable to bind HTTP server to {}: {:?}", bind_addr, e))?;
+        let actual_listen_addr = listener
+            .local_addr()
+            .map_err(|e| format!("Unable to read HTTP server address: {:?}", e))?;
+        let listener = TcpListener::from_std(listener)
+            .map_err(|e| format!("Unable to register HTTP server listener: {:?}", e))?;
+
+        let make_service = make_service_fn(move |_socket: &TlsStream<TcpStream>| {
+            let handle_request = handle_request.clone();
+            async move { Ok::<_, hyper::Error>(service_fn(handle_request)) }
+        });
+
+        let server = Server::builder(accept::from_stream(tls::incoming(
+            listener,
+            acceptor,
+            log.clone(),
+        )))
+        .serve(make_service)
+        .with_graceful_shutdown(server_exit);
+
+        (actual_listen_addr, Box::pin(server))
+    } else {
+        let make_service = make_service_fn(move |_socket: &AddrStream| {
+            let handle_request = handle_request.clone();
+            async move { Ok::<_, hyper::Error>(service_fn(handle_request)) }
+        });
+
+        let server = Server::try_bind(&bind_addr)
+            .map_err(|e| format!("Unable to bind HTTP server to {}: {:?}", bind_addr, e))?
+            .serve(make_service);
+
+        // Determine the address the server is actually listening on.
+        //
+        // This may be different to `bind_addr` if bind port was 0 (this allows the OS to choose a
+        // free port).
+        let actual_listen_addr = server.local_addr();
+
+        (
+            actual_listen_addr,
+            Box::pin(server.with_graceful_shutdown(server_exit)),
+        )
+    };
+
     let inner_log = log.clone();
     let server_future = server
-        .with_graceful_shutdown(async {
-            server_exit.await;
-        })
         .map_err(move |e| {
             warn!(
             inner_log,
@@ -130,6 +170,7 @@ pub fn start_server<T: BeaconChainTypes>(
         "HTTP API started";
         "address" => format!("{}", actual_listen_addr.ip()),
         "port" => actual_listen_addr.port(),
+        "tls" => config.tls_config.is_some(),
     );
 
     executor.spawn_without_exit(server_future, "http");
@@ -2,18 +2,23 @@
 
 use crate::response_builder::ResponseBuilder;
 use crate::ApiResult;
+use eth2_libp2p::types::{SyncChainInfo, SyncState};
 use eth2_libp2p::{NetworkGlobals, PeerInfo};
 use hyper::{Body, Request};
 use serde::Serialize;
 use std::sync::Arc;
 use types::EthSpec;
 
-/// The syncing state of the beacon node.
+/// The syncing state of the beacon node and the progress of each range sync chain.
 pub fn syncing<T: EthSpec>(
     req: Request<Body>,
     network_globals: Arc<NetworkGlobals<T>>,
 ) -> ApiResult {
-    ResponseBuilder::new(&req)?.body_no_ssz(&network_globals.sync_state())
+    let syncing = Syncing {
+        sync_state: network_globals.sync_state(),
+        chains: network_globals.sync_chains(),
+    };
+    ResponseBuilder::new(&req)?.body_no_ssz(&syncing)
 }
 
 /// Returns all known peers and corresponding information
@@ -47,6 +52,15 @@ pub fn connected_peers<T: EthSpec>(
     ResponseBuilder::new(&req)?.body_no_ssz(&peers)
 }
 
+/// Information returned by `syncing`.
+#[derive(Clone, Debug, Serialize)]
+struct Syncing {
+    /// The sync state of the node.
+    sync_state: SyncState,
+    /// The progress of each chain being downloaded by range sync.
+    chains: Vec<SyncChainInfo>,
+}
+
 /// Information returned by `peers` and `connected_peers`.
 #[derive(Clone, Debug, Serialize)]
 #[serde(bound = "T: EthSpec")]
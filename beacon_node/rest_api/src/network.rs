@@ -1,9 +1,14 @@
-use crate::error::ApiResult;
+use crate::error::{ApiError, ApiResult};
+use crate::helpers::{check_content_type_for_json, parse_json_body, send_to_network};
 use crate::response_builder::ResponseBuilder;
-use crate::NetworkGlobals;
+use crate::{NetworkChannel, NetworkGlobals};
 use beacon_chain::BeaconChainTypes;
-use eth2_libp2p::{Multiaddr, PeerId};
+use eth2_libp2p::rpc::GoodbyeReason;
+use eth2_libp2p::{Enr, Multiaddr, PeerId};
 use hyper::{Body, Request};
+use network::NetworkMessage;
+use rest_types::{ConnectPeerRequest, DisconnectPeerRequest, PeerIdRequest};
+use std::str::FromStr;
 use std::sync::Arc;
 
 /// HTTP handler to return the list of libp2p multiaddr the client is listening on.
@@ -70,3 +75,100 @@ pub fn get_peer_list<T: BeaconChainTypes>(
         .collect();
     ResponseBuilder::new(&req)?.body_no_ssz(&connected_peers)
 }
+
+/// HTTP handler to dial a new peer, given either a multiaddr or an ENR.
+pub async fn post_connect_peer<T: BeaconChainTypes>(
+    req: Request<Body>,
+    network_chan: NetworkChannel<T::EthSpec>,
+) -> ApiResult {
+    try_future!(check_content_type_for_json(&req));
+    let response_builder = ResponseBuilder::new(&req);
+
+    let request: ConnectPeerRequest = parse_json_body(req).await?;
+    let message = match (request.multiaddr, request.enr) {
+        (Some(multiaddr), None) => {
+            let multiaddr = multiaddr
+                .parse::<Multiaddr>()
+                .map_err(|e| ApiError::BadRequest(format!("Invalid multiaddr: {:?}", e)))?;
+            NetworkMessage::DialMultiaddr { multiaddr }
+        }
+        (None, Some(enr)) => {
+            let enr = Enr::from_str(&enr)
+                .map_err(|e| ApiError::BadRequest(format!("Invalid ENR: {:?}", e)))?;
+            NetworkMessage::DialEnr { enr }
+        }
+        _ => {
+            return Err(ApiError::BadRequest(
+                "Exactly one of multiaddr or enr must be supplied".to_string(),
+            ))
+        }
+    };
+
+    send_to_network::<T>(&network_chan, message)?;
+    response_builder?.body_no_ssz(&())
+}
+
+/// HTTP handler to disconnect a peer, sending it a goodbye with the given reason.
+///
+/// The peer is not banned and its score is not affected.
+pub async fn post_disconnect_peer<T: BeaconChainTypes>(
+    req: Request<Body>,
+    network_chan: NetworkChannel<T::EthSpec>,
+) -> ApiResult {
+    try_future!(check_content_type_for_json(&req));
+    let response_builder = ResponseBuilder::new(&req);
+
+    let request: DisconnectPeerRequest = parse_json_body(req).await?;
+    let peer_id = parse_peer_id(&request.peer_id)?;
+    let reason = GoodbyeReason::from(request.reason);
+
+    send_to_network::<T>(
+        &network_chan,
+        NetworkMessage::DisconnectPeer { peer_id, reason },
+    )?;
+    response_builder?.body_no_ssz(&())
+}
+
+/// HTTP handler to ban a peer, disconnecting it if it is connected.
+pub async fn post_ban_peer<T: BeaconChainTypes>(
+    req: Request<Body>,
+    network_chan: NetworkChannel<T::EthSpec>,
+) -> ApiResult {
+    try_future!(check_content_type_for_json(&req));
+    let response_builder = ResponseBuilder::new(&req);
+
+    let request: PeerIdRequest = parse_json_body(req).await?;
+    let peer_id = parse_peer_id(&request.peer_id)?;
+
+    send_to_network::<T>(&network_chan, NetworkMessage::BanPeer { peer_id })?;
+    response_builder?.body_no_ssz(&())
+}
+
+/// HTTP handler to remove the ban on a peer.
+pub async fn post_unban_peer<T: BeaconChainTypes>(
+    req: Request<Body>,
+    network_chan: NetworkChannel<T::EthSpec>,
+) -> ApiResult {
+    try_future!(check_content_type_for_json(&req));
+    let response_builder = ResponseBuilder::new(&req);
+
+    let request: PeerIdRequest = parse_json_body(req).await?;
+    let peer_id = parse_peer_id(&request.peer_id)?;
+
+    send_to_network::<T>(&network_chan, NetworkMessage::UnbanPeer { peer_id })?;
+    response_builder?.body_no_ssz(&())
+}
+
+/// HTTP handler to trigger a discovery query for new peers.
+pub fn post_discover_peers<T: BeaconChainTypes>(
+    req: Request<Body>,
+    network_chan: NetworkChannel<T::EthSpec>,
+) -> ApiResult {
+    send_to_network::<T>(&network_chan, NetworkMessage::DiscoverPeers)?;
+    ResponseBuilder::new(&req)?.body_no_ssz(&())
+}
+
+/// Parse a base58-encoded `PeerId`.
+fn parse_peer_id(string: &str) -> Result<PeerId, ApiError> {
+    PeerId::from_str(string).map_err(|e| ApiError::BadRequest(format!("Invalid peer id: {:?}", e)))
+}
@@ -1,18 +1,17 @@
 use crate::{
-    advanced, beacon, consensus, error::ApiError, helpers, lighthouse, metrics, network, node,
-    spec, validator, NetworkChannel,
+    advanced, auth, beacon, consensus, cors, error::ApiError, events, helpers, lighthouse, metrics,
+    network, node, spec, v1, validator, Config, NetworkChannel,
 };
+use beacon_chain::events::{EventSubscribers, EventTopic};
 use beacon_chain::{BeaconChain, BeaconChainTypes};
-use bus::Bus;
 use eth2_config::Eth2Config;
 use eth2_libp2p::NetworkGlobals;
 use hyper::{Body, Error, Method, Request, Response};
-use parking_lot::Mutex;
 use slog::debug;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Instant;
-use types::{SignedBeaconBlockHash, Slot};
+use types::Slot;
 
 // Allowing more than 7 arguments.
 #[allow(clippy::too_many_arguments)]
@@ -22,19 +21,49 @@ pub async fn route<T: BeaconChainTypes>(
     network_globals: Arc<NetworkGlobals<T::EthSpec>>,
     network_channel: NetworkChannel<T::EthSpec>,
     eth2_config: Arc<Eth2Config>,
+    api_config: Arc<Config>,
     local_log: slog::Logger,
     db_path: PathBuf,
     freezer_db_path: PathBuf,
-    events: Arc<Mutex<Bus<SignedBeaconBlockHash>>>,
+    events: EventSubscribers<T::EthSpec>,
 ) -> Result<Response<Body>, Error> {
     metrics::inc_counter(&metrics::REQUEST_COUNT);
     let timer = metrics::start_timer(&metrics::REQUEST_RESPONSE_TIME);
     let received_instant = Instant::now();
 
     let path = req.uri().path().to_string();
+    let allowed_origin = cors::allowed_origin(&req, &api_config.allow_origins);
 
     let log = local_log.clone();
     let request_result = match (req.method(), path.as_ref()) {
+        // Cross-origin preflight requests are answered before authorization, since browsers do
+        // not send credentials with them.
+        _ if cors::is_preflight(&req) => Ok(cors::preflight_response()),
+
+        // `POST` methods which only read from the node, so they are matched before authorization
+        // is checked. They use `POST` so that a large list of parameters can be supplied in the
+        // body, rather than because they have side-effects.
+        (&Method::POST, "/beacon/validators") => {
+            beacon::post_validators::<T>(req, beacon_chain).await
+        }
+        (&Method::POST, "/validator/duties") => {
+            let timer = metrics::start_timer(&metrics::VALIDATOR_GET_DUTIES_REQUEST_RESPONSE_TIME);
+            let response = validator::post_validator_duties::<T>(req, beacon_chain);
+            drop(timer);
+            response.await
+        }
+        (&Method::POST, "/consensus/individual_votes") => {
+            consensus::post_individual_votes::<T>(req, beacon_chain).await
+        }
+        (&Method::POST, "/consensus/individual_rewards") => {
+            consensus::post_individual_rewards::<T>(req, beacon_chain).await
+        }
+
+        // Any other route which may modify the state of the node requires the API token.
+        _ if !auth::is_authorized(&req, api_config.api_token.as_deref()) => Err(
+            ApiError::Unauthorized("Missing or invalid API token.".to_owned()),
+        ),
+
         // Methods for Client
         (&Method::GET, "/node/health") => node::get_health(req),
         (&Method::GET, "/node/version") => node::get_version(req),
@@ -59,25 +88,43 @@ pub async fn route<T: BeaconChainTypes>(
         (&Method::GET, "/network/listen_addresses") => {
             network::get_listen_addresses::<T>(req, network_globals)
         }
+        (&Method::POST, "/network/peers/connect") => {
+            network::post_connect_peer::<T>(req, network_channel).await
+        }
+        (&Method::POST, "/network/peers/disconnect") => {
+            network::post_disconnect_peer::<T>(req, network_channel).await
+        }
+        (&Method::POST, "/network/peers/ban") => {
+            network::post_ban_peer::<T>(req, network_channel).await
+        }
+        (&Method::POST, "/network/peers/unban") => {
+            network::post_unban_peer::<T>(req, network_channel).await
+        }
+        (&Method::POST, "/network/discover") => {
+            network::post_discover_peers::<T>(req, network_channel)
+        }
+
+        // Methods for Events
+        (&Method::GET, "/events") => events::stream_events(req, events, log),
 
         // Methods for Beacon Node
         (&Method::GET, "/beacon/head") => beacon::get_head::<T>(req, beacon_chain),
         (&Method::GET, "/beacon/heads") => beacon::get_heads::<T>(req, beacon_chain),
         (&Method::GET, "/beacon/block") => beacon::get_block::<T>(req, beacon_chain),
         (&Method::GET, "/beacon/block_root") => beacon::get_block_root::<T>(req, beacon_chain),
+        (&Method::GET, "/beacon/light_client/finality_proof") => {
+            beacon::get_light_client_finality_proof::<T>(req, beacon_chain)
+        }
         (&Method::GET, "/beacon/fork") => beacon::get_fork::<T>(req, beacon_chain),
         (&Method::GET, "/beacon/fork/stream") => {
-            let reader = events.lock().add_rx();
-            beacon::stream_forks::<T>(log, reader)
+            let receiver = events.subscribe(vec![EventTopic::Head]);
+            beacon::stream_forks::<T>(log, receiver)
         }
         (&Method::GET, "/beacon/genesis_time") => beacon::get_genesis_time::<T>(req, beacon_chain),
         (&Method::GET, "/beacon/genesis_validators_root") => {
             beacon::get_genesis_validators_root::<T>(req, beacon_chain)
         }
         (&Method::GET, "/beacon/validators") => beacon::get_validators::<T>(req, beacon_chain),
-        (&Method::POST, "/beacon/validators") => {
-            beacon::post_validators::<T>(req, beacon_chain).await
-        }
         (&Method::GET, "/beacon/validators/all") => {
             beacon::get_all_validators::<T>(req, beacon_chain)
         }
@@ -98,12 +145,6 @@ pub async fn route<T: BeaconChainTypes>(
         }
 
         // Methods for Validator
-        (&Method::POST, "/validator/duties") => {
-            let timer = metrics::start_timer(&metrics::VALIDATOR_GET_DUTIES_REQUEST_RESPONSE_TIME);
-            let response = validator::post_validator_duties::<T>(req, beacon_chain);
-            drop(timer);
-            response.await
-        }
         (&Method::POST, "/validator/subscribe") => {
             validator::post_validator_subscriptions::<T>(req, network_channel).await
         }
@@ -144,9 +185,6 @@ pub async fn route<T: BeaconChainTypes>(
         (&Method::GET, "/consensus/global_votes") => {
             consensus::get_vote_count::<T>(req, beacon_chain)
         }
-        (&Method::POST, "/consensus/individual_votes") => {
-            consensus::post_individual_votes::<T>(req, beacon_chain).await
-        }
 
         // Methods for bootstrap and checking configuration
         (&Method::GET, "/spec") => spec::get_spec::<T>(req, beacon_chain),
@@ -178,6 +216,19 @@ pub async fn route<T: BeaconChainTypes>(
         (&Method::GET, "/lighthouse/connected_peers") => {
             lighthouse::connected_peers::<T::EthSpec>(req, network_globals)
         }
+
+        // Standard Eth2 API
+        (_, path) if path.starts_with(v1::PATH_PREFIX) => {
+            v1::route::<T>(
+                req,
+                path,
+                beacon_chain,
+                network_globals,
+                network_channel,
+                log,
+            )
+            .await
+        }
         _ => Err(ApiError::NotFound(
             "Request path and/or method not found.".to_owned(),
         )),
@@ -188,7 +239,7 @@ pub async fn route<T: BeaconChainTypes>(
     // (e.g., a response with a 404 or 500 status).
     let duration = Instant::now().duration_since(received_instant);
     match request_result {
-        Ok(response) => {
+        Ok(mut response) => {
             debug!(
                 local_log,
                 "HTTP API request successful";
@@ -198,10 +249,15 @@ pub async fn route<T: BeaconChainTypes>(
             metrics::inc_counter(&metrics::SUCCESS_COUNT);
             metrics::stop_timer(timer);
 
+            cors::add_allow_origin(&mut response, allowed_origin);
             Ok(response)
         }
         Err(e) => {
-            let error_response = e.into();
+            let mut error_response = if path.starts_with(v1::PATH_PREFIX) {
+                e.into_json_response()
+            } else {
+                e.into()
+            };
 
             debug!(
                 local_log,
@@ -211,6 +267,7 @@ pub async fn route<T: BeaconChainTypes>(
             );
             metrics::stop_timer(timer);
 
+            cors::add_allow_origin(&mut error_response, allowed_origin);
             Ok(error_response)
         }
     }
@@ -0,0 +1,103 @@
+//! Serves the REST API over HTTPS, using the certificate and key in a `TlsConfig`.
+
+use crate::config::TlsConfig;
+use futures::future;
+use futures::stream::{self, Stream, StreamExt};
+use slog::{debug, warn, Logger};
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio_rustls::rustls::internal::pemfile::{certs, pkcs8_private_keys, rsa_private_keys};
+use tokio_rustls::rustls::{NoClientAuth, PrivateKey, ServerConfig};
+use tokio_rustls::server::TlsStream;
+use tokio_rustls::TlsAcceptor;
+
+/// Connections which have not completed the TLS handshake within this time are dropped.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+/// The maximum number of TLS handshakes which may be in progress at once.
+const MAX_CONCURRENT_HANDSHAKES: usize = 64;
+
+/// Builds a `TlsAcceptor` from the PEM-encoded certificate chain and private key in `config`.
+pub fn tls_acceptor(config: &TlsConfig) -> Result<TlsAcceptor, String> {
+    let cert_chain = certs(&mut open(&config.cert)?)
+        .map_err(|_| format!("Unable to parse certificates in {:?}", config.cert))?;
+    if cert_chain.is_empty() {
+        return Err(format!("No certificates found in {:?}", config.cert));
+    }
+
+    let key = read_private_key(config)?;
+
+    let mut server_config = ServerConfig::new(NoClientAuth::new());
+    server_config
+        .set_single_cert(cert_chain, key)
+        .map_err(|e| format!("Invalid TLS certificate or key: {:?}", e))?;
+
+    Ok(TlsAcceptor::from(Arc::new(server_config)))
+}
+
+/// Reads the first PKCS #8 private key in the key file, falling back to the first RSA private key.
+fn read_private_key(config: &TlsConfig) -> Result<PrivateKey, String> {
+    let parse_error = |_| format!("Unable to parse private key in {:?}", config.key);
+
+    let mut keys = pkcs8_private_keys(&mut open(&config.key)?).map_err(parse_error)?;
+    if keys.is_empty() {
+        keys = rsa_private_keys(&mut open(&config.key)?).map_err(parse_error)?;
+    }
+
+    keys.into_iter()
+        .next()
+        .ok_or_else(|| format!("No private key found in {:?}", config.key))
+}
+
+/// Opens the file at `path` for buffered reading.
+fn open(path: &Path) -> Result<BufReader<File>, String> {
+    File::open(path)
+        .map(BufReader::new)
+        .map_err(|e| format!("Unable to open {:?}: {:?}", path, e))
+}
+
+/// Returns a stream of the connections accepted by `listener` which have completed a TLS
+/// handshake.
+///
+/// Failed handshakes are logged and dropped, rather than shutting down the server.
+pub fn incoming(
+    listener: TcpListener,
+    acceptor: TlsAcceptor,
+    log: Logger,
+) -> impl Stream<Item = Result<TlsStream<TcpStream>, io::Error>> {
+    let accept_log = log.clone();
+
+    stream::unfold(listener, |mut listener| async move {
+        let connection = listener.accept().await;
+        Some((connection, listener))
+    })
+    .filter_map(move |connection| {
+        let stream = match connection {
+            Ok((stream, _peer_addr)) => Some(stream),
+            Err(e) => {
+                warn!(accept_log, "Unable to accept HTTPS connection"; "error" => e.to_string());
+                None
+            }
+        };
+        future::ready(stream)
+    })
+    .map(move |stream| tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream)))
+    .buffer_unordered(MAX_CONCURRENT_HANDSHAKES)
+    .filter_map(move |handshake| {
+        let stream = match handshake {
+            Ok(Ok(stream)) => Some(Ok(stream)),
+            Ok(Err(e)) => {
+                debug!(log, "TLS handshake failed"; "error" => e.to_string());
+                None
+            }
+            Err(_) => {
+                debug!(log, "TLS handshake timed out");
+                None
+            }
+        };
+        future::ready(stream)
+    })
+}
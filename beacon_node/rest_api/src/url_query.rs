@@ -1,7 +1,8 @@
 use crate::helpers::{parse_committee_index, parse_epoch, parse_hex_ssz_bytes, parse_slot};
 use crate::ApiError;
+use beacon_chain::events::EventTopic;
 use hyper::Request;
-use types::{AttestationData, CommitteeIndex, Epoch, Signature, Slot};
+use types::{AttestationData, CommitteeIndex, Epoch, Graffiti, Signature, Slot};
 
 /// Provides handy functions for parsing the query parameters of a URL.
 
@@ -109,6 +110,33 @@ impl<'a> UrlQuery<'a> {
             .and_then(|(_key, value)| parse_hex_ssz_bytes(&value))
     }
 
+    /// Returns the value of the first occurrence of the `graffiti` key, if any.
+    pub fn graffiti(self) -> Result<Option<Graffiti>, ApiError> {
+        self.first_of_opt(&["graffiti"])
+            .map(|(_key, value)| parse_hex_ssz_bytes(&value))
+            .transpose()
+    }
+
+    /// Returns the `EventTopic`s listed in all occurrences of the `topics` key.
+    ///
+    /// Each value may be a comma-separated list of topics, e.g. `topics=head,block`.
+    pub fn event_topics(self) -> Result<Vec<EventTopic>, ApiError> {
+        let topics = self
+            .all_of("topics")?
+            .iter()
+            .flat_map(|value| value.split(','))
+            .map(|topic| topic.trim().parse().map_err(ApiError::BadRequest))
+            .collect::<Result<Vec<EventTopic>, _>>()?;
+
+        if topics.is_empty() {
+            return Err(ApiError::BadRequest(
+                "URL query must contain at least one event topic".to_string(),
+            ));
+        }
+
+        Ok(topics)
+    }
+
     /// Returns the value of the first occurrence of the `attestation_data` key.
     pub fn attestation_data(self) -> Result<AttestationData, ApiError> {
         self.first_of(&["attestation_data"])
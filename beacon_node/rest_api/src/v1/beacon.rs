@@ -0,0 +1,445 @@
+use super::{block_and_root, empty_response, respond, state_and_root};
+use crate::helpers::{
+    block_root_at_slot, parse_epoch, parse_json_body, parse_pubkey_bytes, parse_slot,
+    send_to_network,
+};
+use crate::{ApiError, ApiResult, NetworkChannel, UrlQuery};
+use beacon_chain::{
+    observed_operations::ObservationOutcome, BeaconChain, BeaconChainTypes, BlockError,
+};
+use eth2_libp2p::PubsubMessage;
+use hyper::{Body, Request, StatusCode};
+use network::NetworkMessage;
+use rest_types::{
+    BlockHeaderData, BlockId, CommitteeData, FinalityCheckpointsData, GenesisData, RootData,
+    StateId, ValidatorBalanceData, ValidatorData, ValidatorStatus,
+};
+use slog::{error, info, Logger};
+use std::sync::Arc;
+use types::{
+    AttesterSlashing, BeaconState, ChainSpec, EthSpec, Hash256, ProposerSlashing, RelativeEpoch,
+    SignedBeaconBlock, SignedBeaconBlockHeader, SignedVoluntaryExit,
+};
+
+/// HTTP handler to return the genesis information of the chain.
+pub fn get_genesis<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+) -> ApiResult {
+    let head_info = beacon_chain.head_info()?;
+
+    respond(
+        &req,
+        GenesisData {
+            genesis_time: head_info.genesis_time,
+            genesis_validators_root: head_info.genesis_validators_root,
+            genesis_fork_version: beacon_chain.spec.genesis_fork_version,
+        },
+    )
+}
+
+/// HTTP handler to return the root of the state identified by `state_id`.
+pub fn get_state_root<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+    state_id: StateId,
+) -> ApiResult {
+    let (root, _state) = state_and_root(&beacon_chain, state_id)?;
+
+    respond(&req, RootData::from(root))
+}
+
+/// HTTP handler to return the `Fork` of the state identified by `state_id`.
+pub fn get_state_fork<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+    state_id: StateId,
+) -> ApiResult {
+    let (_root, state) = state_and_root(&beacon_chain, state_id)?;
+
+    respond(&req, state.fork)
+}
+
+/// HTTP handler to return the justified and finalized checkpoints of the state identified by
+/// `state_id`.
+pub fn get_state_finality_checkpoints<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+    state_id: StateId,
+) -> ApiResult {
+    let (_root, state) = state_and_root(&beacon_chain, state_id)?;
+
+    respond(
+        &req,
+        FinalityCheckpointsData {
+            previous_justified: state.previous_justified_checkpoint,
+            current_justified: state.current_justified_checkpoint,
+            finalized: state.finalized_checkpoint,
+        },
+    )
+}
+
+/// HTTP handler to return all validators in the state identified by `state_id`.
+pub fn get_state_validators<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+    state_id: StateId,
+) -> ApiResult {
+    let (_root, state) = state_and_root(&beacon_chain, state_id)?;
+
+    let validators = (0..state.validators.len())
+        .map(|index| validator_data(&state, index, &beacon_chain.spec))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    respond(&req, validators)
+}
+
+/// HTTP handler to return a single validator in the state identified by `state_id`.
+///
+/// The validator may be identified by either its index or its `0x`-prefixed public key.
+pub fn get_state_validator<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+    state_id: StateId,
+    validator_id: &str,
+) -> ApiResult {
+    let (_root, mut state) = state_and_root(&beacon_chain, state_id)?;
+
+    let index = if validator_id.starts_with("0x") {
+        let pubkey = parse_pubkey_bytes(validator_id)?;
+        state.update_pubkey_cache()?;
+        state.get_validator_index(&pubkey)?.ok_or_else(|| {
+            ApiError::NotFound(format!("Unknown validator public key: {}", validator_id))
+        })?
+    } else {
+        validator_id.parse::<usize>().map_err(|e| {
+            ApiError::BadRequest(format!("Unable to parse validator index: {:?}", e))
+        })?
+    };
+
+    respond(&req, validator_data(&state, index, &beacon_chain.spec)?)
+}
+
+/// HTTP handler to return the balance of all validators in the state identified by `state_id`.
+pub fn get_state_validator_balances<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+    state_id: StateId,
+) -> ApiResult {
+    let (_root, state) = state_and_root(&beacon_chain, state_id)?;
+
+    let balances = state
+        .balances
+        .iter()
+        .enumerate()
+        .map(|(index, balance)| ValidatorBalanceData {
+            index: index as u64,
+            balance: *balance,
+        })
+        .collect::<Vec<_>>();
+
+    respond(&req, balances)
+}
+
+/// HTTP handler to return the committees of the given `epoch`, as computed from the state
+/// identified by `state_id`.
+///
+/// The `epoch` must be the previous, current or next epoch of the state.
+pub fn get_state_committees<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+    state_id: StateId,
+    epoch: &str,
+) -> ApiResult {
+    let epoch = parse_epoch(epoch)?;
+    let (_root, mut state) = state_and_root(&beacon_chain, state_id)?;
+
+    let relative_epoch = RelativeEpoch::from_epoch(state.current_epoch(), epoch).map_err(|_| {
+        ApiError::BadRequest(format!(
+            "Epoch {} is not within one epoch of the state epoch {}",
+            epoch,
+            state.current_epoch()
+        ))
+    })?;
+
+    state.build_committee_cache(relative_epoch, &beacon_chain.spec)?;
+
+    let committees = state
+        .get_beacon_committees_at_epoch(relative_epoch)?
+        .into_iter()
+        .map(|c| CommitteeData {
+            index: c.index,
+            slot: c.slot,
+            validators: c.committee.iter().map(|i| *i as u64).collect(),
+        })
+        .collect::<Vec<_>>();
+
+    respond(&req, committees)
+}
+
+/// HTTP handler to return the block headers at the `slot` query parameter, or the head block
+/// header if it is not supplied.
+pub fn get_headers<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+) -> ApiResult {
+    let block_id = match UrlQuery::from_request(&req)?.first_of_opt(&["slot"]) {
+        Some((_key, value)) => BlockId::Slot(parse_slot(&value)?),
+        None => BlockId::Head,
+    };
+
+    let (root, block) = block_and_root(&beacon_chain, block_id)?;
+
+    respond(&req, vec![block_header_data(&beacon_chain, root, block)?])
+}
+
+/// HTTP handler to return the header of the block identified by `block_id`.
+pub fn get_header<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+    block_id: BlockId,
+) -> ApiResult {
+    let (root, block) = block_and_root(&beacon_chain, block_id)?;
+
+    respond(&req, block_header_data(&beacon_chain, root, block)?)
+}
+
+/// HTTP handler to return the block identified by `block_id`.
+pub fn get_block<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+    block_id: BlockId,
+) -> ApiResult {
+    let (_root, block) = block_and_root(&beacon_chain, block_id)?;
+
+    respond(&req, block)
+}
+
+/// HTTP handler to return the root of the block identified by `block_id`.
+pub fn get_block_root<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+    block_id: BlockId,
+) -> ApiResult {
+    let (root, _block) = block_and_root(&beacon_chain, block_id)?;
+
+    respond(&req, RootData::from(root))
+}
+
+/// HTTP handler to import a `SignedBeaconBlock` and publish it to the network.
+///
+/// Blocks which fail validation are rejected without being published.
+pub async fn post_block<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+    network_channel: NetworkChannel<T::EthSpec>,
+    log: Logger,
+) -> ApiResult {
+    let block = parse_json_body::<SignedBeaconBlock<T::EthSpec>>(req).await?;
+    let slot = block.slot();
+
+    match beacon_chain.process_block(block.clone()) {
+        Ok(block_root) => {
+            info!(
+                log,
+                "Block from HTTP API";
+                "block_root" => format!("{}", block_root),
+                "block_slot" => slot,
+            );
+
+            send_to_network::<T>(
+                &network_channel,
+                NetworkMessage::Publish {
+                    messages: vec![PubsubMessage::BeaconBlock(Box::new(block))],
+                },
+            )?;
+
+            // The block has been imported, so a failure to update the head is not an error of
+            // the request.
+            if let Err(e) = beacon_chain.fork_choice() {
+                error!(
+                    log,
+                    "Failed to find beacon chain head";
+                    "error" => format!("{:?}", e)
+                );
+            }
+
+            empty_response(StatusCode::OK)
+        }
+        Err(BlockError::BeaconChainError(e)) => Err(ApiError::ServerError(format!(
+            "Error while processing block: {:?}",
+            e
+        ))),
+        Err(e) => Err(ApiError::BadRequest(format!("Invalid block: {:?}", e))),
+    }
+}
+
+/// HTTP handler to return the attestations included in the block identified by `block_id`.
+pub fn get_block_attestations<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+    block_id: BlockId,
+) -> ApiResult {
+    let (_root, block) = block_and_root(&beacon_chain, block_id)?;
+
+    respond(&req, block.message.body.attestations)
+}
+
+/// HTTP handler to return all attestations in the operation pool.
+pub fn get_pool_attestations<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+) -> ApiResult {
+    respond(&req, beacon_chain.op_pool.get_all_attestations())
+}
+
+/// HTTP handler to return all attester slashings in the operation pool.
+pub fn get_pool_attester_slashings<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+) -> ApiResult {
+    respond(&req, beacon_chain.op_pool.get_all_attester_slashings())
+}
+
+/// HTTP handler to verify an `AttesterSlashing`, import it into the operation pool and publish it
+/// to the network.
+pub async fn post_pool_attester_slashings<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+    network_channel: NetworkChannel<T::EthSpec>,
+) -> ApiResult {
+    let slashing = parse_json_body::<AttesterSlashing<T::EthSpec>>(req).await?;
+
+    let outcome = beacon_chain
+        .verify_attester_slashing_for_gossip(slashing.clone())
+        .map_err(|e| ApiError::BadRequest(format!("Invalid attester slashing: {:?}", e)))?;
+
+    if let ObservationOutcome::New(verified_slashing) = outcome {
+        send_to_network::<T>(
+            &network_channel,
+            NetworkMessage::Publish {
+                messages: vec![PubsubMessage::AttesterSlashing(Box::new(slashing))],
+            },
+        )?;
+        beacon_chain
+            .import_attester_slashing(verified_slashing)
+            .map_err(|e| {
+                ApiError::ServerError(format!("Unable to import attester slashing: {:?}", e))
+            })?;
+    }
+
+    empty_response(StatusCode::OK)
+}
+
+/// HTTP handler to return all proposer slashings in the operation pool.
+pub fn get_pool_proposer_slashings<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+) -> ApiResult {
+    respond(&req, beacon_chain.op_pool.get_all_proposer_slashings())
+}
+
+/// HTTP handler to verify a `ProposerSlashing`, import it into the operation pool and publish it
+/// to the network.
+pub async fn post_pool_proposer_slashings<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+    network_channel: NetworkChannel<T::EthSpec>,
+) -> ApiResult {
+    let slashing = parse_json_body::<ProposerSlashing>(req).await?;
+
+    let outcome = beacon_chain
+        .verify_proposer_slashing_for_gossip(slashing.clone())
+        .map_err(|e| ApiError::BadRequest(format!("Invalid proposer slashing: {:?}", e)))?;
+
+    if let ObservationOutcome::New(verified_slashing) = outcome {
+        send_to_network::<T>(
+            &network_channel,
+            NetworkMessage::Publish {
+                messages: vec![PubsubMessage::ProposerSlashing(Box::new(slashing))],
+            },
+        )?;
+        beacon_chain.import_proposer_slashing(verified_slashing);
+    }
+
+    empty_response(StatusCode::OK)
+}
+
+/// HTTP handler to return all voluntary exits in the operation pool.
+pub fn get_pool_voluntary_exits<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+) -> ApiResult {
+    respond(&req, beacon_chain.op_pool.get_all_voluntary_exits())
+}
+
+/// HTTP handler to verify a `SignedVoluntaryExit`, import it into the operation pool and publish
+/// it to the network.
+pub async fn post_pool_voluntary_exits<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+    network_channel: NetworkChannel<T::EthSpec>,
+) -> ApiResult {
+    let exit = parse_json_body::<SignedVoluntaryExit>(req).await?;
+
+    let outcome = beacon_chain
+        .verify_voluntary_exit_for_gossip(exit.clone())
+        .map_err(|e| ApiError::BadRequest(format!("Invalid voluntary exit: {:?}", e)))?;
+
+    if let ObservationOutcome::New(verified_exit) = outcome {
+        send_to_network::<T>(
+            &network_channel,
+            NetworkMessage::Publish {
+                messages: vec![PubsubMessage::VoluntaryExit(Box::new(exit))],
+            },
+        )?;
+        beacon_chain.import_voluntary_exit(verified_exit);
+    }
+
+    empty_response(StatusCode::OK)
+}
+
+/// Returns the `ValidatorData` for the validator at `index` in `state`.
+fn validator_data<E: EthSpec>(
+    state: &BeaconState<E>,
+    index: usize,
+    spec: &ChainSpec,
+) -> Result<ValidatorData, ApiError> {
+    let validator = state
+        .validators
+        .get(index)
+        .ok_or_else(|| ApiError::NotFound(format!("Unknown validator index: {}", index)))?;
+    let balance = state
+        .balances
+        .get(index)
+        .ok_or_else(|| ApiError::ServerError(format!("Invalid balances index: {:?}", index)))?;
+
+    Ok(ValidatorData {
+        index: index as u64,
+        balance: *balance,
+        status: ValidatorStatus::from_validator(
+            validator,
+            state.current_epoch(),
+            spec.far_future_epoch,
+        ),
+        validator: validator.clone(),
+    })
+}
+
+/// Returns the `BlockHeaderData` for `block`, which must have the given `root`.
+fn block_header_data<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+    root: Hash256,
+    block: SignedBeaconBlock<T::EthSpec>,
+) -> Result<BlockHeaderData, ApiError> {
+    let canonical = block_root_at_slot(beacon_chain, block.slot())? == Some(root);
+
+    Ok(BlockHeaderData {
+        root,
+        canonical,
+        header: SignedBeaconBlockHeader {
+            message: block.message.block_header(),
+            signature: block.signature,
+        },
+    })
+}
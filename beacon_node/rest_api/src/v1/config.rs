@@ -0,0 +1,23 @@
+use super::respond;
+use crate::ApiResult;
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use hyper::{Body, Request};
+use std::sync::Arc;
+
+/// HTTP handler to return the `ChainSpec` of the node.
+pub fn get_spec<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+) -> ApiResult {
+    respond(&req, &beacon_chain.spec)
+}
+
+/// HTTP handler to return all forks, past and present.
+///
+/// Lighthouse does not yet schedule any forks, so this is only the fork of the head state.
+pub fn get_fork_schedule<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+) -> ApiResult {
+    respond(&req, vec![beacon_chain.head_info()?.fork])
+}
@@ -0,0 +1,236 @@
+//! The standard Eth2 beacon node API, served under `/eth/v1/`.
+//!
+//! These endpoints exist alongside the Lighthouse-specific endpoints, which are unaffected. All
+//! responses are JSON and are wrapped in a `GenericResponse`; all errors are returned as an
+//! `ErrorMessage`.
+
+mod beacon;
+mod config;
+mod node;
+mod validator;
+
+use crate::helpers::{block_root_at_slot, state_at_slot};
+use crate::response_builder::ResponseBuilder;
+use crate::{ApiError, ApiResult, NetworkChannel};
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use eth2_libp2p::NetworkGlobals;
+use hyper::{Body, Method, Request, Response, StatusCode};
+use rest_types::{BlockId, GenericResponse, StateId};
+use serde::Serialize;
+use std::str::FromStr;
+use std::sync::Arc;
+use types::{BeaconState, Hash256, SignedBeaconBlock};
+
+/// All paths with this prefix are routed to this module.
+pub const PATH_PREFIX: &str = "/eth/v1/";
+
+/// Routes a request for a path starting with `PATH_PREFIX`.
+pub async fn route<T: BeaconChainTypes>(
+    req: Request<Body>,
+    path: &str,
+    beacon_chain: Arc<BeaconChain<T>>,
+    network_globals: Arc<NetworkGlobals<T::EthSpec>>,
+    network_channel: NetworkChannel<T::EthSpec>,
+    log: slog::Logger,
+) -> ApiResult {
+    let segments = path
+        .trim_start_matches(PATH_PREFIX)
+        .trim_end_matches('/')
+        .split('/')
+        .collect::<Vec<_>>();
+
+    match (req.method(), segments.as_slice()) {
+        // Methods for the beacon chain
+        (&Method::GET, ["beacon", "genesis"]) => beacon::get_genesis::<T>(req, beacon_chain),
+        (&Method::GET, ["beacon", "states", state_id, "root"]) => {
+            beacon::get_state_root::<T>(req, beacon_chain, parse_path_id(state_id)?)
+        }
+        (&Method::GET, ["beacon", "states", state_id, "fork"]) => {
+            beacon::get_state_fork::<T>(req, beacon_chain, parse_path_id(state_id)?)
+        }
+        (&Method::GET, ["beacon", "states", state_id, "finality_checkpoints"]) => {
+            beacon::get_state_finality_checkpoints::<T>(req, beacon_chain, parse_path_id(state_id)?)
+        }
+        (&Method::GET, ["beacon", "states", state_id, "validators"]) => {
+            beacon::get_state_validators::<T>(req, beacon_chain, parse_path_id(state_id)?)
+        }
+        (&Method::GET, ["beacon", "states", state_id, "validators", validator_id]) => {
+            beacon::get_state_validator::<T>(
+                req,
+                beacon_chain,
+                parse_path_id(state_id)?,
+                validator_id,
+            )
+        }
+        (&Method::GET, ["beacon", "states", state_id, "validator_balances"]) => {
+            beacon::get_state_validator_balances::<T>(req, beacon_chain, parse_path_id(state_id)?)
+        }
+        (&Method::GET, ["beacon", "states", state_id, "committees", epoch]) => {
+            beacon::get_state_committees::<T>(req, beacon_chain, parse_path_id(state_id)?, epoch)
+        }
+        (&Method::GET, ["beacon", "headers"]) => beacon::get_headers::<T>(req, beacon_chain),
+        (&Method::GET, ["beacon", "headers", block_id]) => {
+            beacon::get_header::<T>(req, beacon_chain, parse_path_id(block_id)?)
+        }
+        (&Method::POST, ["beacon", "blocks"]) => {
+            beacon::post_block::<T>(req, beacon_chain, network_channel, log).await
+        }
+        (&Method::GET, ["beacon", "blocks", block_id]) => {
+            beacon::get_block::<T>(req, beacon_chain, parse_path_id(block_id)?)
+        }
+        (&Method::GET, ["beacon", "blocks", block_id, "root"]) => {
+            beacon::get_block_root::<T>(req, beacon_chain, parse_path_id(block_id)?)
+        }
+        (&Method::GET, ["beacon", "blocks", block_id, "attestations"]) => {
+            beacon::get_block_attestations::<T>(req, beacon_chain, parse_path_id(block_id)?)
+        }
+        (&Method::GET, ["beacon", "pool", "attestations"]) => {
+            beacon::get_pool_attestations::<T>(req, beacon_chain)
+        }
+        (&Method::GET, ["beacon", "pool", "attester_slashings"]) => {
+            beacon::get_pool_attester_slashings::<T>(req, beacon_chain)
+        }
+        (&Method::POST, ["beacon", "pool", "attester_slashings"]) => {
+            beacon::post_pool_attester_slashings::<T>(req, beacon_chain, network_channel).await
+        }
+        (&Method::GET, ["beacon", "pool", "proposer_slashings"]) => {
+            beacon::get_pool_proposer_slashings::<T>(req, beacon_chain)
+        }
+        (&Method::POST, ["beacon", "pool", "proposer_slashings"]) => {
+            beacon::post_pool_proposer_slashings::<T>(req, beacon_chain, network_channel).await
+        }
+        (&Method::GET, ["beacon", "pool", "voluntary_exits"]) => {
+            beacon::get_pool_voluntary_exits::<T>(req, beacon_chain)
+        }
+        (&Method::POST, ["beacon", "pool", "voluntary_exits"]) => {
+            beacon::post_pool_voluntary_exits::<T>(req, beacon_chain, network_channel).await
+        }
+
+        // Methods for the node
+        (&Method::GET, ["node", "version"]) => node::get_version(req),
+        (&Method::GET, ["node", "syncing"]) => node::get_syncing::<T>(req, beacon_chain),
+        (&Method::GET, ["node", "identity"]) => node::get_identity(req, network_globals),
+        (&Method::GET, ["node", "health"]) => node::get_health(network_globals),
+
+        // Methods for the configuration
+        (&Method::GET, ["config", "spec"]) => config::get_spec::<T>(req, beacon_chain),
+        (&Method::GET, ["config", "fork_schedule"]) => {
+            config::get_fork_schedule::<T>(req, beacon_chain)
+        }
+
+        // Methods for validators
+        (&Method::GET, ["validator", "duties", "attester", epoch]) => {
+            validator::get_attester_duties::<T>(req, beacon_chain, epoch)
+        }
+        (&Method::GET, ["validator", "duties", "proposer", epoch]) => {
+            validator::get_proposer_duties::<T>(req, beacon_chain, epoch)
+        }
+
+        _ => Err(ApiError::NotFound(
+            "Request path and/or method not found.".to_owned(),
+        )),
+    }
+}
+
+/// Parses a `StateId` or `BlockId` from a path segment.
+fn parse_path_id<I: FromStr<Err = String>>(segment: &str) -> Result<I, ApiError> {
+    segment.parse().map_err(ApiError::BadRequest)
+}
+
+/// Returns a response with `data` wrapped in a `GenericResponse`.
+fn respond<S: Serialize>(req: &Request<Body>, data: S) -> ApiResult {
+    ResponseBuilder::new(req)?.body_no_ssz(&GenericResponse::from(data))
+}
+
+/// Returns a response with the given `status` and an empty body.
+fn empty_response(status: StatusCode) -> ApiResult {
+    Response::builder()
+        .status(status)
+        .body(Body::empty())
+        .map_err(|e| ApiError::ServerError(format!("Failed to build response: {:?}", e)))
+}
+
+/// Returns the `BeaconState` identified by `state_id`, along with its root.
+fn state_and_root<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+    state_id: StateId,
+) -> Result<(Hash256, BeaconState<T::EthSpec>), ApiError> {
+    let slot = match state_id {
+        StateId::Head => {
+            let head = beacon_chain.head()?;
+            return Ok((head.beacon_state_root, head.beacon_state));
+        }
+        StateId::Root(root) => {
+            let state = beacon_chain.get_state(&root, None)?.ok_or_else(|| {
+                ApiError::NotFound(format!("No state exists with root: {:?}", root))
+            })?;
+            return Ok((root, state));
+        }
+        StateId::Genesis => beacon_chain.spec.genesis_slot,
+        StateId::Finalized => return checkpoint_state_and_root(beacon_chain, BlockId::Finalized),
+        StateId::Justified => return checkpoint_state_and_root(beacon_chain, BlockId::Justified),
+        StateId::Slot(slot) => slot,
+    };
+
+    state_at_slot(beacon_chain, slot)
+}
+
+/// Returns the post-state of the checkpoint block identified by `block_id`, along with its root.
+///
+/// The first slot of the checkpoint epoch may have been skipped, so the state is found via the
+/// `state_root` of the block rather than by slot.
+fn checkpoint_state_and_root<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+    block_id: BlockId,
+) -> Result<(Hash256, BeaconState<T::EthSpec>), ApiError> {
+    let (_block_root, block) = block_and_root(beacon_chain, block_id)?;
+    let state_root = block.state_root();
+
+    let state = beacon_chain
+        .get_state(&state_root, Some(block.slot()))?
+        .ok_or_else(|| {
+            ApiError::NotFound(format!("No state exists with root: {:?}", state_root))
+        })?;
+
+    Ok((state_root, state))
+}
+
+/// Returns the `SignedBeaconBlock` identified by `block_id`, along with its root.
+fn block_and_root<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+    block_id: BlockId,
+) -> Result<(Hash256, SignedBeaconBlock<T::EthSpec>), ApiError> {
+    let root = match block_id {
+        BlockId::Head => beacon_chain.head_info()?.block_root,
+        BlockId::Genesis => beacon_chain.genesis_block_root,
+        BlockId::Finalized => beacon_chain.head_info()?.finalized_checkpoint.root,
+        BlockId::Justified => beacon_chain.head_info()?.current_justified_checkpoint.root,
+        BlockId::Slot(slot) => block_root_at_slot(beacon_chain, slot)?
+            .ok_or_else(|| ApiError::NotFound(format!("No block exists at slot: {}", slot)))?,
+        BlockId::Root(root) => root,
+    };
+
+    // Checkpoints prior to the first justification or finalization have a zero root, which
+    // refers to the genesis block.
+    let root = if root == Hash256::zero() {
+        beacon_chain.genesis_block_root
+    } else {
+        root
+    };
+
+    let block = beacon_chain
+        .get_block(&root)?
+        .ok_or_else(|| ApiError::NotFound(format!("No block exists with root: {:?}", root)))?;
+
+    // `block_root_at_slot` returns the prior block for skipped slots.
+    if let BlockId::Slot(slot) = block_id {
+        if block.slot() != slot {
+            return Err(ApiError::NotFound(format!(
+                "No block exists at slot: {}",
+                slot
+            )));
+        }
+    }
+
+    Ok((root, block))
+}
@@ -0,0 +1,62 @@
+use super::{empty_response, respond};
+use crate::ApiResult;
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use eth2_libp2p::NetworkGlobals;
+use hyper::{Body, Request, StatusCode};
+use rest_types::{IdentityData, SyncingData, VersionData};
+use std::sync::Arc;
+use types::EthSpec;
+
+/// HTTP handler to return the version string of the current Lighthouse build.
+pub fn get_version(req: Request<Body>) -> ApiResult {
+    respond(
+        &req,
+        VersionData {
+            version: version::version(),
+        },
+    )
+}
+
+/// HTTP handler to return the head slot and its distance from the wall-clock slot.
+pub fn get_syncing<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+) -> ApiResult {
+    let head_slot = beacon_chain.head_info()?.slot;
+    let current_slot = beacon_chain.slot()?;
+
+    respond(
+        &req,
+        SyncingData {
+            head_slot,
+            sync_distance: current_slot.saturating_sub(head_slot),
+        },
+    )
+}
+
+/// HTTP handler to return the network identity of the node.
+pub fn get_identity<E: EthSpec>(req: Request<Body>, network: Arc<NetworkGlobals<E>>) -> ApiResult {
+    respond(
+        &req,
+        IdentityData {
+            peer_id: network.local_peer_id().to_base58(),
+            enr: network.local_enr().to_base64(),
+            p2p_addresses: network
+                .listen_multiaddrs()
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+        },
+    )
+}
+
+/// HTTP handler to return the health of the node.
+///
+/// Responds with `200` when the node is synced and `206` whilst it is syncing.
+pub fn get_health<E: EthSpec>(network: Arc<NetworkGlobals<E>>) -> ApiResult {
+    if network.is_syncing() {
+        empty_response(StatusCode::PARTIAL_CONTENT)
+    } else {
+        empty_response(StatusCode::OK)
+    }
+}
@@ -0,0 +1,101 @@
+use super::respond;
+use crate::helpers::parse_epoch;
+use crate::validator::get_state_for_epoch;
+use crate::{ApiError, ApiResult, UrlQuery};
+use beacon_chain::{BeaconChain, BeaconChainTypes, StateSkipConfig};
+use hyper::{Body, Request};
+use rest_types::{AttesterData, ProposerData};
+use std::sync::Arc;
+use types::{EthSpec, RelativeEpoch};
+
+/// HTTP handler to return the attestation duties in `epoch` of the validators given by the `index`
+/// query parameter.
+///
+/// The indices may be supplied as repeated `index` parameters, as a comma-separated list, or both.
+/// Validators without a duty in `epoch` are omitted from the response.
+pub fn get_attester_duties<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+    epoch: &str,
+) -> ApiResult {
+    let epoch = parse_epoch(epoch)?;
+    let indices = UrlQuery::from_request(&req)?
+        .all_of("index")?
+        .iter()
+        .flat_map(|indices| indices.split(','))
+        .map(|index| {
+            index.parse::<usize>().map_err(|e| {
+                ApiError::BadRequest(format!("Unable to parse validator index: {:?}", e))
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut state = get_state_for_epoch(&beacon_chain, epoch, StateSkipConfig::WithoutStateRoots)?;
+
+    let relative_epoch = RelativeEpoch::from_epoch(state.current_epoch(), epoch)
+        .map_err(|_| ApiError::ServerError(String::from("Loaded state is in the wrong epoch")))?;
+
+    state.build_committee_cache(relative_epoch, &beacon_chain.spec)?;
+
+    let mut duties = Vec::with_capacity(indices.len());
+    for index in indices {
+        let validator = state
+            .validators
+            .get(index)
+            .ok_or_else(|| ApiError::BadRequest(format!("Unknown validator index: {}", index)))?;
+
+        if let Some(duty) = state.get_attestation_duties(index, relative_epoch)? {
+            duties.push(AttesterData {
+                pubkey: validator.pubkey.clone(),
+                validator_index: index as u64,
+                committee_index: duty.index,
+                committee_length: duty.committee_len as u64,
+                committees_at_slot: state.get_committee_count_at_slot(duty.slot)?,
+                validator_committee_index: duty.committee_position as u64,
+                slot: duty.slot,
+            });
+        }
+    }
+
+    respond(&req, duties)
+}
+
+/// HTTP handler to return the block proposers for each slot of `epoch`.
+///
+/// Proposers are only known for the current epoch of the wall-clock.
+pub fn get_proposer_duties<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+    epoch: &str,
+) -> ApiResult {
+    let epoch = parse_epoch(epoch)?;
+    let current_epoch = beacon_chain.epoch()?;
+
+    if epoch != current_epoch {
+        return Err(ApiError::BadRequest(format!(
+            "Proposer duties are only available for the current epoch {}",
+            current_epoch
+        )));
+    }
+
+    let mut state = get_state_for_epoch(&beacon_chain, epoch, StateSkipConfig::WithoutStateRoots)?;
+    state.build_committee_cache(RelativeEpoch::Current, &beacon_chain.spec)?;
+
+    let duties = epoch
+        .slot_iter(T::EthSpec::slots_per_epoch())
+        .map(|slot| {
+            let validator_index = state.get_beacon_proposer_index(slot, &beacon_chain.spec)?;
+            let validator = state.validators.get(validator_index).ok_or_else(|| {
+                ApiError::ServerError(format!("Invalid validator index: {}", validator_index))
+            })?;
+
+            Ok(ProposerData {
+                pubkey: validator.pubkey.clone(),
+                validator_index: validator_index as u64,
+                slot,
+            })
+        })
+        .collect::<Result<Vec<_>, ApiError>>()?;
+
+    respond(&req, duties)
+}
@@ -179,6 +179,10 @@ fn return_validator_duties<T: BeaconChainTypes>(
         .update_pubkey_cache()
         .map_err(|e| ApiError::ServerError(format!("Unable to build pubkey cache: {:?}", e)))?;
 
+    let dependent_root = state
+        .attester_shuffling_decision_root(beacon_chain.genesis_block_root, relative_epoch)
+        .map_err(|e| ApiError::ServerError(format!("Unable to find dependent root: {:?}", e)))?;
+
     // Get a list of all validators for this epoch.
     //
     // Used for quickly determining the slot for a proposer.
@@ -260,6 +264,7 @@ fn return_validator_duties<T: BeaconChainTypes>(
                     attestation_committee_position: duties.map(|d| d.committee_position),
                     block_proposal_slots,
                     aggregator_modulo,
+                    dependent_root,
                 })
             } else {
                 Ok(ValidatorDutyBytes {
@@ -271,6 +276,7 @@ fn return_validator_duties<T: BeaconChainTypes>(
                     block_proposal_slots: None,
                     committee_count_at_slot: None,
                     aggregator_modulo: None,
+                    dependent_root,
                 })
             }
         })
@@ -287,9 +293,10 @@ pub fn get_new_beacon_block<T: BeaconChainTypes>(
 
     let slot = query.slot()?;
     let randao_reveal = query.randao_reveal()?;
+    let graffiti = query.graffiti()?;
 
     let (new_block, _state) = beacon_chain
-        .produce_block(randao_reveal, slot)
+        .produce_block(randao_reveal, slot, graffiti)
         .map_err(|e| {
             error!(
                 log,
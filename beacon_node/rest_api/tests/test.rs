@@ -4,25 +4,40 @@
 extern crate assert_matches;
 
 use beacon_chain::{BeaconChain, BeaconChainTypes, StateSkipConfig};
+use eth2_libp2p::{
+    types::{GossipKind, SyncChainInfo, SyncChainType},
+    PeerId,
+};
+use light_client::LightClient;
 use node_test_rig::{
     environment::{Environment, EnvironmentBuilder},
     testing_client_config, ClientConfig, ClientGenesis, LocalBeaconNode,
 };
 use remote_beacon_node::{
-    Committee, HeadBeaconBlock, PersistedOperationPool, PublishStatus, ValidatorResponse,
+    BlockId, Committee, HeadBeaconBlock, PersistedOperationPool, PublishStatus, StateId,
+    ValidatorResponse,
 };
+use rest_api::TlsConfig;
 use rest_types::ValidatorDutyBytes;
 use std::convert::TryInto;
+use std::fs::File;
+use std::io::BufReader;
+use std::net::{IpAddr, Ipv6Addr, SocketAddr};
+use std::path::PathBuf;
 use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio_rustls::{rustls::ClientConfig as TlsClientConfig, webpki::DNSNameRef, TlsConnector};
 use types::{
+    graffiti_from_str,
     test_utils::{
         build_double_vote_attester_slashing, build_proposer_slashing,
         generate_deterministic_keypair, AttesterSlashingTestTask, ProposerSlashingTestTask,
     },
-    BeaconBlock, BeaconState, ChainSpec, Domain, Epoch, EthSpec, MinimalEthSpec, PublicKey,
-    RelativeEpoch, Signature, SignedAggregateAndProof, SignedBeaconBlock, SignedRoot, Slot,
-    SubnetId, Validator,
+    Attestation, BeaconBlock, BeaconState, ChainSpec, Domain, Epoch, EthSpec, Hash256,
+    MinimalEthSpec, PublicKey, RelativeEpoch, Signature, SignedAggregateAndProof,
+    SignedBeaconBlock, SignedRoot, Slot, SubnetId, Validator,
 };
 use version;
 
@@ -45,6 +60,20 @@ fn build_node<E: EthSpec>(env: &mut Environment<E>, config: ClientConfig) -> Loc
         .expect("should block until node created")
 }
 
+/// Returns `true` if `condition` holds within a second, driving the runtime of `env` (and therefore
+/// the services of any node) whilst waiting.
+fn wait_until<E: EthSpec>(env: &mut Environment<E>, condition: impl Fn() -> bool) -> bool {
+    env.runtime().block_on(async {
+        for _ in 0..100 {
+            if condition() {
+                return true;
+            }
+            tokio::time::delay_for(Duration::from_millis(10)).await;
+        }
+        condition()
+    })
+}
+
 /// Returns the randao reveal for the given slot (assuming the given `beacon_chain` uses
 /// deterministic keypairs).
 fn get_randao_reveal<T: BeaconChainTypes>(
@@ -81,6 +110,80 @@ fn sign_block<T: BeaconChainTypes>(
     block.sign(&keypair.sk, &fork, genesis_validators_root, spec)
 }
 
+/// Produces a signed block at `slot` upon the head of `beacon_chain` (assuming the given
+/// `beacon_chain` uses deterministic keypairs).
+fn produce_signed_block<T: BeaconChainTypes>(
+    beacon_chain: Arc<BeaconChain<T>>,
+    slot: Slot,
+    spec: &ChainSpec,
+) -> SignedBeaconBlock<T::EthSpec> {
+    let randao_reveal = get_randao_reveal(beacon_chain.clone(), slot, spec);
+    let (block, _state) = beacon_chain
+        .produce_block(randao_reveal, slot, None)
+        .expect("should produce block");
+    sign_block(beacon_chain, block, spec)
+}
+
+/// Imports `block` into `beacon_chain` and runs fork choice, returning the root of the block.
+fn import_block<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+    block: SignedBeaconBlock<T::EthSpec>,
+) -> Hash256 {
+    let root = beacon_chain
+        .process_block(block)
+        .expect("should import block");
+    beacon_chain.fork_choice().expect("should run fork choice");
+    root
+}
+
+/// Extends the chain of `beacon_chain` with a block at each of the given `slots`, attesting to
+/// each block with every validator (assuming the given `beacon_chain` uses deterministic
+/// keypairs) so that the attestations are included in the following block.
+fn extend_chain_with_attestations<T: BeaconChainTypes>(
+    beacon_chain: Arc<BeaconChain<T>>,
+    slots: impl Iterator<Item = Slot>,
+    spec: &ChainSpec,
+) {
+    for slot in slots {
+        import_block(
+            &beacon_chain,
+            produce_signed_block(beacon_chain.clone(), slot, spec),
+        );
+
+        let state = beacon_chain.head().expect("should get head").beacon_state;
+        for committee in state
+            .get_beacon_committees_at_slot(slot)
+            .expect("should get committees")
+        {
+            let attestation = beacon_chain
+                .produce_unaggregated_attestation(slot, committee.index)
+                .expect("should produce attestation");
+
+            for (position, &validator_index) in committee.committee.iter().enumerate() {
+                let mut attestation = attestation.clone();
+                attestation
+                    .sign(
+                        &generate_deterministic_keypair(validator_index).sk,
+                        position,
+                        &state.fork,
+                        state.genesis_validators_root,
+                        spec,
+                    )
+                    .expect("should sign attestation");
+                beacon_chain
+                    .op_pool
+                    .insert_attestation(
+                        attestation,
+                        &state.fork,
+                        state.genesis_validators_root,
+                        spec,
+                    )
+                    .expect("should insert attestation into op pool");
+            }
+        }
+    }
+}
+
 #[test]
 fn validator_produce_attestation() {
     let mut env = build_env();
@@ -350,6 +453,10 @@ fn check_duties<T: BeaconChainTypes>(
 
     state.build_all_caches(spec).expect("should build caches");
 
+    let dependent_root = state
+        .attester_shuffling_decision_root(beacon_chain.genesis_block_root, RelativeEpoch::Current)
+        .expect("should get dependent root");
+
     validators
         .iter()
         .zip(duties.iter())
@@ -384,6 +491,11 @@ fn check_duties<T: BeaconChainTypes>(
                 "attestation index should match"
             );
 
+            assert_eq!(
+                dependent_root, duty.dependent_root,
+                "dependent root should match"
+            );
+
             if proposers_known {
                 let block_proposal_slots = duty.block_proposal_slots.as_ref().unwrap();
 
@@ -461,7 +573,7 @@ fn validator_block_post() {
             remote_node
                 .http
                 .validator()
-                .produce_block(slot, randao_reveal),
+                .produce_block(slot, randao_reveal, None),
         )
         .expect("should fetch block from http api");
 
@@ -548,7 +660,7 @@ fn validator_block_get() {
             remote_node
                 .http
                 .validator()
-                .produce_block(slot, randao_reveal.clone()),
+                .produce_block(slot, randao_reveal.clone(), None),
         )
         .expect("should fetch block from http api");
 
@@ -556,13 +668,29 @@ fn validator_block_get() {
         .client
         .beacon_chain()
         .expect("client should have beacon chain")
-        .produce_block(randao_reveal, slot)
+        .produce_block(randao_reveal.clone(), slot, None)
         .expect("should produce block");
 
     assert_eq!(
         block, expected_block,
         "the block returned from the API should be as expected"
     );
+
+    let graffiti = graffiti_from_str("validator graffiti").expect("graffiti is valid");
+
+    let block =
+        env.runtime()
+            .block_on(remote_node.http.validator().produce_block(
+                slot,
+                randao_reveal,
+                Some(graffiti),
+            ))
+            .expect("should fetch block with graffiti from http api");
+
+    assert_eq!(
+        block.body.graffiti, graffiti,
+        "the block should include the validator graffiti"
+    );
 }
 
 #[test]
@@ -1171,6 +1299,504 @@ fn attester_slashing() {
     assert_eq!(attester_slashing, attester_slashings[0]);
 }
 
+#[test]
+fn network_peer_management() {
+    let mut env = build_env();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+    let network = remote_node.http.network();
+
+    // Use our own peer id, which is guaranteed to be a well-formed `PeerId`.
+    let peer_id = env
+        .runtime()
+        .block_on(network.get_peer_id())
+        .expect("should fetch peer id from http api");
+
+    let network_globals = node
+        .client
+        .network_globals()
+        .expect("node should have network globals");
+    let libp2p_peer_id = peer_id.parse::<PeerId>().expect("should parse peer id");
+    let is_banned = || network_globals.peers.read().is_banned(&libp2p_peer_id);
+
+    env.runtime()
+        .block_on(network.ban_peer(peer_id.clone()))
+        .expect("should ban peer");
+    assert!(wait_until(&mut env, is_banned), "peer should be banned");
+
+    // Penalise the peer on gossipsub so that unbanning has a score to reset.
+    {
+        let mut peers = network_globals.peers.write();
+        let info = peers
+            .peer_info_mut(&libp2p_peer_id)
+            .expect("banned peer should be known");
+        info.gossip_score.message_rejected(GossipKind::BeaconBlock);
+        assert!(
+            info.gossip_score.score() < 0.0,
+            "gossipsub score should be negative"
+        );
+    }
+
+    env.runtime()
+        .block_on(network.unban_peer(peer_id.clone()))
+        .expect("should unban peer");
+    assert!(
+        wait_until(&mut env, || !is_banned()),
+        "peer should be unbanned"
+    );
+    {
+        let peers = network_globals.peers.read();
+        let info = peers
+            .peer_info(&libp2p_peer_id)
+            .expect("unbanned peer should be known");
+        assert_eq!(
+            info.gossip_score.score(),
+            0.0,
+            "gossipsub score should be reset"
+        );
+        assert_eq!(info.score.score(), 0.0, "score should be reset");
+    }
+
+    env.runtime()
+        .block_on(network.disconnect_peer(peer_id, 1))
+        .expect("should disconnect peer");
+    env.runtime()
+        .block_on(network.discover_peers())
+        .expect("should start discovery");
+    env.runtime()
+        .block_on(network.connect_multiaddr("/ip4/127.0.0.1/tcp/1".to_string()))
+        .expect("should dial multiaddr");
+
+    assert!(
+        env.runtime()
+            .block_on(network.ban_peer("not_a_peer_id".to_string()))
+            .is_err(),
+        "should reject an invalid peer id"
+    );
+    assert!(
+        env.runtime()
+            .block_on(network.connect_multiaddr("not_a_multiaddr".to_string()))
+            .is_err(),
+        "should reject an invalid multiaddr"
+    );
+    assert!(
+        env.runtime()
+            .block_on(network.connect_enr("not_an_enr".to_string()))
+            .is_err(),
+        "should reject an invalid enr"
+    );
+}
+
+#[test]
+fn api_token_required_for_mutating_requests() {
+    let mut env = build_env();
+
+    let mut config = testing_client_config();
+    config.rest_api.api_token = Some("secret".to_string());
+
+    let node = build_node(&mut env, config);
+    let remote_node = node.remote_node().expect("should produce remote node");
+
+    // Requests which only read from the node do not require the token.
+    let peer_id = env
+        .runtime()
+        .block_on(remote_node.http.network().get_peer_id())
+        .expect("should fetch peer id without a token");
+
+    // Neither do `POST` requests which only read from the node.
+    env.runtime()
+        .block_on(
+            remote_node
+                .http
+                .validator()
+                .get_duties(Epoch::new(0), &[generate_deterministic_keypair(0).pk]),
+        )
+        .expect("should fetch duties without a token");
+
+    match env
+        .runtime()
+        .block_on(remote_node.http.network().ban_peer(peer_id.clone()))
+    {
+        Err(remote_beacon_node::Error::DidNotSucceed { status, .. }) => {
+            assert_eq!(status, 401, "should reject a request without a token")
+        }
+        other => panic!("should reject a request without a token, got {:?}", other),
+    }
+
+    let wrong_token = remote_node.clone().with_api_token("wrong".to_string());
+    assert!(
+        env.runtime()
+            .block_on(wrong_token.http.network().ban_peer(peer_id.clone()))
+            .is_err(),
+        "should reject a request with the wrong token"
+    );
+
+    let right_token = remote_node.with_api_token("secret".to_string());
+    env.runtime()
+        .block_on(right_token.http.network().ban_peer(peer_id))
+        .expect("should accept a request with the token");
+}
+
+/// Returns the path of a file in the `tests/tls` directory, which contains a self-signed
+/// certificate for `localhost` and its private key.
+fn tls_file(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("tls")
+        .join(name)
+}
+
+/// Makes a `GET` request for `path` over HTTPS to the server at `addr`, trusting only the
+/// certificate in `tests/tls`, and returns the raw response.
+async fn https_get(addr: SocketAddr, path: &str) -> Result<String, String> {
+    let mut client_config = TlsClientConfig::new();
+    let cert_file = File::open(tls_file("cert.pem")).map_err(|e| format!("{:?}", e))?;
+    client_config
+        .root_store
+        .add_pem_file(&mut BufReader::new(cert_file))
+        .map_err(|()| "Unable to read certificate".to_string())?;
+
+    let domain = DNSNameRef::try_from_ascii_str("localhost").map_err(|e| format!("{:?}", e))?;
+    let stream = TcpStream::connect(addr)
+        .await
+        .map_err(|e| format!("{:?}", e))?;
+    let mut stream = TlsConnector::from(Arc::new(client_config))
+        .connect(domain, stream)
+        .await
+        .map_err(|e| format!("TLS handshake failed: {:?}", e))?;
+
+    let request = format!(
+        "GET {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n",
+        path
+    );
+    stream
+        .write_all(request.as_bytes())
+        .await
+        .map_err(|e| format!("{:?}", e))?;
+
+    let mut response = vec![];
+    stream
+        .read_to_end(&mut response)
+        .await
+        .map_err(|e| format!("{:?}", e))?;
+
+    Ok(String::from_utf8_lossy(&response).into())
+}
+
+#[test]
+fn https_server() {
+    let mut env = build_env();
+
+    let mut config = testing_client_config();
+    config.rest_api.tls_config = Some(TlsConfig {
+        cert: tls_file("cert.pem"),
+        key: tls_file("key.pem"),
+    });
+
+    let node = build_node(&mut env, config);
+    let addr = node
+        .client
+        .http_listen_addr()
+        .expect("should have http server");
+
+    let response = env
+        .runtime()
+        .block_on(https_get(addr, "/node/version"))
+        .expect("should make https request");
+    assert!(
+        response.starts_with("HTTP/1.1 200"),
+        "should succeed, got {}",
+        response
+    );
+    assert!(
+        response.contains(&version::version()),
+        "should return the version, got {}",
+        response
+    );
+
+    let remote_node = node.remote_node().expect("should produce remote node");
+    assert!(
+        env.runtime()
+            .block_on(remote_node.http.node().get_version())
+            .is_err(),
+        "should not serve plain http"
+    );
+}
+
+#[test]
+fn ipv6_server() {
+    let mut env = build_env();
+
+    let mut config = testing_client_config();
+    config.rest_api.listen_address = IpAddr::V6(Ipv6Addr::LOCALHOST);
+
+    let node = build_node(&mut env, config);
+    let addr = node
+        .client
+        .http_listen_addr()
+        .expect("should have http server");
+    assert_eq!(addr.ip(), IpAddr::V6(Ipv6Addr::LOCALHOST));
+
+    let remote_node = node.remote_node().expect("should produce remote node");
+    let version = env
+        .runtime()
+        .block_on(remote_node.http.node().get_version())
+        .expect("should fetch version over ipv6");
+    assert_eq!(version, version::version());
+}
+
+#[test]
+fn v1_beacon_states() {
+    let mut env = build_env();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+    let v1 = remote_node.http.v1();
+
+    let beacon_chain = node
+        .client
+        .beacon_chain()
+        .expect("client should have beacon chain");
+    let head = beacon_chain.head().expect("should get head");
+
+    let genesis = env
+        .runtime()
+        .block_on(v1.get_genesis())
+        .expect("should fetch genesis from http api");
+    assert_eq!(genesis.genesis_time, head.beacon_state.genesis_time);
+    assert_eq!(
+        genesis.genesis_validators_root,
+        head.beacon_state.genesis_validators_root
+    );
+    assert_eq!(
+        genesis.genesis_fork_version,
+        beacon_chain.spec.genesis_fork_version
+    );
+
+    // The head is the genesis block, which is also the finalized and justified checkpoint.
+    for state_id in &[
+        StateId::Head,
+        StateId::Finalized,
+        StateId::Justified,
+        StateId::Slot(head.beacon_state.slot),
+        StateId::Root(head.beacon_state_root),
+    ] {
+        let root = env
+            .runtime()
+            .block_on(v1.get_state_root(*state_id))
+            .expect("should fetch state root from http api");
+        assert_eq!(root, head.beacon_state_root, "state id {}", state_id);
+
+        let fork = env
+            .runtime()
+            .block_on(v1.get_state_fork(*state_id))
+            .expect("should fetch fork from http api");
+        assert_eq!(fork, head.beacon_state.fork, "state id {}", state_id);
+    }
+
+    let checkpoints = env
+        .runtime()
+        .block_on(v1.get_state_finality_checkpoints(StateId::Head))
+        .expect("should fetch finality checkpoints from http api");
+    assert_eq!(
+        checkpoints.finalized,
+        head.beacon_state.finalized_checkpoint
+    );
+    assert_eq!(
+        checkpoints.current_justified,
+        head.beacon_state.current_justified_checkpoint
+    );
+
+    let expected = &head.beacon_state.validators[1];
+    for validator_id in &[
+        "1".to_string(),
+        format!("0x{}", hex::encode(expected.pubkey.as_slice())),
+    ] {
+        let validator = env
+            .runtime()
+            .block_on(v1.get_state_validator(StateId::Head, validator_id))
+            .expect("should fetch validator from http api");
+        assert_eq!(validator.index, 1);
+        assert_eq!(&validator.validator, expected);
+        assert_eq!(validator.balance, head.beacon_state.balances[1]);
+    }
+
+    assert!(
+        env.runtime()
+            .block_on(v1.get_state_validator(StateId::Head, "cats"))
+            .is_err(),
+        "should reject an invalid validator id"
+    );
+}
+
+#[test]
+fn v1_beacon_blocks() {
+    let mut env = build_env();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+    let v1 = remote_node.http.v1();
+
+    let beacon_chain = node
+        .client
+        .beacon_chain()
+        .expect("client should have beacon chain");
+    let head = beacon_chain.head().expect("should get head");
+
+    for block_id in &[
+        BlockId::Head,
+        BlockId::Finalized,
+        BlockId::Slot(head.beacon_block.slot()),
+        BlockId::Root(head.beacon_block_root),
+    ] {
+        let root = env
+            .runtime()
+            .block_on(v1.get_block_root(*block_id))
+            .expect("should fetch block root from http api");
+        assert_eq!(root, head.beacon_block_root, "block id {}", block_id);
+    }
+
+    let header = env
+        .runtime()
+        .block_on(v1.get_block_header(BlockId::Genesis))
+        .expect("should fetch block header from http api");
+    assert_eq!(header.root, beacon_chain.genesis_block_root);
+    assert!(header.canonical, "genesis block should be canonical");
+    assert_eq!(header.header.message.slot, Slot::new(0));
+}
+
+#[test]
+fn v1_post_block() {
+    let mut env = build_env();
+
+    let spec = &E::default_spec();
+
+    let two_slots_secs = (spec.milliseconds_per_slot / 1_000) * 2;
+
+    let mut config = testing_client_config();
+    config.genesis = ClientGenesis::Interop {
+        validator_count: 8,
+        genesis_time: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            - two_slots_secs,
+    };
+
+    let node = build_node(&mut env, config);
+    let remote_node = node.remote_node().expect("should produce remote node");
+    let v1 = remote_node.http.v1();
+
+    let beacon_chain = node
+        .client
+        .beacon_chain()
+        .expect("client should have beacon chain");
+
+    let slot = Slot::new(1);
+    let randao_reveal = get_randao_reveal(beacon_chain.clone(), slot, spec);
+    let (block, _state) = beacon_chain
+        .produce_block(randao_reveal, slot, None)
+        .expect("should produce block");
+
+    if cfg!(not(feature = "fake_crypto")) {
+        let empty_sig_block = SignedBeaconBlock {
+            message: block.clone(),
+            signature: Signature::empty_signature(),
+        };
+
+        match env.runtime().block_on(v1.post_block(&empty_sig_block)) {
+            Err(remote_beacon_node::Error::DidNotSucceed { status, .. }) => {
+                assert_eq!(status, 400, "should reject an unsigned block")
+            }
+            other => panic!("should reject an unsigned block, got {:?}", other),
+        }
+    }
+
+    let signed_block = sign_block(beacon_chain.clone(), block, spec);
+    let block_root = signed_block.canonical_root();
+
+    env.runtime()
+        .block_on(v1.post_block(&signed_block))
+        .expect("should publish block");
+
+    assert_eq!(
+        beacon_chain
+            .head_info()
+            .expect("should get head info")
+            .block_root,
+        block_root,
+        "the published block should become the head"
+    );
+}
+
+#[test]
+fn v1_node_and_validator_duties() {
+    let mut env = build_env();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+    let v1 = remote_node.http.v1();
+
+    let beacon_chain = node
+        .client
+        .beacon_chain()
+        .expect("client should have beacon chain");
+
+    let version = env
+        .runtime()
+        .block_on(v1.get_node_version())
+        .expect("should fetch version from http api");
+    assert_eq!(version, version::version());
+
+    let syncing = env
+        .runtime()
+        .block_on(v1.get_node_syncing())
+        .expect("should fetch syncing status from http api");
+    assert_eq!(
+        syncing.head_slot,
+        beacon_chain.head_info().expect("should get head info").slot
+    );
+
+    let epoch = beacon_chain.epoch().expect("should get epoch");
+
+    let proposers = env
+        .runtime()
+        .block_on(v1.get_proposer_duties(epoch))
+        .expect("should fetch proposer duties from http api");
+    assert_eq!(proposers.len(), E::slots_per_epoch() as usize);
+    for (proposer, slot) in proposers.iter().zip(epoch.slot_iter(E::slots_per_epoch())) {
+        assert_eq!(proposer.slot, slot);
+        assert_eq!(
+            proposer.validator_index as usize,
+            beacon_chain
+                .block_proposer(slot)
+                .expect("should get proposer index")
+        );
+    }
+
+    let attesters = env
+        .runtime()
+        .block_on(v1.get_attester_duties(epoch, &[0, 1]))
+        .expect("should fetch attester duties from http api");
+    let mut state = beacon_chain.head().expect("should get head").beacon_state;
+    state
+        .build_committee_cache(RelativeEpoch::Current, &beacon_chain.spec)
+        .expect("should build committee cache");
+    for attester in &attesters {
+        let duty = state
+            .get_attestation_duties(attester.validator_index as usize, RelativeEpoch::Current)
+            .expect("should get attestation duties")
+            .expect("validator should have a duty");
+        assert_eq!(attester.slot, duty.slot);
+        assert_eq!(attester.committee_index, duty.index);
+        assert_eq!(
+            attester.validator_committee_index,
+            duty.committee_position as u64
+        );
+    }
+}
+
 mod validator_attestation {
     use super::*;
     use http::StatusCode;
@@ -1297,3 +1923,402 @@ fn get_health() {
         .block_on(remote_node.http.node().get_health())
         .unwrap();
 }
+
+#[test]
+fn events_are_streamed() {
+    let mut env = build_env();
+
+    let spec = &E::default_spec();
+
+    let slot_secs = spec.milliseconds_per_slot / 1_000;
+
+    let mut config = testing_client_config();
+    config.genesis = ClientGenesis::Interop {
+        validator_count: 8,
+        genesis_time: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            - slot_secs,
+    };
+
+    let node = build_node(&mut env, config);
+    let remote_node = node.remote_node().expect("should produce remote node");
+
+    let beacon_chain = node
+        .client
+        .beacon_chain()
+        .expect("client should have beacon chain");
+
+    let mut stream = env
+        .runtime()
+        .block_on(
+            remote_node
+                .http
+                .events()
+                .stream(&["block", "attestation"], Duration::from_secs(10)),
+        )
+        .expect("should open event stream");
+
+    let slot = Slot::new(1);
+    let block_root = import_block(
+        &beacon_chain,
+        produce_signed_block(beacon_chain.clone(), slot, spec),
+    );
+
+    let event = env
+        .runtime()
+        .block_on(stream.next_event())
+        .expect("should read event")
+        .expect("should receive block event");
+    assert_eq!(event.event, "beacon_block_imported");
+    let fields: serde_json::Value = event.fields().expect("should decode block event");
+    assert_eq!(
+        fields["block_root"],
+        serde_json::to_value(block_root).unwrap(),
+        "should stream the imported block"
+    );
+
+    let state = beacon_chain.head().expect("should get head").beacon_state;
+    let committee = state
+        .get_beacon_committee(slot, 0)
+        .expect("should get committee");
+    let mut attestation = beacon_chain
+        .produce_unaggregated_attestation(slot, committee.index)
+        .expect("should produce attestation");
+    attestation
+        .sign(
+            &generate_deterministic_keypair(committee.committee[0]).sk,
+            0,
+            &state.fork,
+            state.genesis_validators_root,
+            spec,
+        )
+        .expect("should sign attestation");
+    let subnet_id = SubnetId::compute_subnet::<E>(
+        slot,
+        committee.index,
+        state
+            .get_committee_count_at_slot(slot)
+            .expect("should get committee count"),
+        spec,
+    )
+    .unwrap();
+
+    let publish_status = env
+        .runtime()
+        .block_on(
+            remote_node
+                .http
+                .validator()
+                .publish_attestations(vec![(attestation.clone(), subnet_id)]),
+        )
+        .expect("should publish attestation");
+    assert!(publish_status.is_valid(), "attestation should be valid");
+
+    // The attestation event is only sent once the attestation has been imported, which is before
+    // the http api responds.
+    let event = env
+        .runtime()
+        .block_on(stream.next_event())
+        .expect("should read event")
+        .expect("should receive attestation event");
+    assert_eq!(event.event, "beacon_attestation_imported");
+    let fields: serde_json::Value = event.fields().expect("should decode attestation event");
+    assert_eq!(
+        serde_json::from_value::<Attestation<E>>(fields["attestation"].clone())
+            .expect("should decode attestation"),
+        attestation,
+        "should stream the imported attestation"
+    );
+}
+
+#[test]
+fn lighthouse_syncing() {
+    let mut env = build_env();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+    let network_globals = node
+        .client
+        .network_globals()
+        .expect("node should have network globals");
+
+    let chain = SyncChainInfo {
+        id: 1,
+        chain_type: SyncChainType::Finalized,
+        syncing: true,
+        start_slot: Slot::new(0),
+        target_head_slot: Slot::new(64),
+        target_head_root: Hash256::repeat_byte(1),
+        processed_slot: Slot::new(16),
+        processed_batches: 1,
+        pending_batches: 2,
+        completed_batches: 1,
+        peers: 3,
+    };
+    *network_globals.sync_chains.write() = vec![chain.clone()];
+
+    let url = remote_node
+        .http
+        .server_url()
+        .join("lighthouse/syncing")
+        .expect("should build url");
+    let syncing: serde_json::Value = env
+        .runtime()
+        .block_on(remote_node.http.json_get(url, vec![]))
+        .expect("should fetch syncing from http api");
+
+    let syncing = syncing.as_object().expect("response should be an object");
+    assert_eq!(syncing.len(), 2, "response should only have two fields");
+    assert_eq!(
+        syncing["sync_state"],
+        serde_json::to_value(network_globals.sync_state()).unwrap(),
+        "should return the sync state"
+    );
+    let chains: Vec<SyncChainInfo> =
+        serde_json::from_value(syncing["chains"].clone()).expect("should decode chains");
+    assert_eq!(
+        chains,
+        vec![chain],
+        "should return the progress of each chain"
+    );
+}
+
+#[test]
+fn light_client_follows_chain() {
+    let mut env = build_env();
+
+    let spec = &E::default_spec();
+
+    let slot_count = 8;
+    let slot_secs = spec.milliseconds_per_slot / 1_000;
+
+    let mut config = testing_client_config();
+    config.genesis = ClientGenesis::Interop {
+        validator_count: 8,
+        genesis_time: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            - slot_secs * slot_count,
+    };
+
+    let node = build_node(&mut env, config);
+    let remote_node = node.remote_node().expect("should produce remote node");
+
+    let beacon_chain = node
+        .client
+        .beacon_chain()
+        .expect("client should have beacon chain");
+    let genesis_state = beacon_chain.head().expect("should get head").beacon_state;
+
+    // Build a chain with a block at every slot which is not a multiple of three, so that the
+    // light client must step over skipped slots.
+    for slot in (1..=slot_count).map(Slot::new) {
+        if slot % 3 == 0 {
+            continue;
+        }
+
+        let randao_reveal = get_randao_reveal(beacon_chain.clone(), slot, spec);
+        let (block, _state) = beacon_chain
+            .produce_block(randao_reveal, slot, None)
+            .expect("should produce block");
+        let signed_block = sign_block(beacon_chain.clone(), block, spec);
+
+        beacon_chain
+            .process_block(signed_block)
+            .expect("should import block");
+        beacon_chain.fork_choice().expect("should run fork choice");
+    }
+
+    let head = beacon_chain.head().expect("should get head");
+    assert_eq!(head.beacon_block.slot(), Slot::new(slot_count));
+
+    let mut light_client = LightClient::new(genesis_state, spec.clone());
+
+    env.runtime()
+        .block_on(light_client.update(&remote_node))
+        .expect("should follow the chain from the http api");
+
+    assert_eq!(light_client.latest_block_root(), head.beacon_block_root);
+    assert_eq!(light_client.slot(), head.beacon_block.slot());
+}
+
+#[test]
+fn light_client_finality_proof() {
+    let mut env = build_env();
+
+    let spec = &E::default_spec();
+
+    let slot_count = 5 * E::slots_per_epoch();
+    let slot_secs = spec.milliseconds_per_slot / 1_000;
+
+    let mut config = testing_client_config();
+    config.light_client_server = true;
+    config.genesis = ClientGenesis::Interop {
+        validator_count: 8,
+        genesis_time: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            - slot_secs * slot_count,
+    };
+
+    let node = build_node(&mut env, config);
+    let remote_node = node.remote_node().expect("should produce remote node");
+
+    let beacon_chain = node
+        .client
+        .beacon_chain()
+        .expect("client should have beacon chain");
+    let genesis_state = beacon_chain.head().expect("should get head").beacon_state;
+
+    extend_chain_with_attestations(beacon_chain.clone(), (1..=slot_count).map(Slot::new), spec);
+
+    let finalized_checkpoint = beacon_chain
+        .head_info()
+        .expect("should get head info")
+        .finalized_checkpoint;
+    assert!(
+        finalized_checkpoint.epoch > 0,
+        "the chain should have finalized"
+    );
+
+    let proof = env
+        .runtime()
+        .block_on(
+            remote_node
+                .http
+                .beacon()
+                .get_light_client_finality_proof(None),
+        )
+        .expect("should fetch the latest finality proof from the http api");
+
+    assert_eq!(proof.finalized_checkpoint, finalized_checkpoint);
+    assert!(
+        proof.verify(),
+        "the checkpoint branches should prove against the header's state root"
+    );
+
+    // The header must be of a block in the chain whose post-state records the finalized
+    // checkpoint, rather than of the finalized block itself.
+    let header_root = proof.header.canonical_root();
+    let block = beacon_chain
+        .get_block(&header_root)
+        .expect("should read block")
+        .expect("the proven header should be of a known block");
+    assert_eq!(block.state_root(), proof.header.state_root);
+    let state = beacon_chain
+        .get_state(&block.state_root(), Some(block.slot()))
+        .expect("should read state")
+        .expect("should have the post-state of the proven header");
+    assert_eq!(state.finalized_checkpoint, finalized_checkpoint);
+    assert_ne!(header_root, finalized_checkpoint.root);
+
+    let proof_by_root = env
+        .runtime()
+        .block_on(
+            remote_node
+                .http
+                .beacon()
+                .get_light_client_finality_proof(Some(finalized_checkpoint.root)),
+        )
+        .expect("should fetch the finality proof by root from the http api");
+    assert_eq!(proof_by_root, proof);
+
+    // A light client following the headers of the chain can verify finality with the proof.
+    let mut light_client = LightClient::new(genesis_state, spec.clone());
+    let light_client_finalized_checkpoint = env
+        .runtime()
+        .block_on(light_client.update(&remote_node))
+        .expect("should follow the chain from the http api");
+    assert_eq!(light_client_finalized_checkpoint, finalized_checkpoint);
+    assert_eq!(
+        light_client.justified_checkpoint(),
+        proof.current_justified_checkpoint
+    );
+    assert_eq!(
+        light_client.latest_block_root(),
+        beacon_chain
+            .head_info()
+            .expect("should get head info")
+            .block_root
+    );
+}
+
+#[test]
+fn light_client_recovers_from_reorg() {
+    let mut env = build_env();
+
+    let spec = &E::default_spec();
+
+    let slot_count = 6;
+    let slot_secs = spec.milliseconds_per_slot / 1_000;
+
+    let mut config = testing_client_config();
+    config.genesis = ClientGenesis::Interop {
+        validator_count: 8,
+        genesis_time: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            - slot_secs * slot_count,
+    };
+
+    // Two nodes which share a genesis, and later a prefix of their chains.
+    let node_a = build_node(&mut env, config.clone());
+    let node_b = build_node(&mut env, config);
+    let remote_node_a = node_a.remote_node().expect("should produce remote node");
+    let remote_node_b = node_b.remote_node().expect("should produce remote node");
+    let chain_a = node_a
+        .client
+        .beacon_chain()
+        .expect("client should have beacon chain");
+    let chain_b = node_b
+        .client
+        .beacon_chain()
+        .expect("client should have beacon chain");
+    let genesis_state = chain_a.head().expect("should get head").beacon_state;
+
+    for slot in (1..=3).map(Slot::new) {
+        let block = produce_signed_block(chain_a.clone(), slot, spec);
+        import_block(&chain_a, block.clone());
+        import_block(&chain_b, block);
+    }
+
+    // The chain of node A continues at slots 4 and 5, whilst that of node B skips slot 4.
+    for slot in (4..=5).map(Slot::new) {
+        import_block(&chain_a, produce_signed_block(chain_a.clone(), slot, spec));
+    }
+    import_block(
+        &chain_b,
+        produce_signed_block(chain_b.clone(), Slot::new(5), spec),
+    );
+    let root_b = import_block(
+        &chain_b,
+        produce_signed_block(chain_b.clone(), Slot::new(6), spec),
+    );
+
+    let mut light_client = LightClient::new(genesis_state, spec.clone());
+
+    env.runtime()
+        .block_on(light_client.update(&remote_node_a))
+        .expect("should follow the chain of node a");
+    assert_eq!(
+        light_client.latest_block_root(),
+        chain_a
+            .head_info()
+            .expect("should get head info")
+            .block_root
+    );
+    assert_eq!(light_client.slot(), Slot::new(5));
+
+    // The header at slot 6 of node B builds upon a block which the light client has not seen,
+    // so it must walk back to the common ancestor at slot 3.
+    env.runtime()
+        .block_on(light_client.update(&remote_node_b))
+        .expect("should follow the chain of node b");
+    assert_eq!(light_client.latest_block_root(), root_b);
+    assert_eq!(light_client.slot(), Slot::new(6));
+}
@@ -143,7 +143,7 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
             Arg::with_name("http-address")
                 .long("http-address")
                 .value_name("ADDRESS")
-                .help("Set the listen address for the RESTful HTTP API server.")
+                .help("Set the IPv4 or IPv6 listen address for the RESTful HTTP API server.")
                 .default_value("127.0.0.1")
                 .takes_value(true),
         )
@@ -155,6 +155,42 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .default_value("5052")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("http-tls-cert")
+                .long("http-tls-cert")
+                .value_name("PATH")
+                .help("Path to a PEM-encoded certificate chain. If supplied with --http-tls-key, \
+                       the RESTful HTTP API server is served over HTTPS.")
+                .requires("http-tls-key")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("http-tls-key")
+                .long("http-tls-key")
+                .value_name("PATH")
+                .help("Path to the PEM-encoded private key for --http-tls-cert.")
+                .requires("http-tls-cert")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("http-allow-origin")
+                .long("http-allow-origin")
+                .value_name("ORIGINS")
+                .help("Comma-separated list of origins (e.g., http://localhost:8080) which may \
+                       make cross-origin requests to the RESTful HTTP API server. Use \"*\" to \
+                       allow any origin. Cross-origin requests are refused by default.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("http-api-token-file")
+                .long("http-api-token-file")
+                .value_name("PATH")
+                .help("Path to a file containing a secret token. If supplied, requests to \
+                       RESTful HTTP API endpoints which modify the node (e.g., publishing blocks \
+                       or banning peers) must include an \"Authorization: Bearer <token>\" \
+                       header.")
+                .takes_value(true),
+        )
         /* Websocket related arguments */
         .arg(
             Arg::with_name("ws")
@@ -219,6 +255,42 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .takes_value(true)
         )
 
+        /*
+         * Block production.
+         */
+        .arg(
+            Arg::with_name("graffiti")
+                .long("graffiti")
+                .value_name("GRAFFITI")
+                .help("Specify your custom graffiti to be included in blocks. Validators may \
+                       override this with their own graffiti. Must be at most 32 bytes long.")
+                .takes_value(true)
+        )
+
+        /*
+         * Logging.
+         */
+        .arg(
+            Arg::with_name("reorg-warn-depth")
+                .long("reorg-warn-depth")
+                .value_name("SLOTS")
+                .help("Re-orgs which revert more than this many slots of the canonical chain are \
+                       logged as warnings, others are logged as info.")
+                .default_value("1")
+                .takes_value(true)
+        )
+
+        /*
+         * Light client server.
+         */
+        .arg(
+            Arg::with_name("light-client-server")
+                .long("light-client-server")
+                .help("Store a Merkle proof of the checkpoints of each finalized block and serve \
+                       them to light clients via the HTTP API.")
+                .takes_value(false)
+        )
+
         /*
          * Purge.
          */
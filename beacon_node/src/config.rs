@@ -10,7 +10,7 @@ use std::fs;
 use std::net::{IpAddr, Ipv4Addr, ToSocketAddrs};
 use std::net::{TcpListener, UdpSocket};
 use std::path::PathBuf;
-use types::{ChainSpec, EthSpec};
+use types::{graffiti_from_str, ChainSpec, EthSpec};
 
 pub const BEACON_NODE_DIR: &str = "beacon";
 pub const NETWORK_DIR: &str = "network";
@@ -210,8 +210,8 @@ pub fn get_config<E: EthSpec>(
 
     if let Some(address) = cli_args.value_of("http-address") {
         client_config.rest_api.listen_address = address
-            .parse::<Ipv4Addr>()
-            .map_err(|_| "http-address is not a valid IPv4 address.")?;
+            .parse::<IpAddr>()
+            .map_err(|_| "http-address is not a valid IP address.")?;
     }
 
     if let Some(port) = cli_args.value_of("http-port") {
@@ -220,6 +220,34 @@ pub fn get_config<E: EthSpec>(
             .map_err(|_| "http-port is not a valid u16.")?;
     }
 
+    if let (Some(cert), Some(key)) = (
+        cli_args.value_of("http-tls-cert"),
+        cli_args.value_of("http-tls-key"),
+    ) {
+        client_config.rest_api.tls_config = Some(rest_api::TlsConfig {
+            cert: PathBuf::from(cert),
+            key: PathBuf::from(key),
+        });
+    }
+
+    if let Some(origins) = cli_args.value_of("http-allow-origin") {
+        client_config.rest_api.allow_origins = origins
+            .split(',')
+            .map(|origin| origin.trim().to_string())
+            .filter(|origin| !origin.is_empty())
+            .collect();
+    }
+
+    if let Some(token_file) = cli_args.value_of("http-api-token-file") {
+        let token = fs::read_to_string(token_file)
+            .map_err(|e| format!("Unable to read http-api-token-file: {:?}", e))?;
+        let token = token.trim();
+        if token.is_empty() {
+            return Err("http-api-token-file is empty.".to_string());
+        }
+        client_config.rest_api.api_token = Some(token.to_string());
+    }
+
     /*
      * Websocket server
      */
@@ -285,6 +313,20 @@ pub fn get_config<E: EthSpec>(
             .map_err(|_| "block-cache-size is not a valid integer".to_string())?;
     }
 
+    if let Some(graffiti) = cli_args.value_of("graffiti") {
+        client_config.graffiti = graffiti_from_str(graffiti)?;
+    }
+
+    if let Some(reorg_warn_depth) = cli_args.value_of("reorg-warn-depth") {
+        client_config.reorg_warn_depth = reorg_warn_depth
+            .parse()
+            .map_err(|_| "reorg-warn-depth is not a valid integer".to_string())?;
+    }
+
+    if cli_args.is_present("light-client-server") {
+        client_config.light_client_server = true;
+    }
+
     if spec_constants != client_config.spec_constants {
         crit!(log, "Specification constants do not match.";
               "client_config" => client_config.spec_constants,
@@ -1,6 +1,7 @@
 use crate::chunked_vector::ChunkError;
 use crate::hot_cold_store::HotColdDBError;
 use ssz::DecodeError;
+use state_processing::BlockReplayError;
 use types::{BeaconStateError, Hash256, Slot};
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -37,6 +38,12 @@ impl From<HotColdDBError> for Error {
     }
 }
 
+impl From<BlockReplayError> for Error {
+    fn from(e: BlockReplayError) -> Error {
+        Error::HotColdDBError(HotColdDBError::BlockReplayError(e))
+    }
+}
+
 impl From<BeaconStateError> for Error {
     fn from(e: BeaconStateError) -> Error {
         Error::BeaconStateError(e)
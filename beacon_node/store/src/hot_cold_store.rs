@@ -9,18 +9,15 @@ use crate::leveldb_store::LevelDB;
 use crate::memory_store::MemoryStore;
 use crate::metrics;
 use crate::{
-    get_key_for_col, DBColumn, Error, ItemStore, KeyValueStoreOp, PartialBeaconState, StoreItem,
-    StoreOp,
+    get_key_for_col, DBColumn, Error, ItemStore, KeyValueStoreOp, PartialBeaconState,
+    PartialBeaconStateView, StoreItem, StoreOp,
 };
 use lru::LruCache;
 use parking_lot::{Mutex, RwLock};
 use slog::{debug, error, trace, warn, Logger};
-use ssz::{Decode, Encode};
+use ssz::{Decode, Encode, ListView};
 use ssz_derive::{Decode, Encode};
-use state_processing::{
-    per_block_processing, per_slot_processing, BlockProcessingError, BlockSignatureStrategy,
-    SlotProcessingError,
-};
+use state_processing::{BlockReplayError, BlockReplayer};
 use std::convert::TryInto;
 use std::marker::PhantomData;
 use std::path::Path;
@@ -77,8 +74,7 @@ pub enum HotColdDBError {
     HotStateSummaryError(BeaconStateError),
     RestorePointDecodeError(ssz::DecodeError),
     BlockReplayBeaconError(BeaconStateError),
-    BlockReplaySlotError(SlotProcessingError),
-    BlockReplayBlockError(BlockProcessingError),
+    BlockReplayError(BlockReplayError),
     InvalidSlotsPerRestorePoint {
         slots_per_restore_point: u64,
         slots_per_historical_root: u64,
@@ -303,6 +299,10 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         self.hot_db.exists::<I>(key)
     }
 
+    pub fn delete_item<I: StoreItem>(&self, key: &Hash256) -> Result<(), Error> {
+        self.hot_db.delete::<I>(key)
+    }
+
     pub fn do_atomically(&self, batch: Vec<StoreOp<E>>) -> Result<(), Error> {
         let mut guard = self.block_cache.lock();
 
@@ -425,7 +425,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
             } else {
                 let blocks =
                     self.load_blocks_to_replay(boundary_state.slot, slot, latest_block_root)?;
-                self.replay_blocks(boundary_state, blocks, slot)?
+                self.replay_blocks(boundary_state, blocks, slot, std::iter::empty())?
             };
 
             Ok(Some(state))
@@ -527,6 +527,54 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         self.load_restore_point(&state_root)
     }
 
+    /// Apply `f` to a view over the restore point state with `state_root`.
+    ///
+    /// Only the offsets of the stored `PartialBeaconState` are checked, so `f` pays for decoding
+    /// just the fields it reads. The vector fields stored separately (e.g., `block_roots`) are
+    /// not available from the view.
+    pub fn with_restore_point_view<F, R>(&self, state_root: &Hash256, f: F) -> Result<R, Error>
+    where
+        F: FnOnce(&PartialBeaconStateView<E>) -> Result<R, Error>,
+    {
+        let bytes = self
+            .cold_db
+            .get_bytes(DBColumn::BeaconState.into(), state_root.as_bytes())?
+            .ok_or_else(|| HotColdDBError::MissingRestorePoint(*state_root))?;
+        let view = PartialBeaconStateView::from_ssz_bytes(&bytes)?;
+
+        f(&view)
+    }
+
+    /// Return `true` if the state with `state_root` is stored in the freezer as a restore point,
+    /// i.e. it can be read via `with_restore_point_view`.
+    pub fn is_restore_point(&self, state_root: &Hash256) -> Result<bool, Error> {
+        self.cold_db
+            .key_exists(DBColumn::BeaconState.into(), state_root.as_bytes())
+    }
+
+    /// Load the validator at `validator_index` and its balance from the restore point state with
+    /// `state_root`, without decoding the rest of the registry.
+    ///
+    /// Return `None` if there is no such validator in the state.
+    pub fn load_restore_point_validator(
+        &self,
+        state_root: &Hash256,
+        validator_index: usize,
+    ) -> Result<Option<(Validator, u64)>, Error> {
+        self.with_restore_point_view(state_root, |view| {
+            let validators = ListView::<Validator>::from_ssz_bytes(view.validators_bytes())?;
+            let balances = ListView::<u64>::from_ssz_bytes(view.balances_bytes())?;
+
+            match (
+                validators.get(validator_index),
+                balances.get(validator_index),
+            ) {
+                (Some(validator), Some(balance)) => Ok(Some((validator?, balance?))),
+                _ => Ok(None),
+            }
+        })
+    }
+
     /// Load a frozen state that lies between restore points.
     fn load_cold_intermediate_state(&self, slot: Slot) -> Result<BeaconState<E>, Error> {
         // 1. Load the restore points either side of the intermediate state.
@@ -555,8 +603,17 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
             self.get_high_restore_point_block_root(&high_restore_point, slot)?,
         )?;
 
-        // 3. Replay the blocks on top of the low restore point.
-        self.replay_blocks(low_restore_point, blocks, slot)
+        // 3. Replay the blocks on top of the low restore point, using the state roots from the
+        // high restore point to avoid hashing the intermediate states.
+        let state_root_iter = (low_restore_point.slot.as_u64()..slot.as_u64())
+            .map(Slot::new)
+            .filter_map(|slot| {
+                high_restore_point
+                    .get_state_root(slot)
+                    .ok()
+                    .map(|state_root| Ok((*state_root, slot)))
+            });
+        self.replay_blocks(low_restore_point, blocks, slot, state_root_iter)
     }
 
     /// Get a suitable block root for backtracking from `high_restore_point` to the state at `slot`.
@@ -608,54 +665,21 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
 
     /// Replay `blocks` on top of `state` until `target_slot` is reached.
     ///
-    /// Will skip slots as necessary. The returned state is not guaranteed
-    /// to have any caches built, beyond those immediately required by block processing.
+    /// Will skip slots as necessary, using the roots from `state_root_iter` where possible. The
+    /// returned state is not guaranteed to have any caches built, beyond those immediately
+    /// required by block processing.
     fn replay_blocks(
         &self,
-        mut state: BeaconState<E>,
+        state: BeaconState<E>,
         blocks: Vec<SignedBeaconBlock<E>>,
         target_slot: Slot,
+        state_root_iter: impl Iterator<Item = Result<(Hash256, Slot), Error>>,
     ) -> Result<BeaconState<E>, Error> {
-        let state_root_from_prev_block = |i: usize, state: &BeaconState<E>| {
-            if i > 0 {
-                let prev_block = &blocks[i - 1].message;
-                if prev_block.slot == state.slot {
-                    Some(prev_block.state_root)
-                } else {
-                    None
-                }
-            } else {
-                None
-            }
-        };
-
-        for (i, block) in blocks.iter().enumerate() {
-            if block.message.slot <= state.slot {
-                continue;
-            }
-
-            while state.slot < block.message.slot {
-                let state_root = state_root_from_prev_block(i, &state);
-                per_slot_processing(&mut state, state_root, &self.spec)
-                    .map_err(HotColdDBError::BlockReplaySlotError)?;
-            }
-            per_block_processing(
-                &mut state,
-                &block,
-                None,
-                BlockSignatureStrategy::NoVerification,
-                &self.spec,
-            )
-            .map_err(HotColdDBError::BlockReplayBlockError)?;
-        }
-
-        while state.slot < target_slot {
-            let state_root = state_root_from_prev_block(blocks.len(), &state);
-            per_slot_processing(&mut state, state_root, &self.spec)
-                .map_err(HotColdDBError::BlockReplaySlotError)?;
-        }
-
-        Ok(state)
+        BlockReplayer::new(state, &self.spec)
+            .no_signature_verification()
+            .state_root_iter(state_root_iter)
+            .apply_blocks(blocks, Some(target_slot))
+            .map(BlockReplayer::into_state)
     }
 
     /// Fetch a copy of the current split slot from memory.
@@ -30,7 +30,7 @@ pub use self::config::StoreConfig;
 pub use self::hot_cold_store::{HotColdDB, HotStateSummary, Split};
 pub use self::leveldb_store::LevelDB;
 pub use self::memory_store::MemoryStore;
-pub use self::partial_beacon_state::PartialBeaconState;
+pub use self::partial_beacon_state::{PartialBeaconState, PartialBeaconStateView};
 pub use errors::Error;
 pub use impls::beacon_state::StorageContainer as BeaconStateStorageContainer;
 pub use metrics::scrape_for_metrics;
@@ -138,6 +138,8 @@ pub enum DBColumn {
     BeaconState,
     /// For persisting in-memory state to the database.
     BeaconChain,
+    /// For the tree hash cache of the head state, which is persisted on shutdown.
+    BeaconTreeHashCache,
     OpPool,
     Eth1Cache,
     ForkChoice,
@@ -150,6 +152,10 @@ pub enum DBColumn {
     BeaconHistoricalRoots,
     BeaconRandaoMixes,
     DhtEnrs,
+    /// For range sync batches which have been downloaded but not yet processed.
+    SyncBatches,
+    /// For the finality proofs served to light clients, keyed by finalized block root.
+    LightClientFinalityProof,
 }
 
 impl Into<&'static str> for DBColumn {
@@ -160,6 +166,7 @@ impl Into<&'static str> for DBColumn {
             DBColumn::BeaconBlock => "blk",
             DBColumn::BeaconState => "ste",
             DBColumn::BeaconChain => "bch",
+            DBColumn::BeaconTreeHashCache => "thc",
             DBColumn::OpPool => "opo",
             DBColumn::Eth1Cache => "etc",
             DBColumn::ForkChoice => "frk",
@@ -170,6 +177,8 @@ impl Into<&'static str> for DBColumn {
             DBColumn::BeaconHistoricalRoots => "bhr",
             DBColumn::BeaconRandaoMixes => "brm",
             DBColumn::DhtEnrs => "dht",
+            DBColumn::SyncBatches => "syb",
+            DBColumn::LightClientFinalityProof => "lcf",
         }
     }
 }
@@ -13,6 +13,7 @@ use types::*;
 ///
 /// Spec v0.12.1
 #[derive(Debug, PartialEq, Clone, Encode, Decode)]
+#[ssz(view = "PartialBeaconStateView")]
 pub struct PartialBeaconState<T>
 where
     T: EthSpec,
@@ -1,8 +1,10 @@
 use slog::{debug, error, info, warn};
 use std::marker::PhantomData;
 use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 use types::EthSpec;
-use ws::{Sender, WebSocket};
+use ws::{Handler, Sender, WebSocket};
 
 mod config;
 
@@ -10,18 +12,47 @@ pub use config::Config;
 
 pub struct WebSocketSender<T: EthSpec> {
     sender: Option<Sender>,
+    /// The number of clients which are currently connected.
+    connections: Arc<AtomicUsize>,
     _phantom: PhantomData<T>,
 }
 
+/// Ignores any incoming messages, whilst keeping count of the open connections.
+struct Connection {
+    connections: Arc<AtomicUsize>,
+}
+
+impl Connection {
+    fn new(connections: Arc<AtomicUsize>) -> Self {
+        connections.fetch_add(1, Ordering::Relaxed);
+        Self { connections }
+    }
+}
+
+impl Handler for Connection {}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        self.connections.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
 impl<T: EthSpec> WebSocketSender<T> {
     /// Creates a dummy websocket server that never starts and where all future calls are no-ops.
     pub fn dummy() -> Self {
         Self {
             sender: None,
+            connections: Arc::new(AtomicUsize::new(0)),
             _phantom: PhantomData,
         }
     }
 
+    /// Returns `true` if any clients are connected, i.e., if there is any point in sending them
+    /// a message.
+    pub fn has_clients(&self) -> bool {
+        self.sender.is_some() && self.connections.load(Ordering::Relaxed) > 0
+    }
+
     pub fn send_string(&self, string: String) -> Result<(), String> {
         if let Some(sender) = &self.sender {
             sender
@@ -41,7 +72,9 @@ pub fn start_server<T: EthSpec>(
     let server_string = format!("{}:{}", config.listen_address, config.port);
 
     // Create a server that simply ignores any incoming messages.
-    let server = WebSocket::new(|_| |_| Ok(()))
+    let connections = Arc::new(AtomicUsize::new(0));
+    let inner_connections = connections.clone();
+    let server = WebSocket::new(move |_| Connection::new(inner_connections.clone()))
         .map_err(|e| format!("Failed to initialize websocket server: {:?}", e))?
         .bind(server_string.clone())
         .map_err(|e| {
@@ -109,6 +142,7 @@ pub fn start_server<T: EthSpec>(
     Ok((
         WebSocketSender {
             sender: Some(broadcaster),
+            connections,
             _phantom: PhantomData,
         },
         actual_listen_addr,
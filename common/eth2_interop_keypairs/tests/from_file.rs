@@ -13,11 +13,18 @@ fn load_from_yaml() {
     let keypairs = keypairs_from_yaml_file(yaml_path()).expect("should read keypairs from file");
 
     keypairs.into_iter().enumerate().for_each(|(i, keypair)| {
+        let reference = reference_keypair(i);
+
+        assert_eq!(
+            keypair.pk, reference.pk,
+            "Decoded public key {} does not match generated key",
+            i
+        );
         assert_eq!(
-            keypair,
-            reference_keypair(i),
-            "Decoded key {} does not match generated key",
+            keypair.sk.as_bytes().as_bytes(),
+            reference.sk.as_bytes().as_bytes(),
+            "Decoded secret key {} does not match generated key",
             i
-        )
+        );
     });
 }
@@ -0,0 +1,329 @@
+//! A client which follows the finality of the beacon chain without running a beacon node.
+//!
+//! Starting from a trusted checkpoint state, the `LightClient` follows the block headers served by
+//! a remote beacon node and verifies that each header:
+//!
+//! - Builds upon a previously verified header.
+//! - Is signed by its proposer, using the validator set of the trusted state.
+//!
+//! No blocks are applied to any state. Instead, the justified and finalized checkpoints are
+//! advanced using `LightClientFinalityProof`s, whose Merkle branches prove the checkpoints against
+//! the `state_root` of a verified header.
+
+use futures::future;
+use remote_beacon_node::{BlockId, RemoteBeaconNode, V1};
+use std::convert::TryInto;
+use std::marker::PhantomData;
+use types::{
+    BeaconBlockHeader, BeaconState, ChainSpec, Checkpoint, Domain, EthSpec, Fork, Hash256,
+    LightClientFinalityProof, PublicKey, PublicKeyBytes, SignedBeaconBlockHeader, SignedRoot, Slot,
+};
+
+/// The number of headers which are requested from the remote beacon node concurrently.
+pub const HEADER_BATCH_SIZE: u64 = 32;
+
+#[derive(Debug)]
+pub enum Error {
+    /// The remote beacon node returned an error.
+    RemoteBeaconNode(remote_beacon_node::Error),
+    /// The header is not later than its parent.
+    HeaderNotLater {
+        header_slot: Slot,
+        parent_slot: Slot,
+    },
+    /// The parent of the header is not a verified header which descends from the finalized block.
+    UnknownParent { parent_root: Hash256 },
+    /// The proposer of the header is not in the validator set of the trusted state.
+    UnknownProposer(u64),
+    /// The public key of the proposer is invalid.
+    InvalidProposerPubkey(u64),
+    /// The header does not carry a valid signature from its proposer.
+    InvalidProposerSignature,
+    /// The chain of the remote beacon node does not descend from the finalized block.
+    ConflictsWithFinality { root: Hash256 },
+    /// The header of the finality proof has not been verified.
+    UnknownFinalityProofHeader(Hash256),
+    /// The Merkle branches of the finality proof do not prove its checkpoints against the
+    /// `state_root` of its header.
+    InvalidFinalityProof,
+}
+
+impl From<remote_beacon_node::Error> for Error {
+    fn from(e: remote_beacon_node::Error) -> Self {
+        Error::RemoteBeaconNode(e)
+    }
+}
+
+/// Follows the finality of the beacon chain from a trusted checkpoint state.
+///
+/// Headers and proofs are verified all-or-nothing: if one is rejected, the client remains at the
+/// most recently verified header.
+pub struct LightClient<E: EthSpec> {
+    /// The public keys of the validators of the trusted state.
+    validator_pubkeys: Vec<PublicKeyBytes>,
+    fork: Fork,
+    genesis_validators_root: Hash256,
+    /// The verified chain of headers (and their roots), from the finalized block (or the block of
+    /// the trusted state) to the most recently verified header.
+    headers: Vec<(Hash256, BeaconBlockHeader)>,
+    justified_checkpoint: Checkpoint,
+    finalized_checkpoint: Checkpoint,
+    spec: ChainSpec,
+    _phantom: PhantomData<E>,
+}
+
+impl<E: EthSpec> LightClient<E> {
+    /// Starts following the chain from a trusted `state`.
+    ///
+    /// The `state` must be the post-state of a block (e.g., a finalized state obtained from a
+    /// trusted source), since its latest block is the parent of the first header to be verified.
+    pub fn new(state: BeaconState<E>, spec: ChainSpec) -> Self {
+        let mut latest_block_header = state.latest_block_header.clone();
+        if latest_block_header.state_root == Hash256::zero() {
+            latest_block_header.state_root = state.canonical_root();
+        }
+
+        Self {
+            validator_pubkeys: state
+                .validators
+                .iter()
+                .map(|validator| validator.pubkey.clone())
+                .collect(),
+            fork: state.fork,
+            genesis_validators_root: state.genesis_validators_root,
+            headers: vec![(latest_block_header.canonical_root(), latest_block_header)],
+            justified_checkpoint: state.current_justified_checkpoint,
+            finalized_checkpoint: state.finalized_checkpoint,
+            spec,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns the slot of the most recently verified header.
+    pub fn slot(&self) -> Slot {
+        self.latest_header().1.slot
+    }
+
+    /// Returns the root of the most recently verified header.
+    pub fn latest_block_root(&self) -> Hash256 {
+        self.latest_header().0
+    }
+
+    /// Returns the justified checkpoint, as proven by the most recent finality proof.
+    pub fn justified_checkpoint(&self) -> Checkpoint {
+        self.justified_checkpoint
+    }
+
+    /// Returns the finalized checkpoint, as proven by the most recent finality proof.
+    pub fn finalized_checkpoint(&self) -> Checkpoint {
+        self.finalized_checkpoint
+    }
+
+    fn latest_header(&self) -> &(Hash256, BeaconBlockHeader) {
+        self.headers
+            .last()
+            .expect("there is always at least the finalized header")
+    }
+
+    fn header_index(&self, root: &Hash256) -> Option<usize> {
+        self.headers
+            .iter()
+            .position(|(header_root, _)| header_root == root)
+    }
+
+    /// Fetches and verifies the headers between the most recently verified header and the head
+    /// of `beacon_node`, then advances finality using the latest finality proof of `beacon_node`.
+    ///
+    /// Returns the finalized checkpoint after the update.
+    pub async fn update(&mut self, beacon_node: &RemoteBeaconNode<E>) -> Result<Checkpoint, Error> {
+        let v1 = beacon_node.http.v1();
+        let head = v1.get_block_header(BlockId::Head).await?.header;
+
+        let mut slot = self.slot() + 1;
+        while slot <= head.message.slot {
+            let end = std::cmp::min(slot + HEADER_BATCH_SIZE, head.message.slot + 1);
+
+            let headers = future::try_join_all(
+                (slot.as_u64()..end.as_u64()).map(|slot| get_header_at_slot(&v1, Slot::new(slot))),
+            )
+            .await?;
+
+            for header in headers.into_iter().flatten() {
+                self.process_remote_header(&v1, header).await?;
+            }
+
+            slot = end;
+        }
+
+        // The head may be at or prior to the slot of the latest header if the remote beacon node
+        // has re-orged to a shorter chain.
+        self.process_remote_header(&v1, head).await?;
+
+        match beacon_node
+            .http
+            .beacon()
+            .get_light_client_finality_proof(None)
+            .await
+        {
+            Ok(proof) => self.process_finality_proof(&proof)?,
+            // The remote beacon node has not finalized a block whilst serving light clients.
+            Err(e) if is_not_found(&e) => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        Ok(self.finalized_checkpoint())
+    }
+
+    /// Verifies `header` and any of its ancestors which have not been verified, fetching them
+    /// from the remote beacon node.
+    ///
+    /// This walks back to a verified ancestor when the remote beacon node has re-orged.
+    async fn process_remote_header(
+        &mut self,
+        v1: &V1<E>,
+        header: SignedBeaconBlockHeader,
+    ) -> Result<(), Error> {
+        let finalized_slot = self.headers[0].1.slot;
+
+        let mut branch = vec![header];
+        while let Some(oldest) = branch.last().map(|header| &header.message) {
+            if self.header_index(&oldest.parent_root).is_some()
+                || self.header_index(&oldest.canonical_root()).is_some()
+            {
+                break;
+            }
+
+            if oldest.slot <= finalized_slot {
+                return Err(Error::ConflictsWithFinality {
+                    root: oldest.canonical_root(),
+                });
+            }
+
+            let parent = v1
+                .get_block_header(BlockId::Root(oldest.parent_root))
+                .await?
+                .header;
+            branch.push(parent);
+        }
+
+        for header in branch.iter().rev() {
+            self.process_header(header)?;
+        }
+
+        Ok(())
+    }
+
+    /// Verifies `header` and, if it is valid, makes it the most recently verified header.
+    ///
+    /// A header which builds upon a verified header other than the most recently verified one
+    /// (i.e., a re-org) replaces the headers which descend from its parent. Processing a header
+    /// which has already been verified makes it the most recently verified header.
+    pub fn process_header(&mut self, header: &SignedBeaconBlockHeader) -> Result<(), Error> {
+        let message = &header.message;
+        let root = message.canonical_root();
+
+        if let Some(index) = self.header_index(&root) {
+            self.headers.truncate(index + 1);
+            return Ok(());
+        }
+
+        let parent_index =
+            self.header_index(&message.parent_root)
+                .ok_or_else(|| Error::UnknownParent {
+                    parent_root: message.parent_root,
+                })?;
+
+        let parent_slot = self.headers[parent_index].1.slot;
+        if message.slot <= parent_slot {
+            return Err(Error::HeaderNotLater {
+                header_slot: message.slot,
+                parent_slot,
+            });
+        }
+
+        let pubkey: PublicKey = self
+            .validator_pubkeys
+            .get(message.proposer_index as usize)
+            .ok_or_else(|| Error::UnknownProposer(message.proposer_index))?
+            .try_into()
+            .map_err(|_| Error::InvalidProposerPubkey(message.proposer_index))?;
+
+        let domain = self.spec.get_domain(
+            message.slot.epoch(E::slots_per_epoch()),
+            Domain::BeaconProposer,
+            &self.fork,
+            self.genesis_validators_root,
+        );
+        if !header
+            .signature
+            .verify(message.signing_root(domain).as_bytes(), &pubkey)
+        {
+            return Err(Error::InvalidProposerSignature);
+        }
+
+        self.headers.truncate(parent_index + 1);
+        self.headers.push((root, message.clone()));
+
+        Ok(())
+    }
+
+    /// Verifies `proof` against a verified header and, if it is valid, advances the justified and
+    /// finalized checkpoints to those it proves.
+    ///
+    /// Proofs which do not advance either checkpoint are ignored.
+    pub fn process_finality_proof(
+        &mut self,
+        proof: &LightClientFinalityProof,
+    ) -> Result<(), Error> {
+        if proof.finalized_checkpoint.epoch <= self.finalized_checkpoint.epoch
+            && proof.current_justified_checkpoint.epoch <= self.justified_checkpoint.epoch
+        {
+            return Ok(());
+        }
+
+        let header_root = proof.header.canonical_root();
+        if self.header_index(&header_root).is_none() {
+            return Err(Error::UnknownFinalityProofHeader(header_root));
+        }
+
+        if !proof.verify() {
+            return Err(Error::InvalidFinalityProof);
+        }
+
+        if proof.current_justified_checkpoint.epoch > self.justified_checkpoint.epoch {
+            self.justified_checkpoint = proof.current_justified_checkpoint;
+        }
+
+        if proof.finalized_checkpoint.epoch > self.finalized_checkpoint.epoch {
+            self.finalized_checkpoint = proof.finalized_checkpoint;
+
+            // Headers prior to the finalized block can never be re-orged, so they are no longer
+            // needed.
+            if let Some(index) = self.header_index(&proof.finalized_checkpoint.root) {
+                self.headers.drain(..index);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the header of the block at `slot` in the canonical chain of the remote beacon node, or
+/// `None` if the slot was skipped.
+async fn get_header_at_slot<E: EthSpec>(
+    v1: &V1<E>,
+    slot: Slot,
+) -> Result<Option<SignedBeaconBlockHeader>, Error> {
+    match v1.get_block_header(BlockId::Slot(slot)).await {
+        Ok(data) => Ok(Some(data.header)),
+        Err(e) if is_not_found(&e) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn is_not_found(e: &remote_beacon_node::Error) -> bool {
+    match e {
+        remote_beacon_node::Error::DidNotSucceed { status, .. } => status.as_u16() == 404,
+        _ => false,
+    }
+}
@@ -0,0 +1,323 @@
+#![cfg(not(debug_assertions))]
+
+#[macro_use]
+extern crate lazy_static;
+
+use beacon_chain::{
+    test_utils::{AttestationStrategy, BeaconChainHarness, BlockStrategy, HarnessType},
+    BeaconSnapshot,
+};
+use light_client::{Error, LightClient};
+use store::config::StoreConfig;
+use types::{
+    Epoch, EthSpec, Keypair, LightClientFinalityProof, MinimalEthSpec, Signature,
+    SignedBeaconBlock, SignedBeaconBlockHeader, Slot,
+};
+
+type E = MinimalEthSpec;
+
+pub const VALIDATOR_COUNT: usize = 24;
+
+lazy_static! {
+    /// A cached set of keys.
+    static ref KEYPAIRS: Vec<Keypair> = types::test_utils::generate_deterministic_keypairs(VALIDATOR_COUNT);
+}
+
+fn get_harness() -> BeaconChainHarness<HarnessType<E>> {
+    let harness =
+        BeaconChainHarness::new(MinimalEthSpec, KEYPAIRS.to_vec(), StoreConfig::default());
+
+    harness.advance_slot();
+
+    harness
+}
+
+/// Builds a chain which finalizes, with some skipped slots, and returns each of its blocks (and
+/// their post-states) from genesis to the head.
+fn build_chain(harness: &BeaconChainHarness<HarnessType<E>>) -> Vec<BeaconSnapshot<E>> {
+    harness.extend_chain(
+        E::slots_per_epoch() as usize * 2 + 3,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    // Advance past the last block, then skip two slots.
+    for _ in 0..3 {
+        harness.advance_slot();
+    }
+
+    harness.extend_chain(
+        E::slots_per_epoch() as usize * 3,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    harness.chain.chain_dump().expect("should dump chain")
+}
+
+/// Returns the signed header of `block`.
+fn signed_header(block: &SignedBeaconBlock<E>) -> SignedBeaconBlockHeader {
+    SignedBeaconBlockHeader {
+        message: block.message.block_header(),
+        signature: block.signature.clone(),
+    }
+}
+
+/// Returns a finality proof of the checkpoints of the post-state of `snapshot`.
+fn finality_proof(snapshot: &BeaconSnapshot<E>) -> LightClientFinalityProof {
+    let mut state = snapshot.beacon_state.clone();
+    LightClientFinalityProof::new(snapshot.beacon_block.message.block_header(), &mut state)
+        .expect("should compute finality proof")
+}
+
+#[test]
+fn follows_finality_from_genesis() {
+    let harness = get_harness();
+    let chain = build_chain(&harness);
+
+    let mut light_client = LightClient::new(chain[0].beacon_state.clone(), harness.spec.clone());
+    assert_eq!(light_client.latest_block_root(), chain[0].beacon_block_root);
+
+    for snapshot in &chain[1..] {
+        light_client
+            .process_header(&signed_header(&snapshot.beacon_block))
+            .expect("should verify header");
+    }
+
+    let head = harness.chain.head().expect("should get head");
+    assert_eq!(light_client.latest_block_root(), head.beacon_block_root);
+    assert_eq!(light_client.slot(), head.beacon_block.slot());
+
+    // Following headers alone does not advance finality.
+    assert_eq!(light_client.finalized_checkpoint().epoch, Epoch::new(0));
+
+    light_client
+        .process_finality_proof(&finality_proof(&head))
+        .expect("should verify finality proof");
+
+    assert!(
+        head.beacon_state.finalized_checkpoint.epoch > 0,
+        "the chain should finalize"
+    );
+    assert_eq!(
+        light_client.finalized_checkpoint(),
+        head.beacon_state.finalized_checkpoint
+    );
+    assert_eq!(
+        light_client.justified_checkpoint(),
+        head.beacon_state.current_justified_checkpoint
+    );
+
+    // Headers prior to the finalized block are pruned, so a header which conflicts with
+    // finality is rejected.
+    assert!(matches!(
+        light_client.process_header(&signed_header(&chain[1].beacon_block)),
+        Err(Error::UnknownParent { .. })
+    ));
+    assert_eq!(light_client.latest_block_root(), head.beacon_block_root);
+}
+
+#[test]
+fn follows_finality_from_checkpoint() {
+    let harness = get_harness();
+    let chain = build_chain(&harness);
+
+    let start = chain
+        .iter()
+        .position(|snapshot| snapshot.beacon_block.slot() == E::slots_per_epoch() * 2 + 2)
+        .expect("should find start block");
+
+    let mut light_client =
+        LightClient::new(chain[start].beacon_state.clone(), harness.spec.clone());
+    assert_eq!(
+        light_client.latest_block_root(),
+        chain[start].beacon_block_root
+    );
+    assert_eq!(
+        light_client.finalized_checkpoint(),
+        chain[start].beacon_state.finalized_checkpoint
+    );
+
+    for snapshot in &chain[start + 1..] {
+        light_client
+            .process_header(&signed_header(&snapshot.beacon_block))
+            .expect("should verify header");
+    }
+
+    let head = harness.chain.head().expect("should get head");
+    light_client
+        .process_finality_proof(&finality_proof(&head))
+        .expect("should verify finality proof");
+
+    assert!(
+        head.beacon_state.finalized_checkpoint.epoch
+            > chain[start].beacon_state.finalized_checkpoint.epoch,
+        "finality should advance after the checkpoint"
+    );
+    assert_eq!(
+        light_client.finalized_checkpoint(),
+        head.beacon_state.finalized_checkpoint
+    );
+}
+
+#[test]
+fn rejects_invalid_finality_proof() {
+    let harness = get_harness();
+    let chain = build_chain(&harness);
+
+    let mut light_client = LightClient::new(chain[0].beacon_state.clone(), harness.spec.clone());
+    let head = harness.chain.head().expect("should get head");
+    let proof = finality_proof(&head);
+
+    // The header of the proof has not been verified yet.
+    assert!(matches!(
+        light_client.process_finality_proof(&proof),
+        Err(Error::UnknownFinalityProofHeader(_))
+    ));
+
+    for snapshot in &chain[1..] {
+        light_client
+            .process_header(&signed_header(&snapshot.beacon_block))
+            .expect("should verify header");
+    }
+
+    let mut wrong_checkpoint = proof.clone();
+    wrong_checkpoint.finalized_checkpoint.epoch += 1;
+    assert!(matches!(
+        light_client.process_finality_proof(&wrong_checkpoint),
+        Err(Error::InvalidFinalityProof)
+    ));
+
+    let mut wrong_branch = proof.clone();
+    wrong_branch.finalized_checkpoint_branch[0] = wrong_branch.finalized_checkpoint_branch[1];
+    assert!(matches!(
+        light_client.process_finality_proof(&wrong_branch),
+        Err(Error::InvalidFinalityProof)
+    ));
+    assert_eq!(light_client.finalized_checkpoint().epoch, Epoch::new(0));
+
+    light_client
+        .process_finality_proof(&proof)
+        .expect("should verify valid proof");
+    assert_eq!(
+        light_client.finalized_checkpoint(),
+        head.beacon_state.finalized_checkpoint
+    );
+}
+
+#[test]
+fn follows_reorg() {
+    let harness = get_harness();
+    let chain = build_chain(&harness);
+
+    let mut light_client = LightClient::new(chain[0].beacon_state.clone(), harness.spec.clone());
+    for snapshot in &chain[1..] {
+        light_client
+            .process_header(&signed_header(&snapshot.beacon_block))
+            .expect("should verify header");
+    }
+
+    // Build a block which forks from the head's grandparent.
+    let head = chain.last().expect("should have head");
+    let fork_parent = &chain[chain.len() - 3];
+    let fork_root = harness.extend_chain(
+        1,
+        BlockStrategy::ForkCanonicalChainAt {
+            previous_slot: fork_parent.beacon_block.slot(),
+            first_slot: head.beacon_block.slot() + 1,
+        },
+        AttestationStrategy::SomeValidators(vec![]),
+    );
+    let fork_block = harness
+        .chain
+        .get_block(&fork_root)
+        .expect("should read fork block")
+        .expect("should have fork block");
+    assert_eq!(fork_block.parent_root(), fork_parent.beacon_block_root);
+
+    light_client
+        .process_header(&signed_header(&fork_block))
+        .expect("should verify fork header");
+    assert_eq!(light_client.latest_block_root(), fork_root);
+    assert_eq!(light_client.slot(), head.beacon_block.slot() + 1);
+
+    // The headers of the abandoned chain are no longer verified.
+    assert!(matches!(
+        light_client.process_header(&signed_header(&head.beacon_block)),
+        Err(Error::UnknownParent { .. })
+    ));
+
+    // Re-processing a verified header makes it the latest header again.
+    light_client
+        .process_header(&signed_header(&fork_parent.beacon_block))
+        .expect("should accept verified header");
+    assert_eq!(
+        light_client.latest_block_root(),
+        fork_parent.beacon_block_root
+    );
+}
+
+#[test]
+fn rejects_unknown_parent() {
+    let harness = get_harness();
+    let chain = build_chain(&harness);
+
+    let mut light_client = LightClient::new(chain[0].beacon_state.clone(), harness.spec.clone());
+
+    assert!(matches!(
+        light_client.process_header(&signed_header(&chain[2].beacon_block)),
+        Err(Error::UnknownParent { .. })
+    ));
+    assert_eq!(light_client.latest_block_root(), chain[0].beacon_block_root);
+}
+
+#[test]
+fn rejects_header_not_later_than_parent() {
+    let harness = get_harness();
+    let chain = build_chain(&harness);
+
+    let mut light_client = LightClient::new(chain[0].beacon_state.clone(), harness.spec.clone());
+
+    let mut header = signed_header(&chain[1].beacon_block);
+    header.message.slot = Slot::new(0);
+    assert!(matches!(
+        light_client.process_header(&header),
+        Err(Error::HeaderNotLater { .. })
+    ));
+}
+
+#[test]
+fn rejects_invalid_proposer_signature() {
+    let harness = get_harness();
+    let chain = build_chain(&harness);
+
+    let mut light_client = LightClient::new(chain[0].beacon_state.clone(), harness.spec.clone());
+
+    let mut header = signed_header(&chain[1].beacon_block);
+    header.signature = Signature::empty_signature();
+    assert!(matches!(
+        light_client.process_header(&header),
+        Err(Error::InvalidProposerSignature)
+    ));
+
+    // A header claiming a different proposer is not signed by that proposer.
+    let mut header = signed_header(&chain[1].beacon_block);
+    header.message.proposer_index = (header.message.proposer_index + 1) % VALIDATOR_COUNT as u64;
+    assert!(matches!(
+        light_client.process_header(&header),
+        Err(Error::InvalidProposerSignature)
+    ));
+
+    let mut header = signed_header(&chain[1].beacon_block);
+    header.message.proposer_index = VALIDATOR_COUNT as u64;
+    assert!(matches!(
+        light_client.process_header(&header),
+        Err(Error::UnknownProposer(_))
+    ));
+
+    // The client should remain usable after rejecting a header.
+    light_client
+        .process_header(&signed_header(&chain[1].beacon_block))
+        .expect("should verify valid header");
+    assert_eq!(light_client.latest_block_root(), chain[1].beacon_block_root);
+}
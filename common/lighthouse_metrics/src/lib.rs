@@ -102,6 +102,19 @@ pub fn try_create_histogram(name: &str, help: &str) -> Result<Histogram> {
     Ok(histogram)
 }
 
+/// Attempts to crate a `Histogram` with the given `buckets`, returning `Err` if the registry does
+/// not accept the histogram (potentially due to naming conflict).
+pub fn try_create_histogram_with_buckets(
+    name: &str,
+    help: &str,
+    buckets: Vec<f64>,
+) -> Result<Histogram> {
+    let opts = HistogramOpts::new(name, help).buckets(buckets);
+    let histogram = Histogram::with_opts(opts)?;
+    prometheus::register(Box::new(histogram.clone()))?;
+    Ok(histogram)
+}
+
 /// Attempts to crate a `HistogramVec`, returning `Err` if the registry does not accept the counter
 /// (potentially due to naming conflict).
 pub fn try_create_histogram_vec(
@@ -11,17 +11,20 @@ use std::marker::PhantomData;
 use std::time::Duration;
 use types::{
     Attestation, AttestationData, AttesterSlashing, BeaconBlock, BeaconState, CommitteeIndex,
-    Epoch, EthSpec, Fork, Hash256, ProposerSlashing, PublicKey, PublicKeyBytes, Signature,
-    SignedAggregateAndProof, SignedBeaconBlock, Slot, SubnetId,
+    Epoch, EthSpec, Fork, Graffiti, Hash256, LightClientFinalityProof, ProposerSlashing, PublicKey,
+    PublicKeyBytes, Signature, SignedAggregateAndProof, SignedBeaconBlock, Slot, SubnetId,
 };
 use url::Url;
 
 pub use operation_pool::PersistedOperationPool;
 pub use proto_array::core::ProtoArray;
 pub use rest_types::{
-    CanonicalHeadResponse, Committee, HeadBeaconBlock, Health, IndividualVotesRequest,
-    IndividualVotesResponse, SyncingResponse, ValidatorDutiesRequest, ValidatorDutyBytes,
-    ValidatorRequest, ValidatorResponse, ValidatorSubscription,
+    AttesterData, BlockHeaderData, BlockId, CanonicalHeadResponse, Committee, ConnectPeerRequest,
+    DisconnectPeerRequest, FinalityCheckpointsData, GenericResponse, GenesisData, HeadBeaconBlock,
+    Health, IndividualRewardsRequest, IndividualRewardsResponse, IndividualVotesRequest,
+    IndividualVotesResponse, PeerIdRequest, ProposerData, RootData, StateId, SyncingData,
+    SyncingResponse, ValidatorData, ValidatorDutiesRequest, ValidatorDutyBytes, ValidatorRequest,
+    ValidatorResponse, ValidatorSubscription, VersionData,
 };
 
 // Setting a long timeout for debug ensures that crypto-heavy operations can still succeed.
@@ -49,6 +52,12 @@ impl<E: EthSpec> RemoteBeaconNode<E> {
                 .map_err(|e| format!("Unable to create http client: {:?}", e))?,
         })
     }
+
+    /// Sends `api_token` as a bearer token with all requests which modify the remote node.
+    pub fn with_api_token(mut self, api_token: String) -> Self {
+        self.http.api_token = Some(api_token);
+        self
+    }
 }
 
 #[derive(Debug)]
@@ -70,6 +79,7 @@ pub struct HttpClient<E> {
     client: Client,
     url: Url,
     timeout: Duration,
+    api_token: Option<String>,
     _phantom: PhantomData<E>,
 }
 
@@ -83,10 +93,16 @@ impl<E: EthSpec> HttpClient<E> {
                 .expect("should build from static configuration"),
             url: Url::parse(&server_url)?,
             timeout: Duration::from_secs(15),
+            api_token: None,
             _phantom: PhantomData,
         })
     }
 
+    /// Returns the URL of the remote node.
+    pub fn server_url(&self) -> &Url {
+        &self.url
+    }
+
     pub fn beacon(&self) -> Beacon<E> {
         Beacon(self.clone())
     }
@@ -111,17 +127,29 @@ impl<E: EthSpec> HttpClient<E> {
         Consensus(self.clone())
     }
 
+    pub fn network(&self) -> Network<E> {
+        Network(self.clone())
+    }
+
+    pub fn v1(&self) -> V1<E> {
+        V1(self.clone())
+    }
+
+    pub fn events(&self) -> Events<E> {
+        Events(self.clone())
+    }
+
     fn url(&self, path: &str) -> Result<Url, Error> {
         self.url.join(path).map_err(|e| e.into())
     }
 
     pub async fn json_post<T: Serialize>(&self, url: Url, body: T) -> Result<Response, Error> {
-        self.client
-            .post(&url.to_string())
-            .json(&body)
-            .send()
-            .await
-            .map_err(Error::from)
+        let mut request = self.client.post(&url.to_string()).json(&body);
+        if let Some(api_token) = &self.api_token {
+            request = request.bearer_auth(api_token);
+        }
+
+        request.send().await.map_err(Error::from)
     }
 
     pub async fn json_get<T: DeserializeOwned>(
@@ -309,22 +337,27 @@ impl<E: EthSpec> Validator<E> {
     }
 
     /// Requests a new (unsigned) block from the beacon node.
+    ///
+    /// If `graffiti` is `None`, the beacon node will use its own graffiti.
     pub async fn produce_block(
         &self,
         slot: Slot,
         randao_reveal: Signature,
+        graffiti: Option<Graffiti>,
     ) -> Result<BeaconBlock<E>, Error> {
         let client = self.0.clone();
         let url = self.url("block")?;
-        client
-            .json_get::<BeaconBlock<E>>(
-                url,
-                vec![
-                    ("slot".into(), format!("{}", slot.as_u64())),
-                    ("randao_reveal".into(), as_ssz_hex_string(&randao_reveal)),
-                ],
-            )
-            .await
+
+        let mut query_params = vec![
+            ("slot".into(), format!("{}", slot.as_u64())),
+            ("randao_reveal".into(), as_ssz_hex_string(&randao_reveal)),
+        ];
+
+        if let Some(graffiti) = graffiti {
+            query_params.push(("graffiti".into(), as_ssz_hex_string(&graffiti)));
+        }
+
+        client.json_get::<BeaconBlock<E>>(url, query_params).await
     }
 
     /// Subscribes a list of validators to particular slots for attestation production/publication.
@@ -414,6 +447,20 @@ impl<E: EthSpec> Beacon<E> {
             .await
     }
 
+    /// Returns the light client finality proof of the finalized block with the given root, or of
+    /// the latest finalized block if `root` is `None`.
+    pub async fn get_light_client_finality_proof(
+        &self,
+        root: Option<Hash256>,
+    ) -> Result<LightClientFinalityProof, Error> {
+        let client = self.0.clone();
+        let url = self.url("light_client/finality_proof")?;
+        let query_params = root
+            .map(|root| vec![("root".into(), root_as_string(root))])
+            .unwrap_or_default();
+        client.json_get(url, query_params).await
+    }
+
     /// Returns the block and block root at the given slot.
     async fn get_block(
         &self,
@@ -625,6 +672,72 @@ impl<E: EthSpec> Node<E> {
     }
 }
 
+/// Provides the functions on the `/network` endpoint of the node.
+#[derive(Clone)]
+pub struct Network<E>(HttpClient<E>);
+
+impl<E: EthSpec> Network<E> {
+    fn url(&self, path: &str) -> Result<Url, Error> {
+        self.0
+            .url("network/")
+            .and_then(move |url| url.join(path).map_err(Error::from))
+            .map_err(Into::into)
+    }
+
+    pub async fn get_peer_id(&self) -> Result<String, Error> {
+        let client = self.0.clone();
+        let url = self.url("peer_id")?;
+        client.json_get(url, vec![]).await
+    }
+
+    /// Requests the node to dial a peer at the given multiaddr.
+    pub async fn connect_multiaddr(&self, multiaddr: String) -> Result<(), Error> {
+        let request = ConnectPeerRequest {
+            multiaddr: Some(multiaddr),
+            enr: None,
+        };
+        self.post("peers/connect", request).await
+    }
+
+    /// Requests the node to dial the peer described by the given base64 ENR.
+    pub async fn connect_enr(&self, enr: String) -> Result<(), Error> {
+        let request = ConnectPeerRequest {
+            multiaddr: None,
+            enr: Some(enr),
+        };
+        self.post("peers/connect", request).await
+    }
+
+    /// Requests the node to disconnect a peer with the given goodbye reason code.
+    pub async fn disconnect_peer(&self, peer_id: String, reason: u64) -> Result<(), Error> {
+        self.post(
+            "peers/disconnect",
+            DisconnectPeerRequest { peer_id, reason },
+        )
+        .await
+    }
+
+    pub async fn ban_peer(&self, peer_id: String) -> Result<(), Error> {
+        self.post("peers/ban", PeerIdRequest { peer_id }).await
+    }
+
+    pub async fn unban_peer(&self, peer_id: String) -> Result<(), Error> {
+        self.post("peers/unban", PeerIdRequest { peer_id }).await
+    }
+
+    /// Requests the node to start a discovery query for new peers.
+    pub async fn discover_peers(&self) -> Result<(), Error> {
+        self.post("discover", ()).await
+    }
+
+    async fn post<T: Serialize>(&self, path: &str, body: T) -> Result<(), Error> {
+        let client = self.0.clone();
+        let url = self.url(path)?;
+        let response = client.json_post::<_>(url, body).await?;
+        error_for_status(response).await.map(|_| ())
+    }
+}
+
 /// Provides the functions on the `/advanced` endpoint of the node.
 #[derive(Clone)]
 pub struct Advanced<E>(HttpClient<E>);
@@ -679,6 +792,22 @@ impl<E: EthSpec> Consensus<E> {
         success.json().await.map_err(Error::from)
     }
 
+    /// Gets the rewards and penalties for the attestations of each of the given `pubkeys` in
+    /// `epoch`.
+    pub async fn get_individual_rewards(
+        &self,
+        epoch: Epoch,
+        pubkeys: Vec<PublicKeyBytes>,
+    ) -> Result<Vec<IndividualRewardsResponse>, Error> {
+        let client = self.0.clone();
+        let req_body = IndividualRewardsRequest { epoch, pubkeys };
+
+        let url = self.url("individual_rewards")?;
+        let response = client.json_post::<_>(url, req_body).await?;
+        let success = error_for_status(response).await.map_err(Error::from)?;
+        success.json().await.map_err(Error::from)
+    }
+
     /// Gets a `VoteCount` for the given `epoch`.
     pub async fn get_vote_count(&self, epoch: Epoch) -> Result<IndividualVotesResponse, Error> {
         let client = self.0.clone();
@@ -688,6 +817,269 @@ impl<E: EthSpec> Consensus<E> {
     }
 }
 
+/// Provides the functions on the standard `/eth/v1` endpoints of the node.
+#[derive(Clone)]
+pub struct V1<E>(HttpClient<E>);
+
+impl<E: EthSpec> V1<E> {
+    fn url(&self, path: &str) -> Result<Url, Error> {
+        self.0
+            .url("eth/v1/")
+            .and_then(move |url| url.join(path).map_err(Error::from))
+            .map_err(Into::into)
+    }
+
+    /// Fetches `path` and removes the `GenericResponse` envelope.
+    async fn get<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query_pairs: Vec<(String, String)>,
+    ) -> Result<T, Error> {
+        let client = self.0.clone();
+        let url = self.url(path)?;
+        client
+            .json_get::<GenericResponse<T>>(url, query_pairs)
+            .await
+            .map(|response| response.data)
+    }
+
+    /// Posts `body` to `path`, which responds without any data.
+    async fn post<T: Serialize>(&self, path: &str, body: T) -> Result<(), Error> {
+        let client = self.0.clone();
+        let url = self.url(path)?;
+        let response = client.json_post::<_>(url, body).await?;
+        error_for_status(response).await.map(|_| ())
+    }
+
+    pub async fn get_genesis(&self) -> Result<GenesisData, Error> {
+        self.get("beacon/genesis", vec![]).await
+    }
+
+    pub async fn get_state_root(&self, state_id: StateId) -> Result<Hash256, Error> {
+        self.get::<RootData>(&format!("beacon/states/{}/root", state_id), vec![])
+            .await
+            .map(|data| data.root)
+    }
+
+    pub async fn get_state_fork(&self, state_id: StateId) -> Result<Fork, Error> {
+        self.get(&format!("beacon/states/{}/fork", state_id), vec![])
+            .await
+    }
+
+    pub async fn get_state_finality_checkpoints(
+        &self,
+        state_id: StateId,
+    ) -> Result<FinalityCheckpointsData, Error> {
+        self.get(
+            &format!("beacon/states/{}/finality_checkpoints", state_id),
+            vec![],
+        )
+        .await
+    }
+
+    /// Returns the validator with the given index or `0x`-prefixed public key.
+    pub async fn get_state_validator(
+        &self,
+        state_id: StateId,
+        validator_id: &str,
+    ) -> Result<ValidatorData, Error> {
+        self.get(
+            &format!("beacon/states/{}/validators/{}", state_id, validator_id),
+            vec![],
+        )
+        .await
+    }
+
+    pub async fn get_block_header(&self, block_id: BlockId) -> Result<BlockHeaderData, Error> {
+        self.get(&format!("beacon/headers/{}", block_id), vec![])
+            .await
+    }
+
+    pub async fn get_block_root(&self, block_id: BlockId) -> Result<Hash256, Error> {
+        self.get::<RootData>(&format!("beacon/blocks/{}/root", block_id), vec![])
+            .await
+            .map(|data| data.root)
+    }
+
+    /// Imports `block` into the node and publishes it to the network.
+    pub async fn post_block(&self, block: &SignedBeaconBlock<E>) -> Result<(), Error> {
+        self.post("beacon/blocks", block).await
+    }
+
+    pub async fn get_node_version(&self) -> Result<String, Error> {
+        self.get::<VersionData>("node/version", vec![])
+            .await
+            .map(|data| data.version)
+    }
+
+    pub async fn get_node_syncing(&self) -> Result<SyncingData, Error> {
+        self.get("node/syncing", vec![]).await
+    }
+
+    pub async fn get_attester_duties(
+        &self,
+        epoch: Epoch,
+        indices: &[u64],
+    ) -> Result<Vec<AttesterData>, Error> {
+        let query_pairs = indices
+            .iter()
+            .map(|index| ("index".to_string(), index.to_string()))
+            .collect();
+        self.get(&format!("validator/duties/attester/{}", epoch), query_pairs)
+            .await
+    }
+
+    pub async fn get_proposer_duties(&self, epoch: Epoch) -> Result<Vec<ProposerData>, Error> {
+        self.get(&format!("validator/duties/proposer/{}", epoch), vec![])
+            .await
+    }
+}
+
+/// Provides the functions on the `/events` endpoint of the node.
+#[derive(Clone)]
+pub struct Events<E>(HttpClient<E>);
+
+impl<E: EthSpec> Events<E> {
+    /// Opens a stream of all future server-sent events which match any of the `topics`.
+    ///
+    /// The stream is closed once `timeout` elapses, so a long-lived listener must re-open it.
+    pub async fn stream(&self, topics: &[&str], timeout: Duration) -> Result<EventStream, Error> {
+        let mut url = self.0.url("events")?;
+        url.query_pairs_mut()
+            .append_pair("topics", &topics.join(","));
+
+        let response = self
+            .0
+            .client
+            .get(&url.to_string())
+            .timeout(timeout)
+            .send()
+            .await
+            .map_err(Error::from)?;
+
+        error_for_status(response)
+            .await
+            .map(|response| EventStream {
+                response,
+                buffer: EventBuffer::default(),
+            })
+    }
+}
+
+/// A single server-sent event from the `/events` endpoint.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ServerSentEvent {
+    /// The name of the event, e.g. `beacon_head_changed`.
+    pub event: String,
+    /// The JSON encoding of the event.
+    pub data: String,
+}
+
+impl ServerSentEvent {
+    /// Parses a single event from the lines of `raw`, returning `None` if there is no `data`.
+    fn parse(raw: &str) -> Option<Self> {
+        let mut event = String::new();
+        let mut data: Option<String> = None;
+
+        for line in raw.lines() {
+            let mut split = line.splitn(2, ':');
+            match (split.next(), split.next().map(str::trim_start)) {
+                (Some("event"), Some(value)) => event = value.to_string(),
+                (Some("data"), Some(value)) => {
+                    data = Some(match data {
+                        Some(existing) => existing + "\n" + value,
+                        None => value.to_string(),
+                    })
+                }
+                _ => {}
+            }
+        }
+
+        data.map(|data| Self { event, data })
+    }
+
+    /// Decodes the fields of the event (i.e., the `data` of its JSON encoding).
+    pub fn fields<T: DeserializeOwned>(&self) -> Result<T, Error> {
+        #[derive(Deserialize)]
+        struct Envelope<T> {
+            data: T,
+        }
+
+        serde_json::from_str::<Envelope<T>>(&self.data)
+            .map(|envelope| envelope.data)
+            .map_err(Error::from)
+    }
+}
+
+/// A stream of server-sent events, as returned by `Events::stream`.
+pub struct EventStream {
+    response: Response,
+    buffer: EventBuffer,
+}
+
+impl EventStream {
+    /// Waits for the next event, returning `None` once the server (or the timeout) closes the
+    /// stream.
+    pub async fn next_event(&mut self) -> Result<Option<ServerSentEvent>, Error> {
+        loop {
+            if let Some(event) = self.buffer.next_event() {
+                return Ok(Some(event));
+            }
+
+            match self.response.chunk().await.map_err(Error::from)? {
+                Some(chunk) => self.buffer.extend(&chunk),
+                None => return Ok(None),
+            }
+        }
+    }
+}
+
+/// Accumulates the chunks of a response until they contain complete server-sent events.
+///
+/// An event may be split across any number of chunks, and a chunk may contain any number of
+/// events.
+#[derive(Default)]
+struct EventBuffer {
+    bytes: Vec<u8>,
+}
+
+impl EventBuffer {
+    /// Appends a chunk of the response to the buffer.
+    fn extend(&mut self, chunk: &[u8]) {
+        self.bytes.extend_from_slice(chunk)
+    }
+
+    /// Removes and returns the first complete event from the buffer, if any.
+    fn next_event(&mut self) -> Option<ServerSentEvent> {
+        // Events are terminated by a blank line.
+        while let Some(end) = self.bytes.windows(2).position(|window| window == b"\n\n") {
+            let raw = self.bytes.drain(..end + 2).collect::<Vec<_>>();
+
+            // Skip comments (e.g., keep-alives) and any other events without data.
+            if let Some(event) = ServerSentEvent::parse(&String::from_utf8_lossy(&raw)) {
+                return Some(event);
+            }
+        }
+
+        None
+    }
+}
+
+/// The fields of a `beacon_head_changed` event.
+#[derive(Debug, PartialEq, Clone, Deserialize)]
+pub struct HeadEvent {
+    pub reorg: bool,
+    pub current_head_slot: Slot,
+    pub current_head_beacon_block_root: Hash256,
+    pub previous_head_beacon_block_root: Hash256,
+    /// The root of the block which decided the attester shuffling for the epoch of
+    /// `current_head_slot`.
+    pub current_epoch_dependent_root: Hash256,
+    /// The root of the block which decided the attester shuffling for the epoch after that of
+    /// `current_head_slot`.
+    pub next_epoch_dependent_root: Hash256,
+}
+
 #[derive(Deserialize)]
 #[serde(bound = "T: EthSpec")]
 pub struct BlockResponse<T: EthSpec> {
@@ -727,3 +1119,103 @@ impl From<serde_json::Error> for Error {
         Error::SerdeJsonError(e)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(event: &str, data: &str) -> ServerSentEvent {
+        ServerSentEvent {
+            event: event.into(),
+            data: data.into(),
+        }
+    }
+
+    #[test]
+    fn parse_single_line_data() {
+        assert_eq!(
+            ServerSentEvent::parse("event: beacon_head_changed\ndata: {\"data\":1}\n"),
+            Some(event("beacon_head_changed", "{\"data\":1}"))
+        );
+    }
+
+    #[test]
+    fn parse_multi_line_data() {
+        assert_eq!(
+            ServerSentEvent::parse("event: head\ndata: {\ndata: \"data\": 1\ndata: }\n"),
+            Some(event("head", "{\n\"data\": 1\n}"))
+        );
+    }
+
+    #[test]
+    fn parse_ignores_comments_and_unknown_fields() {
+        assert_eq!(
+            ServerSentEvent::parse(": keep-alive\nid: 7\nevent: head\n:another\ndata: 1\n"),
+            Some(event("head", "1"))
+        );
+        assert_eq!(ServerSentEvent::parse(": keep-alive\n"), None);
+        assert_eq!(ServerSentEvent::parse("event: head\n"), None);
+    }
+
+    #[test]
+    fn buffer_joins_partial_chunks() {
+        let raw = b"event: head\ndata: {\"data\":1}\n\n";
+        let mut buffer = EventBuffer::default();
+
+        for (i, byte) in raw.iter().enumerate() {
+            assert_eq!(
+                buffer.next_event(),
+                None,
+                "event should be incomplete after {} bytes",
+                i
+            );
+            buffer.extend(&[*byte]);
+        }
+
+        assert_eq!(buffer.next_event(), Some(event("head", "{\"data\":1}")));
+        assert_eq!(buffer.next_event(), None);
+    }
+
+    #[test]
+    fn buffer_splits_events_and_skips_comments() {
+        let mut buffer = EventBuffer::default();
+        buffer.extend(b": keep-alive\n\nevent: a\ndata: 1\n\nevent: b\nda");
+
+        assert_eq!(buffer.next_event(), Some(event("a", "1")));
+        assert_eq!(buffer.next_event(), None);
+
+        buffer.extend(b"ta: 2\ndata: 3\n\n: keep-alive\n\n");
+
+        assert_eq!(buffer.next_event(), Some(event("b", "2\n3")));
+        assert_eq!(buffer.next_event(), None);
+    }
+
+    #[test]
+    fn fields_decodes_head_event() {
+        let root = Hash256::repeat_byte(1);
+        let data = serde_json::json!({
+            "data": {
+                "reorg": true,
+                "current_head_slot": 3,
+                "current_head_beacon_block_root": root,
+                "previous_head_beacon_block_root": root,
+                "current_epoch_dependent_root": root,
+                "next_epoch_dependent_root": root,
+            }
+        });
+
+        assert_eq!(
+            event("beacon_head_changed", &data.to_string())
+                .fields::<HeadEvent>()
+                .expect("should decode head event"),
+            HeadEvent {
+                reorg: true,
+                current_head_slot: Slot::new(3),
+                current_head_beacon_block_root: root,
+                previous_head_beacon_block_root: root,
+                current_epoch_dependent_root: root,
+                next_epoch_dependent_root: root,
+            }
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
-use state_processing::per_epoch_processing::ValidatorStatus;
+use state_processing::per_epoch_processing::{RewardBreakdown, ValidatorStatus};
 use types::{Epoch, PublicKeyBytes};
 
 #[derive(PartialEq, Debug, Serialize, Deserialize, Clone, Encode, Decode)]
@@ -64,3 +64,59 @@ pub struct IndividualVotesResponse {
     /// Voting statistics for the validator, if they voted in the given epoch.
     pub vote: Option<IndividualVote>,
 }
+
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone, Encode, Decode)]
+pub struct IndividualRewardsRequest {
+    pub epoch: Epoch,
+    pub pubkeys: Vec<PublicKeyBytes>,
+}
+
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone, Encode, Decode)]
+pub struct IndividualRewards {
+    /// Reward for attesting to the correct source during the epoch.
+    pub source_reward_gwei: u64,
+    /// Penalty for not attesting to the correct source during the epoch.
+    pub source_penalty_gwei: u64,
+    /// Reward for attesting to the correct target during the epoch.
+    pub target_reward_gwei: u64,
+    /// Penalty for not attesting to the correct target during the epoch.
+    pub target_penalty_gwei: u64,
+    /// Reward for attesting to the correct head during the epoch.
+    pub head_reward_gwei: u64,
+    /// Penalty for not attesting to the correct head during the epoch.
+    pub head_penalty_gwei: u64,
+    /// Reward for the inclusion delay of the validator's attestation.
+    pub inclusion_delay_reward_gwei: u64,
+    /// Reward for including the attestations of other validators in proposed blocks.
+    pub proposer_reward_gwei: u64,
+    /// Penalty for inactivity whilst the chain is not finalizing.
+    pub inactivity_penalty_gwei: u64,
+}
+
+impl From<RewardBreakdown> for IndividualRewards {
+    fn from(breakdown: RewardBreakdown) -> IndividualRewards {
+        IndividualRewards {
+            source_reward_gwei: breakdown.source.rewards,
+            source_penalty_gwei: breakdown.source.penalties,
+            target_reward_gwei: breakdown.target.rewards,
+            target_penalty_gwei: breakdown.target.penalties,
+            head_reward_gwei: breakdown.head.rewards,
+            head_penalty_gwei: breakdown.head.penalties,
+            inclusion_delay_reward_gwei: breakdown.inclusion_delay.rewards,
+            proposer_reward_gwei: breakdown.proposer.rewards,
+            inactivity_penalty_gwei: breakdown.inactivity.penalties,
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone, Encode, Decode)]
+pub struct IndividualRewardsResponse {
+    /// The epoch in which the rewarded attestations were made.
+    pub epoch: Epoch,
+    /// The validators public key.
+    pub pubkey: PublicKeyBytes,
+    /// The index of the validator in state.validators.
+    pub validator_index: Option<usize>,
+    /// The rewards and penalties of the validator, if it is known.
+    pub rewards: Option<IndividualRewards>,
+}
@@ -4,7 +4,9 @@
 
 mod beacon;
 mod consensus;
+mod network;
 mod node;
+mod v1;
 mod validator;
 
 pub use beacon::{
@@ -16,6 +18,17 @@ pub use validator::{
     ValidatorDutiesRequest, ValidatorDuty, ValidatorDutyBytes, ValidatorSubscription,
 };
 
-pub use consensus::{IndividualVote, IndividualVotesRequest, IndividualVotesResponse};
+pub use consensus::{
+    IndividualRewards, IndividualRewardsRequest, IndividualRewardsResponse, IndividualVote,
+    IndividualVotesRequest, IndividualVotesResponse,
+};
+
+pub use network::{ConnectPeerRequest, DisconnectPeerRequest, PeerIdRequest};
 
 pub use node::{Health, SyncingResponse, SyncingStatus};
+
+pub use v1::{
+    AttesterData, BlockHeaderData, BlockId, CommitteeData, ErrorMessage, FinalityCheckpointsData,
+    GenericResponse, GenesisData, IdentityData, ProposerData, RootData, StateId, SyncingData,
+    ValidatorBalanceData, ValidatorData, ValidatorStatus, VersionData,
+};
@@ -0,0 +1,30 @@
+//! Collection of types for the /network HTTP
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+/// The request body for the /network/peers/connect HTTP POST.
+///
+/// Exactly one of `multiaddr` or `enr` must be supplied.
+pub struct ConnectPeerRequest {
+    /// A libp2p multiaddr to dial, e.g. `/ip4/127.0.0.1/tcp/9000`.
+    pub multiaddr: Option<String>,
+    /// A base64-encoded ENR, e.g. `enr:-Iu4QG...`.
+    pub enr: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+/// The request body for the /network/peers/disconnect HTTP POST.
+pub struct DisconnectPeerRequest {
+    /// The base58-encoded `PeerId` of the peer to disconnect.
+    pub peer_id: String,
+    /// The goodbye reason code sent to the peer. Defaults to `0` (unknown) if omitted.
+    #[serde(default)]
+    pub reason: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+/// The request body for the /network/peers/ban and /network/peers/unban HTTP POSTs.
+pub struct PeerIdRequest {
+    /// The base58-encoded `PeerId` of the peer.
+    pub peer_id: String,
+}
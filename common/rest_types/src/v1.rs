@@ -0,0 +1,366 @@
+//! Types for the standard Eth2 beacon node API, served under `/eth/v1/`.
+//!
+//! Every successful response is wrapped in a `GenericResponse` and every error is returned as an
+//! `ErrorMessage`.
+
+use bls::PublicKeyBytes;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use types::utils::{fork_from_hex_str, fork_to_hex_str};
+use types::{Checkpoint, CommitteeIndex, Epoch, Hash256, SignedBeaconBlockHeader, Slot, Validator};
+
+/// The envelope around every successful response.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GenericResponse<T> {
+    pub data: T,
+}
+
+impl<T> From<T> for GenericResponse<T> {
+    fn from(data: T) -> Self {
+        Self { data }
+    }
+}
+
+/// The body of every error response.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ErrorMessage {
+    pub code: u16,
+    pub message: String,
+}
+
+/// Identifies a `BeaconState` in a request path.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StateId {
+    Head,
+    Genesis,
+    Finalized,
+    Justified,
+    Slot(Slot),
+    Root(Hash256),
+}
+
+impl FromStr for StateId {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "head" => Ok(StateId::Head),
+            "genesis" => Ok(StateId::Genesis),
+            "finalized" => Ok(StateId::Finalized),
+            "justified" => Ok(StateId::Justified),
+            other => parse_slot_or_root(other)
+                .map(|id| id.either(StateId::Slot, StateId::Root))
+                .map_err(|e| format!("Invalid state id {}: {}", other, e)),
+        }
+    }
+}
+
+impl fmt::Display for StateId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StateId::Head => write!(f, "head"),
+            StateId::Genesis => write!(f, "genesis"),
+            StateId::Finalized => write!(f, "finalized"),
+            StateId::Justified => write!(f, "justified"),
+            StateId::Slot(slot) => write!(f, "{}", slot),
+            StateId::Root(root) => write!(f, "{:?}", root),
+        }
+    }
+}
+
+/// Identifies a `SignedBeaconBlock` in a request path.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BlockId {
+    Head,
+    Genesis,
+    Finalized,
+    Justified,
+    Slot(Slot),
+    Root(Hash256),
+}
+
+impl FromStr for BlockId {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "head" => Ok(BlockId::Head),
+            "genesis" => Ok(BlockId::Genesis),
+            "finalized" => Ok(BlockId::Finalized),
+            "justified" => Ok(BlockId::Justified),
+            other => parse_slot_or_root(other)
+                .map(|id| id.either(BlockId::Slot, BlockId::Root))
+                .map_err(|e| format!("Invalid block id {}: {}", other, e)),
+        }
+    }
+}
+
+impl fmt::Display for BlockId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BlockId::Head => write!(f, "head"),
+            BlockId::Genesis => write!(f, "genesis"),
+            BlockId::Finalized => write!(f, "finalized"),
+            BlockId::Justified => write!(f, "justified"),
+            BlockId::Slot(slot) => write!(f, "{}", slot),
+            BlockId::Root(root) => write!(f, "{:?}", root),
+        }
+    }
+}
+
+/// Either a slot or a root, the two non-named forms of `StateId` and `BlockId`.
+enum SlotOrRoot {
+    Slot(Slot),
+    Root(Hash256),
+}
+
+impl SlotOrRoot {
+    fn either<T>(self, slot: impl FnOnce(Slot) -> T, root: impl FnOnce(Hash256) -> T) -> T {
+        match self {
+            SlotOrRoot::Slot(s) => slot(s),
+            SlotOrRoot::Root(r) => root(r),
+        }
+    }
+}
+
+/// Parses a `0x`-prefixed root or a decimal slot.
+fn parse_slot_or_root(s: &str) -> Result<SlotOrRoot, String> {
+    if s.starts_with("0x") {
+        let hex = s.trim_start_matches("0x");
+        if hex.len() != 64 {
+            return Err("a root must be 32 bytes".to_string());
+        }
+        hex.parse()
+            .map(SlotOrRoot::Root)
+            .map_err(|e| format!("{:?}", e))
+    } else {
+        s.parse::<u64>()
+            .map(|slot| SlotOrRoot::Slot(Slot::new(slot)))
+            .map_err(|e| format!("{:?}", e))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GenesisData {
+    pub genesis_time: u64,
+    pub genesis_validators_root: Hash256,
+    #[serde(
+        serialize_with = "fork_to_hex_str",
+        deserialize_with = "fork_from_hex_str"
+    )]
+    pub genesis_fork_version: [u8; 4],
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RootData {
+    pub root: Hash256,
+}
+
+impl From<Hash256> for RootData {
+    fn from(root: Hash256) -> Self {
+        Self { root }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FinalityCheckpointsData {
+    pub previous_justified: Checkpoint,
+    pub current_justified: Checkpoint,
+    pub finalized: Checkpoint,
+}
+
+/// The status of a validator, as defined by the standard API.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidatorStatus {
+    /// The validator has deposited but is not yet eligible for activation.
+    PendingInitialized,
+    /// The validator is waiting in the activation queue.
+    PendingQueued,
+    /// The validator is active and has not initiated an exit.
+    ActiveOngoing,
+    /// The validator is active and has initiated an exit.
+    ActiveExiting,
+    /// The validator is active but has been slashed.
+    ActiveSlashed,
+    /// The validator has exited without being slashed.
+    ExitedUnslashed,
+    /// The validator has exited after being slashed.
+    ExitedSlashed,
+    /// The validator may withdraw its balance.
+    WithdrawalPossible,
+}
+
+impl ValidatorStatus {
+    /// Returns the status of `validator` at the given `epoch`.
+    pub fn from_validator(validator: &Validator, epoch: Epoch, far_future_epoch: Epoch) -> Self {
+        if validator.is_withdrawable_at(epoch) {
+            ValidatorStatus::WithdrawalPossible
+        } else if validator.is_exited_at(epoch) {
+            if validator.slashed {
+                ValidatorStatus::ExitedSlashed
+            } else {
+                ValidatorStatus::ExitedUnslashed
+            }
+        } else if validator.is_active_at(epoch) {
+            if validator.slashed {
+                ValidatorStatus::ActiveSlashed
+            } else if validator.exit_epoch != far_future_epoch {
+                ValidatorStatus::ActiveExiting
+            } else {
+                ValidatorStatus::ActiveOngoing
+            }
+        } else if validator.activation_eligibility_epoch == far_future_epoch {
+            ValidatorStatus::PendingInitialized
+        } else {
+            ValidatorStatus::PendingQueued
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ValidatorData {
+    pub index: u64,
+    pub balance: u64,
+    pub status: ValidatorStatus,
+    pub validator: Validator,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ValidatorBalanceData {
+    pub index: u64,
+    pub balance: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CommitteeData {
+    pub index: CommitteeIndex,
+    pub slot: Slot,
+    pub validators: Vec<u64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BlockHeaderData {
+    pub root: Hash256,
+    /// `true` if the block is in the canonical chain of the node's head.
+    pub canonical: bool,
+    pub header: SignedBeaconBlockHeader,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct VersionData {
+    pub version: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SyncingData {
+    pub head_slot: Slot,
+    /// The number of slots between the head and the wall-clock slot.
+    pub sync_distance: Slot,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct IdentityData {
+    pub peer_id: String,
+    pub enr: String,
+    pub p2p_addresses: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AttesterData {
+    pub pubkey: PublicKeyBytes,
+    pub validator_index: u64,
+    pub committee_index: CommitteeIndex,
+    pub committee_length: u64,
+    pub committees_at_slot: u64,
+    pub validator_committee_index: u64,
+    pub slot: Slot,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ProposerData {
+    pub pubkey: PublicKeyBytes,
+    pub validator_index: u64,
+    pub slot: Slot,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_state_id() {
+        assert_eq!("head".parse(), Ok(StateId::Head));
+        assert_eq!("genesis".parse(), Ok(StateId::Genesis));
+        assert_eq!("finalized".parse(), Ok(StateId::Finalized));
+        assert_eq!("justified".parse(), Ok(StateId::Justified));
+        assert_eq!("42".parse(), Ok(StateId::Slot(Slot::new(42))));
+        assert_eq!(
+            "0x000000000000000000000000000000000000000000000000000000000000002a".parse(),
+            Ok(StateId::Root(Hash256::from_low_u64_be(42)))
+        );
+        assert!("0x2a".parse::<StateId>().is_err());
+        assert!("cats".parse::<StateId>().is_err());
+        assert!("-1".parse::<StateId>().is_err());
+    }
+
+    #[test]
+    fn parse_block_id() {
+        assert_eq!("head".parse(), Ok(BlockId::Head));
+        assert_eq!("0".parse(), Ok(BlockId::Slot(Slot::new(0))));
+        assert_eq!(
+            "0x0000000000000000000000000000000000000000000000000000000000000000".parse(),
+            Ok(BlockId::Root(Hash256::zero()))
+        );
+        assert!("Head".parse::<BlockId>().is_err());
+    }
+
+    #[test]
+    fn state_id_display_round_trips() {
+        for id in &[
+            StateId::Head,
+            StateId::Justified,
+            StateId::Slot(Slot::new(7)),
+            StateId::Root(Hash256::from_low_u64_be(7)),
+        ] {
+            assert_eq!(id.to_string().parse(), Ok(*id));
+        }
+    }
+
+    #[test]
+    fn validator_status() {
+        let far_future_epoch = Epoch::new(u64::max_value());
+        let mut validator = Validator {
+            pubkey: PublicKeyBytes::empty(),
+            withdrawal_credentials: Hash256::zero(),
+            effective_balance: 32_000_000_000,
+            slashed: false,
+            activation_eligibility_epoch: far_future_epoch,
+            activation_epoch: far_future_epoch,
+            exit_epoch: far_future_epoch,
+            withdrawable_epoch: far_future_epoch,
+        };
+        let status = |v: &Validator, epoch| {
+            ValidatorStatus::from_validator(v, Epoch::new(epoch), far_future_epoch)
+        };
+
+        assert_eq!(status(&validator, 0), ValidatorStatus::PendingInitialized);
+
+        validator.activation_eligibility_epoch = Epoch::new(1);
+        assert_eq!(status(&validator, 1), ValidatorStatus::PendingQueued);
+
+        validator.activation_epoch = Epoch::new(2);
+        assert_eq!(status(&validator, 2), ValidatorStatus::ActiveOngoing);
+
+        validator.exit_epoch = Epoch::new(4);
+        validator.withdrawable_epoch = Epoch::new(8);
+        assert_eq!(status(&validator, 3), ValidatorStatus::ActiveExiting);
+        assert_eq!(status(&validator, 4), ValidatorStatus::ExitedUnslashed);
+        assert_eq!(status(&validator, 8), ValidatorStatus::WithdrawalPossible);
+
+        validator.slashed = true;
+        assert_eq!(status(&validator, 3), ValidatorStatus::ActiveSlashed);
+        assert_eq!(status(&validator, 4), ValidatorStatus::ExitedSlashed);
+    }
+}
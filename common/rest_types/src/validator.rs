@@ -3,14 +3,16 @@ use eth2_hashing::hash;
 use serde::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
 use std::convert::TryInto;
-use types::{CommitteeIndex, Epoch, Slot};
+use types::{CommitteeIndex, Epoch, Hash256, Slot};
 
 /// A Validator duty with the validator public key represented a `PublicKeyBytes`.
 pub type ValidatorDutyBytes = ValidatorDutyBase<PublicKeyBytes>;
 /// A validator duty with the pubkey represented as a `PublicKey`.
 pub type ValidatorDuty = ValidatorDutyBase<PublicKey>;
 
-// NOTE: if you add or remove fields, please adjust `eq_ignoring_proposal_slots`
+// NOTE: if you add or remove fields, please adjust `eq_ignoring_proposal_slots`. The
+// `dependent_root` is deliberately ignored there, since it identifies the chain which decided the
+// duties rather than the duties themselves.
 #[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
 pub struct ValidatorDutyBase<T> {
     /// The validator's BLS public key, uniquely identifying them.
@@ -33,6 +35,10 @@ pub struct ValidatorDutyBase<T> {
     /// which allows the validator client to determine if this duty requires the validator to be
     /// aggregate attestations.
     pub aggregator_modulo: Option<u64>,
+    /// The root of the block which decided the attester shuffling for these duties.
+    ///
+    /// The attestation duties can only change if a re-org replaces this block.
+    pub dependent_root: Hash256,
 }
 
 impl<T> ValidatorDutyBase<T> {
@@ -95,11 +101,12 @@ pub struct ValidatorSubscription {
 #[cfg(test)]
 mod test {
     use super::*;
+    use bls::Keypair;
 
     #[test]
     fn eq_ignoring_proposal_slots() {
         let duty1 = ValidatorDuty {
-            validator_pubkey: PublicKey::default(),
+            validator_pubkey: Keypair::random().pk,
             validator_index: Some(10),
             attestation_slot: Some(Slot::new(50)),
             attestation_committee_index: Some(2),
@@ -107,6 +114,7 @@ mod test {
             committee_count_at_slot: Some(4),
             block_proposal_slots: None,
             aggregator_modulo: Some(99),
+            dependent_root: Hash256::from_low_u64_be(42),
         };
         let duty2 = ValidatorDuty {
             block_proposal_slots: Some(vec![Slot::new(42), Slot::new(45)]),
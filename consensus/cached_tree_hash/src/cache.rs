@@ -158,6 +158,28 @@ impl TreeHashCache {
     pub fn leaves(&mut self) -> &mut CacheArenaAllocation {
         &mut self.layers[self.depth]
     }
+
+    /// Get the Merkle branch of the leaf at `index`, in bottom-up order, without doing any
+    /// updates/computation.
+    ///
+    /// The branch is only valid if the cache has been updated with the latest leaves.
+    pub fn get_proof(&self, arena: &CacheArena, index: usize) -> Result<Vec<Hash256>, Error> {
+        if index >= self.layers[self.depth].len(arena)? {
+            return Err(Error::MissingLeaf(index));
+        } else if !self.initialized {
+            return Err(Error::CacheNotInitialized);
+        }
+
+        (0..self.depth)
+            .map(|height| {
+                let sibling = self.layers[self.depth - height]
+                    .get(arena, (index >> height) ^ 1)?
+                    .copied()
+                    .unwrap_or_else(|| Hash256::from_slice(&ZERO_HASHES[height]));
+                Ok(sibling)
+            })
+            .collect()
+    }
 }
 
 /// Compute the dirty indices for one layer up.
@@ -219,6 +241,32 @@ mod test {
             .expect("should calculate root");
     }
 
+    #[test]
+    fn proof() {
+        let arena = &mut CacheArena::default();
+
+        let depth = 4;
+        let leaves = (0..11).map(Hash256::from_low_u64_be).collect::<Vec<_>>();
+
+        let mut cache = TreeHashCache::new(arena, depth, leaves.len());
+        assert_eq!(cache.get_proof(arena, 0), Err(Error::CacheNotInitialized));
+
+        cache
+            .recalculate_merkle_root(arena, leaves.iter().map(|leaf| leaf.to_fixed_bytes()))
+            .expect("should calculate root");
+
+        for index in 0..leaves.len() {
+            assert_eq!(
+                cache.get_proof(arena, index),
+                Ok(tree_hash::proof::merkle_branch(&leaves, depth, index))
+            );
+        }
+        assert_eq!(
+            cache.get_proof(arena, leaves.len()),
+            Err(Error::MissingLeaf(leaves.len()))
+        );
+    }
+
     #[test]
     fn test_node_per_layer_unbalanced_tree() {
         assert_eq!(nodes_per_layer(0, 3, 5), 1);
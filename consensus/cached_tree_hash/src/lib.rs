@@ -25,6 +25,10 @@ pub enum Error {
     CacheArenaError(cache_arena::Error),
     /// Unable to find left index in Merkle tree.
     MissingLeftIdx(usize),
+    /// Unable to find a leaf to prove in the Merkle tree.
+    MissingLeaf(usize),
+    /// A proof was requested from a cache which has never computed a root.
+    CacheNotInitialized,
 }
 
 impl From<cache_arena::Error> for Error {
@@ -0,0 +1,258 @@
+//! Functions for working with generalized indices, as defined in the SSZ "Merkle proofs"
+//! specification.
+//!
+//! A generalized index identifies a node in a binary Merkle tree: the root has index `1` and the
+//! children of the node at index `i` have indices `2 * i` and `2 * i + 1`.
+
+use crate::MerkleTreeError;
+use eth2_hashing::hash32_concat;
+use ethereum_types::H256;
+use std::collections::{BTreeSet, HashMap};
+
+pub type GeneralizedIndex = usize;
+
+/// Given generalized indices `i1` for A -> B, `i2` for B -> C, ..., returns the generalized index
+/// for A -> Z.
+pub fn concat_generalized_indices(indices: &[GeneralizedIndex]) -> GeneralizedIndex {
+    indices.iter().fold(1, |acc, &index| {
+        let length = get_generalized_index_length(index);
+        (acc << length) | (index ^ (1 << length))
+    })
+}
+
+/// Returns the depth of the node at `index`, where the root has a depth of zero.
+pub fn get_generalized_index_length(index: GeneralizedIndex) -> usize {
+    debug_assert!(index > 0, "generalized indices start at 1");
+    (std::mem::size_of::<GeneralizedIndex>() * 8) - 1 - index.leading_zeros() as usize
+}
+
+/// Returns `true` if the path from the root to `index` goes right at the given `position`, where
+/// position `0` is the step taken to reach `index` itself.
+pub fn get_generalized_index_bit(index: GeneralizedIndex, position: usize) -> bool {
+    (index >> position) & 1 == 1
+}
+
+pub fn generalized_index_sibling(index: GeneralizedIndex) -> GeneralizedIndex {
+    index ^ 1
+}
+
+pub fn generalized_index_child(index: GeneralizedIndex, right_side: bool) -> GeneralizedIndex {
+    index * 2 + right_side as usize
+}
+
+pub fn generalized_index_parent(index: GeneralizedIndex) -> GeneralizedIndex {
+    index / 2
+}
+
+/// Returns the generalized indices of the sister nodes along the path from `index` to the root,
+/// in bottom-up order. These are the nodes required by a single-leaf Merkle proof.
+pub fn get_branch_indices(index: GeneralizedIndex) -> Vec<GeneralizedIndex> {
+    let mut branch = vec![];
+    let mut current = index;
+    while current > 1 {
+        branch.push(generalized_index_sibling(current));
+        current = generalized_index_parent(current);
+    }
+    branch
+}
+
+/// Returns the generalized indices of the nodes along the path from `index` to the root, in
+/// bottom-up order. Includes `index` but excludes the root.
+pub fn get_path_indices(index: GeneralizedIndex) -> Vec<GeneralizedIndex> {
+    let mut path = vec![];
+    let mut current = index;
+    while current > 1 {
+        path.push(current);
+        current = generalized_index_parent(current);
+    }
+    path
+}
+
+/// Returns the generalized indices of all the nodes required to prove all of `indices`, in
+/// decreasing order.
+///
+/// Nodes which can be computed from the leaves are omitted, so the result is usually smaller than
+/// the combined branches of each index.
+pub fn get_helper_indices(indices: &[GeneralizedIndex]) -> Vec<GeneralizedIndex> {
+    let mut branch_indices = BTreeSet::new();
+    let mut path_indices = BTreeSet::new();
+
+    for &index in indices {
+        branch_indices.extend(get_branch_indices(index));
+        path_indices.extend(get_path_indices(index));
+    }
+
+    let mut helper_indices = branch_indices
+        .difference(&path_indices)
+        .copied()
+        .collect::<Vec<_>>();
+    helper_indices.reverse();
+    helper_indices
+}
+
+/// Compute the root of a tree from a `leaf` at `index` and its `proof`, which is in bottom-up
+/// order.
+pub fn calculate_merkle_root(
+    leaf: H256,
+    proof: &[H256],
+    index: GeneralizedIndex,
+) -> Result<H256, MerkleTreeError> {
+    if proof.len() != get_generalized_index_length(index) {
+        return Err(MerkleTreeError::InvalidProof);
+    }
+
+    let root = proof.iter().enumerate().fold(leaf, |node, (i, sibling)| {
+        if get_generalized_index_bit(index, i) {
+            hash_pair(sibling, &node)
+        } else {
+            hash_pair(&node, sibling)
+        }
+    });
+
+    Ok(root)
+}
+
+/// Compute the root of a tree from `leaves` at `indices` and the `proof` nodes at the indices
+/// returned by `get_helper_indices(indices)`.
+pub fn calculate_multi_merkle_root(
+    leaves: &[H256],
+    proof: &[H256],
+    indices: &[GeneralizedIndex],
+) -> Result<H256, MerkleTreeError> {
+    let helper_indices = get_helper_indices(indices);
+    if leaves.len() != indices.len() || proof.len() != helper_indices.len() || indices.contains(&0)
+    {
+        return Err(MerkleTreeError::InvalidProof);
+    }
+
+    let mut objects: HashMap<GeneralizedIndex, H256> = indices
+        .iter()
+        .copied()
+        .zip(leaves.iter().copied())
+        .chain(helper_indices.into_iter().zip(proof.iter().copied()))
+        .collect();
+
+    // Process the nodes from the deepest upwards, so that both children of a parent are known by
+    // the time the parent is reached.
+    let mut keys = objects.keys().copied().collect::<Vec<_>>();
+    keys.sort_by(|a, b| b.cmp(a));
+
+    let mut pos = 0;
+    while pos < keys.len() {
+        let key = keys[pos];
+        let parent = generalized_index_parent(key);
+        if key > 1 && !objects.contains_key(&parent) {
+            let (left, right) = match (objects.get(&(key & !1)), objects.get(&(key | 1))) {
+                (Some(left), Some(right)) => (*left, *right),
+                _ => return Err(MerkleTreeError::InvalidProof),
+            };
+            objects.insert(parent, hash_pair(&left, &right));
+            keys.push(parent);
+        }
+        pos += 1;
+    }
+
+    objects
+        .get(&1)
+        .copied()
+        .ok_or(MerkleTreeError::InvalidProof)
+}
+
+/// Verify that `leaves` exist at `indices` in a tree with the given `root`.
+pub fn verify_merkle_multiproof(
+    leaves: &[H256],
+    proof: &[H256],
+    indices: &[GeneralizedIndex],
+    root: H256,
+) -> bool {
+    calculate_multi_merkle_root(leaves, proof, indices) == Ok(root)
+}
+
+fn hash_pair(left: &H256, right: &H256) -> H256 {
+    H256::from_slice(&hash32_concat(left.as_bytes(), right.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MerkleTree;
+
+    #[test]
+    fn index_helpers() {
+        assert_eq!(get_generalized_index_length(1), 0);
+        assert_eq!(get_generalized_index_length(2), 1);
+        assert_eq!(get_generalized_index_length(7), 2);
+        assert_eq!(get_generalized_index_length(8), 3);
+
+        assert_eq!(concat_generalized_indices(&[]), 1);
+        assert_eq!(concat_generalized_indices(&[1, 1]), 1);
+        assert_eq!(concat_generalized_indices(&[2, 3]), 5);
+        assert_eq!(concat_generalized_indices(&[3, 6, 1]), 14);
+
+        assert_eq!(get_branch_indices(9), vec![8, 5, 3]);
+        assert_eq!(get_path_indices(9), vec![9, 4, 2]);
+        assert_eq!(get_helper_indices(&[8, 9, 14]), vec![15, 6, 5]);
+    }
+
+    #[test]
+    fn single_and_multi_proofs() {
+        let depth = 3;
+        let leaves = (0..7).map(H256::from_low_u64_be).collect::<Vec<_>>();
+        let tree = MerkleTree::create(&leaves, depth);
+        let root = tree.hash();
+        let first_leaf = 1 << depth;
+
+        for (i, leaf) in leaves.iter().enumerate() {
+            let (_, proof) = tree.generate_proof(i, depth);
+            assert_eq!(
+                calculate_merkle_root(*leaf, &proof, first_leaf + i),
+                Ok(root)
+            );
+            assert_eq!(
+                calculate_merkle_root(*leaf, &proof[1..], first_leaf + i),
+                Err(MerkleTreeError::InvalidProof)
+            );
+        }
+
+        let indices = vec![first_leaf, first_leaf + 1, first_leaf + 6];
+        let multi_leaves = vec![leaves[0], leaves[1], leaves[6]];
+        let proof = get_helper_indices(&indices)
+            .into_iter()
+            .map(|index| {
+                let level = depth - get_generalized_index_length(index);
+                let leaves_per_node = 1 << level;
+                let first = (index - (1 << get_generalized_index_length(index))) * leaves_per_node;
+                let subtree = leaves
+                    .iter()
+                    .skip(first)
+                    .take(leaves_per_node)
+                    .copied()
+                    .collect::<Vec<_>>();
+                MerkleTree::create(&subtree, level).hash()
+            })
+            .collect::<Vec<_>>();
+
+        assert!(verify_merkle_multiproof(
+            &multi_leaves,
+            &proof,
+            &indices,
+            root
+        ));
+        assert!(!verify_merkle_multiproof(
+            &multi_leaves,
+            &proof,
+            &indices,
+            H256::zero()
+        ));
+        assert!(!verify_merkle_multiproof(
+            &multi_leaves[1..],
+            &proof,
+            &indices[1..],
+            root
+        ));
+        assert_eq!(
+            calculate_multi_merkle_root(&multi_leaves, &proof[1..], &indices),
+            Err(MerkleTreeError::InvalidProof)
+        );
+    }
+}
@@ -1,8 +1,14 @@
+pub mod generalized_index;
+
 use eth2_hashing::{hash, hash32_concat, ZERO_HASHES};
 use ethereum_types::H256;
 use lazy_static::lazy_static;
 use safe_arith::ArithError;
 
+pub use generalized_index::{
+    calculate_merkle_root, calculate_multi_merkle_root, verify_merkle_multiproof, GeneralizedIndex,
+};
+
 const MAX_TREE_DEPTH: usize = 32;
 const EMPTY_SLICE: &[H256] = &[];
 
@@ -41,6 +47,8 @@ pub enum MerkleTreeError {
     DepthTooSmall,
     // Overflow occurred
     ArithError,
+    // Proof does not match the provided leaves or indices
+    InvalidProof,
 }
 
 impl MerkleTree {
@@ -4,6 +4,9 @@ use smallvec::{smallvec, SmallVec};
 type SmallVec8<T> = SmallVec<[T; 8]>;
 
 pub mod impls;
+mod list_view;
+
+pub use list_view::ListView;
 
 /// Returned when SSZ decoding fails.
 #[derive(Debug, PartialEq, Clone)]
@@ -47,6 +50,8 @@ pub enum DecodeError {
     ZeroLengthItem,
     /// The given bytes were invalid for some application-level reason.
     BytesInvalid(String),
+    /// The given union selector is out of bounds.
+    UnionSelectorInvalid(u8),
 }
 
 /// Performs checks on the `offset` based upon the other parameters provided.
@@ -274,6 +279,38 @@ impl<'a> SszDecoder<'a> {
     pub fn decode_next<T: Decode>(&mut self) -> Result<T, DecodeError> {
         T::from_ssz_bytes(self.items.remove(0))
     }
+
+    /// Returns the bytes of the next item, without decoding them.
+    ///
+    /// # Panics
+    ///
+    /// Panics when attempting to read more items than actually exist.
+    pub fn next_bytes(&mut self) -> &'a [u8] {
+        self.items.remove(0)
+    }
+}
+
+/// Takes `bytes`, assuming it is the encoding for a SSZ union, and returns the union-selector and
+/// the body (trailing bytes).
+///
+/// ## Errors
+///
+/// Returns an error if:
+///
+/// - `bytes` is empty.
+/// - the union selector is not a valid value (i.e., larger than `MAX_UNION_SELECTOR`).
+pub fn split_union_bytes(bytes: &[u8]) -> Result<(UnionSelector, &[u8]), DecodeError> {
+    let selector = bytes
+        .first()
+        .copied()
+        .ok_or(DecodeError::OutOfBoundsByte { i: 0 })
+        .and_then(UnionSelector::new)?;
+    let body = bytes
+        .get(BYTES_PER_UNION_SELECTOR..)
+        .ok_or(DecodeError::OutOfBoundsByte {
+            i: BYTES_PER_UNION_SELECTOR,
+        })?;
+    Ok((selector, body))
 }
 
 /// Reads a `BYTES_PER_LENGTH_OFFSET`-byte union index from `bytes`, where `bytes.len() >=
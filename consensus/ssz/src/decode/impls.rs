@@ -242,6 +242,9 @@ impl Decode for NonZeroUsize {
 }
 
 /// The SSZ union type.
+///
+/// Uses the `BYTES_PER_LENGTH_OFFSET`-byte union index from earlier versions of the
+/// specification, rather than a `UnionSelector`, so that existing encodings remain valid.
 impl<T: Decode> Decode for Option<T> {
     fn is_ssz_fixed_len() -> bool {
         false
@@ -0,0 +1,131 @@
+use super::*;
+use std::marker::PhantomData;
+
+/// A view over the SSZ bytes of a list (or vector) of `T`, which decodes items only when they are
+/// accessed.
+///
+/// The offsets of variable-length items are checked once, when the view is created, so each item
+/// can then be found without reading the items before it. The maximum length of the list is _not_
+/// checked.
+///
+/// Views over containers are generated by `#[derive(Decode)]`, see the `ssz_derive` crate.
+///
+/// ## Example
+///
+/// ```rust
+/// use ssz::{Encode, ListView};
+///
+/// let bytes = vec![vec![1_u16], vec![], vec![3, 3, 7]].as_ssz_bytes();
+/// let view = ListView::<Vec<u16>>::from_ssz_bytes(&bytes).unwrap();
+///
+/// assert_eq!(view.len(), 3);
+/// assert_eq!(view.get(2), Some(Ok(vec![3, 3, 7])));
+/// assert_eq!(view.get(3), None);
+/// ```
+pub struct ListView<'a, T> {
+    bytes: &'a [u8],
+    len: usize,
+    _phantom: PhantomData<fn() -> T>,
+}
+
+// Implemented manually, since deriving would require `T: Clone`.
+impl<'a, T> Clone for ListView<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T> Copy for ListView<'a, T> {}
+
+impl<'a, T: Decode> ListView<'a, T> {
+    /// Instantiates a view over `bytes`, which are assumed to be the SSZ encoding of a list of
+    /// `T`.
+    pub fn from_ssz_bytes(bytes: &'a [u8]) -> Result<Self, DecodeError> {
+        let len = if bytes.is_empty() {
+            0
+        } else if T::is_ssz_fixed_len() {
+            let item_len = T::ssz_fixed_len();
+
+            if item_len == 0 {
+                return Err(DecodeError::ZeroLengthItem);
+            } else if bytes.len() % item_len != 0 {
+                return Err(DecodeError::InvalidByteLength {
+                    len: bytes.len(),
+                    expected: (bytes.len() / item_len + 1) * item_len,
+                });
+            }
+
+            bytes.len() / item_len
+        } else {
+            let first_offset = read_offset(bytes)?;
+            sanitize_offset(first_offset, None, bytes.len(), Some(first_offset))?;
+
+            if first_offset % BYTES_PER_LENGTH_OFFSET != 0 || first_offset < BYTES_PER_LENGTH_OFFSET
+            {
+                return Err(DecodeError::InvalidListFixedBytesLen(first_offset));
+            }
+
+            let len = first_offset / BYTES_PER_LENGTH_OFFSET;
+
+            let mut offset = first_offset;
+            for i in 1..len {
+                let next_offset = read_offset(&bytes[(i * BYTES_PER_LENGTH_OFFSET)..])?;
+                offset =
+                    sanitize_offset(next_offset, Some(offset), bytes.len(), Some(first_offset))?;
+            }
+
+            len
+        };
+
+        Ok(Self {
+            bytes,
+            len,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Returns the number of items in the list.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the list has no items.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the SSZ bytes of the item at `index`, without decoding them.
+    pub fn get_bytes(&self, index: usize) -> Option<&'a [u8]> {
+        if index >= self.len {
+            None
+        } else if T::is_ssz_fixed_len() {
+            let item_len = T::ssz_fixed_len();
+            self.bytes.get(index * item_len..(index + 1) * item_len)
+        } else {
+            // The offsets were checked in `Self::from_ssz_bytes`.
+            let start = read_offset(&self.bytes[index * BYTES_PER_LENGTH_OFFSET..]).ok()?;
+            let end = if index + 1 == self.len {
+                self.bytes.len()
+            } else {
+                read_offset(&self.bytes[(index + 1) * BYTES_PER_LENGTH_OFFSET..]).ok()?
+            };
+            self.bytes.get(start..end)
+        }
+    }
+
+    /// Decodes the item at `index`.
+    ///
+    /// Returns `None` if `index` is out-of-bounds.
+    pub fn get(&self, index: usize) -> Option<Result<T, DecodeError>> {
+        self.get_bytes(index).map(T::from_ssz_bytes)
+    }
+
+    /// Returns an iterator which decodes each item in turn.
+    pub fn iter(&self) -> impl Iterator<Item = Result<T, DecodeError>> + 'a
+    where
+        T: 'a,
+    {
+        let view = *self;
+        (0..self.len).filter_map(move |i| view.get(i))
+    }
+}
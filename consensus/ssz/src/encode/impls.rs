@@ -202,6 +202,9 @@ impl_encode_for_tuples! {
 }
 
 /// The SSZ "union" type.
+///
+/// Uses the `BYTES_PER_LENGTH_OFFSET`-byte union index from earlier versions of the
+/// specification, rather than a `UnionSelector`, so that existing encodings remain valid.
 impl<T: Encode> Encode for Option<T> {
     fn is_ssz_fixed_len() -> bool {
         false
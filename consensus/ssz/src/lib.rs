@@ -36,14 +36,22 @@
 
 mod decode;
 mod encode;
+mod union_selector;
 
 pub use decode::{
-    impls::decode_list_of_variable_length_items, Decode, DecodeError, SszDecoder, SszDecoderBuilder,
+    impls::decode_list_of_variable_length_items, split_union_bytes, Decode, DecodeError, ListView,
+    SszDecoder, SszDecoderBuilder,
 };
 pub use encode::{Encode, SszEncoder};
+pub use union_selector::UnionSelector;
 
 /// The number of bytes used to represent an offset.
 pub const BYTES_PER_LENGTH_OFFSET: usize = 4;
+/// The number of bytes used to indicate the variant of a union.
+pub const BYTES_PER_UNION_SELECTOR: usize = 1;
+/// The highest possible union selector value (higher values are reserved for backwards compatible
+/// extensions).
+pub const MAX_UNION_SELECTOR: u8 = 127;
 /// The maximum value that can be represented using `BYTES_PER_LENGTH_OFFSET`.
 #[cfg(target_pointer_width = "32")]
 pub const MAX_LENGTH_VALUE: usize = (std::u32::MAX >> (8 * (4 - BYTES_PER_LENGTH_OFFSET))) as usize;
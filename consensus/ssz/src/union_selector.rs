@@ -0,0 +1,48 @@
+use crate::*;
+
+/// Provides the one-byte "selector" from the SSZ union specification:
+///
+/// https://github.com/ethereum/eth2.0-specs/blob/dev/ssz/simple-serialize.md#union
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct UnionSelector(u8);
+
+impl From<UnionSelector> for u8 {
+    fn from(union_selector: UnionSelector) -> u8 {
+        union_selector.0
+    }
+}
+
+impl PartialEq<u8> for UnionSelector {
+    fn eq(&self, other: &u8) -> bool {
+        self.0 == *other
+    }
+}
+
+impl UnionSelector {
+    /// Instantiate `self`, returning an error if `selector > MAX_UNION_SELECTOR`.
+    pub fn new(selector: u8) -> Result<Self, DecodeError> {
+        if selector <= MAX_UNION_SELECTOR {
+            Ok(Self(selector))
+        } else {
+            Err(DecodeError::UnionSelectorInvalid(selector))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selector_bounds() {
+        assert_eq!(UnionSelector::new(0).map(u8::from), Ok(0));
+        assert_eq!(
+            UnionSelector::new(MAX_UNION_SELECTOR).map(u8::from),
+            Ok(MAX_UNION_SELECTOR)
+        );
+        assert_eq!(
+            UnionSelector::new(MAX_UNION_SELECTOR + 1),
+            Err(DecodeError::UnionSelectorInvalid(MAX_UNION_SELECTOR + 1))
+        );
+    }
+}
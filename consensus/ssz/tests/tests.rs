@@ -378,4 +378,296 @@ mod round_trip {
 
         round_trip(vec);
     }
+
+    #[derive(Debug, PartialEq, Encode, Decode)]
+    #[ssz(enum_behaviour = "union")]
+    enum UnionWithNone {
+        None,
+        A(u16),
+        B(Vec<u8>),
+    }
+
+    #[test]
+    fn union_encoding() {
+        let items = vec![
+            UnionWithNone::None,
+            UnionWithNone::A(0x0201),
+            UnionWithNone::B(vec![]),
+            UnionWithNone::B(vec![42, 43]),
+        ];
+
+        let expected_encodings = vec![
+            //  | selector
+            vec![00],
+            //  | selector | u16-- |
+            vec![01, 01, 02],
+            //  | selector
+            vec![02],
+            //  | selector | list- |
+            vec![02, 42, 43],
+        ];
+
+        for i in 0..items.len() {
+            assert_eq!(
+                items[i].as_ssz_bytes(),
+                expected_encodings[i],
+                "Failed on {}",
+                i
+            );
+        }
+
+        round_trip(items);
+    }
+
+    #[test]
+    fn union_invalid_bytes() {
+        assert_eq!(
+            UnionWithNone::from_ssz_bytes(&[]),
+            Err(DecodeError::OutOfBoundsByte { i: 0 })
+        );
+        assert_eq!(
+            UnionWithNone::from_ssz_bytes(&[03]),
+            Err(DecodeError::UnionSelectorInvalid(3))
+        );
+        assert_eq!(
+            UnionWithNone::from_ssz_bytes(&[128]),
+            Err(DecodeError::UnionSelectorInvalid(128))
+        );
+        assert_eq!(
+            UnionWithNone::from_ssz_bytes(&[00, 00]),
+            Err(DecodeError::InvalidByteLength {
+                len: 1,
+                expected: 0
+            })
+        );
+        assert_eq!(
+            UnionWithNone::from_ssz_bytes(&[01, 00]),
+            Err(DecodeError::InvalidByteLength {
+                len: 1,
+                expected: 2
+            })
+        );
+    }
+
+    #[test]
+    fn union_list_encoding() {
+        let items = vec![
+            UnionWithNone::None,
+            UnionWithNone::A(0x0201),
+            UnionWithNone::B(vec![42]),
+        ];
+
+        let bytes = vec![
+            //  | offset        | offset        | offset        |
+            12, 00, 00, 00, 13, 00, 00, 00, 16, 00, 00, 00,
+            //  | selector | selector | u16 | selector | list |
+            00, 01, 01, 02, 02, 42,
+        ];
+
+        assert_eq!(items.as_ssz_bytes(), bytes);
+        assert_eq!(<Vec<UnionWithNone>>::from_ssz_bytes(&bytes), Ok(items));
+    }
+
+    #[derive(Debug, PartialEq, Encode, Decode)]
+    struct VariableLenWithUnion {
+        a: u8,
+        b: UnionWithNone,
+    }
+
+    #[test]
+    fn union_in_struct() {
+        let s = VariableLenWithUnion {
+            a: 42,
+            b: UnionWithNone::A(1),
+        };
+
+        let bytes = vec![
+            //  | offset        | selector | u16 |
+            42, 05, 00, 00, 00, 01, 01, 00,
+        ];
+
+        assert_eq!(s.as_ssz_bytes(), bytes);
+
+        round_trip(vec![
+            s,
+            VariableLenWithUnion {
+                a: 1,
+                b: UnionWithNone::None,
+            },
+            VariableLenWithUnion {
+                a: 2,
+                b: UnionWithNone::B(vec![1, 2, 3]),
+            },
+        ]);
+    }
+
+    #[derive(Debug, PartialEq, Encode, Decode)]
+    #[ssz(enum_behaviour = "transparent")]
+    enum Transparent {
+        Fixed(FixedLen),
+        Short(u16),
+    }
+
+    #[test]
+    fn transparent_encoding() {
+        let fixed = FixedLen { a: 1, b: 2, c: 3 };
+
+        assert_eq!(
+            Transparent::Fixed(FixedLen { a: 1, b: 2, c: 3 }).as_ssz_bytes(),
+            fixed.as_ssz_bytes()
+        );
+        assert_eq!(Transparent::Short(0x0201).as_ssz_bytes(), vec![01, 02]);
+
+        round_trip(vec![Transparent::Fixed(fixed), Transparent::Short(42)]);
+    }
+
+    #[derive(Debug, PartialEq, Encode, Decode)]
+    #[ssz(enum_behaviour = "transparent")]
+    enum FixedTransparent {
+        A(u32),
+        B(FixedLen),
+    }
+
+    #[derive(Debug, PartialEq, Encode, Decode)]
+    #[ssz(enum_behaviour = "transparent")]
+    enum SameLenTransparent {
+        A(u32),
+        B([u8; 4]),
+    }
+
+    #[derive(Debug, PartialEq, Encode, Decode)]
+    struct FixedLenWithTransparent {
+        a: u8,
+        b: SameLenTransparent,
+    }
+
+    #[test]
+    fn transparent_fixed_len() {
+        assert!(!<Transparent as Encode>::is_ssz_fixed_len());
+        assert!(!<Transparent as Decode>::is_ssz_fixed_len());
+        assert!(
+            !<FixedTransparent as Encode>::is_ssz_fixed_len(),
+            "variants with different lengths should be variable-length"
+        );
+        assert!(!<FixedTransparent as Decode>::is_ssz_fixed_len());
+
+        assert!(<SameLenTransparent as Encode>::is_ssz_fixed_len());
+        assert!(<SameLenTransparent as Decode>::is_ssz_fixed_len());
+        assert_eq!(<SameLenTransparent as Encode>::ssz_fixed_len(), 4);
+        assert_eq!(<SameLenTransparent as Decode>::ssz_fixed_len(), 4);
+        assert!(<FixedLenWithTransparent as Encode>::is_ssz_fixed_len());
+    }
+
+    #[test]
+    fn transparent_in_fixed_len_struct() {
+        let s = FixedLenWithTransparent {
+            a: 42,
+            b: SameLenTransparent::A(0x04030201),
+        };
+
+        let bytes = vec![
+            //  | u32-------- |
+            42, 01, 02, 03, 04,
+        ];
+
+        assert_eq!(s.as_ssz_bytes(), bytes);
+        assert_eq!(FixedLenWithTransparent::from_ssz_bytes(&bytes), Ok(s));
+        assert_eq!(
+            FixedLenWithTransparent::from_ssz_bytes(&bytes[..4]),
+            Err(DecodeError::InvalidByteLength {
+                len: 4,
+                expected: 5
+            })
+        );
+    }
+
+    #[test]
+    fn transparent_invalid_bytes() {
+        assert_eq!(
+            Transparent::from_ssz_bytes(&[01, 02, 03]),
+            Err(DecodeError::BytesInvalid(
+                "No variant of Transparent could be decoded from the bytes".to_string()
+            ))
+        );
+    }
+}
+
+mod views {
+    use super::*;
+    use ssz::ListView;
+
+    #[derive(Debug, PartialEq, Encode, Decode)]
+    #[ssz(view = "FixedView")]
+    struct Fixed {
+        a: u16,
+        b: u64,
+    }
+
+    #[derive(Debug, PartialEq, Encode, Decode)]
+    #[ssz(view = "VariableView")]
+    struct Variable {
+        a: u16,
+        b: Vec<u16>,
+        c: Vec<Vec<u8>>,
+        d: Fixed,
+    }
+
+    #[test]
+    fn fixed_len_view() {
+        let item = Fixed { a: 1, b: 2 };
+        let bytes = item.as_ssz_bytes();
+        let view = FixedView::from_ssz_bytes(&bytes).unwrap();
+
+        assert_eq!(view.a(), Ok(1));
+        assert_eq!(view.b(), Ok(2));
+        assert_eq!(view.a_bytes(), &[1, 0]);
+
+        assert!(FixedView::from_ssz_bytes(&bytes[1..]).is_err());
+        assert!(FixedView::from_ssz_bytes(&[bytes.clone(), vec![0]].concat()).is_err());
+    }
+
+    #[test]
+    fn variable_len_view() {
+        let item = Variable {
+            a: 1,
+            b: vec![2, 3, 4],
+            c: vec![vec![], vec![5, 6]],
+            d: Fixed { a: 7, b: 8 },
+        };
+        let bytes = item.as_ssz_bytes();
+        let view = VariableView::from_ssz_bytes(&bytes).unwrap();
+
+        assert_eq!(view.a(), Ok(item.a));
+        assert_eq!(view.b(), Ok(item.b.clone()));
+        assert_eq!(view.c(), Ok(item.c.clone()));
+        assert_eq!(view.d(), Ok(Fixed { a: 7, b: 8 }));
+
+        let b = ListView::<u16>::from_ssz_bytes(view.b_bytes()).unwrap();
+        assert_eq!(b.len(), 3);
+        assert_eq!(b.get(1), Some(Ok(3)));
+        assert_eq!(b.get(3), None);
+
+        let c = ListView::<Vec<u8>>::from_ssz_bytes(view.c_bytes()).unwrap();
+        assert_eq!(c.iter().collect::<Result<Vec<_>, _>>(), Ok(item.c.clone()));
+
+        let d = FixedView::from_ssz_bytes(view.d_bytes()).unwrap();
+        assert_eq!(d.b(), Ok(8));
+    }
+
+    #[test]
+    fn invalid_view_offsets() {
+        let item = Variable {
+            a: 1,
+            b: vec![2, 3],
+            c: vec![],
+            d: Fixed { a: 4, b: 5 },
+        };
+        let mut bytes = item.as_ssz_bytes();
+        // Point the offset of `c` before the offset of `b`.
+        bytes[6] = 0;
+
+        assert!(VariableView::from_ssz_bytes(&bytes).is_err());
+        assert!(ListView::<u16>::from_ssz_bytes(&[1, 2, 3]).is_err());
+        assert!(ListView::<Vec<u8>>::from_ssz_bytes(&[3, 0, 0, 0]).is_err());
+    }
 }
@@ -2,12 +2,129 @@
 //! Provides procedural derive macros for the `Encode` and `Decode` traits of the `eth2_ssz` crate.
 //!
 //! Supports field attributes, see each derive macro for more information.
+//!
+//! ## Enums
+//!
+//! Enums are supported when they declare how they should be encoded, using one of the following
+//! attributes:
+//!
+//! - `#[ssz(enum_behaviour = "union")]`: encoded as an SSZ union; a one-byte selector (the index
+//!   of the variant) followed by the encoding of the variant. Each variant must wrap exactly one
+//!   type, except the first variant which may also be a unit variant (i.e., `None`).
+//! - `#[ssz(enum_behaviour = "transparent")]`: encoded as the value wrapped by the variant,
+//!   without any selector. Each variant must wrap exactly one type. Decoding returns the first
+//!   variant which decodes successfully, so the variants should not share encodings.
+//!
+//! Unions are always variable-length. Transparent enums are fixed-length if all of their variants
+//! are fixed-length with the same length, otherwise they are variable-length.
 
 extern crate proc_macro;
 
 use proc_macro::TokenStream;
-use quote::quote;
-use syn::{parse_macro_input, DeriveInput};
+use quote::{quote, ToTokens};
+use syn::{parse_macro_input, DeriveInput, Lit, Meta, NestedMeta};
+
+/// The highest possible union selector value, matching `ssz::MAX_UNION_SELECTOR`.
+///
+/// Defined here, rather than depending upon `eth2_ssz`, since `eth2_ssz` uses this crate in its
+/// tests.
+const MAX_UNION_SELECTOR: u8 = 127;
+
+/// Defines how an enum is encoded, as set with `#[ssz(enum_behaviour = "...")]`.
+#[derive(Clone, Copy, PartialEq)]
+enum EnumBehaviour {
+    /// A one-byte selector followed by the encoding of the variant.
+    Union,
+    /// The encoding of the variant, without a selector.
+    Transparent,
+}
+
+/// Returns the value of the `#[ssz(name = "value")]` attribute with the given `name`, if any.
+///
+/// # Panics
+/// Panics at compile time if the value is not a string.
+fn get_ssz_string_attribute(attrs: &[syn::Attribute], name: &str) -> Option<String> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("ssz"))
+        .flat_map(|attr| attr.parse_meta())
+        .filter_map(|meta| match meta {
+            Meta::List(list) => Some(list.nested),
+            _ => None,
+        })
+        .flatten()
+        .find_map(|nested| match nested {
+            NestedMeta::Meta(Meta::NameValue(name_value)) if name_value.path.is_ident(name) => {
+                match name_value.lit {
+                    Lit::Str(s) => Some(s.value()),
+                    _ => panic!("ssz {} must be a string", name),
+                }
+            }
+            _ => None,
+        })
+}
+
+/// Reads the `#[ssz(enum_behaviour = "...")]` attribute of an enum.
+///
+/// # Panics
+/// Panics at compile time if the attribute is missing or invalid.
+fn get_enum_behaviour(attrs: &[syn::Attribute]) -> EnumBehaviour {
+    let behaviour = get_ssz_string_attribute(attrs, "enum_behaviour");
+
+    match behaviour.as_deref() {
+        Some("union") => EnumBehaviour::Union,
+        Some("transparent") => EnumBehaviour::Transparent,
+        Some(other) => panic!(
+            "{} is not a valid ssz enum_behaviour, use \"union\" or \"transparent\"",
+            other
+        ),
+        None => panic!(
+            "ssz_derive requires enums to have #[ssz(enum_behaviour = \"union\")] or \
+             #[ssz(enum_behaviour = \"transparent\")]"
+        ),
+    }
+}
+
+/// Returns the ident of each variant of an enum, along with the type it wraps (or `None` for a
+/// unit variant).
+///
+/// # Panics
+/// Panics at compile time if a variant is not supported by `behaviour`.
+fn get_enum_variants(
+    enum_data: &syn::DataEnum,
+    behaviour: EnumBehaviour,
+) -> Vec<(&syn::Ident, Option<&syn::Type>)> {
+    if enum_data.variants.is_empty() {
+        panic!("ssz_derive does not support enums without variants.");
+    }
+
+    if behaviour == EnumBehaviour::Union
+        && enum_data.variants.len() > usize::from(MAX_UNION_SELECTOR) + 1
+    {
+        panic!(
+            "ssz_derive supports at most {} union variants.",
+            usize::from(MAX_UNION_SELECTOR) + 1
+        );
+    }
+
+    enum_data
+        .variants
+        .iter()
+        .enumerate()
+        .map(|(i, variant)| match &variant.fields {
+            syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                (&variant.ident, Some(&fields.unnamed[0].ty))
+            }
+            syn::Fields::Unit if behaviour == EnumBehaviour::Union && i == 0 => {
+                (&variant.ident, None)
+            }
+            syn::Fields::Unit if behaviour == EnumBehaviour::Union => {
+                panic!("ssz_derive only supports a unit variant as the first union variant.")
+            }
+            _ => panic!("ssz_derive only supports enum variants which wrap exactly one type."),
+        })
+        .collect()
+}
 
 /// Returns a Vec of `syn::Ident` for each named field in the struct, whilst filtering out fields
 /// that should not be serialized.
@@ -59,9 +176,9 @@ fn should_skip_serializing(field: &syn::Field) -> bool {
     })
 }
 
-/// Implements `ssz::Encode` for some `struct`.
+/// Implements `ssz::Encode` for some `struct` or `enum`.
 ///
-/// Fields are encoded in the order they are defined.
+/// Fields are encoded in the order they are defined. See the crate documentation for enums.
 ///
 /// ## Field attributes
 ///
@@ -70,14 +187,20 @@ fn should_skip_serializing(field: &syn::Field) -> bool {
 pub fn ssz_encode_derive(input: TokenStream) -> TokenStream {
     let item = parse_macro_input!(input as DeriveInput);
 
+    match &item.data {
+        syn::Data::Struct(struct_data) => ssz_encode_derive_struct(&item, struct_data),
+        syn::Data::Enum(enum_data) => match get_enum_behaviour(&item.attrs) {
+            EnumBehaviour::Union => ssz_encode_derive_enum_union(&item, enum_data),
+            EnumBehaviour::Transparent => ssz_encode_derive_enum_transparent(&item, enum_data),
+        },
+        _ => panic!("ssz_derive only supports structs and enums."),
+    }
+}
+
+fn ssz_encode_derive_struct(item: &DeriveInput, struct_data: &syn::DataStruct) -> TokenStream {
     let name = &item.ident;
     let (impl_generics, ty_generics, where_clause) = &item.generics.split_for_impl();
 
-    let struct_data = match &item.data {
-        syn::Data::Struct(s) => s,
-        _ => panic!("ssz_derive only supports structs."),
-    };
-
     let field_idents = get_serializable_named_field_idents(&struct_data);
     let field_idents_a = get_serializable_named_field_idents(&struct_data);
     let field_types_a = get_serializable_field_types(&struct_data);
@@ -144,6 +267,139 @@ pub fn ssz_encode_derive(input: TokenStream) -> TokenStream {
     output.into()
 }
 
+/// Implements `ssz::Encode` for an enum with `#[ssz(enum_behaviour = "union")]`.
+fn ssz_encode_derive_enum_union(item: &DeriveInput, enum_data: &syn::DataEnum) -> TokenStream {
+    let name = &item.ident;
+    let (impl_generics, ty_generics, where_clause) = &item.generics.split_for_impl();
+
+    let mut bytes_lens = vec![];
+    let mut appends = vec![];
+
+    for (i, (ident, ty)) in get_enum_variants(enum_data, EnumBehaviour::Union)
+        .into_iter()
+        .enumerate()
+    {
+        let selector = i as u8;
+
+        if ty.is_some() {
+            bytes_lens.push(quote! {
+                #name::#ident(inner) => inner.ssz_bytes_len() + ssz::BYTES_PER_UNION_SELECTOR
+            });
+            appends.push(quote! {
+                #name::#ident(inner) => {
+                    buf.push(#selector);
+                    inner.ssz_append(buf);
+                }
+            });
+        } else {
+            bytes_lens.push(quote! {
+                #name::#ident => ssz::BYTES_PER_UNION_SELECTOR
+            });
+            appends.push(quote! {
+                #name::#ident => buf.push(#selector)
+            });
+        }
+    }
+
+    let output = quote! {
+        #[allow(clippy::integer_arithmetic)]
+        impl #impl_generics ssz::Encode for #name #ty_generics #where_clause {
+            fn is_ssz_fixed_len() -> bool {
+                false
+            }
+
+            fn ssz_bytes_len(&self) -> usize {
+                match self {
+                    #(
+                        #bytes_lens,
+                    )*
+                }
+            }
+
+            fn ssz_append(&self, buf: &mut Vec<u8>) {
+                match self {
+                    #(
+                        #appends,
+                    )*
+                }
+            }
+        }
+    };
+    output.into()
+}
+
+/// Implements `ssz::Encode` for an enum with `#[ssz(enum_behaviour = "transparent")]`.
+fn ssz_encode_derive_enum_transparent(
+    item: &DeriveInput,
+    enum_data: &syn::DataEnum,
+) -> TokenStream {
+    let name = &item.ident;
+    let (impl_generics, ty_generics, where_clause) = &item.generics.split_for_impl();
+
+    let (variant_idents, variant_types): (Vec<_>, Vec<_>) =
+        get_enum_variants(enum_data, EnumBehaviour::Transparent)
+            .into_iter()
+            .map(|(ident, ty)| (ident, ty.expect("transparent variants always wrap a type")))
+            .unzip();
+    let variant_idents_a = variant_idents.clone();
+    let fixed_len_fns = transparent_fixed_len_fns(&quote! { ssz::Encode }, &variant_types);
+
+    let output = quote! {
+        impl #impl_generics ssz::Encode for #name #ty_generics #where_clause {
+            #fixed_len_fns
+
+            fn ssz_bytes_len(&self) -> usize {
+                match self {
+                    #(
+                        #name::#variant_idents(inner) => inner.ssz_bytes_len(),
+                    )*
+                }
+            }
+
+            fn ssz_append(&self, buf: &mut Vec<u8>) {
+                match self {
+                    #(
+                        #name::#variant_idents_a(inner) => inner.ssz_append(buf),
+                    )*
+                }
+            }
+        }
+    };
+    output.into()
+}
+
+/// Returns the `is_ssz_fixed_len` and `ssz_fixed_len` functions of `trait_path` (i.e.,
+/// `ssz::Encode` or `ssz::Decode`) for a transparent enum with the given `variant_types`.
+///
+/// The enum is only fixed-length if every variant is fixed-length with the same length, since
+/// the length cannot depend upon the variant.
+fn transparent_fixed_len_fns(
+    trait_path: &impl ToTokens,
+    variant_types: &[&syn::Type],
+) -> impl ToTokens {
+    let first_type = variant_types[0];
+    let variant_types_a = variant_types;
+
+    quote! {
+        fn is_ssz_fixed_len() -> bool {
+            #(
+                <#variant_types as #trait_path>::is_ssz_fixed_len() &&
+                <#variant_types_a as #trait_path>::ssz_fixed_len()
+                    == <#first_type as #trait_path>::ssz_fixed_len() &&
+            )*
+                true
+        }
+
+        fn ssz_fixed_len() -> usize {
+            if <Self as #trait_path>::is_ssz_fixed_len() {
+                <#first_type as #trait_path>::ssz_fixed_len()
+            } else {
+                ssz::BYTES_PER_LENGTH_OFFSET
+            }
+        }
+    }
+}
+
 /// Returns true if some field has an attribute declaring it should not be deserialized.
 ///
 /// The field attribute is: `#[ssz(skip_deserializing)]`
@@ -154,26 +410,152 @@ fn should_skip_deserializing(field: &syn::Field) -> bool {
     })
 }
 
-/// Implements `ssz::Decode` for some `struct`.
+/// Implements `ssz::Decode` for some `struct` or `enum`.
 ///
-/// Fields are decoded in the order they are defined.
+/// Fields are decoded in the order they are defined. See the crate documentation for enums.
 ///
 /// ## Field attributes
 ///
 /// - `#[ssz(skip_deserializing)]`: during de-serialization the field will be instantiated from a
 /// `Default` implementation. The decoder will assume that the field was not serialized at all
 /// (e.g., if it has been serialized, an error will be raised instead of `Default` overriding it).
-#[proc_macro_derive(Decode)]
+///
+/// ## Struct attributes
+///
+/// - `#[ssz(view = "FooView")]`: also generates `FooView<'a, ..>`, a view over the SSZ bytes of
+/// the struct. The view checks the offsets of all fields when it is created, then decodes each
+/// field only when its accessor (named after the field) is called. The bytes of a field are
+/// available from the `<field>_bytes` accessor, e.g. to create an `ssz::ListView` over them.
+#[proc_macro_derive(Decode, attributes(ssz))]
 pub fn ssz_decode_derive(input: TokenStream) -> TokenStream {
     let item = parse_macro_input!(input as DeriveInput);
 
+    match &item.data {
+        syn::Data::Struct(struct_data) => ssz_decode_derive_struct(&item, struct_data),
+        syn::Data::Enum(enum_data) => match get_enum_behaviour(&item.attrs) {
+            EnumBehaviour::Union => ssz_decode_derive_enum_union(&item, enum_data),
+            EnumBehaviour::Transparent => ssz_decode_derive_enum_transparent(&item, enum_data),
+        },
+        _ => panic!("ssz_derive only supports structs and enums."),
+    }
+}
+
+fn ssz_decode_derive_struct(item: &DeriveInput, struct_data: &syn::DataStruct) -> TokenStream {
+    let mut output = ssz_decode_derive_struct_impl(item, struct_data);
+
+    if let Some(view_name) = get_ssz_string_attribute(&item.attrs, "view") {
+        output.extend(ssz_decode_derive_struct_view(item, struct_data, &view_name));
+    }
+
+    output
+}
+
+/// Implements a view over the SSZ bytes of a struct with `#[ssz(view = "...")]`, named by the
+/// attribute.
+fn ssz_decode_derive_struct_view(
+    item: &DeriveInput,
+    struct_data: &syn::DataStruct,
+    view_name: &str,
+) -> TokenStream {
     let name = &item.ident;
-    let (impl_generics, ty_generics, where_clause) = &item.generics.split_for_impl();
+    let vis = &item.vis;
+    let (_, ty_generics, _) = &item.generics.split_for_impl();
+
+    let view_name = syn::Ident::new(view_name, name.span());
+    let view_doc = format!(
+        "A view over the SSZ bytes of a `{}`, which decodes fields only when they are accessed.",
+        name
+    );
+
+    // The view borrows the bytes for the `'ssz_view` lifetime.
+    let mut view_generics = item.generics.clone();
+    view_generics.params.insert(
+        0,
+        syn::GenericParam::Lifetime(syn::LifetimeDef::new(syn::Lifetime::new(
+            "'ssz_view",
+            name.span(),
+        ))),
+    );
+    let (view_impl_generics, view_ty_generics, where_clause) = view_generics.split_for_impl();
+
+    let fields = struct_data
+        .fields
+        .iter()
+        .filter(|field| !should_skip_deserializing(field))
+        .map(|field| {
+            let ident = field
+                .ident
+                .as_ref()
+                .expect("ssz_derive only supports named struct fields.");
+            (ident, &field.ty)
+        })
+        .collect::<Vec<_>>();
 
-    let struct_data = match &item.data {
-        syn::Data::Struct(s) => s,
-        _ => panic!("ssz_derive only supports structs."),
+    let idents = fields.iter().map(|(ident, _)| ident).collect::<Vec<_>>();
+    let types = fields.iter().map(|(_, ty)| ty).collect::<Vec<_>>();
+    let bytes_idents = idents
+        .iter()
+        .map(|ident| syn::Ident::new(&format!("{}_bytes", ident), ident.span()))
+        .collect::<Vec<_>>();
+    let decode_docs = idents
+        .iter()
+        .map(|ident| format!("Decodes the `{}` field.", ident))
+        .collect::<Vec<_>>();
+    let bytes_docs = idents
+        .iter()
+        .map(|ident| format!("Returns the SSZ bytes of the `{}` field.", ident))
+        .collect::<Vec<_>>();
+
+    let output = quote! {
+        #[doc = #view_doc]
+        #vis struct #view_name #view_impl_generics #where_clause {
+            #(
+                #idents: &'ssz_view [u8],
+            )*
+            _phantom: std::marker::PhantomData<fn() -> #name #ty_generics>,
+        }
+
+        impl #view_impl_generics #view_name #view_ty_generics #where_clause {
+            /// Instantiates a view over `bytes`, checking the offsets of all fields but without
+            /// decoding any of them.
+            pub fn from_ssz_bytes(
+                bytes: &'ssz_view [u8],
+            ) -> std::result::Result<Self, ssz::DecodeError> {
+                let mut builder = ssz::SszDecoderBuilder::new(bytes);
+
+                #(
+                    builder.register_type::<#types>()?;
+                )*
+
+                let mut decoder = builder.build()?;
+
+                Ok(Self {
+                    #(
+                        #idents: decoder.next_bytes(),
+                    )*
+                    _phantom: std::marker::PhantomData,
+                })
+            }
+
+            #(
+                #[doc = #decode_docs]
+                pub fn #idents(&self) -> std::result::Result<#types, ssz::DecodeError> {
+                    <#types as ssz::Decode>::from_ssz_bytes(self.#idents)
+                }
+
+                #[doc = #bytes_docs]
+                pub fn #bytes_idents(&self) -> &'ssz_view [u8] {
+                    self.#idents
+                }
+            )*
+        }
     };
+    output.into()
+}
+
+fn ssz_decode_derive_struct_impl(item: &DeriveInput, struct_data: &syn::DataStruct) -> TokenStream {
+    let name = &item.ident;
+    let (impl_generics, ty_generics, where_clause) = &item.generics.split_for_impl();
 
     let mut register_types = vec![];
     let mut field_names = vec![];
@@ -308,3 +690,92 @@ pub fn ssz_decode_derive(input: TokenStream) -> TokenStream {
     };
     output.into()
 }
+
+/// Implements `ssz::Decode` for an enum with `#[ssz(enum_behaviour = "union")]`.
+fn ssz_decode_derive_enum_union(item: &DeriveInput, enum_data: &syn::DataEnum) -> TokenStream {
+    let name = &item.ident;
+    let (impl_generics, ty_generics, where_clause) = &item.generics.split_for_impl();
+
+    let mut selectors = vec![];
+    let mut decodes = vec![];
+
+    for (i, (ident, ty)) in get_enum_variants(enum_data, EnumBehaviour::Union)
+        .into_iter()
+        .enumerate()
+    {
+        selectors.push(i as u8);
+
+        match ty {
+            Some(ty) => decodes.push(quote! {
+                <#ty as ssz::Decode>::from_ssz_bytes(body).map(#name::#ident)
+            }),
+            None => decodes.push(quote! {
+                if body.is_empty() {
+                    Ok(#name::#ident)
+                } else {
+                    Err(ssz::DecodeError::InvalidByteLength {
+                        len: body.len(),
+                        expected: 0,
+                    })
+                }
+            }),
+        }
+    }
+
+    let output = quote! {
+        impl #impl_generics ssz::Decode for #name #ty_generics #where_clause {
+            fn is_ssz_fixed_len() -> bool {
+                false
+            }
+
+            fn from_ssz_bytes(bytes: &[u8]) -> std::result::Result<Self, ssz::DecodeError> {
+                let (selector, body) = ssz::split_union_bytes(bytes)?;
+
+                match u8::from(selector) {
+                    #(
+                        #selectors => #decodes,
+                    )*
+                    other => Err(ssz::DecodeError::UnionSelectorInvalid(other)),
+                }
+            }
+        }
+    };
+    output.into()
+}
+
+/// Implements `ssz::Decode` for an enum with `#[ssz(enum_behaviour = "transparent")]`.
+///
+/// Each variant is attempted in the order it is defined and the first successful decoding is
+/// returned.
+fn ssz_decode_derive_enum_transparent(
+    item: &DeriveInput,
+    enum_data: &syn::DataEnum,
+) -> TokenStream {
+    let name = &item.ident;
+    let (impl_generics, ty_generics, where_clause) = &item.generics.split_for_impl();
+
+    let (variant_idents, variant_types): (Vec<_>, Vec<_>) =
+        get_enum_variants(enum_data, EnumBehaviour::Transparent)
+            .into_iter()
+            .map(|(ident, ty)| (ident, ty.expect("transparent variants always wrap a type")))
+            .unzip();
+    let error = format!("No variant of {} could be decoded from the bytes", name);
+    let fixed_len_fns = transparent_fixed_len_fns(&quote! { ssz::Decode }, &variant_types);
+
+    let output = quote! {
+        impl #impl_generics ssz::Decode for #name #ty_generics #where_clause {
+            #fixed_len_fns
+
+            fn from_ssz_bytes(bytes: &[u8]) -> std::result::Result<Self, ssz::DecodeError> {
+                #(
+                    if let Ok(inner) = <#variant_types as ssz::Decode>::from_ssz_bytes(bytes) {
+                        return Ok(#name::#variant_idents(inner));
+                    }
+                )*
+
+                Err(ssz::DecodeError::BytesInvalid(#error.to_string()))
+            }
+        }
+    };
+    output.into()
+}
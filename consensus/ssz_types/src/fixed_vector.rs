@@ -1,10 +1,12 @@
-use crate::tree_hash::vec_tree_hash_root;
+use crate::tree_hash::{
+    vec_chunk_count, vec_chunks, vec_item_generalized_index, vec_item_proof, vec_tree_hash_root,
+};
 use crate::Error;
 use serde_derive::{Deserialize, Serialize};
 use std::marker::PhantomData;
 use std::ops::{Deref, Index, IndexMut};
 use std::slice::SliceIndex;
-use tree_hash::Hash256;
+use tree_hash::{Hash256, PathElement, ProofError};
 use typenum::Unsigned;
 
 pub use typenum;
@@ -166,6 +168,45 @@ where
     fn tree_hash_root(&self) -> Hash256 {
         vec_tree_hash_root::<T, N>(&self.vec)
     }
+
+    fn tree_hash_generalized_index(path: &[PathElement]) -> Result<usize, ProofError> {
+        match path.split_first() {
+            None => Ok(1),
+            Some((PathElement::Index(index), rest)) => {
+                if *index >= N::to_usize() {
+                    return Err(ProofError::IndexOutOfBounds {
+                        index: *index,
+                        len: N::to_usize(),
+                    });
+                }
+
+                let (chunk_index, inner) = vec_item_generalized_index::<T>(*index, rest)?;
+                Ok(tree_hash::proof::vector_generalized_index(
+                    vec_chunk_count::<T, N>(),
+                    chunk_index,
+                    inner,
+                ))
+            }
+            Some((other, _)) => Err(ProofError::unexpected(other)),
+        }
+    }
+
+    fn tree_hash_proof(&self, path: &[PathElement]) -> Result<(Hash256, Vec<Hash256>), ProofError> {
+        match path.split_first() {
+            None => Ok((self.tree_hash_root(), vec![])),
+            Some((PathElement::Index(index), rest)) => {
+                let chunks = vec_chunks(&self.vec);
+                let (chunk_index, inner) = vec_item_proof(&self.vec, &chunks, *index, rest)?;
+                Ok(tree_hash::proof::vector_proof(
+                    &chunks,
+                    vec_chunk_count::<T, N>(),
+                    chunk_index,
+                    inner,
+                ))
+            }
+            Some((other, _)) => Err(ProofError::unexpected(other)),
+        }
+    }
 }
 
 impl<T, N: Unsigned> ssz::Encode for FixedVector<T, N>
@@ -1,4 +1,6 @@
-use tree_hash::{Hash256, MerkleHasher, TreeHash, TreeHashType, BYTES_PER_CHUNK};
+use tree_hash::{
+    Hash256, MerkleHasher, PathElement, ProofError, TreeHash, TreeHashType, BYTES_PER_CHUNK,
+};
 use typenum::Unsigned;
 
 /// A helper function providing common functionality between the `TreeHash` implementations for
@@ -40,6 +42,88 @@ where
     }
 }
 
+/// Returns the number of leaves in the tree used by `vec_tree_hash_root`, before padding.
+pub fn vec_chunk_count<T, N>() -> usize
+where
+    T: TreeHash,
+    N: Unsigned,
+{
+    match T::tree_hash_type() {
+        TreeHashType::Basic => {
+            (N::to_usize() + T::tree_hash_packing_factor() - 1) / T::tree_hash_packing_factor()
+        }
+        TreeHashType::Container | TreeHashType::List | TreeHashType::Vector => N::to_usize(),
+    }
+}
+
+/// Returns the leaves of the tree used by `vec_tree_hash_root`, excluding any padding.
+pub fn vec_chunks<T: TreeHash>(vec: &[T]) -> Vec<Hash256> {
+    match T::tree_hash_type() {
+        TreeHashType::Basic => {
+            let mut bytes = vec
+                .iter()
+                .flat_map(|item| item.tree_hash_packed_encoding())
+                .collect::<Vec<_>>();
+            let chunk_count = (bytes.len() + BYTES_PER_CHUNK - 1) / BYTES_PER_CHUNK;
+            bytes.resize(chunk_count * BYTES_PER_CHUNK, 0);
+
+            bytes
+                .chunks(BYTES_PER_CHUNK)
+                .map(Hash256::from_slice)
+                .collect()
+        }
+        TreeHashType::Container | TreeHashType::List | TreeHashType::Vector => {
+            vec.iter().map(TreeHash::tree_hash_root).collect()
+        }
+    }
+}
+
+/// Returns the index of the leaf containing the item at `index`, and the generalized index of
+/// `path` relative to that leaf.
+pub fn vec_item_generalized_index<T: TreeHash>(
+    index: usize,
+    path: &[PathElement],
+) -> Result<(usize, usize), ProofError> {
+    match T::tree_hash_type() {
+        TreeHashType::Basic => match path.first() {
+            None => Ok((index / T::tree_hash_packing_factor(), 1)),
+            Some(element) => Err(ProofError::unexpected(element)),
+        },
+        TreeHashType::Container | TreeHashType::List | TreeHashType::Vector => {
+            Ok((index, T::tree_hash_generalized_index(path)?))
+        }
+    }
+}
+
+/// Returns the index of the leaf containing the item at `index`, and a proof of the node at
+/// `path` relative to that leaf.
+///
+/// `chunks` must be the result of `vec_chunks(vec)`.
+pub fn vec_item_proof<T: TreeHash>(
+    vec: &[T],
+    chunks: &[Hash256],
+    index: usize,
+    path: &[PathElement],
+) -> Result<(usize, (Hash256, Vec<Hash256>)), ProofError> {
+    let item = vec.get(index).ok_or(ProofError::IndexOutOfBounds {
+        index,
+        len: vec.len(),
+    })?;
+
+    match T::tree_hash_type() {
+        TreeHashType::Basic => match path.first() {
+            None => {
+                let chunk_index = index / T::tree_hash_packing_factor();
+                Ok((chunk_index, (chunks[chunk_index], vec![])))
+            }
+            Some(element) => Err(ProofError::unexpected(element)),
+        },
+        TreeHashType::Container | TreeHashType::List | TreeHashType::Vector => {
+            Ok((index, item.tree_hash_proof(path)?))
+        }
+    }
+}
+
 /// A helper function providing common functionality for finding the Merkle root of some bytes that
 /// represent a bitfield.
 pub fn bitfield_bytes_tree_hash_root<N: Unsigned>(bytes: &[u8]) -> Hash256 {
@@ -1,10 +1,12 @@
-use crate::tree_hash::vec_tree_hash_root;
+use crate::tree_hash::{
+    vec_chunk_count, vec_chunks, vec_item_generalized_index, vec_item_proof, vec_tree_hash_root,
+};
 use crate::Error;
 use serde_derive::{Deserialize, Serialize};
 use std::marker::PhantomData;
 use std::ops::{Deref, DerefMut, Index, IndexMut};
 use std::slice::SliceIndex;
-use tree_hash::Hash256;
+use tree_hash::{Hash256, PathElement, ProofError};
 use typenum::Unsigned;
 
 pub use typenum;
@@ -195,6 +197,52 @@ where
 
         tree_hash::mix_in_length(&root, self.len())
     }
+
+    fn tree_hash_generalized_index(path: &[PathElement]) -> Result<usize, ProofError> {
+        match path.split_first() {
+            None => Ok(1),
+            Some((PathElement::Index(index), rest)) => {
+                if *index >= N::to_usize() {
+                    return Err(ProofError::IndexOutOfBounds {
+                        index: *index,
+                        len: N::to_usize(),
+                    });
+                }
+
+                let (chunk_index, inner) = vec_item_generalized_index::<T>(*index, rest)?;
+                Ok(tree_hash::proof::list_generalized_index(
+                    vec_chunk_count::<T, N>(),
+                    chunk_index,
+                    inner,
+                ))
+            }
+            Some((PathElement::Length, [])) => Ok(tree_hash::proof::LIST_LENGTH_GENERALIZED_INDEX),
+            Some((other, _)) => Err(ProofError::unexpected(other)),
+        }
+    }
+
+    fn tree_hash_proof(&self, path: &[PathElement]) -> Result<(Hash256, Vec<Hash256>), ProofError> {
+        match path.split_first() {
+            None => Ok((self.tree_hash_root(), vec![])),
+            Some((PathElement::Index(index), rest)) => {
+                let chunks = vec_chunks(&self.vec);
+                let (chunk_index, inner) = vec_item_proof(&self.vec, &chunks, *index, rest)?;
+                Ok(tree_hash::proof::list_proof(
+                    &chunks,
+                    vec_chunk_count::<T, N>(),
+                    chunk_index,
+                    self.len(),
+                    inner,
+                ))
+            }
+            Some((PathElement::Length, [])) => Ok(tree_hash::proof::list_length_proof(
+                &vec_chunks(&self.vec),
+                vec_chunk_count::<T, N>(),
+                self.len(),
+            )),
+            Some((other, _)) => Err(ProofError::unexpected(other)),
+        }
+    }
 }
 
 impl<T, N: Unsigned> ssz::Encode for VariableList<T, N>
@@ -458,4 +506,55 @@ mod test {
             );
         }
     }
+
+    #[test]
+    fn proof_packed() {
+        let list: VariableList<u64, U13> = VariableList::from((0..10).collect::<Vec<_>>());
+        let root = list.tree_hash_root();
+
+        // Items 4..8 are packed into the second of four chunks, beneath the length mix-in.
+        let path = [PathElement::Index(5)];
+        let index = VariableList::<u64, U13>::tree_hash_generalized_index(&path).unwrap();
+        assert_eq!(index, 9);
+
+        let (leaf, branch) = list.tree_hash_proof(&path).unwrap();
+        assert_eq!(leaf, merkle_root(&vec![4_u64, 5, 6, 7].as_ssz_bytes(), 0));
+        assert_eq!(
+            merkle_proof::calculate_merkle_root(leaf, &branch, index),
+            Ok(root)
+        );
+
+        assert_eq!(
+            list.tree_hash_proof(&[PathElement::Index(10)]),
+            Err(ProofError::IndexOutOfBounds { index: 10, len: 10 })
+        );
+        assert_eq!(
+            VariableList::<u64, U13>::tree_hash_generalized_index(&[PathElement::Index(13)]),
+            Err(ProofError::IndexOutOfBounds { index: 13, len: 13 })
+        );
+    }
+
+    #[test]
+    fn proof_composite() {
+        let list: VariableList<A, U13> = VariableList::from(vec![A { a: 3, b: 4 }; 5]);
+        let root = list.tree_hash_root();
+
+        let paths: Vec<&[PathElement]> = vec![
+            &[PathElement::Length],
+            &[PathElement::Index(4)],
+            &[PathElement::Index(4), PathElement::Field("b")],
+        ];
+        let expected_indices = vec![3, 36, 73];
+
+        for (path, expected_index) in paths.into_iter().zip(expected_indices) {
+            let index = VariableList::<A, U13>::tree_hash_generalized_index(path).unwrap();
+            assert_eq!(index, expected_index);
+
+            let (leaf, branch) = list.tree_hash_proof(path).unwrap();
+            assert_eq!(
+                merkle_proof::calculate_merkle_root(leaf, &branch, index),
+                Ok(root)
+            );
+        }
+    }
 }
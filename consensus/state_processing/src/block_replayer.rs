@@ -0,0 +1,275 @@
+//! Provides `BlockReplayer`, which re-applies a sequence of blocks to a state in order to
+//! reconstruct some later state.
+
+use crate::{
+    per_block_processing, per_epoch_processing::EpochProcessingSummary, per_slot_processing,
+    BlockProcessingError, BlockSignatureStrategy, SlotProcessingError,
+};
+use std::iter::Peekable;
+use types::{BeaconState, BeaconStateError, ChainSpec, EthSpec, Hash256, SignedBeaconBlock, Slot};
+
+/// Called with the state and the block, before and after the block is applied.
+pub type BlockHook<'a, E, Error> =
+    Box<dyn FnMut(&mut BeaconState<E>, &SignedBeaconBlock<E>) -> Result<(), Error> + 'a>;
+/// Called with the state before per-slot processing is applied.
+pub type PreSlotHook<'a, E, Error> = Box<dyn FnMut(&mut BeaconState<E>) -> Result<(), Error> + 'a>;
+/// Called with the state after per-slot processing is applied, along with the summary of the
+/// per-epoch processing if it was performed.
+pub type PostSlotHook<'a, E, Error> =
+    Box<dyn FnMut(&mut BeaconState<E>, Option<EpochProcessingSummary>) -> Result<(), Error> + 'a>;
+/// The iterator of state roots used when none is supplied.
+pub type StateRootIterDefault<Error> = std::iter::Empty<Result<(Hash256, Slot), Error>>;
+
+#[derive(Debug, PartialEq)]
+pub enum BlockReplayError {
+    SlotProcessing(SlotProcessingError),
+    BlockProcessing(BlockProcessingError),
+    /// An error from a hook which operates on the state.
+    BeaconStateError(BeaconStateError),
+}
+
+impl From<SlotProcessingError> for BlockReplayError {
+    fn from(e: SlotProcessingError) -> Self {
+        BlockReplayError::SlotProcessing(e)
+    }
+}
+
+impl From<BeaconStateError> for BlockReplayError {
+    fn from(e: BeaconStateError) -> Self {
+        BlockReplayError::BeaconStateError(e)
+    }
+}
+
+impl From<BlockProcessingError> for BlockReplayError {
+    fn from(e: BlockProcessingError) -> Self {
+        BlockReplayError::BlockProcessing(e)
+    }
+}
+
+/// Applies blocks (and skipped slots) to a state, calling the configured hooks along the way.
+///
+/// Computing the root of a state is the most expensive part of per-slot processing, so the
+/// replayer avoids it where the root is already known: either as the `state_root` of the
+/// previous block, or from the iterator supplied to `Self::state_root_iter`.
+///
+/// `Error` is the error type of the hooks and the state root iterator, which must be able to
+/// represent the errors of the replay itself.
+pub struct BlockReplayer<
+    'a,
+    E: EthSpec,
+    Error = BlockReplayError,
+    StateRootIter = StateRootIterDefault<Error>,
+> where
+    StateRootIter: Iterator<Item = Result<(Hash256, Slot), Error>>,
+{
+    state: BeaconState<E>,
+    spec: &'a ChainSpec,
+    block_sig_strategy: BlockSignatureStrategy,
+    pre_block_hook: Option<BlockHook<'a, E, Error>>,
+    post_block_hook: Option<BlockHook<'a, E, Error>>,
+    pre_slot_hook: Option<PreSlotHook<'a, E, Error>>,
+    post_slot_hook: Option<PostSlotHook<'a, E, Error>>,
+    state_root_iter: Option<Peekable<StateRootIter>>,
+    state_root_miss: bool,
+}
+
+impl<'a, E, Error> BlockReplayer<'a, E, Error, StateRootIterDefault<Error>>
+where
+    E: EthSpec,
+    Error: From<BlockReplayError>,
+{
+    /// Create a replayer which will apply blocks on top of `state`.
+    ///
+    /// Signatures are verified in bulk by default.
+    pub fn new(state: BeaconState<E>, spec: &'a ChainSpec) -> Self {
+        Self {
+            state,
+            spec,
+            block_sig_strategy: BlockSignatureStrategy::VerifyBulk,
+            pre_block_hook: None,
+            post_block_hook: None,
+            pre_slot_hook: None,
+            post_slot_hook: None,
+            state_root_iter: None,
+            state_root_miss: false,
+        }
+    }
+}
+
+impl<'a, E, Error, StateRootIter> BlockReplayer<'a, E, Error, StateRootIter>
+where
+    E: EthSpec,
+    Error: From<BlockReplayError>,
+    StateRootIter: Iterator<Item = Result<(Hash256, Slot), Error>>,
+{
+    /// Set the strategy used to verify the signatures of the blocks.
+    pub fn block_signature_strategy(mut self, block_sig_strategy: BlockSignatureStrategy) -> Self {
+        self.block_sig_strategy = block_sig_strategy;
+        self
+    }
+
+    /// Disable signature verification, e.g., for blocks which have been verified before.
+    pub fn no_signature_verification(self) -> Self {
+        self.block_signature_strategy(BlockSignatureStrategy::NoVerification)
+    }
+
+    /// Supply the known roots of the states which will be passed through, as `(root, slot)`
+    /// pairs in slot-ascending order. Slots which are absent will have their roots computed.
+    pub fn state_root_iter<I>(self, iter: I) -> BlockReplayer<'a, E, Error, I::IntoIter>
+    where
+        I: IntoIterator<Item = Result<(Hash256, Slot), Error>>,
+    {
+        BlockReplayer {
+            state: self.state,
+            spec: self.spec,
+            block_sig_strategy: self.block_sig_strategy,
+            pre_block_hook: self.pre_block_hook,
+            post_block_hook: self.post_block_hook,
+            pre_slot_hook: self.pre_slot_hook,
+            post_slot_hook: self.post_slot_hook,
+            state_root_iter: Some(iter.into_iter().peekable()),
+            state_root_miss: self.state_root_miss,
+        }
+    }
+
+    /// Call `hook` before each block is applied.
+    pub fn pre_block_hook(mut self, hook: BlockHook<'a, E, Error>) -> Self {
+        self.pre_block_hook = Some(hook);
+        self
+    }
+
+    /// Call `hook` after each block is applied.
+    pub fn post_block_hook(mut self, hook: BlockHook<'a, E, Error>) -> Self {
+        self.post_block_hook = Some(hook);
+        self
+    }
+
+    /// Call `hook` before each slot is processed.
+    pub fn pre_slot_hook(mut self, hook: PreSlotHook<'a, E, Error>) -> Self {
+        self.pre_slot_hook = Some(hook);
+        self
+    }
+
+    /// Call `hook` after each slot is processed.
+    pub fn post_slot_hook(mut self, hook: PostSlotHook<'a, E, Error>) -> Self {
+        self.post_slot_hook = Some(hook);
+        self
+    }
+
+    /// Apply `blocks` in order, processing any skipped slots in between, then process slots
+    /// until `target_slot` (if any) is reached.
+    ///
+    /// Blocks which are already part of the state (i.e., those prior to the slot of the state, or
+    /// at its slot if the state is the post-state of a block) are not applied, however their
+    /// `state_root` may still be used to avoid computing the root of the state. A block at the
+    /// slot of a state which was advanced through skipped slots is applied.
+    pub fn apply_blocks<I>(mut self, blocks: I, target_slot: Option<Slot>) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = SignedBeaconBlock<E>>,
+    {
+        let mut last_block_root = None;
+
+        for block in blocks {
+            if block.slot() > self.state.slot
+                || (block.slot() == self.state.slot
+                    && self.state.latest_block_header.slot < block.slot())
+            {
+                while self.state.slot < block.slot() {
+                    self.apply_slot(last_block_root)?;
+                }
+
+                if let Some(hook) = self.pre_block_hook.as_mut() {
+                    hook(&mut self.state, &block)?;
+                }
+
+                per_block_processing(
+                    &mut self.state,
+                    &block,
+                    None,
+                    self.block_sig_strategy,
+                    self.spec,
+                )
+                .map_err(BlockReplayError::from)?;
+
+                if let Some(hook) = self.post_block_hook.as_mut() {
+                    hook(&mut self.state, &block)?;
+                }
+            }
+
+            last_block_root = Some((block.state_root(), block.slot()));
+        }
+
+        if let Some(target_slot) = target_slot {
+            while self.state.slot < target_slot {
+                self.apply_slot(last_block_root)?;
+            }
+        }
+
+        Ok(self)
+    }
+
+    /// Returns `true` if the root of any state had to be computed, because it was not known.
+    pub fn state_root_miss(&self) -> bool {
+        self.state_root_miss
+    }
+
+    /// Consume the replayer, returning the resulting state.
+    pub fn into_state(self) -> BeaconState<E> {
+        self.state
+    }
+
+    /// Process the slot of the state, advancing it to the next slot.
+    fn apply_slot(&mut self, last_block_root: Option<(Hash256, Slot)>) -> Result<(), Error> {
+        let state_root = self.get_state_root(last_block_root)?;
+        if state_root.is_none() {
+            self.state_root_miss = true;
+        }
+
+        if let Some(hook) = self.pre_slot_hook.as_mut() {
+            hook(&mut self.state)?;
+        }
+
+        let summary = per_slot_processing(&mut self.state, state_root, self.spec)
+            .map_err(BlockReplayError::from)?;
+
+        if let Some(hook) = self.post_slot_hook.as_mut() {
+            hook(&mut self.state, summary)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the root of the state at its current slot, if known.
+    fn get_state_root(
+        &mut self,
+        last_block_root: Option<(Hash256, Slot)>,
+    ) -> Result<Option<Hash256>, Error> {
+        let slot = self.state.slot;
+
+        // The state at the slot of the last block is the post-state of that block.
+        if let Some((root, block_slot)) = last_block_root {
+            if block_slot == slot {
+                return Ok(Some(root));
+            }
+        }
+
+        if let Some(iter) = self.state_root_iter.as_mut() {
+            // Discard the roots of earlier slots, stopping at the first root of a later slot.
+            while let Some(result) = iter.peek() {
+                if let Ok((_, root_slot)) = result {
+                    if *root_slot > slot {
+                        break;
+                    }
+                }
+
+                match iter.next() {
+                    Some(Ok((root, root_slot))) if root_slot == slot => return Ok(Some(root)),
+                    Some(Err(e)) => return Err(e),
+                    _ => (),
+                }
+            }
+        }
+
+        Ok(None)
+    }
+}
@@ -3,6 +3,7 @@
 #[macro_use]
 mod macros;
 
+pub mod block_replayer;
 pub mod common;
 pub mod genesis;
 pub mod per_block_processing;
@@ -11,6 +12,7 @@ pub mod per_slot_processing;
 pub mod test_utils;
 pub mod verify_operation;
 
+pub use block_replayer::{BlockReplayError, BlockReplayer};
 pub use genesis::{
     eth2_genesis_time, initialize_beacon_state_from_eth1, is_valid_genesis_state,
     process_activations,
@@ -19,6 +21,8 @@ pub use per_block_processing::{
     block_signature_verifier, errors::BlockProcessingError, per_block_processing, signature_sets,
     BlockSignatureStrategy, BlockSignatureVerifier, VerifySignatures,
 };
-pub use per_epoch_processing::{errors::EpochProcessingError, per_epoch_processing};
+pub use per_epoch_processing::{
+    errors::EpochProcessingError, per_epoch_processing, per_epoch_processing_with_reward_breakdown,
+};
 pub use per_slot_processing::{per_slot_processing, Error as SlotProcessingError};
 pub use verify_operation::{SigVerifiedOp, VerifyOperation};
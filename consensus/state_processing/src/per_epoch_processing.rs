@@ -10,7 +10,10 @@ pub mod registry_updates;
 pub mod tests;
 pub mod validator_statuses;
 
-pub use apply_rewards::process_rewards_and_penalties;
+pub use apply_rewards::{
+    process_rewards_and_penalties, process_rewards_and_penalties_with_breakdown, Delta,
+    RewardBreakdown,
+};
 pub use process_slashings::process_slashings;
 pub use registry_updates::process_registry_updates;
 pub use validator_statuses::{TotalBalances, ValidatorStatus, ValidatorStatuses};
@@ -18,6 +21,10 @@ pub use validator_statuses::{TotalBalances, ValidatorStatus, ValidatorStatuses};
 /// Provides a summary of validator participation during the epoch.
 pub struct EpochProcessingSummary {
     pub total_balances: TotalBalances,
+    /// The rewards and penalties applied to each validator, indexed by validator index.
+    ///
+    /// Only present if requested with `per_epoch_processing_with_reward_breakdown`.
+    pub reward_breakdown: Option<Vec<RewardBreakdown>>,
 }
 
 /// Performs per-epoch processing on some BeaconState.
@@ -29,6 +36,23 @@ pub struct EpochProcessingSummary {
 pub fn per_epoch_processing<T: EthSpec>(
     state: &mut BeaconState<T>,
     spec: &ChainSpec,
+) -> Result<EpochProcessingSummary, Error> {
+    process_epoch(state, false, spec)
+}
+
+/// Performs per-epoch processing as per `per_epoch_processing`, additionally returning the
+/// breakdown of the rewards and penalties applied to each validator.
+pub fn per_epoch_processing_with_reward_breakdown<T: EthSpec>(
+    state: &mut BeaconState<T>,
+    spec: &ChainSpec,
+) -> Result<EpochProcessingSummary, Error> {
+    process_epoch(state, true, spec)
+}
+
+fn process_epoch<T: EthSpec>(
+    state: &mut BeaconState<T>,
+    with_reward_breakdown: bool,
+    spec: &ChainSpec,
 ) -> Result<EpochProcessingSummary, Error> {
     // Ensure the committee caches are built.
     state.build_committee_cache(RelativeEpoch::Previous, spec)?;
@@ -45,7 +69,16 @@ pub fn per_epoch_processing<T: EthSpec>(
     process_justification_and_finalization(state, &validator_statuses.total_balances)?;
 
     // Rewards and Penalties.
-    process_rewards_and_penalties(state, &mut validator_statuses, spec)?;
+    let reward_breakdown = if with_reward_breakdown {
+        Some(process_rewards_and_penalties_with_breakdown(
+            state,
+            &mut validator_statuses,
+            spec,
+        )?)
+    } else {
+        process_rewards_and_penalties(state, &mut validator_statuses, spec)?;
+        None
+    };
 
     // Registry Updates.
     process_registry_updates(state, spec)?;
@@ -65,6 +98,7 @@ pub fn per_epoch_processing<T: EthSpec>(
 
     Ok(EpochProcessingSummary {
         total_balances: validator_statuses.total_balances,
+        reward_breakdown,
     })
 }
 
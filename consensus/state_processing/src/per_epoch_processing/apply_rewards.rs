@@ -6,10 +6,10 @@ use safe_arith::SafeArith;
 use types::*;
 
 /// Use to track the changes to a validators balance.
-#[derive(Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq)]
 pub struct Delta {
-    rewards: u64,
-    penalties: u64,
+    pub rewards: u64,
+    pub penalties: u64,
 }
 
 impl Delta {
@@ -32,6 +32,42 @@ impl Delta {
     }
 }
 
+/// The rewards and penalties applied to a single validator by `process_rewards_and_penalties`,
+/// split by the component which computed them.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct RewardBreakdown {
+    /// For attesting to the correct source in the previous epoch.
+    pub source: Delta,
+    /// For attesting to the correct target in the previous epoch.
+    pub target: Delta,
+    /// For attesting to the correct head in the previous epoch.
+    pub head: Delta,
+    /// For the inclusion delay of the validator's attestation in the previous epoch.
+    pub inclusion_delay: Delta,
+    /// For including the attestations of other validators in blocks proposed by the validator.
+    pub proposer: Delta,
+    /// For failing to participate while the chain is not finalizing.
+    pub inactivity: Delta,
+}
+
+impl RewardBreakdown {
+    /// Returns the sum of all the components, which is the change applied to the balance.
+    pub fn total(&self) -> Result<Delta, Error> {
+        let mut total = Delta::default();
+        for delta in &[
+            &self.source,
+            &self.target,
+            &self.head,
+            &self.inclusion_delay,
+            &self.proposer,
+            &self.inactivity,
+        ] {
+            total.combine((*delta).clone())?;
+        }
+        Ok(total)
+    }
+}
+
 /// Apply attester and proposer rewards.
 ///
 /// Spec v0.12.1
@@ -39,6 +75,28 @@ pub fn process_rewards_and_penalties<T: EthSpec>(
     state: &mut BeaconState<T>,
     validator_statuses: &mut ValidatorStatuses,
     spec: &ChainSpec,
+) -> Result<(), Error> {
+    apply_rewards_and_penalties(state, validator_statuses, None, spec)
+}
+
+/// Apply attester and proposer rewards, as per `process_rewards_and_penalties`, returning the
+/// breakdown of the rewards and penalties applied to each validator.
+pub fn process_rewards_and_penalties_with_breakdown<T: EthSpec>(
+    state: &mut BeaconState<T>,
+    validator_statuses: &mut ValidatorStatuses,
+    spec: &ChainSpec,
+) -> Result<Vec<RewardBreakdown>, Error> {
+    let mut breakdowns = vec![RewardBreakdown::default(); state.validators.len()];
+    apply_rewards_and_penalties(state, validator_statuses, Some(&mut breakdowns[..]), spec)?;
+    Ok(breakdowns)
+}
+
+/// Apply attester and proposer rewards, recording them in `breakdowns` if it is provided.
+fn apply_rewards_and_penalties<T: EthSpec>(
+    state: &mut BeaconState<T>,
+    validator_statuses: &mut ValidatorStatuses,
+    breakdowns: Option<&mut [RewardBreakdown]>,
+    spec: &ChainSpec,
 ) -> Result<(), Error> {
     if state.current_epoch() == T::genesis_epoch() {
         return Ok(());
@@ -51,7 +109,7 @@ pub fn process_rewards_and_penalties<T: EthSpec>(
         return Err(Error::ValidatorStatusesInconsistent);
     }
 
-    let deltas = get_attestation_deltas(state, &validator_statuses, spec)?;
+    let deltas = get_attestation_deltas(state, &validator_statuses, breakdowns, spec)?;
 
     // Apply the deltas, erroring on overflow above but not on overflow below (saturating at 0
     // instead).
@@ -65,12 +123,21 @@ pub fn process_rewards_and_penalties<T: EthSpec>(
 
 /// Apply rewards for participation in attestations during the previous epoch.
 ///
+/// If `breakdowns` is provided, the components of each delta are also recorded in it.
+///
 /// Spec v0.12.1
 fn get_attestation_deltas<T: EthSpec>(
     state: &BeaconState<T>,
     validator_statuses: &ValidatorStatuses,
+    mut breakdowns: Option<&mut [RewardBreakdown]>,
     spec: &ChainSpec,
 ) -> Result<Vec<Delta>, Error> {
+    if breakdowns.as_ref().map_or(false, |breakdowns| {
+        breakdowns.len() != state.validators.len()
+    }) {
+        return Err(Error::ValidatorStatusesInconsistent);
+    }
+
     let finality_delay = (state.previous_epoch() - state.finalized_checkpoint.epoch).as_u64();
 
     let mut deltas = vec![Delta::default(); state.validators.len()];
@@ -99,6 +166,15 @@ fn get_attestation_deltas<T: EthSpec>(
         let inactivity_penalty_delta =
             get_inactivity_penalty_delta(validator, base_reward, finality_delay, spec)?;
 
+        if let Some(breakdowns) = breakdowns.as_mut() {
+            let breakdown = &mut breakdowns[index];
+            breakdown.source = source_delta.clone();
+            breakdown.target = target_delta.clone();
+            breakdown.head = head_delta.clone();
+            breakdown.inclusion_delay = inclusion_delay_delta.clone();
+            breakdown.inactivity = inactivity_penalty_delta.clone();
+        }
+
         deltas[index].combine(source_delta)?;
         deltas[index].combine(target_delta)?;
         deltas[index].combine(head_delta)?;
@@ -110,6 +186,12 @@ fn get_attestation_deltas<T: EthSpec>(
                 return Err(Error::ValidatorStatusesInconsistent);
             }
 
+            if let Some(breakdowns) = breakdowns.as_mut() {
+                breakdowns[proposer_index]
+                    .proposer
+                    .combine(proposer_delta.clone())?;
+            }
+
             deltas[proposer_index].combine(proposer_delta)?;
         }
     }
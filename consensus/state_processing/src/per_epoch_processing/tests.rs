@@ -1,5 +1,7 @@
 #![cfg(test)]
-use crate::per_epoch_processing::per_epoch_processing;
+use crate::per_epoch_processing::{
+    per_epoch_processing, per_epoch_processing_with_reward_breakdown,
+};
 use env_logger::{Builder, Env};
 use types::test_utils::TestingBeaconStateBuilder;
 use types::*;
@@ -21,3 +23,36 @@ fn runs_without_error() {
 
     per_epoch_processing(&mut state, &spec).unwrap();
 }
+
+#[test]
+fn reward_breakdown_matches_balances() {
+    let spec = MinimalEthSpec::default_spec();
+
+    let mut builder: TestingBeaconStateBuilder<MinimalEthSpec> =
+        TestingBeaconStateBuilder::from_deterministic_keypairs(8, &spec);
+
+    let target_slot =
+        (MinimalEthSpec::genesis_epoch() + 4).end_slot(MinimalEthSpec::slots_per_epoch());
+    builder.teleport_to_slot(target_slot);
+
+    let (mut state, _keypairs) = builder.build();
+    let balances_before = state.balances.clone();
+
+    let summary = per_epoch_processing_with_reward_breakdown(&mut state, &spec).unwrap();
+    let breakdowns = summary.reward_breakdown.expect("breakdown was requested");
+
+    assert_eq!(breakdowns.len(), state.validators.len());
+
+    for (i, breakdown) in breakdowns.iter().enumerate() {
+        // No attestations were included, so all validators miss every duty.
+        assert!(breakdown.source.penalties > 0);
+        assert_eq!(breakdown.source.rewards, 0);
+        assert_eq!(breakdown.inclusion_delay.rewards, 0);
+
+        let total = breakdown.total().unwrap();
+        assert_eq!(
+            state.balances[i],
+            (balances_before[i] + total.rewards).saturating_sub(total.penalties)
+        );
+    }
+}
@@ -1,7 +1,8 @@
 #![cfg(not(feature = "fake_crypto"))]
 
 use state_processing::{
-    per_block_processing, test_utils::BlockBuilder, BlockProcessingError, BlockSignatureStrategy,
+    per_block_processing, test_utils::BlockBuilder, BlockProcessingError, BlockReplayError,
+    BlockReplayer, BlockSignatureStrategy,
 };
 use types::{
     AggregateSignature, BeaconState, ChainSpec, EthSpec, Keypair, MinimalEthSpec, Signature,
@@ -226,3 +227,92 @@ mod signatures_minimal {
         );
     }
 }
+
+#[test]
+fn block_replayer_uses_known_state_roots() {
+    let spec = MinimalEthSpec::default_spec();
+    let (_block, state) = get_block::<MinimalEthSpec, _>(|_| {});
+    let start_slot = state.slot;
+    let target_slot = start_slot + 10;
+
+    let mut slots_processed = 0;
+    let mut epochs_processed = 0;
+
+    let replayer = BlockReplayer::<_, BlockReplayError>::new(state.clone(), &spec)
+        .pre_slot_hook(Box::new(|_| {
+            slots_processed += 1;
+            Ok(())
+        }))
+        .post_slot_hook(Box::new(|_, summary| {
+            if summary.is_some() {
+                epochs_processed += 1;
+            }
+            Ok(())
+        }))
+        .apply_blocks(vec![], Some(target_slot))
+        .expect("should replay slots");
+    assert!(replayer.state_root_miss());
+    let expected_state = replayer.into_state();
+
+    assert_eq!(expected_state.slot, target_slot);
+    assert_eq!(slots_processed, 10);
+    assert_eq!(epochs_processed, 2);
+
+    let state_roots = (start_slot.as_u64()..target_slot.as_u64())
+        .map(|slot| {
+            let slot = Slot::new(slot);
+            Ok::<_, BlockReplayError>((*expected_state.get_state_root(slot).unwrap(), slot))
+        })
+        .collect::<Vec<_>>();
+
+    let replayer = BlockReplayer::new(state, &spec)
+        .state_root_iter(state_roots)
+        .apply_blocks(vec![], Some(target_slot))
+        .expect("should replay slots");
+    assert!(!replayer.state_root_miss());
+
+    assert_eq!(
+        replayer.into_state().canonical_root(),
+        expected_state.canonical_root()
+    );
+}
+
+#[test]
+fn block_replayer_applies_block_at_state_slot() {
+    let spec = TestEthSpec::default_spec();
+    let (block, state) = get_block::<TestEthSpec, _>(|_| {});
+
+    // The state has been advanced to the slot of the block, but the block is yet to be applied.
+    assert_eq!(state.slot, block.slot());
+
+    let mut expected_state = state.clone();
+    per_block_processing(
+        &mut expected_state,
+        &block,
+        None,
+        BlockSignatureStrategy::VerifyIndividual,
+        &spec,
+    )
+    .expect("block should be valid");
+
+    let post_state = BlockReplayer::<_, BlockReplayError>::new(state, &spec)
+        .apply_blocks(vec![block.clone()], None)
+        .expect("should apply block")
+        .into_state();
+    assert_eq!(
+        post_state.canonical_root(),
+        expected_state.canonical_root(),
+        "should apply the block at the slot of the state"
+    );
+
+    // The post-state of the block already includes it, so it must not be applied again.
+    let replayed_state = BlockReplayer::<_, BlockReplayError>::new(post_state, &spec)
+        .apply_blocks(vec![block], None)
+        .expect("should skip block")
+        .into_state();
+    assert_eq!(
+        replayed_state.canonical_root(),
+        expected_state.canonical_root(),
+        "should not re-apply the block"
+    );
+}
@@ -1,6 +1,8 @@
 use criterion::Criterion;
 use criterion::{black_box, criterion_group, criterion_main, Benchmark};
-use swap_or_not_shuffle::{compute_shuffled_index, shuffle_list as fast_shuffle};
+use swap_or_not_shuffle::{
+    compute_shuffled_index, shuffle_list as fast_shuffle, shuffle_list_parallel as parallel_shuffle,
+};
 
 const SHUFFLE_ROUND_COUNT: u8 = 90;
 
@@ -86,6 +88,40 @@ fn shuffles(c: &mut Criterion) {
         })
         .sample_size(10),
     );
+
+    c.bench(
+        "_parallel_ whole list shuffle",
+        Benchmark::new("16384 elements", move |b| {
+            let seed = vec![42; 32];
+            let list: Vec<usize> = (0..16384).collect();
+            b.iter(|| {
+                black_box(parallel_shuffle(
+                    list.clone(),
+                    SHUFFLE_ROUND_COUNT,
+                    &seed,
+                    true,
+                ))
+            })
+        })
+        .sample_size(10),
+    );
+
+    c.bench(
+        "_parallel_ whole list shuffle",
+        Benchmark::new("4m elements", move |b| {
+            let seed = vec![42; 32];
+            let list: Vec<usize> = (0..4_000_000).collect();
+            b.iter(|| {
+                black_box(parallel_shuffle(
+                    list.clone(),
+                    SHUFFLE_ROUND_COUNT,
+                    &seed,
+                    true,
+                ))
+            })
+        })
+        .sample_size(10),
+    );
 }
 
 criterion_group!(benches, shuffles,);
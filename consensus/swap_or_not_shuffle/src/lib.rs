@@ -4,12 +4,14 @@
 //! [compute_shuffled_index](https://github.com/ethereum/eth2.0-specs/blob/v0.12.1/specs/phase0/beacon-chain.md#compute_shuffled_index)
 //! for specifications.
 //!
-//! There are two functions exported by this crate:
+//! There are three functions exported by this crate:
 //!
 //! - `compute_shuffled_index`: given a single index, computes the index resulting from a shuffle.
 //! Runs in less time than it takes to run `shuffle_list`.
 //! - `shuffle_list`: shuffles an entire list in-place. Runs in less time than it takes to run
 //! `compute_shuffled_index` on each index.
+//! - `shuffle_list_parallel`: produces the same result as `shuffle_list`, but uses `rayon` to
+//! shuffle large lists across multiple threads.
 //!
 //! In general, use `compute_shuffled_index` to calculate the shuffling of a small subset of a much
 //! larger list (~250x larger is a good guide, but solid figures yet to be calculated).
@@ -18,6 +20,6 @@ mod compute_shuffled_index;
 mod shuffle_list;
 
 pub use compute_shuffled_index::compute_shuffled_index;
-pub use shuffle_list::shuffle_list;
+pub use shuffle_list::{shuffle_list, shuffle_list_parallel, PARALLEL_SHUFFLE_MIN_LIST_SIZE};
 
 type Hash256 = ethereum_types::H256;
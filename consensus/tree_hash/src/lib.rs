@@ -2,10 +2,12 @@ pub mod impls;
 mod merkle_hasher;
 mod merkleize_padded;
 mod merkleize_standard;
+pub mod proof;
 
 pub use merkle_hasher::{Error, MerkleHasher};
 pub use merkleize_padded::merkleize_padded;
 pub use merkleize_standard::merkleize_standard;
+pub use proof::{MultiProof, PathElement, ProofError};
 
 use eth2_hashing::{Context, SHA256};
 use eth2_hashing::{ZERO_HASHES, ZERO_HASHES_MAX_INDEX};
@@ -13,6 +15,8 @@ use eth2_hashing::{ZERO_HASHES, ZERO_HASHES_MAX_INDEX};
 pub const BYTES_PER_CHUNK: usize = 32;
 pub const HASHSIZE: usize = 32;
 pub const MERKLE_HASH_CHUNK: usize = 2 * BYTES_PER_CHUNK;
+/// The highest possible union selector value, matching `ssz::MAX_UNION_SELECTOR`.
+pub const MAX_UNION_SELECTOR: u8 = 127;
 
 pub type Hash256 = ethereum_types::H256;
 
@@ -68,6 +72,22 @@ pub fn mix_in_length(root: &Hash256, length: usize) -> Hash256 {
     Hash256::from_slice(&eth2_hashing::hash32_concat(root.as_bytes(), &length_bytes)[..])
 }
 
+/// Returns `Some(root)` created by hashing `root` and `selector`, if `selector <=
+/// MAX_UNION_SELECTOR`. Otherwise, returns `None`.
+///
+/// Used in `TreeHash` for the "union" type.
+pub fn mix_in_selector(root: &Hash256, selector: u8) -> Option<Hash256> {
+    if selector > MAX_UNION_SELECTOR {
+        return None;
+    }
+
+    let mut chunk = [0; BYTES_PER_CHUNK];
+    chunk[0] = selector;
+
+    let root = eth2_hashing::hash32_concat(root.as_bytes(), &chunk);
+    Some(Hash256::from_slice(&root))
+}
+
 /// Returns a cached padding node for a given height.
 fn get_zero_hash(height: usize) -> &'static [u8] {
     if height <= ZERO_HASHES_MAX_INDEX {
@@ -93,6 +113,27 @@ pub trait TreeHash {
     fn tree_hash_packing_factor() -> usize;
 
     fn tree_hash_root(&self) -> Hash256;
+
+    /// Returns the generalized index of the node at `path`, relative to the root of `Self`.
+    ///
+    /// By default `Self` has no descendants, so only the empty path is valid.
+    fn tree_hash_generalized_index(path: &[PathElement]) -> Result<usize, ProofError> {
+        match path.first() {
+            None => Ok(1),
+            Some(element) => Err(ProofError::unexpected(element)),
+        }
+    }
+
+    /// Returns the node at `path` and its Merkle branch, in bottom-up order. The branch proves
+    /// the node against `self.tree_hash_root()`, at `Self::tree_hash_generalized_index(path)`.
+    ///
+    /// By default `Self` has no descendants, so only the empty path is valid.
+    fn tree_hash_proof(&self, path: &[PathElement]) -> Result<(Hash256, Vec<Hash256>), ProofError> {
+        match path.first() {
+            None => Ok((self.tree_hash_root(), vec![])),
+            Some(element) => Err(ProofError::unexpected(element)),
+        }
+    }
 }
 
 #[macro_export]
@@ -159,4 +200,21 @@ mod test {
             &hash[..]
         );
     }
+
+    #[test]
+    fn mix_selector() {
+        let hash = {
+            let mut preimage = vec![42; BYTES_PER_CHUNK];
+            preimage.append(&mut vec![3]);
+            preimage.append(&mut vec![0; BYTES_PER_CHUNK - 1]);
+            eth2_hashing::hash(&preimage)
+        };
+
+        let root = Hash256::from_slice(&[42; BYTES_PER_CHUNK]);
+        assert_eq!(
+            mix_in_selector(&root, 3).map(|hash| hash.as_bytes().to_vec()),
+            Some(hash)
+        );
+        assert_eq!(mix_in_selector(&root, MAX_UNION_SELECTOR + 1), None);
+    }
 }
@@ -0,0 +1,213 @@
+//! Merkle proofs for values which implement `TreeHash`.
+//!
+//! Nodes are identified by a "generalized index", as defined in the SSZ Merkle proofs
+//! specification: the root has index `1` and the children of the node at index `i` have indices
+//! `2 * i` and `2 * i + 1`. A node may be found by following a path of `PathElement`s from the
+//! root of a value, through its containers, vectors and lists.
+
+use super::{get_zero_hash, Hash256, TreeHash, BYTES_PER_CHUNK};
+use eth2_hashing::hash32_concat;
+use merkle_proof::generalized_index::concat_generalized_indices;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The generalized index of the length of a list, relative to the root of the list.
+pub const LIST_LENGTH_GENERALIZED_INDEX: usize = 3;
+
+/// A step along the path from the root of a value to one of its descendants.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PathElement<'a> {
+    /// The field of a container with the given name.
+    Field(&'a str),
+    /// The item of a vector or list at the given position.
+    ///
+    /// Items with a basic type (e.g., `u64`) are packed, so this refers to the chunk which contains
+    /// the item.
+    Index(usize),
+    /// The length of a list.
+    Length,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProofError {
+    /// A container has no field with the given name.
+    UnknownField(String),
+    /// An index is beyond the length (or capacity) of a vector or list.
+    IndexOutOfBounds { index: usize, len: usize },
+    /// The path element cannot be applied to the type at its position in the path.
+    UnexpectedPathElement(String),
+}
+
+impl ProofError {
+    pub fn unexpected(element: &PathElement) -> Self {
+        ProofError::UnexpectedPathElement(format!("{:?}", element))
+    }
+}
+
+/// A proof of the values of several nodes in the same tree.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultiProof {
+    /// The generalized indices of the proven nodes.
+    pub indices: Vec<usize>,
+    /// The values of the proven nodes, in the same order as `indices`.
+    pub leaves: Vec<Hash256>,
+    /// The additional nodes required to compute the root, in decreasing order of generalized
+    /// index.
+    pub proof: Vec<Hash256>,
+}
+
+/// Returns the generalized index of the node at `path`, relative to the root of `T`.
+pub fn generalized_index<T: TreeHash>(path: &[PathElement]) -> Result<usize, ProofError> {
+    T::tree_hash_generalized_index(path)
+}
+
+/// Produces a proof of the nodes at each of `paths` in `value`.
+///
+/// Nodes that can be computed from the proven nodes are omitted, so the proof may be
+/// considerably smaller than the combined Merkle branches of each node.
+pub fn multiproof<T: TreeHash>(
+    value: &T,
+    paths: &[&[PathElement]],
+) -> Result<MultiProof, ProofError> {
+    let mut indices = Vec::with_capacity(paths.len());
+    let mut leaves = Vec::with_capacity(paths.len());
+    let mut nodes = BTreeMap::new();
+    let mut path_indices = BTreeSet::new();
+
+    for path in paths {
+        let index = T::tree_hash_generalized_index(path)?;
+        let (leaf, branch) = value.tree_hash_proof(path)?;
+
+        let mut current = index;
+        for sibling in branch {
+            nodes.insert(current ^ 1, sibling);
+            path_indices.insert(current);
+            current /= 2;
+        }
+
+        indices.push(index);
+        leaves.push(leaf);
+    }
+
+    let proof = nodes
+        .into_iter()
+        .rev()
+        .filter(|(index, _)| !path_indices.contains(index))
+        .map(|(_, node)| node)
+        .collect();
+
+    Ok(MultiProof {
+        indices,
+        leaves,
+        proof,
+    })
+}
+
+/// Returns the generalized index of `inner` within the chunk at `chunk_index` of a vector (or
+/// container) with `chunk_count` chunks.
+pub fn vector_generalized_index(chunk_count: usize, chunk_index: usize, inner: usize) -> usize {
+    concat_generalized_indices(&[chunk_count.next_power_of_two() + chunk_index, inner])
+}
+
+/// Returns the generalized index of `inner` within the chunk at `chunk_index` of a list with a
+/// capacity of `chunk_limit` chunks.
+pub fn list_generalized_index(chunk_limit: usize, chunk_index: usize, inner: usize) -> usize {
+    concat_generalized_indices(&[2 * chunk_limit.next_power_of_two() + chunk_index, inner])
+}
+
+/// Extends the `inner` proof of a node within the chunk at `chunk_index` to a proof against the
+/// root of a vector (or container) with the given `chunks`, padded to `chunk_count`.
+pub fn vector_proof(
+    chunks: &[Hash256],
+    chunk_count: usize,
+    chunk_index: usize,
+    inner: (Hash256, Vec<Hash256>),
+) -> (Hash256, Vec<Hash256>) {
+    let (leaf, mut branch) = inner;
+    branch.extend(merkle_branch(chunks, tree_depth(chunk_count), chunk_index));
+    (leaf, branch)
+}
+
+/// Extends the `inner` proof of a node within the chunk at `chunk_index` to a proof against the
+/// root of a list with the given `chunks` and `length`, and a capacity of `chunk_limit` chunks.
+pub fn list_proof(
+    chunks: &[Hash256],
+    chunk_limit: usize,
+    chunk_index: usize,
+    length: usize,
+    inner: (Hash256, Vec<Hash256>),
+) -> (Hash256, Vec<Hash256>) {
+    let (leaf, mut branch) = vector_proof(chunks, chunk_limit, chunk_index, inner);
+    branch.push(length_chunk(length));
+    (leaf, branch)
+}
+
+/// Returns a proof of the length of a list with the given `chunks`, and a capacity of
+/// `chunk_limit` chunks.
+pub fn list_length_proof(
+    chunks: &[Hash256],
+    chunk_limit: usize,
+    length: usize,
+) -> (Hash256, Vec<Hash256>) {
+    let data_root = merkle_root_of_chunks(chunks, tree_depth(chunk_limit));
+    (length_chunk(length), vec![data_root])
+}
+
+/// Returns the Merkle branch of the leaf at `index` in a tree of the given `depth`, in bottom-up
+/// order. Any leaves beyond `leaves.len()` are zero.
+pub fn merkle_branch(leaves: &[Hash256], depth: usize, index: usize) -> Vec<Hash256> {
+    let mut branch = Vec::with_capacity(depth);
+    let mut layer = leaves.to_vec();
+    let mut index = index;
+
+    for height in 0..depth {
+        let sibling = layer
+            .get(index ^ 1)
+            .copied()
+            .unwrap_or_else(|| Hash256::from_slice(get_zero_hash(height)));
+        branch.push(sibling);
+
+        layer = next_layer(&layer, height);
+        index /= 2;
+    }
+
+    branch
+}
+
+/// Returns the root of a tree of the given `depth` with the given `leaves`, padded with zeros.
+fn merkle_root_of_chunks(leaves: &[Hash256], depth: usize) -> Hash256 {
+    let mut layer = leaves.to_vec();
+
+    for height in 0..depth {
+        layer = next_layer(&layer, height);
+    }
+
+    layer
+        .first()
+        .copied()
+        .unwrap_or_else(|| Hash256::from_slice(get_zero_hash(depth)))
+}
+
+/// Hashes each pair of nodes in `layer`, which is at `height` above the leaves.
+fn next_layer(layer: &[Hash256], height: usize) -> Vec<Hash256> {
+    layer
+        .chunks(2)
+        .map(|pair| {
+            let right = pair
+                .get(1)
+                .map_or_else(|| get_zero_hash(height), |h| h.as_bytes());
+            Hash256::from_slice(&hash32_concat(pair[0].as_bytes(), right))
+        })
+        .collect()
+}
+
+/// Returns the chunk which is mixed in with the root of a list of `length`.
+pub fn length_chunk(length: usize) -> Hash256 {
+    let mut chunk = [0; BYTES_PER_CHUNK];
+    chunk[0..std::mem::size_of::<usize>()].copy_from_slice(&length.to_le_bytes());
+    Hash256::from_slice(&chunk)
+}
+
+/// Returns the depth of a tree with `leaf_count` leaves, once padded to a power of two.
+fn tree_depth(leaf_count: usize) -> usize {
+    leaf_count.next_power_of_two().trailing_zeros() as usize
+}
@@ -0,0 +1,182 @@
+use merkle_proof::{calculate_merkle_root, verify_merkle_multiproof};
+use tree_hash::{self, mix_in_selector, proof, Hash256, PathElement, ProofError, TreeHash};
+use tree_hash_derive::TreeHash;
+
+#[derive(TreeHash)]
+struct Container {
+    a: u64,
+    b: u16,
+}
+
+#[derive(TreeHash)]
+struct Outer {
+    x: u64,
+    inner: Container,
+    y: u8,
+}
+
+#[derive(TreeHash)]
+#[tree_hash(enum_behaviour = "union")]
+enum Union {
+    None,
+    A(u64),
+    B(Container),
+}
+
+#[derive(TreeHash)]
+#[tree_hash(enum_behaviour = "transparent")]
+enum Transparent<T: TreeHash> {
+    A(u64),
+    B(T),
+}
+
+#[test]
+fn union_root() {
+    let container = Container { a: 42, b: 7 };
+
+    assert_eq!(
+        Union::None.tree_hash_root(),
+        mix_in_selector(&Hash256::zero(), 0).unwrap()
+    );
+    assert_eq!(
+        Union::A(42).tree_hash_root(),
+        mix_in_selector(&42_u64.tree_hash_root(), 1).unwrap()
+    );
+    assert_eq!(
+        Union::B(Container { a: 42, b: 7 }).tree_hash_root(),
+        mix_in_selector(&container.tree_hash_root(), 2).unwrap()
+    );
+    assert_ne!(
+        Union::A(42).tree_hash_root(),
+        Union::B(Container { a: 42, b: 0 }).tree_hash_root()
+    );
+}
+
+/// Roots computed independently of this crate, as `hash(root || selector)` where `root` is the
+/// root of the value of the variant (or zero for `None`) and `selector` is a little-endian 32-byte
+/// integer.
+#[test]
+fn union_root_vectors() {
+    let vectors = vec![
+        (
+            Union::None,
+            "f5a5fd42d16a20302798ef6ed309979b43003d2320d9f0e8ea9831a92759fb4b",
+        ),
+        (
+            Union::A(42),
+            "bd7eb7bbb9e22cb784bd59247b4b6977ceb51de6713cf29938558b71464678b3",
+        ),
+        (
+            Union::B(Container { a: 42, b: 7 }),
+            "1da5f7d8f354f75f01da2c9b7d4b864173b5b30fdffc781d2aa528e0d856b281",
+        ),
+    ];
+
+    for (union, expected) in vectors {
+        assert_eq!(
+            union.tree_hash_root(),
+            expected.parse::<Hash256>().expect("should parse root")
+        );
+    }
+}
+
+#[test]
+fn transparent_root() {
+    let container = Container { a: 42, b: 7 };
+
+    assert_eq!(
+        Transparent::<Container>::A(42).tree_hash_root(),
+        42_u64.tree_hash_root()
+    );
+    assert_eq!(
+        Transparent::B(Container { a: 42, b: 7 }).tree_hash_root(),
+        container.tree_hash_root()
+    );
+}
+
+#[test]
+fn container_proof() {
+    let outer = Outer {
+        x: 1,
+        inner: Container { a: 42, b: 7 },
+        y: 3,
+    };
+    let root = outer.tree_hash_root();
+
+    let paths: Vec<&[PathElement]> = vec![
+        &[],
+        &[PathElement::Field("x")],
+        &[PathElement::Field("inner")],
+        &[PathElement::Field("inner"), PathElement::Field("b")],
+        &[PathElement::Field("y")],
+    ];
+    let expected_indices = vec![1, 4, 5, 11, 6];
+
+    for (path, expected_index) in paths.iter().zip(expected_indices) {
+        let index = proof::generalized_index::<Outer>(path).unwrap();
+        assert_eq!(index, expected_index);
+
+        let (leaf, branch) = outer.tree_hash_proof(path).unwrap();
+        assert_eq!(calculate_merkle_root(leaf, &branch, index), Ok(root));
+    }
+
+    let y_and_padding = Hash256::from_slice(&eth2_hashing::hash32_concat(
+        outer.y.tree_hash_root().as_bytes(),
+        &[0; 32],
+    ));
+    assert_eq!(
+        outer.tree_hash_proof(&[PathElement::Field("inner"), PathElement::Field("b")]),
+        Ok((
+            7_u16.tree_hash_root(),
+            vec![
+                42_u64.tree_hash_root(),
+                outer.x.tree_hash_root(),
+                y_and_padding
+            ]
+        ))
+    );
+}
+
+#[test]
+fn container_multiproof() {
+    let outer = Outer {
+        x: 1,
+        inner: Container { a: 42, b: 7 },
+        y: 3,
+    };
+    let paths: Vec<&[PathElement]> = vec![
+        &[PathElement::Field("x")],
+        &[PathElement::Field("inner"), PathElement::Field("a")],
+    ];
+
+    let multiproof = proof::multiproof(&outer, &paths).unwrap();
+    assert_eq!(multiproof.indices, vec![4, 10]);
+    assert_eq!(
+        multiproof.leaves,
+        vec![1_u64.tree_hash_root(), 42_u64.tree_hash_root()]
+    );
+    // The siblings of `inner.a` and of the parent of `x` and `inner`.
+    assert_eq!(multiproof.proof.len(), 2);
+    assert!(verify_merkle_multiproof(
+        &multiproof.leaves,
+        &multiproof.proof,
+        &multiproof.indices,
+        outer.tree_hash_root()
+    ));
+}
+
+#[test]
+fn invalid_paths() {
+    assert_eq!(
+        proof::generalized_index::<Outer>(&[PathElement::Field("z")]),
+        Err(ProofError::UnknownField("z".to_string()))
+    );
+    assert_eq!(
+        proof::generalized_index::<Outer>(&[PathElement::Index(0)]),
+        Err(ProofError::UnexpectedPathElement("Index(0)".to_string()))
+    );
+    assert_eq!(
+        proof::generalized_index::<Outer>(&[PathElement::Field("x"), PathElement::Length]),
+        Err(ProofError::UnexpectedPathElement("Length".to_string()))
+    );
+}
@@ -3,7 +3,59 @@ extern crate proc_macro;
 
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{parse_macro_input, Attribute, DeriveInput, Meta};
+use syn::{parse_macro_input, Attribute, DeriveInput, Lit, Meta, NestedMeta};
+
+/// The highest possible union selector value, matching `tree_hash::MAX_UNION_SELECTOR`.
+const MAX_UNION_SELECTOR: u8 = 127;
+
+/// Defines how an enum is hashed, as set with `#[tree_hash(enum_behaviour = "...")]`.
+#[derive(Clone, Copy, PartialEq)]
+enum EnumBehaviour {
+    /// The root of the variant, mixed in with the index of the variant.
+    Union,
+    /// The root of the variant.
+    Transparent,
+}
+
+/// Reads the `#[tree_hash(enum_behaviour = "...")]` attribute of an enum.
+///
+/// # Panics
+/// Panics at compile time if the attribute is missing or invalid.
+fn get_enum_behaviour(attrs: &[Attribute]) -> EnumBehaviour {
+    let behaviour = attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("tree_hash"))
+        .flat_map(|attr| attr.parse_meta())
+        .filter_map(|meta| match meta {
+            Meta::List(list) => Some(list.nested),
+            _ => None,
+        })
+        .flatten()
+        .find_map(|nested| match nested {
+            NestedMeta::Meta(Meta::NameValue(name_value))
+                if name_value.path.is_ident("enum_behaviour") =>
+            {
+                match name_value.lit {
+                    Lit::Str(s) => Some(s.value()),
+                    _ => panic!("tree_hash enum_behaviour must be a string"),
+                }
+            }
+            _ => None,
+        });
+
+    match behaviour.as_deref() {
+        Some("union") => EnumBehaviour::Union,
+        Some("transparent") => EnumBehaviour::Transparent,
+        Some(other) => panic!(
+            "{} is not a valid tree_hash enum_behaviour, use \"union\" or \"transparent\"",
+            other
+        ),
+        None => panic!(
+            "tree_hash_derive requires enums to have #[tree_hash(enum_behaviour = \"union\")] \
+             or #[tree_hash(enum_behaviour = \"transparent\")]"
+        ),
+    }
+}
 
 /// Return a Vec of `syn::Ident` for each named field in the struct, whilst filtering out fields
 /// that should not be hashed.
@@ -78,22 +130,46 @@ fn should_skip_hashing(field: &syn::Field) -> bool {
     })
 }
 
-/// Implements `tree_hash::TreeHash` for some `struct`.
+/// Implements `tree_hash::TreeHash` for some `struct` or `enum`.
 ///
 /// Fields are hashed in the order they are defined.
+///
+/// ## Enums
+///
+/// Enums must declare how they should be hashed, using one of the following attributes:
+///
+/// - `#[tree_hash(enum_behaviour = "union")]`: hashed as an SSZ union; the root of the variant
+///   (or a zero hash for a unit variant) mixed in with the index of the variant. Each variant must
+///   wrap exactly one type, except the first variant which may also be a unit variant.
+/// - `#[tree_hash(enum_behaviour = "transparent")]`: hashed as the value wrapped by the variant.
+///   Each variant must wrap exactly one type.
 #[proc_macro_derive(TreeHash, attributes(tree_hash))]
 pub fn tree_hash_derive(input: TokenStream) -> TokenStream {
     let item = parse_macro_input!(input as DeriveInput);
 
+    match &item.data {
+        syn::Data::Struct(struct_data) => tree_hash_derive_struct(&item, struct_data),
+        syn::Data::Enum(enum_data) => {
+            tree_hash_derive_enum(&item, enum_data, get_enum_behaviour(&item.attrs))
+        }
+        _ => panic!("tree_hash_derive only supports structs and enums."),
+    }
+}
+
+fn tree_hash_derive_struct(item: &DeriveInput, struct_data: &syn::DataStruct) -> TokenStream {
     let name = &item.ident;
     let (impl_generics, ty_generics, where_clause) = &item.generics.split_for_impl();
 
-    let struct_data = match &item.data {
-        syn::Data::Struct(s) => s,
-        _ => panic!("tree_hash_derive only supports structs."),
-    };
-
     let idents = get_hashable_fields(&struct_data);
+    let types = get_hashable_fields_and_their_caches(struct_data)
+        .into_iter()
+        .map(|(_, ty, _)| ty)
+        .collect::<Vec<_>>();
+    let field_names = idents
+        .iter()
+        .map(|ident| ident.to_string())
+        .collect::<Vec<_>>();
+    let positions = (0..idents.len()).collect::<Vec<_>>();
     let num_leaves = idents.len();
 
     let output = quote! {
@@ -120,6 +196,153 @@ pub fn tree_hash_derive(input: TokenStream) -> TokenStream {
 
                 hasher.finish().expect("tree hash derive should not have a remaining buffer")
             }
+
+            fn tree_hash_generalized_index(
+                path: &[tree_hash::PathElement],
+            ) -> Result<usize, tree_hash::ProofError> {
+                let (first, rest) = match path.split_first() {
+                    Some(split) => split,
+                    None => return Ok(1),
+                };
+
+                let (position, inner) = match first {
+                    #(
+                        tree_hash::PathElement::Field(#field_names) => (
+                            #positions,
+                            <#types as tree_hash::TreeHash>::tree_hash_generalized_index(rest)?,
+                        ),
+                    )*
+                    tree_hash::PathElement::Field(name) => {
+                        return Err(tree_hash::ProofError::UnknownField(name.to_string()))
+                    }
+                    other => return Err(tree_hash::ProofError::unexpected(other)),
+                };
+
+                Ok(tree_hash::proof::vector_generalized_index(#num_leaves, position, inner))
+            }
+
+            fn tree_hash_proof(
+                &self,
+                path: &[tree_hash::PathElement],
+            ) -> Result<(tree_hash::Hash256, Vec<tree_hash::Hash256>), tree_hash::ProofError> {
+                let (first, rest) = match path.split_first() {
+                    Some(split) => split,
+                    None => return Ok((tree_hash::TreeHash::tree_hash_root(self), vec![])),
+                };
+
+                let (position, inner) = match first {
+                    #(
+                        tree_hash::PathElement::Field(#field_names) => (
+                            #positions,
+                            self.#idents.tree_hash_proof(rest)?,
+                        ),
+                    )*
+                    tree_hash::PathElement::Field(name) => {
+                        return Err(tree_hash::ProofError::UnknownField(name.to_string()))
+                    }
+                    other => return Err(tree_hash::ProofError::unexpected(other)),
+                };
+
+                let field_roots = vec![
+                    #(
+                        self.#idents.tree_hash_root(),
+                    )*
+                ];
+
+                Ok(tree_hash::proof::vector_proof(&field_roots, #num_leaves, position, inner))
+            }
+        }
+    };
+    output.into()
+}
+
+/// Implements `tree_hash::TreeHash` for some `enum`, according to `behaviour`.
+fn tree_hash_derive_enum(
+    item: &DeriveInput,
+    enum_data: &syn::DataEnum,
+    behaviour: EnumBehaviour,
+) -> TokenStream {
+    let name = &item.ident;
+    let (impl_generics, ty_generics, where_clause) = &item.generics.split_for_impl();
+
+    if enum_data.variants.is_empty() {
+        panic!("tree_hash_derive does not support enums without variants.");
+    }
+
+    if behaviour == EnumBehaviour::Union
+        && enum_data.variants.len() > usize::from(MAX_UNION_SELECTOR) + 1
+    {
+        panic!(
+            "tree_hash_derive supports at most {} union variants.",
+            usize::from(MAX_UNION_SELECTOR) + 1
+        );
+    }
+
+    let roots = enum_data
+        .variants
+        .iter()
+        .enumerate()
+        .map(|(i, variant)| {
+            let ident = &variant.ident;
+            let selector = i as u8;
+
+            match (&variant.fields, behaviour) {
+                (syn::Fields::Unnamed(fields), EnumBehaviour::Union)
+                    if fields.unnamed.len() == 1 =>
+                {
+                    quote! {
+                        #name::#ident(inner) => {
+                            tree_hash::mix_in_selector(&inner.tree_hash_root(), #selector)
+                                .expect("derive macro should prevent out-of-bounds selectors")
+                        }
+                    }
+                }
+                (syn::Fields::Unnamed(fields), EnumBehaviour::Transparent)
+                    if fields.unnamed.len() == 1 =>
+                {
+                    quote! {
+                        #name::#ident(inner) => inner.tree_hash_root()
+                    }
+                }
+                (syn::Fields::Unit, EnumBehaviour::Union) if i == 0 => quote! {
+                    #name::#ident => {
+                        tree_hash::mix_in_selector(&tree_hash::Hash256::zero(), #selector)
+                            .expect("derive macro should prevent out-of-bounds selectors")
+                    }
+                },
+                (syn::Fields::Unit, EnumBehaviour::Union) => {
+                    panic!(
+                        "tree_hash_derive only supports a unit variant as the first union variant."
+                    )
+                }
+                _ => panic!(
+                    "tree_hash_derive only supports enum variants which wrap exactly one type."
+                ),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let output = quote! {
+        impl #impl_generics tree_hash::TreeHash for #name #ty_generics #where_clause {
+            fn tree_hash_type() -> tree_hash::TreeHashType {
+                tree_hash::TreeHashType::Container
+            }
+
+            fn tree_hash_packed_encoding(&self) -> Vec<u8> {
+                unreachable!("Enum should never be packed.")
+            }
+
+            fn tree_hash_packing_factor() -> usize {
+                unreachable!("Enum should never be packed.")
+            }
+
+            fn tree_hash_root(&self) -> tree_hash::Hash256 {
+                match self {
+                    #(
+                        #roots,
+                    )*
+                }
+            }
         }
     };
     output.into()
@@ -2,6 +2,7 @@ use criterion::Criterion;
 use criterion::{black_box, criterion_group, criterion_main, Benchmark};
 use rayon::prelude::*;
 use ssz::{Decode, Encode};
+use std::sync::Arc;
 use types::{
     test_utils::generate_deterministic_keypair, BeaconState, Epoch, Eth1Data, EthSpec, Hash256,
     MainnetEthSpec, Validator,
@@ -167,6 +168,75 @@ fn all_benches(c: &mut Criterion) {
         )
         .sample_size(10),
     );
+
+    // Compare the parallel tree hashing of the large lists against hashing on a single thread.
+    for &num_threads in &[1, rayon::current_num_threads()] {
+        let pool = Arc::new(
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(num_threads)
+                .build()
+                .expect("should build thread pool"),
+        );
+
+        let mut inner_state = state.clone();
+        inner_state.drop_all_caches();
+        let inner_pool = pool.clone();
+        c.bench(
+            &format!("{}_validators", validator_count),
+            Benchmark::new(
+                format!("non_initialized_cached_tree_hash/{}_threads", num_threads),
+                move |b| {
+                    b.iter_batched_ref(
+                        || inner_state.clone(),
+                        |state| {
+                            inner_pool.install(|| {
+                                black_box(
+                                    state
+                                        .update_tree_hash_cache()
+                                        .expect("should update tree hash"),
+                                )
+                            })
+                        },
+                        criterion::BatchSize::SmallInput,
+                    )
+                },
+            )
+            .sample_size(10),
+        );
+
+        let inner_state = state.clone();
+        c.bench(
+            &format!("{}_validators", validator_count),
+            Benchmark::new(
+                format!(
+                    "initialized_cached_tree_hash_with_new_balances/{}_threads",
+                    num_threads
+                ),
+                move |b| {
+                    b.iter_batched_ref(
+                        || {
+                            let mut state = inner_state.clone();
+                            for balance in state.balances.iter_mut() {
+                                *balance += 1;
+                            }
+                            state
+                        },
+                        |state| {
+                            pool.install(|| {
+                                black_box(
+                                    state
+                                        .update_tree_hash_cache()
+                                        .expect("should update tree hash"),
+                                )
+                            })
+                        },
+                        criterion::BatchSize::SmallInput,
+                    )
+                },
+            )
+            .sample_size(10),
+        );
+    }
 }
 
 criterion_group!(benches, all_benches,);
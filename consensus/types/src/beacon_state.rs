@@ -17,7 +17,7 @@ use std::convert::TryInto;
 use std::fmt;
 use swap_or_not_shuffle::compute_shuffled_index;
 use test_random_derive::TestRandom;
-use tree_hash::TreeHash;
+use tree_hash::{PathElement, TreeHash};
 use tree_hash_derive::TreeHash;
 
 pub use self::committee_cache::CommitteeCache;
@@ -71,6 +71,7 @@ pub enum Error {
     SszTypesError(ssz_types::Error),
     TreeHashCacheNotInitialized,
     TreeHashError(tree_hash::Error),
+    TreeHashProofError(tree_hash::ProofError),
     CachedTreeHashError(cached_tree_hash::Error),
     InvalidValidatorPubkey(ssz::DecodeError),
     ValidatorRegistryShrunk,
@@ -578,6 +579,25 @@ impl<T: EthSpec> BeaconState<T> {
         self.get_block_root(epoch.start_slot(T::slots_per_epoch()))
     }
 
+    /// Returns the root of the block which decided the attester shuffling for `relative_epoch`.
+    ///
+    /// This is the block at the last slot of the epoch two epochs prior. The shufflings of the
+    /// first two epochs are decided by the genesis block, the root of which is `genesis_block_root`.
+    pub fn attester_shuffling_decision_root(
+        &self,
+        genesis_block_root: Hash256,
+        relative_epoch: RelativeEpoch,
+    ) -> Result<Hash256, Error> {
+        let epoch = relative_epoch.into_epoch(self.current_epoch());
+
+        if epoch.as_u64() < 2 {
+            Ok(genesis_block_root)
+        } else {
+            let decision_slot = epoch.saturating_sub(2_u64).end_slot(T::slots_per_epoch());
+            self.get_block_root(decision_slot).map(|root| *root)
+        }
+    }
+
     /// Sets the block root for some given slot.
     ///
     /// Spec v0.12.1
@@ -1043,6 +1063,32 @@ impl<T: EthSpec> BeaconState<T> {
         }
     }
 
+    /// Compute the node at `path` and a Merkle branch proving it against the tree hash root of
+    /// the state, using the tree hash cache.
+    ///
+    /// Initialize the tree hash cache if it isn't already initialized.
+    pub fn compute_merkle_proof(
+        &mut self,
+        path: &[PathElement],
+    ) -> Result<(Hash256, Vec<Hash256>), Error> {
+        self.initialize_tree_hash_cache();
+
+        let cache = self.tree_hash_cache.take();
+
+        if let Some(mut cache) = cache {
+            // Note: if the tree hash fails `self.tree_hash_cache` is left as None, since there's
+            // no need to keep a cache that fails. An invalid `path` does not affect the cache.
+            let result = cache.recalculate_proof(&self, path);
+            match result {
+                Ok(_) | Err(Error::TreeHashProofError(_)) => self.tree_hash_cache = Some(cache),
+                Err(_) => (),
+            }
+            result
+        } else {
+            Err(Error::TreeHashCacheNotInitialized)
+        }
+    }
+
     /// Compute the tree hash root of the validators using the tree hash cache.
     ///
     /// Initialize the tree hash cache if it isn't already initialized.
@@ -1153,6 +1199,12 @@ impl From<ssz_types::Error> for Error {
     }
 }
 
+impl From<tree_hash::ProofError> for Error {
+    fn from(e: tree_hash::ProofError) -> Error {
+        Error::TreeHashProofError(e)
+    }
+}
+
 impl From<cached_tree_hash::Error> for Error {
     fn from(e: cached_tree_hash::Error) -> Error {
         Error::CachedTreeHashError(e)
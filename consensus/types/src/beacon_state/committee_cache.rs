@@ -6,7 +6,7 @@ use core::num::NonZeroUsize;
 use serde_derive::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
 use std::ops::Range;
-use swap_or_not_shuffle::shuffle_list;
+use swap_or_not_shuffle::shuffle_list_parallel;
 
 mod tests;
 
@@ -49,7 +49,7 @@ impl CommitteeCache {
 
         let seed = state.get_seed(epoch, Domain::BeaconAttester, spec)?;
 
-        let shuffling = shuffle_list(
+        let shuffling = shuffle_list_parallel(
             active_validator_indices,
             spec.shuffle_round_count,
             &seed[..],
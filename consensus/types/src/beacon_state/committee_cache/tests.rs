@@ -1,6 +1,7 @@
 #![cfg(test)]
 use super::*;
 use crate::{test_utils::*, *};
+use swap_or_not_shuffle::shuffle_list;
 
 #[test]
 fn default_values() {
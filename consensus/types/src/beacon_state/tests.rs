@@ -213,6 +213,68 @@ fn tree_hash_cache() {
     assert_eq!(root.as_bytes(), &state.tree_hash_root()[..]);
 }
 
+#[test]
+fn compute_merkle_proof() {
+    use tree_hash::{PathElement, TreeHash};
+
+    let spec = MinimalEthSpec::default_spec();
+    let builder: TestingBeaconStateBuilder<MinimalEthSpec> =
+        TestingBeaconStateBuilder::from_deterministic_keypairs(16, &spec);
+    let (mut state, _keypairs) = builder.build();
+    let root = state.tree_hash_root();
+
+    let paths: Vec<&[PathElement]> = vec![
+        &[],
+        &[PathElement::Field("slot")],
+        &[
+            PathElement::Field("finalized_checkpoint"),
+            PathElement::Field("root"),
+        ],
+        &[PathElement::Field("block_roots"), PathElement::Index(3)],
+        &[PathElement::Field("balances"), PathElement::Index(1)],
+        &[PathElement::Field("validators"), PathElement::Length],
+        &[
+            PathElement::Field("validators"),
+            PathElement::Index(0),
+            PathElement::Field("effective_balance"),
+        ],
+    ];
+
+    for path in paths {
+        let index =
+            tree_hash::proof::generalized_index::<BeaconState<MinimalEthSpec>>(path).unwrap();
+        let proof = state.compute_merkle_proof(path).unwrap();
+
+        assert_eq!(proof, state.tree_hash_proof(path).unwrap(), "{:?}", path);
+        assert_eq!(
+            merkle_proof::calculate_merkle_root(proof.0, &proof.1, index),
+            Ok(root),
+            "{:?}",
+            path
+        );
+    }
+
+    let validator_count = state.validators.len();
+    assert_eq!(
+        state.compute_merkle_proof(&[
+            PathElement::Field("validators"),
+            PathElement::Index(validator_count)
+        ]),
+        Err(Error::TreeHashProofError(
+            tree_hash::ProofError::IndexOutOfBounds {
+                index: validator_count,
+                len: validator_count
+            }
+        ))
+    );
+    assert_eq!(
+        state.compute_merkle_proof(&[PathElement::Field("unknown")]),
+        Err(Error::TreeHashProofError(
+            tree_hash::ProofError::UnknownField("unknown".to_string())
+        ))
+    );
+}
+
 /// Tests committee-specific components
 #[cfg(test)]
 mod committees {
@@ -406,3 +468,62 @@ mod get_outstanding_deposit_len {
         );
     }
 }
+
+mod attester_shuffling_decision_root {
+    use super::*;
+    use crate::test_utils::TestingBeaconStateBuilder;
+    use crate::MinimalEthSpec;
+
+    type E = MinimalEthSpec;
+
+    fn state_at_slot(slot: Slot) -> BeaconState<E> {
+        let spec = E::default_spec();
+        let mut builder: TestingBeaconStateBuilder<E> =
+            TestingBeaconStateBuilder::from_deterministic_keypairs(16, &spec);
+        builder.teleport_to_slot(slot);
+        let (mut state, _keypairs) = builder.build();
+
+        for i in 0..state.block_roots.len() {
+            state.block_roots[i] = Hash256::from_low_u64_be(i as u64);
+        }
+
+        state
+    }
+
+    #[test]
+    fn genesis_decides_first_two_epochs() {
+        let genesis_block_root = Hash256::repeat_byte(42);
+        let state = state_at_slot(E::slots_per_epoch().into());
+
+        assert_eq!(
+            state.attester_shuffling_decision_root(genesis_block_root, RelativeEpoch::Current),
+            Ok(genesis_block_root)
+        );
+        assert_ne!(
+            state.attester_shuffling_decision_root(genesis_block_root, RelativeEpoch::Next),
+            Ok(genesis_block_root)
+        );
+    }
+
+    #[test]
+    fn last_slot_of_epoch_two_prior() {
+        let genesis_block_root = Hash256::repeat_byte(42);
+        let current_epoch = Epoch::new(4);
+        let state = state_at_slot(current_epoch.start_slot(E::slots_per_epoch()) + 3);
+
+        let root_at_end_of = |epoch: Epoch| {
+            *state
+                .get_block_root(epoch.end_slot(E::slots_per_epoch()))
+                .expect("should get block root")
+        };
+
+        assert_eq!(
+            state.attester_shuffling_decision_root(genesis_block_root, RelativeEpoch::Current),
+            Ok(root_at_end_of(current_epoch - 2))
+        );
+        assert_eq!(
+            state.attester_shuffling_decision_root(genesis_block_root, RelativeEpoch::Next),
+            Ok(root_at_end_of(current_epoch - 1))
+        );
+    }
+}
@@ -5,10 +5,10 @@ use crate::{BeaconState, EthSpec, Hash256, Unsigned, Validator};
 use cached_tree_hash::{int_log, CacheArena, CachedTreeHash, TreeHashCache};
 use rayon::prelude::*;
 use ssz_derive::{Decode, Encode};
-use tree_hash::{mix_in_length, MerkleHasher, TreeHash};
+use tree_hash::{mix_in_length, MerkleHasher, PathElement, ProofError, TreeHash};
 
 /// The number of fields on a beacon state.
-const NUM_BEACON_STATE_HASHING_FIELDS: usize = 20;
+const NUM_BEACON_STATE_HASHING_FIELDS: usize = 21;
 
 /// The number of nodes in the Merkle tree of a validator record.
 const NODES_PER_VALIDATOR: usize = 15;
@@ -27,7 +27,12 @@ pub struct BeaconTreeHashCache {
     // Validators cache
     validators: ValidatorsListTreeHashCache,
     // Arenas
+    //
+    // Each of the large lists has its own arena, so that their caches may be updated in parallel.
     fixed_arena: CacheArena,
+    block_roots_arena: CacheArena,
+    state_roots_arena: CacheArena,
+    randao_mixes_arena: CacheArena,
     balances_arena: CacheArena,
     slashings_arena: CacheArena,
     // Caches
@@ -46,10 +51,22 @@ impl BeaconTreeHashCache {
     /// hashing.
     pub fn new<T: EthSpec>(state: &BeaconState<T>) -> Self {
         let mut fixed_arena = CacheArena::default();
-        let block_roots = state.block_roots.new_tree_hash_cache(&mut fixed_arena);
-        let state_roots = state.state_roots.new_tree_hash_cache(&mut fixed_arena);
         let historical_roots = state.historical_roots.new_tree_hash_cache(&mut fixed_arena);
-        let randao_mixes = state.randao_mixes.new_tree_hash_cache(&mut fixed_arena);
+
+        let mut block_roots_arena = CacheArena::default();
+        let block_roots = state
+            .block_roots
+            .new_tree_hash_cache(&mut block_roots_arena);
+
+        let mut state_roots_arena = CacheArena::default();
+        let state_roots = state
+            .state_roots
+            .new_tree_hash_cache(&mut state_roots_arena);
+
+        let mut randao_mixes_arena = CacheArena::default();
+        let randao_mixes = state
+            .randao_mixes
+            .new_tree_hash_cache(&mut randao_mixes_arena);
 
         let validators = ValidatorsListTreeHashCache::new::<T>(&state.validators[..]);
 
@@ -62,6 +79,9 @@ impl BeaconTreeHashCache {
         Self {
             validators,
             fixed_arena,
+            block_roots_arena,
+            state_roots_arena,
+            randao_mixes_arena,
             balances_arena,
             slashings_arena,
             block_roots,
@@ -81,80 +101,133 @@ impl BeaconTreeHashCache {
         &mut self,
         state: &BeaconState<T>,
     ) -> Result<Hash256, Error> {
-        let mut hasher = MerkleHasher::with_leaves(NUM_BEACON_STATE_HASHING_FIELDS);
-
-        hasher.write(state.genesis_time.tree_hash_root().as_bytes())?;
-        hasher.write(state.genesis_validators_root.tree_hash_root().as_bytes())?;
-        hasher.write(state.slot.tree_hash_root().as_bytes())?;
-        hasher.write(state.fork.tree_hash_root().as_bytes())?;
-        hasher.write(state.latest_block_header.tree_hash_root().as_bytes())?;
-        hasher.write(
-            state
-                .block_roots
-                .recalculate_tree_hash_root(&mut self.fixed_arena, &mut self.block_roots)?
-                .as_bytes(),
-        )?;
-        hasher.write(
-            state
-                .state_roots
-                .recalculate_tree_hash_root(&mut self.fixed_arena, &mut self.state_roots)?
-                .as_bytes(),
-        )?;
-        hasher.write(
+        let field_roots = self.recalculate_field_roots(state)?;
+        merkle_root_of_fields(&field_roots)
+    }
+
+    /// Updates the cache and returns the node at `path` within the given `state`, along with a
+    /// Merkle branch proving it against the tree hash root of the `state`.
+    ///
+    /// The roots of the fields of the `state` and the Merkle tree of `state.validators` are read
+    /// from the cache, which avoids hashing the entire validator registry.
+    pub fn recalculate_proof<T: EthSpec>(
+        &mut self,
+        state: &BeaconState<T>,
+        path: &[PathElement],
+    ) -> Result<(Hash256, Vec<Hash256>), Error> {
+        let field_roots = self.recalculate_field_roots(state)?;
+
+        let (first, rest) = match path.split_first() {
+            Some(split) => split,
+            None => return Ok((merkle_root_of_fields(&field_roots)?, vec![])),
+        };
+
+        let (position, inner) = match first {
+            PathElement::Field("validators") => {
+                (11, self.validators.proof(&state.validators, rest)?)
+            }
+            PathElement::Field(name) => field_proof(state, name, rest)?,
+            other => return Err(ProofError::unexpected(other).into()),
+        };
+
+        Ok(tree_hash::proof::vector_proof(
+            &field_roots,
+            NUM_BEACON_STATE_HASHING_FIELDS,
+            position,
+            inner,
+        ))
+    }
+
+    /// Updates the cache and returns the tree hash root of each field of the given `state`.
+    ///
+    /// The caches of the large lists are updated in parallel, each on its own arena.
+    fn recalculate_field_roots<T: EthSpec>(
+        &mut self,
+        state: &BeaconState<T>,
+    ) -> Result<Vec<Hash256>, Error> {
+        let Self {
+            validators,
+            fixed_arena,
+            block_roots_arena,
+            state_roots_arena,
+            randao_mixes_arena,
+            balances_arena,
+            slashings_arena,
+            block_roots,
+            state_roots,
+            historical_roots,
+            balances,
+            randao_mixes,
+            slashings,
+        } = self;
+
+        let (state_validators, state_balances) = (&state.validators, &state.balances);
+        let (state_block_roots, state_state_roots, state_randao_mixes) =
+            (&state.block_roots, &state.state_roots, &state.randao_mixes);
+
+        let ((validators_root, balances_root), ((block_roots_root, state_roots_root), randao_root)) =
+            rayon::join(
+                || {
+                    rayon::join(
+                        || validators.recalculate_tree_hash_root(&state_validators[..]),
+                        || state_balances.recalculate_tree_hash_root(balances_arena, balances),
+                    )
+                },
+                || {
+                    rayon::join(
+                        || {
+                            rayon::join(
+                                || {
+                                    state_block_roots
+                                        .recalculate_tree_hash_root(block_roots_arena, block_roots)
+                                },
+                                || {
+                                    state_state_roots
+                                        .recalculate_tree_hash_root(state_roots_arena, state_roots)
+                                },
+                            )
+                        },
+                        || {
+                            state_randao_mixes
+                                .recalculate_tree_hash_root(randao_mixes_arena, randao_mixes)
+                        },
+                    )
+                },
+            );
+
+        let mut roots = Vec::with_capacity(NUM_BEACON_STATE_HASHING_FIELDS);
+
+        roots.push(state.genesis_time.tree_hash_root());
+        roots.push(state.genesis_validators_root.tree_hash_root());
+        roots.push(state.slot.tree_hash_root());
+        roots.push(state.fork.tree_hash_root());
+        roots.push(state.latest_block_header.tree_hash_root());
+        roots.push(block_roots_root?);
+        roots.push(state_roots_root?);
+        roots.push(
             state
                 .historical_roots
-                .recalculate_tree_hash_root(&mut self.fixed_arena, &mut self.historical_roots)?
-                .as_bytes(),
-        )?;
-        hasher.write(state.eth1_data.tree_hash_root().as_bytes())?;
-        hasher.write(state.eth1_data_votes.tree_hash_root().as_bytes())?;
-        hasher.write(state.eth1_deposit_index.tree_hash_root().as_bytes())?;
-        hasher.write(
-            self.validators
-                .recalculate_tree_hash_root(&state.validators[..])?
-                .as_bytes(),
-        )?;
-        hasher.write(
-            state
-                .balances
-                .recalculate_tree_hash_root(&mut self.balances_arena, &mut self.balances)?
-                .as_bytes(),
-        )?;
-        hasher.write(
-            state
-                .randao_mixes
-                .recalculate_tree_hash_root(&mut self.fixed_arena, &mut self.randao_mixes)?
-                .as_bytes(),
-        )?;
-        hasher.write(
+                .recalculate_tree_hash_root(fixed_arena, historical_roots)?,
+        );
+        roots.push(state.eth1_data.tree_hash_root());
+        roots.push(state.eth1_data_votes.tree_hash_root());
+        roots.push(state.eth1_deposit_index.tree_hash_root());
+        roots.push(validators_root?);
+        roots.push(balances_root?);
+        roots.push(randao_root?);
+        roots.push(
             state
                 .slashings
-                .recalculate_tree_hash_root(&mut self.slashings_arena, &mut self.slashings)?
-                .as_bytes(),
-        )?;
-        hasher.write(
-            state
-                .previous_epoch_attestations
-                .tree_hash_root()
-                .as_bytes(),
-        )?;
-        hasher.write(state.current_epoch_attestations.tree_hash_root().as_bytes())?;
-        hasher.write(state.justification_bits.tree_hash_root().as_bytes())?;
-        hasher.write(
-            state
-                .previous_justified_checkpoint
-                .tree_hash_root()
-                .as_bytes(),
-        )?;
-        hasher.write(
-            state
-                .current_justified_checkpoint
-                .tree_hash_root()
-                .as_bytes(),
-        )?;
-        hasher.write(state.finalized_checkpoint.tree_hash_root().as_bytes())?;
-
-        hasher.finish().map_err(Into::into)
+                .recalculate_tree_hash_root(slashings_arena, slashings)?,
+        );
+        roots.push(state.previous_epoch_attestations.tree_hash_root());
+        roots.push(state.current_epoch_attestations.tree_hash_root());
+        roots.push(state.justification_bits.tree_hash_root());
+        roots.push(state.previous_justified_checkpoint.tree_hash_root());
+        roots.push(state.current_justified_checkpoint.tree_hash_root());
+        roots.push(state.finalized_checkpoint.tree_hash_root());
+
+        Ok(roots)
     }
 
     /// Updates the cache and provides the root of the given `validators`.
@@ -166,6 +239,64 @@ impl BeaconTreeHashCache {
     }
 }
 
+/// Returns the root of the Merkle tree of the given `field_roots` of a `BeaconState`.
+fn merkle_root_of_fields(field_roots: &[Hash256]) -> Result<Hash256, Error> {
+    let mut hasher = MerkleHasher::with_leaves(NUM_BEACON_STATE_HASHING_FIELDS);
+
+    for root in field_roots {
+        hasher.write(root.as_bytes())?;
+    }
+
+    hasher.finish().map_err(Into::into)
+}
+
+/// Returns the position of the field `name` within the given `state`, and a proof of the node at
+/// `path` within that field.
+///
+/// Does not use any cache.
+fn field_proof<T: EthSpec>(
+    state: &BeaconState<T>,
+    name: &str,
+    path: &[PathElement],
+) -> Result<(usize, (Hash256, Vec<Hash256>)), ProofError> {
+    let proof = match name {
+        "genesis_time" => (0, state.genesis_time.tree_hash_proof(path)?),
+        "genesis_validators_root" => (1, state.genesis_validators_root.tree_hash_proof(path)?),
+        "slot" => (2, state.slot.tree_hash_proof(path)?),
+        "fork" => (3, state.fork.tree_hash_proof(path)?),
+        "latest_block_header" => (4, state.latest_block_header.tree_hash_proof(path)?),
+        "block_roots" => (5, state.block_roots.tree_hash_proof(path)?),
+        "state_roots" => (6, state.state_roots.tree_hash_proof(path)?),
+        "historical_roots" => (7, state.historical_roots.tree_hash_proof(path)?),
+        "eth1_data" => (8, state.eth1_data.tree_hash_proof(path)?),
+        "eth1_data_votes" => (9, state.eth1_data_votes.tree_hash_proof(path)?),
+        "eth1_deposit_index" => (10, state.eth1_deposit_index.tree_hash_proof(path)?),
+        "validators" => (11, state.validators.tree_hash_proof(path)?),
+        "balances" => (12, state.balances.tree_hash_proof(path)?),
+        "randao_mixes" => (13, state.randao_mixes.tree_hash_proof(path)?),
+        "slashings" => (14, state.slashings.tree_hash_proof(path)?),
+        "previous_epoch_attestations" => {
+            (15, state.previous_epoch_attestations.tree_hash_proof(path)?)
+        }
+        "current_epoch_attestations" => {
+            (16, state.current_epoch_attestations.tree_hash_proof(path)?)
+        }
+        "justification_bits" => (17, state.justification_bits.tree_hash_proof(path)?),
+        "previous_justified_checkpoint" => (
+            18,
+            state.previous_justified_checkpoint.tree_hash_proof(path)?,
+        ),
+        "current_justified_checkpoint" => (
+            19,
+            state.current_justified_checkpoint.tree_hash_proof(path)?,
+        ),
+        "finalized_checkpoint" => (20, state.finalized_checkpoint.tree_hash_proof(path)?),
+        _ => return Err(ProofError::UnknownField(name.to_string())),
+    };
+
+    Ok(proof)
+}
+
 /// A specialized cache for computing the tree hash root of `state.validators`.
 #[derive(Debug, PartialEq, Clone, Default, Encode, Decode)]
 struct ValidatorsListTreeHashCache {
@@ -215,6 +346,39 @@ impl ValidatorsListTreeHashCache {
 
         Ok(mix_in_length(&list_root, validators.len()))
     }
+
+    /// Returns the node at `path` within `validators`, along with a Merkle branch proving it
+    /// against the root of `validators`.
+    ///
+    /// The cache must have been updated with `validators` by `Self::recalculate_tree_hash_root`.
+    fn proof(
+        &self,
+        validators: &[Validator],
+        path: &[PathElement],
+    ) -> Result<(Hash256, Vec<Hash256>), Error> {
+        let list_root = self.list_cache.root(&self.list_arena);
+
+        match path.split_first() {
+            None => Ok((mix_in_length(&list_root, validators.len()), vec![])),
+            Some((PathElement::Index(index), rest)) => {
+                let validator = validators.get(*index).ok_or(ProofError::IndexOutOfBounds {
+                    index: *index,
+                    len: validators.len(),
+                })?;
+
+                let (leaf, mut branch) = validator.tree_hash_proof(rest)?;
+                branch.extend(self.list_cache.get_proof(&self.list_arena, *index)?);
+                branch.push(tree_hash::proof::length_chunk(validators.len()));
+
+                Ok((leaf, branch))
+            }
+            Some((PathElement::Length, [])) => Ok((
+                tree_hash::proof::length_chunk(validators.len()),
+                vec![list_root],
+            )),
+            Some((other, _)) => Err(ProofError::unexpected(other).into()),
+        }
+    }
 }
 
 /// Provides a cache for each of the `Validator` objects in `state.validators` and computes the
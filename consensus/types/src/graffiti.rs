@@ -0,0 +1,52 @@
+//! The free-form bytes that a block proposer may include in a `BeaconBlockBody`.
+use crate::utils::GRAFFITI_BYTES_LEN;
+
+/// The `graffiti` field of a `BeaconBlockBody`.
+pub type Graffiti = [u8; GRAFFITI_BYTES_LEN];
+
+/// Converts a UTF-8 `string` into `Graffiti`, padding any remaining bytes with zeros.
+///
+/// Returns an error if `string` is longer than `GRAFFITI_BYTES_LEN` bytes.
+pub fn graffiti_from_str(string: &str) -> Result<Graffiti, String> {
+    let bytes = string.as_bytes();
+
+    if bytes.len() > GRAFFITI_BYTES_LEN {
+        return Err(format!(
+            "Graffiti exceeds max length of {} bytes: {} bytes",
+            GRAFFITI_BYTES_LEN,
+            bytes.len()
+        ));
+    }
+
+    let mut graffiti = [0; GRAFFITI_BYTES_LEN];
+    graffiti[..bytes.len()].copy_from_slice(bytes);
+
+    Ok(graffiti)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn pads_short_strings() {
+        let graffiti = graffiti_from_str("hello").unwrap();
+
+        assert_eq!(&graffiti[..5], b"hello");
+        assert!(graffiti[5..].iter().all(|byte| *byte == 0));
+    }
+
+    #[test]
+    fn accepts_max_length() {
+        let string = "a".repeat(GRAFFITI_BYTES_LEN);
+
+        assert_eq!(&graffiti_from_str(&string).unwrap()[..], string.as_bytes());
+    }
+
+    #[test]
+    fn rejects_long_strings() {
+        let string = "a".repeat(GRAFFITI_BYTES_LEN) + "b";
+
+        assert!(graffiti_from_str(&string).is_err());
+    }
+}
@@ -29,8 +29,10 @@ pub mod eth_spec;
 pub mod fork;
 pub mod fork_data;
 pub mod free_attestation;
+pub mod graffiti;
 pub mod historical_batch;
 pub mod indexed_attestation;
+pub mod light_client_finality_proof;
 pub mod pending_attestation;
 pub mod proposer_slashing;
 pub mod relative_epoch;
@@ -74,8 +76,10 @@ pub use crate::eth1_data::Eth1Data;
 pub use crate::fork::Fork;
 pub use crate::fork_data::ForkData;
 pub use crate::free_attestation::FreeAttestation;
+pub use crate::graffiti::{graffiti_from_str, Graffiti};
 pub use crate::historical_batch::HistoricalBatch;
 pub use crate::indexed_attestation::IndexedAttestation;
+pub use crate::light_client_finality_proof::LightClientFinalityProof;
 pub use crate::pending_attestation::PendingAttestation;
 pub use crate::proposer_slashing::ProposerSlashing;
 pub use crate::relative_epoch::{Error as RelativeEpochError, RelativeEpoch};
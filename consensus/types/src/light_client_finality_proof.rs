@@ -0,0 +1,136 @@
+use crate::test_utils::TestRandom;
+use crate::{BeaconBlockHeader, BeaconState, BeaconStateError, Checkpoint, EthSpec, Hash256};
+use serde_derive::{Deserialize, Serialize};
+use ssz_derive::{Decode, Encode};
+use ssz_types::{typenum::U5, FixedVector};
+use test_random_derive::TestRandom;
+use tree_hash::{PathElement, TreeHash};
+use tree_hash_derive::TreeHash;
+
+/// The generalized index of `finalized_checkpoint` within a `BeaconState`.
+pub const FINALIZED_CHECKPOINT_GENERALIZED_INDEX: usize = 52;
+
+/// The generalized index of `current_justified_checkpoint` within a `BeaconState`.
+pub const CURRENT_JUSTIFIED_CHECKPOINT_GENERALIZED_INDEX: usize = 51;
+
+/// The header of a block, along with Merkle branches proving the checkpoints of its post-state
+/// against `header.state_root`.
+///
+/// Proofs are stored under `finalized_checkpoint.root`, using the header of a block whose
+/// post-state records that checkpoint as finalized. This allows a light client to follow finality
+/// using only headers and proofs, rather than full states.
+#[cfg_attr(feature = "arbitrary-fuzz", derive(arbitrary::Arbitrary))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Encode, Decode, TreeHash, TestRandom)]
+pub struct LightClientFinalityProof {
+    /// The header of the block whose post-state records the checkpoints.
+    pub header: BeaconBlockHeader,
+    /// The `finalized_checkpoint` of the block's post-state.
+    pub finalized_checkpoint: Checkpoint,
+    /// The Merkle branch of `finalized_checkpoint`, in bottom-up order.
+    pub finalized_checkpoint_branch: FixedVector<Hash256, U5>,
+    /// The `current_justified_checkpoint` of the block's post-state.
+    pub current_justified_checkpoint: Checkpoint,
+    /// The Merkle branch of `current_justified_checkpoint`, in bottom-up order.
+    pub current_justified_checkpoint_branch: FixedVector<Hash256, U5>,
+}
+
+impl LightClientFinalityProof {
+    /// Produces a proof of the checkpoints of `state`, which must be the post-state of the block
+    /// with the given `header`.
+    ///
+    /// Uses (and initializes, if necessary) the tree hash cache of the `state`.
+    pub fn new<T: EthSpec>(
+        header: BeaconBlockHeader,
+        state: &mut BeaconState<T>,
+    ) -> Result<Self, BeaconStateError> {
+        let (_, finalized_checkpoint_branch) =
+            state.compute_merkle_proof(&[PathElement::Field("finalized_checkpoint")])?;
+        let (_, current_justified_checkpoint_branch) =
+            state.compute_merkle_proof(&[PathElement::Field("current_justified_checkpoint")])?;
+
+        Ok(Self {
+            header,
+            finalized_checkpoint: state.finalized_checkpoint,
+            finalized_checkpoint_branch: FixedVector::new(finalized_checkpoint_branch)?,
+            current_justified_checkpoint: state.current_justified_checkpoint,
+            current_justified_checkpoint_branch: FixedVector::new(
+                current_justified_checkpoint_branch,
+            )?,
+        })
+    }
+
+    /// Returns `true` if both checkpoints are proven against `self.header.state_root`.
+    pub fn verify(&self) -> bool {
+        let proves = |checkpoint: &Checkpoint, branch: &[Hash256], index: usize| {
+            merkle_proof::calculate_merkle_root(checkpoint.tree_hash_root(), branch, index)
+                .map_or(false, |root| root == self.header.state_root)
+        };
+
+        proves(
+            &self.finalized_checkpoint,
+            &self.finalized_checkpoint_branch,
+            FINALIZED_CHECKPOINT_GENERALIZED_INDEX,
+        ) && proves(
+            &self.current_justified_checkpoint,
+            &self.current_justified_checkpoint_branch,
+            CURRENT_JUSTIFIED_CHECKPOINT_GENERALIZED_INDEX,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TestingBeaconStateBuilder;
+    use crate::{Epoch, MinimalEthSpec};
+
+    ssz_and_tree_hash_tests!(LightClientFinalityProof);
+
+    #[test]
+    fn generalized_indices() {
+        type E = MinimalEthSpec;
+        assert_eq!(
+            tree_hash::proof::generalized_index::<BeaconState<E>>(&[PathElement::Field(
+                "finalized_checkpoint"
+            )]),
+            Ok(FINALIZED_CHECKPOINT_GENERALIZED_INDEX)
+        );
+        assert_eq!(
+            tree_hash::proof::generalized_index::<BeaconState<E>>(&[PathElement::Field(
+                "current_justified_checkpoint"
+            )]),
+            Ok(CURRENT_JUSTIFIED_CHECKPOINT_GENERALIZED_INDEX)
+        );
+    }
+
+    #[test]
+    fn verify() {
+        let spec = MinimalEthSpec::default_spec();
+        let builder: TestingBeaconStateBuilder<MinimalEthSpec> =
+            TestingBeaconStateBuilder::from_deterministic_keypairs(16, &spec);
+        let (mut state, _keypairs) = builder.build();
+        state.finalized_checkpoint = Checkpoint {
+            epoch: Epoch::new(2),
+            root: Hash256::repeat_byte(2),
+        };
+        state.current_justified_checkpoint = Checkpoint {
+            epoch: Epoch::new(3),
+            root: Hash256::repeat_byte(3),
+        };
+
+        let header = BeaconBlockHeader {
+            state_root: state.tree_hash_root(),
+            ..state.latest_block_header.clone()
+        };
+        let proof = LightClientFinalityProof::new(header, &mut state).unwrap();
+        assert!(proof.verify());
+
+        let mut wrong_checkpoint = proof.clone();
+        wrong_checkpoint.finalized_checkpoint.epoch = Epoch::new(4);
+        assert!(!wrong_checkpoint.verify());
+
+        let mut wrong_state_root = proof;
+        wrong_state_root.header.state_root = Hash256::zero();
+        assert!(!wrong_state_root.verify());
+    }
+}
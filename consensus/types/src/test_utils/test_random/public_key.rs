@@ -4,6 +4,6 @@ use bls::{PublicKey, SecretKey};
 impl TestRandom for PublicKey {
     fn random_for_test(rng: &mut impl RngCore) -> Self {
         let secret_key = SecretKey::random_for_test(rng);
-        PublicKey::from_secret_key(&secret_key)
+        secret_key.public_key()
     }
 }
@@ -0,0 +1,49 @@
+use criterion::{black_box, criterion_group, criterion_main, Benchmark, Criterion};
+use std::borrow::Cow;
+
+const MSG: &[u8] = &[42; 32];
+
+/// Generates a benchmark of `verify_signature_sets` for the backend types in `$type`.
+macro_rules! bench_signature_sets {
+    ($c: ident, $type: ident, $name: expr, $num_sets: expr) => {{
+        use bls::$type::*;
+
+        let keypairs = (0..$num_sets)
+            .map(|_| Keypair::random())
+            .collect::<Vec<_>>();
+        let signatures = keypairs
+            .iter()
+            .map(|keypair| keypair.sk.sign(MSG))
+            .collect::<Vec<_>>();
+
+        $c.bench(
+            "verify_signature_sets",
+            Benchmark::new(format!("{} {} sets", $name, $num_sets), move |b| {
+                b.iter(|| {
+                    let sets = keypairs
+                        .iter()
+                        .zip(signatures.iter())
+                        .map(|(keypair, sig)| {
+                            SignatureSet::single(sig, Cow::Borrowed(&keypair.pk), MSG.to_vec())
+                        })
+                        .collect::<Vec<_>>();
+
+                    assert!(black_box(verify_signature_sets(sets)))
+                })
+            })
+            .sample_size(10),
+        );
+    }};
+}
+
+fn signature_sets(c: &mut Criterion) {
+    for num_sets in &[1, 16, 64] {
+        #[cfg(feature = "milagro")]
+        bench_signature_sets!(c, milagro_implementations, "milagro", *num_sets);
+        #[cfg(feature = "supranational")]
+        bench_signature_sets!(c, blst_implementations, "blst", *num_sets);
+    }
+}
+
+criterion_group!(benches, signature_sets);
+criterion_main!(benches);
@@ -0,0 +1,145 @@
+use crate::{
+    generic_public_key::{GenericPublicKey, TPublicKey},
+    BLS_PUBLIC_KEY_BYTE_SIZE,
+};
+use serde::de::Deserialize;
+use serde::ser::Serialize;
+use ssz::{Decode, DecodeError, Encode};
+use std::fmt;
+use std::marker::PhantomData;
+use tree_hash::TreeHash;
+
+/// Implemented on some struct from a BLS library so it may be used as the `point` in a
+/// `GenericAggregatePublicKey`.
+pub trait TAggregatePublicKey<PublicKeyPoint>: Sized + Clone {
+    /// Initialize `Self` to the point at infinity, to which public keys may be added.
+    fn infinity() -> Self;
+
+    /// Aggregates `other` into `self`.
+    fn add_assign(&mut self, other: &PublicKeyPoint);
+
+    /// Serialize `self` as compressed bytes.
+    fn serialize(&self) -> [u8; BLS_PUBLIC_KEY_BYTE_SIZE];
+
+    /// Deserialize `self` from compressed bytes.
+    fn deserialize(bytes: &[u8]) -> Result<Self, DecodeError>;
+}
+
+/// A BLS aggregate public key that is generic across some BLS point (`AggPub`).
+///
+/// Provides generic functionality whilst deferring all serious cryptographic operations to
+/// `AggPub`.
+#[derive(Clone)]
+pub struct GenericAggregatePublicKey<Pub, AggPub> {
+    /// The underlying point which performs *actual* cryptographic operations.
+    point: AggPub,
+    _phantom: PhantomData<Pub>,
+}
+
+impl<Pub, AggPub> GenericAggregatePublicKey<Pub, AggPub>
+where
+    Pub: TPublicKey,
+    AggPub: TAggregatePublicKey<Pub>,
+{
+    /// Initialize `Self` to the point at infinity, to which public keys may be added.
+    pub fn new() -> Self {
+        Self {
+            point: AggPub::infinity(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Instantiates `Self` as the aggregate of `pubkeys`.
+    pub fn aggregate(pubkeys: &[&GenericPublicKey<Pub>]) -> Self {
+        let mut aggregate = Self::new();
+        for pubkey in pubkeys {
+            aggregate.add(pubkey);
+        }
+        aggregate
+    }
+
+    /// Returns a reference to the underlying BLS point.
+    pub(crate) fn point(&self) -> &AggPub {
+        &self.point
+    }
+
+    /// Aggregates `public_key` into `self`.
+    pub fn add(&mut self, public_key: &GenericPublicKey<Pub>) {
+        self.point.add_assign(public_key.point())
+    }
+
+    /// Returns `self` as compressed bytes.
+    pub fn as_bytes(&self) -> [u8; BLS_PUBLIC_KEY_BYTE_SIZE] {
+        self.point.serialize()
+    }
+
+    /// Deserialize `self` from compressed bytes.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        if bytes.len() != BLS_PUBLIC_KEY_BYTE_SIZE {
+            return Err(DecodeError::InvalidByteLength {
+                len: bytes.len(),
+                expected: BLS_PUBLIC_KEY_BYTE_SIZE,
+            });
+        }
+
+        Ok(Self {
+            point: AggPub::deserialize(bytes)?,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+impl<Pub, AggPub> Default for GenericAggregatePublicKey<Pub, AggPub>
+where
+    Pub: TPublicKey,
+    AggPub: TAggregatePublicKey<Pub>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Pub: TPublicKey, AggPub: TAggregatePublicKey<Pub>> fmt::Debug
+    for GenericAggregatePublicKey<Pub, AggPub>
+{
+    impl_debug!();
+}
+
+impl<Pub: TPublicKey, AggPub: TAggregatePublicKey<Pub>> Encode
+    for GenericAggregatePublicKey<Pub, AggPub>
+{
+    impl_ssz_encode!(BLS_PUBLIC_KEY_BYTE_SIZE);
+}
+
+impl<Pub: TPublicKey, AggPub: TAggregatePublicKey<Pub>> Decode
+    for GenericAggregatePublicKey<Pub, AggPub>
+{
+    impl_ssz_decode!(BLS_PUBLIC_KEY_BYTE_SIZE);
+}
+
+impl<Pub: TPublicKey, AggPub: TAggregatePublicKey<Pub>> TreeHash
+    for GenericAggregatePublicKey<Pub, AggPub>
+{
+    impl_tree_hash!(BLS_PUBLIC_KEY_BYTE_SIZE);
+}
+
+impl<Pub: TPublicKey, AggPub: TAggregatePublicKey<Pub>> Serialize
+    for GenericAggregatePublicKey<Pub, AggPub>
+{
+    impl_serde_serialize!();
+}
+
+impl<'de, Pub: TPublicKey, AggPub: TAggregatePublicKey<Pub>> Deserialize<'de>
+    for GenericAggregatePublicKey<Pub, AggPub>
+{
+    impl_serde_deserialize!();
+}
+
+#[cfg(feature = "arbitrary")]
+impl<Pub, AggPub> arbitrary::Arbitrary for GenericAggregatePublicKey<Pub, AggPub>
+where
+    Pub: TPublicKey + 'static,
+    AggPub: TAggregatePublicKey<Pub> + 'static,
+{
+    impl_arbitrary!(BLS_PUBLIC_KEY_BYTE_SIZE);
+}
@@ -0,0 +1,305 @@
+use crate::{
+    generic_aggregate_public_key::{GenericAggregatePublicKey, TAggregatePublicKey},
+    generic_public_key::{GenericPublicKey, TPublicKey},
+    generic_signature::{GenericSignature, TSignature},
+    BLS_AGG_SIG_BYTE_SIZE,
+};
+use serde::de::Deserialize;
+use serde::ser::Serialize;
+use ssz::{Decode, DecodeError, Encode};
+use std::fmt;
+use std::marker::PhantomData;
+use tree_hash::TreeHash;
+
+/// Implemented on some struct from a BLS library so it may be used as the `point` in a
+/// `GenericAggregateSignature`.
+pub trait TAggregateSignature<Pub, AggPub, Sig>: Sized + Clone {
+    /// Initialize `Self` to the point at infinity, to which signatures may be added.
+    fn infinity() -> Self;
+
+    /// Aggregates a signature onto `self`.
+    fn add_assign(&mut self, other: &Sig);
+
+    /// Aggregates an aggregate signature onto `self`.
+    fn add_assign_aggregate(&mut self, other: &Self);
+
+    /// Serialize `self` as compressed bytes.
+    fn serialize(&self) -> [u8; BLS_AGG_SIG_BYTE_SIZE];
+
+    /// Deserialize `self` from compressed bytes.
+    fn deserialize(bytes: &[u8]) -> Result<Self, DecodeError>;
+
+    /// Verify that `self` represents an aggregate signature across `msg` by the keys which were
+    /// aggregated into `aggregate_pubkey`.
+    fn fast_aggregate_verify_pre_aggregated(&self, msg: &[u8], aggregate_pubkey: &AggPub) -> bool;
+
+    /// Verify that `self` represents an aggregate signature across `msg` by all of `pubkeys`.
+    fn fast_aggregate_verify(&self, msg: &[u8], pubkeys: &[&GenericPublicKey<Pub>]) -> bool;
+
+    /// Verify that `self` represents an aggregate signature where each pubkey signed its
+    /// corresponding message.
+    fn aggregate_verify(&self, msgs: &[&[u8]], pubkeys: &[&GenericPublicKey<Pub>]) -> bool;
+}
+
+/// A BLS aggregate signature that is generic across:
+///
+/// - `Pub`: A BLS public key.
+/// - `AggPub`: A BLS aggregate public key.
+/// - `Sig`: A BLS signature.
+/// - `AggSig`: A BLS aggregate signature.
+///
+/// Provides generic functionality whilst deferring all serious cryptographic operations to the
+/// generics.
+///
+/// As with `GenericSignature`, an aggregate signature deserialized from all-zero bytes is
+/// considered "empty" and never passes verification.
+#[derive(Clone)]
+pub struct GenericAggregateSignature<Pub, AggPub, Sig, AggSig> {
+    /// The underlying point which performs *actual* cryptographic operations.
+    point: Option<AggSig>,
+    _phantom_pub: PhantomData<Pub>,
+    _phantom_agg_pub: PhantomData<AggPub>,
+    _phantom_signature: PhantomData<Sig>,
+}
+
+impl<Pub, AggPub, Sig, AggSig> GenericAggregateSignature<Pub, AggPub, Sig, AggSig>
+where
+    Pub: TPublicKey,
+    AggPub: TAggregatePublicKey<Pub>,
+    Sig: TSignature<Pub>,
+    AggSig: TAggregateSignature<Pub, AggPub, Sig>,
+{
+    /// Initialize `Self` to the point at infinity, to which signatures may be added.
+    ///
+    /// Note: this is _not_ an empty signature.
+    pub fn new() -> Self {
+        Self::from_point(Some(AggSig::infinity()))
+    }
+
+    /// Returns a new empty signature.
+    pub fn empty_signature() -> Self {
+        Self::from_point(None)
+    }
+
+    fn from_point(point: Option<AggSig>) -> Self {
+        Self {
+            point,
+            _phantom_pub: PhantomData,
+            _phantom_agg_pub: PhantomData,
+            _phantom_signature: PhantomData,
+        }
+    }
+
+    /// Instantiates `Self` as the aggregate of the single `signature`.
+    ///
+    /// The result is empty if `signature` is empty.
+    pub fn from_signature(signature: &GenericSignature<Pub, Sig>) -> Self {
+        let mut aggregate = Self::empty_signature();
+        aggregate.add(signature);
+        aggregate
+    }
+
+    /// Returns a reference to the underlying BLS point, if the signature is not empty.
+    pub(crate) fn point(&self) -> Option<&AggSig> {
+        self.point.as_ref()
+    }
+
+    /// Returns `true` if `self` was deserialized from all-zero bytes, or is the aggregate of
+    /// only empty signatures.
+    pub fn is_empty(&self) -> bool {
+        self.point.is_none()
+    }
+
+    /// Aggregates `signature` onto `self`.
+    ///
+    /// Adding an empty signature is equivalent to adding the point at infinity.
+    pub fn add(&mut self, signature: &GenericSignature<Pub, Sig>) {
+        if let Some(other_point) = signature.point() {
+            self.point
+                .get_or_insert_with(AggSig::infinity)
+                .add_assign(other_point)
+        }
+    }
+
+    /// Aggregates `aggregate_signature` onto `self`.
+    ///
+    /// Adding an empty signature is equivalent to adding the point at infinity.
+    pub fn add_aggregate(&mut self, aggregate_signature: &Self) {
+        if let Some(other_point) = aggregate_signature.point() {
+            self.point
+                .get_or_insert_with(AggSig::infinity)
+                .add_assign_aggregate(other_point)
+        }
+    }
+
+    /// Returns `self` as compressed bytes, or all-zeros if `self` is empty.
+    pub fn as_bytes(&self) -> [u8; BLS_AGG_SIG_BYTE_SIZE] {
+        match &self.point {
+            Some(point) => point.serialize(),
+            None => [0; BLS_AGG_SIG_BYTE_SIZE],
+        }
+    }
+
+    /// Deserialize `self` from compressed bytes, producing an empty signature from all-zero
+    /// bytes.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        if bytes.len() != BLS_AGG_SIG_BYTE_SIZE {
+            return Err(DecodeError::InvalidByteLength {
+                len: bytes.len(),
+                expected: BLS_AGG_SIG_BYTE_SIZE,
+            });
+        }
+
+        if bytes.iter().all(|byte| *byte == 0) {
+            Ok(Self::empty_signature())
+        } else {
+            AggSig::deserialize(bytes).map(|point| Self::from_point(Some(point)))
+        }
+    }
+
+    /// Verify the `AggregateSignature` against an `AggregatePublicKey`.
+    ///
+    /// Only returns `true` if the set of keys in the `AggregatePublicKey` match the set of keys
+    /// that signed the `AggregateSignature`.
+    pub fn verify(
+        &self,
+        msg: &[u8],
+        aggregate_public_key: &GenericAggregatePublicKey<Pub, AggPub>,
+    ) -> bool {
+        match &self.point {
+            Some(point) => {
+                point.fast_aggregate_verify_pre_aggregated(msg, aggregate_public_key.point())
+            }
+            None => false,
+        }
+    }
+
+    /// Verify the `AggregateSignature` against each of the `public_keys`, which all signed
+    /// `msg`.
+    pub fn verify_unaggregated(&self, msg: &[u8], public_keys: &[&GenericPublicKey<Pub>]) -> bool {
+        match &self.point {
+            Some(point) => point.fast_aggregate_verify(msg, public_keys),
+            None => false,
+        }
+    }
+
+    /// Verify this AggregateSignature against multiple public keys and messages.
+    ///
+    /// Each public key has a 1:1 ratio with a 32 byte message.
+    pub fn verify_multiple(
+        &self,
+        messages: &[&[u8]],
+        public_keys: &[&GenericPublicKey<Pub>],
+    ) -> bool {
+        match &self.point {
+            Some(point) => point.aggregate_verify(messages, public_keys),
+            None => false,
+        }
+    }
+}
+
+impl<Pub, AggPub, Sig, AggSig> Default for GenericAggregateSignature<Pub, AggPub, Sig, AggSig>
+where
+    Pub: TPublicKey,
+    AggPub: TAggregatePublicKey<Pub>,
+    Sig: TSignature<Pub>,
+    AggSig: TAggregateSignature<Pub, AggPub, Sig>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Pub, AggPub, Sig, AggSig> Eq for GenericAggregateSignature<Pub, AggPub, Sig, AggSig>
+where
+    Pub: TPublicKey,
+    AggPub: TAggregatePublicKey<Pub>,
+    Sig: TSignature<Pub>,
+    AggSig: TAggregateSignature<Pub, AggPub, Sig>,
+{
+}
+
+impl<Pub, AggPub, Sig, AggSig> PartialEq for GenericAggregateSignature<Pub, AggPub, Sig, AggSig>
+where
+    Pub: TPublicKey,
+    AggPub: TAggregatePublicKey<Pub>,
+    Sig: TSignature<Pub>,
+    AggSig: TAggregateSignature<Pub, AggPub, Sig>,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.as_bytes()[..] == other.as_bytes()[..]
+    }
+}
+
+impl<Pub, AggPub, Sig, AggSig> fmt::Debug for GenericAggregateSignature<Pub, AggPub, Sig, AggSig>
+where
+    Pub: TPublicKey,
+    AggPub: TAggregatePublicKey<Pub>,
+    Sig: TSignature<Pub>,
+    AggSig: TAggregateSignature<Pub, AggPub, Sig>,
+{
+    impl_debug!();
+}
+
+impl<Pub, AggPub, Sig, AggSig> Encode for GenericAggregateSignature<Pub, AggPub, Sig, AggSig>
+where
+    Pub: TPublicKey,
+    AggPub: TAggregatePublicKey<Pub>,
+    Sig: TSignature<Pub>,
+    AggSig: TAggregateSignature<Pub, AggPub, Sig>,
+{
+    impl_ssz_encode!(BLS_AGG_SIG_BYTE_SIZE);
+}
+
+impl<Pub, AggPub, Sig, AggSig> Decode for GenericAggregateSignature<Pub, AggPub, Sig, AggSig>
+where
+    Pub: TPublicKey,
+    AggPub: TAggregatePublicKey<Pub>,
+    Sig: TSignature<Pub>,
+    AggSig: TAggregateSignature<Pub, AggPub, Sig>,
+{
+    impl_ssz_decode!(BLS_AGG_SIG_BYTE_SIZE);
+}
+
+impl<Pub, AggPub, Sig, AggSig> TreeHash for GenericAggregateSignature<Pub, AggPub, Sig, AggSig>
+where
+    Pub: TPublicKey,
+    AggPub: TAggregatePublicKey<Pub>,
+    Sig: TSignature<Pub>,
+    AggSig: TAggregateSignature<Pub, AggPub, Sig>,
+{
+    impl_tree_hash!(BLS_AGG_SIG_BYTE_SIZE);
+}
+
+impl<Pub, AggPub, Sig, AggSig> Serialize for GenericAggregateSignature<Pub, AggPub, Sig, AggSig>
+where
+    Pub: TPublicKey,
+    AggPub: TAggregatePublicKey<Pub>,
+    Sig: TSignature<Pub>,
+    AggSig: TAggregateSignature<Pub, AggPub, Sig>,
+{
+    impl_serde_serialize!();
+}
+
+impl<'de, Pub, AggPub, Sig, AggSig> Deserialize<'de>
+    for GenericAggregateSignature<Pub, AggPub, Sig, AggSig>
+where
+    Pub: TPublicKey,
+    AggPub: TAggregatePublicKey<Pub>,
+    Sig: TSignature<Pub>,
+    AggSig: TAggregateSignature<Pub, AggPub, Sig>,
+{
+    impl_serde_deserialize!();
+}
+
+#[cfg(feature = "arbitrary")]
+impl<Pub, AggPub, Sig, AggSig> arbitrary::Arbitrary
+    for GenericAggregateSignature<Pub, AggPub, Sig, AggSig>
+where
+    Pub: TPublicKey + 'static,
+    AggPub: TAggregatePublicKey<Pub> + 'static,
+    Sig: TSignature<Pub> + 'static,
+    AggSig: TAggregateSignature<Pub, AggPub, Sig> + 'static,
+{
+    impl_arbitrary!(BLS_AGG_SIG_BYTE_SIZE);
+}
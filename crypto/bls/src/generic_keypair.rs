@@ -0,0 +1,58 @@
+use crate::{
+    generic_public_key::{GenericPublicKey, TPublicKey},
+    generic_secret_key::{GenericSecretKey, TSecretKey},
+    generic_signature::TSignature,
+};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+/// A BLS secret key and the public key derived from it.
+#[derive(Clone)]
+pub struct GenericKeypair<Pub, Sec, Sig> {
+    pub pk: GenericPublicKey<Pub>,
+    pub sk: GenericSecretKey<Sig, Pub, Sec>,
+}
+
+impl<Pub, Sec, Sig> GenericKeypair<Pub, Sec, Sig>
+where
+    Pub: TPublicKey,
+    Sec: TSecretKey<Sig, Pub>,
+    Sig: TSignature<Pub>,
+{
+    /// Instantiate a Keypair using SecretKey::random().
+    pub fn random() -> Self {
+        let sk = GenericSecretKey::random();
+        Self {
+            pk: sk.public_key(),
+            sk,
+        }
+    }
+
+    pub fn identifier(&self) -> String {
+        self.pk.concatenated_hex_id()
+    }
+}
+
+impl<Pub, Sec, Sig> Hash for GenericKeypair<Pub, Sec, Sig>
+where
+    Pub: TPublicKey,
+{
+    /// Note: this is distinct from consensus serialization, it will produce a different hash.
+    ///
+    /// This method uses the uncompressed bytes, which are much faster to obtain than the
+    /// compressed bytes required for consensus serialization.
+    ///
+    /// Use `ssz::Encode` to obtain the bytes required for consensus hashing.
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.pk.hash(state)
+    }
+}
+
+impl<Pub, Sec, Sig> fmt::Display for GenericKeypair<Pub, Sec, Sig>
+where
+    Pub: TPublicKey,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.pk)
+    }
+}
@@ -0,0 +1,141 @@
+use crate::BLS_PUBLIC_KEY_BYTE_SIZE;
+use serde::de::Deserialize;
+use serde::ser::Serialize;
+use serde_hex::encode as hex_encode;
+use ssz::{Decode, DecodeError, Encode};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use tree_hash::TreeHash;
+
+/// Implemented on some struct from a BLS library so it may be used as the `point` in a
+/// `GenericPublicKey`.
+pub trait TPublicKey: Sized + Clone {
+    /// Serialize `self` as compressed bytes.
+    fn serialize(&self) -> [u8; BLS_PUBLIC_KEY_BYTE_SIZE];
+
+    /// Serialize `self` as uncompressed bytes.
+    fn serialize_uncompressed(&self) -> [u8; BLS_PUBLIC_KEY_BYTE_SIZE * 2];
+
+    /// Deserialize `self` from compressed bytes.
+    ///
+    /// Must reject the point at infinity and points which are not in the correct subgroup.
+    fn deserialize(bytes: &[u8]) -> Result<Self, DecodeError>;
+}
+
+/// A BLS public key that is generic across some BLS point (`Pub`).
+///
+/// Provides generic functionality whilst deferring all serious cryptographic operations to `Pub`.
+#[derive(Clone)]
+pub struct GenericPublicKey<Pub> {
+    /// The underlying point which performs *actual* cryptographic operations.
+    point: Pub,
+}
+
+impl<Pub> GenericPublicKey<Pub>
+where
+    Pub: TPublicKey,
+{
+    /// Instantiates `Self` from a `point`.
+    pub(crate) fn from_point(point: Pub) -> Self {
+        Self { point }
+    }
+
+    /// Returns a reference to the underlying BLS point.
+    pub(crate) fn point(&self) -> &Pub {
+        &self.point
+    }
+
+    /// Returns `self` as compressed bytes.
+    pub fn as_bytes(&self) -> [u8; BLS_PUBLIC_KEY_BYTE_SIZE] {
+        self.point.serialize()
+    }
+
+    /// Deserialize `self` from compressed bytes.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        if bytes.len() != BLS_PUBLIC_KEY_BYTE_SIZE {
+            return Err(DecodeError::InvalidByteLength {
+                len: bytes.len(),
+                expected: BLS_PUBLIC_KEY_BYTE_SIZE,
+            });
+        }
+
+        Ok(Self {
+            point: Pub::deserialize(bytes)?,
+        })
+    }
+
+    /// Returns `self` as uncompressed (x, y) bytes.
+    pub fn as_uncompressed_bytes(&self) -> [u8; BLS_PUBLIC_KEY_BYTE_SIZE * 2] {
+        self.point.serialize_uncompressed()
+    }
+
+    /// Returns the first 6 characters of the hex encoding of the public key (including the `0x`
+    /// prefix).
+    ///
+    /// Useful for providing a short identifier to the user.
+    pub fn concatenated_hex_id(&self) -> String {
+        self.as_hex_string()[0..6].to_string()
+    }
+
+    /// Returns the point as a hex string of the SSZ encoding.
+    ///
+    /// Note: the string is prefixed with `0x`.
+    pub fn as_hex_string(&self) -> String {
+        hex_encode(&self.as_bytes()[..])
+    }
+}
+
+impl<Pub: TPublicKey> Eq for GenericPublicKey<Pub> {}
+
+impl<Pub: TPublicKey> PartialEq for GenericPublicKey<Pub> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_bytes()[..] == other.as_bytes()[..]
+    }
+}
+
+impl<Pub: TPublicKey> Hash for GenericPublicKey<Pub> {
+    /// Note: this is distinct from consensus serialization, it will produce a different hash.
+    ///
+    /// This method uses the uncompressed bytes, which are much faster to obtain than the
+    /// compressed bytes required for consensus serialization.
+    ///
+    /// Use `ssz::Encode` to obtain the bytes required for consensus hashing.
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_uncompressed_bytes()[..].hash(state)
+    }
+}
+
+impl<Pub: TPublicKey> fmt::Display for GenericPublicKey<Pub> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.concatenated_hex_id())
+    }
+}
+
+impl<Pub: TPublicKey> fmt::Debug for GenericPublicKey<Pub> {
+    impl_debug!();
+}
+
+impl<Pub: TPublicKey> Encode for GenericPublicKey<Pub> {
+    impl_ssz_encode!(BLS_PUBLIC_KEY_BYTE_SIZE);
+}
+
+impl<Pub: TPublicKey> Decode for GenericPublicKey<Pub> {
+    impl_ssz_decode!(BLS_PUBLIC_KEY_BYTE_SIZE);
+}
+
+impl<Pub: TPublicKey> TreeHash for GenericPublicKey<Pub> {
+    impl_tree_hash!(BLS_PUBLIC_KEY_BYTE_SIZE);
+}
+
+impl<Pub: TPublicKey> Serialize for GenericPublicKey<Pub> {
+    impl_serde_serialize!();
+}
+
+impl<'de, Pub: TPublicKey> Deserialize<'de> for GenericPublicKey<Pub> {
+    impl_serde_deserialize!();
+}
+
+#[cfg(feature = "arbitrary")]
+impl<Pub: TPublicKey + 'static> arbitrary::Arbitrary for GenericPublicKey<Pub> {
+    impl_arbitrary!(BLS_PUBLIC_KEY_BYTE_SIZE);
+}
@@ -0,0 +1,90 @@
+use crate::{
+    generic_public_key::{GenericPublicKey, TPublicKey},
+    generic_signature::{GenericSignature, TSignature},
+    SecretHash, BLS_SECRET_KEY_BYTE_SIZE,
+};
+use ssz::DecodeError;
+use std::marker::PhantomData;
+
+/// Implemented on some struct from a BLS library so it may be used as the `point` in a
+/// `GenericSecretKey`.
+pub trait TSecretKey<SignaturePoint, PublicKeyPoint>: Sized {
+    /// Generate a new `Self` using `rand::thread_rng`.
+    fn random() -> Self;
+
+    /// Signs `msg`.
+    fn sign(&self, msg: &[u8]) -> SignaturePoint;
+
+    /// Produces a public key from `self`.
+    fn public_key(&self) -> PublicKeyPoint;
+
+    /// Serialize `self` as bytes.
+    fn serialize(&self) -> SecretHash;
+
+    /// Deserialize `self` from bytes.
+    fn deserialize(bytes: &[u8]) -> Result<Self, DecodeError>;
+}
+
+/// A BLS secret key that is generic across some BLS point (`Sec`).
+///
+/// Provides generic functionality whilst deferring all serious cryptographic operations to `Sec`.
+#[derive(Clone)]
+pub struct GenericSecretKey<Sig, Pub, Sec> {
+    /// The underlying point which performs *actual* cryptographic operations.
+    point: Sec,
+    _phantom_signature: PhantomData<Sig>,
+    _phantom_public_key: PhantomData<Pub>,
+}
+
+impl<Sig, Pub, Sec> GenericSecretKey<Sig, Pub, Sec>
+where
+    Sig: TSignature<Pub>,
+    Pub: TPublicKey,
+    Sec: TSecretKey<Sig, Pub>,
+{
+    /// Generate a new `Self` using `rand::thread_rng`.
+    pub fn random() -> Self {
+        Self {
+            point: Sec::random(),
+            _phantom_signature: PhantomData,
+            _phantom_public_key: PhantomData,
+        }
+    }
+
+    /// Signs `msg`.
+    pub fn sign(&self, msg: &[u8]) -> GenericSignature<Pub, Sig> {
+        GenericSignature::from_point(self.point.sign(msg))
+    }
+
+    /// Produces a public key from `self`.
+    pub fn public_key(&self) -> GenericPublicKey<Pub> {
+        GenericPublicKey::from_point(self.point.public_key())
+    }
+
+    /// Returns the secret key as a byte array (wrapped in `SecretHash` wrapper so it is zeroized on
+    /// `Drop`).
+    ///
+    /// Extreme care should be taken not to leak these bytes as they are the unencrypted secret
+    /// key.
+    pub fn as_bytes(&self) -> SecretHash {
+        self.point.serialize()
+    }
+
+    /// Instantiate a SecretKey from existing bytes.
+    ///
+    /// Note: this is _not_ SSZ decoding.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        if bytes.len() != BLS_SECRET_KEY_BYTE_SIZE {
+            return Err(DecodeError::InvalidByteLength {
+                len: bytes.len(),
+                expected: BLS_SECRET_KEY_BYTE_SIZE,
+            });
+        }
+
+        Ok(Self {
+            point: Sec::deserialize(bytes)?,
+            _phantom_signature: PhantomData,
+            _phantom_public_key: PhantomData,
+        })
+    }
+}
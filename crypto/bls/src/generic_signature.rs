@@ -0,0 +1,158 @@
+use crate::{
+    generic_public_key::{GenericPublicKey, TPublicKey},
+    generic_secret_key::{GenericSecretKey, TSecretKey},
+    BLS_SIG_BYTE_SIZE,
+};
+use serde::de::Deserialize;
+use serde::ser::Serialize;
+use ssz::{Decode, DecodeError, Encode};
+use std::fmt;
+use std::marker::PhantomData;
+use tree_hash::TreeHash;
+
+/// Implemented on some struct from a BLS library so it may be used as the `point` in a
+/// `GenericSignature`.
+pub trait TSignature<PublicKeyPoint>: Sized + Clone {
+    /// Serialize `self` as compressed bytes.
+    fn serialize(&self) -> [u8; BLS_SIG_BYTE_SIZE];
+
+    /// Deserialize `self` from compressed bytes.
+    fn deserialize(bytes: &[u8]) -> Result<Self, DecodeError>;
+
+    /// Returns `true` if `self` is a signature across `msg` by `pubkey`.
+    fn verify(&self, pubkey: &PublicKeyPoint, msg: &[u8]) -> bool;
+}
+
+/// A BLS signature that is generic across:
+///
+/// - `Pub`: A BLS public key.
+/// - `Sig`: A BLS signature.
+///
+/// Provides generic functionality whilst deferring all serious cryptographic operations to the
+/// generics.
+///
+/// A signature deserialized from all-zero bytes is considered "empty" (i.e., it has no `point`).
+/// Empty signatures serialize back to all-zero bytes and never pass verification.
+#[derive(Clone)]
+pub struct GenericSignature<Pub, Sig> {
+    /// The underlying point which performs *actual* cryptographic operations.
+    point: Option<Sig>,
+    _phantom: PhantomData<Pub>,
+}
+
+impl<Pub, Sig> GenericSignature<Pub, Sig>
+where
+    Sig: TSignature<Pub>,
+{
+    /// Instantiate a new signature across `msg` by the secret key `sk`.
+    pub fn new<Sec>(msg: &[u8], sk: &GenericSecretKey<Sig, Pub, Sec>) -> Self
+    where
+        Pub: TPublicKey,
+        Sec: TSecretKey<Sig, Pub>,
+    {
+        sk.sign(msg)
+    }
+
+    /// Returns a new empty signature.
+    pub fn empty_signature() -> Self {
+        Self {
+            point: None,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Instantiates `Self` from a `point`.
+    pub(crate) fn from_point(point: Sig) -> Self {
+        Self {
+            point: Some(point),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the underlying BLS point, if the signature is not empty.
+    pub(crate) fn point(&self) -> Option<&Sig> {
+        self.point.as_ref()
+    }
+
+    /// Returns `true` if `self` was deserialized from all-zero bytes.
+    pub fn is_empty(&self) -> bool {
+        self.point.is_none()
+    }
+
+    /// Returns `self` as compressed bytes, or all-zeros if `self` is empty.
+    pub fn as_bytes(&self) -> [u8; BLS_SIG_BYTE_SIZE] {
+        match &self.point {
+            Some(point) => point.serialize(),
+            None => [0; BLS_SIG_BYTE_SIZE],
+        }
+    }
+
+    /// Deserialize `self` from compressed bytes, producing an empty signature from all-zero
+    /// bytes.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        if bytes.len() != BLS_SIG_BYTE_SIZE {
+            return Err(DecodeError::InvalidByteLength {
+                len: bytes.len(),
+                expected: BLS_SIG_BYTE_SIZE,
+            });
+        }
+
+        if bytes.iter().all(|byte| *byte == 0) {
+            Ok(Self::empty_signature())
+        } else {
+            Sig::deserialize(bytes).map(Self::from_point)
+        }
+    }
+
+    /// Returns `true` if `self` is a signature across `msg` by `pubkey`.
+    ///
+    /// Always returns `false` if `self` is empty.
+    pub fn verify(&self, msg: &[u8], pubkey: &GenericPublicKey<Pub>) -> bool
+    where
+        Pub: TPublicKey,
+    {
+        match &self.point {
+            Some(point) => point.verify(pubkey.point(), msg),
+            None => false,
+        }
+    }
+}
+
+impl<Pub, Sig: TSignature<Pub>> Eq for GenericSignature<Pub, Sig> {}
+
+impl<Pub, Sig: TSignature<Pub>> PartialEq for GenericSignature<Pub, Sig> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_bytes()[..] == other.as_bytes()[..]
+    }
+}
+
+impl<Pub, Sig: TSignature<Pub>> fmt::Debug for GenericSignature<Pub, Sig> {
+    impl_debug!();
+}
+
+impl<Pub, Sig: TSignature<Pub>> Encode for GenericSignature<Pub, Sig> {
+    impl_ssz_encode!(BLS_SIG_BYTE_SIZE);
+}
+
+impl<Pub, Sig: TSignature<Pub>> Decode for GenericSignature<Pub, Sig> {
+    impl_ssz_decode!(BLS_SIG_BYTE_SIZE);
+}
+
+impl<Pub, Sig: TSignature<Pub>> TreeHash for GenericSignature<Pub, Sig> {
+    impl_tree_hash!(BLS_SIG_BYTE_SIZE);
+}
+
+impl<Pub, Sig: TSignature<Pub>> Serialize for GenericSignature<Pub, Sig> {
+    impl_serde_serialize!();
+}
+
+impl<'de, Pub, Sig: TSignature<Pub>> Deserialize<'de> for GenericSignature<Pub, Sig> {
+    impl_serde_deserialize!();
+}
+
+#[cfg(feature = "arbitrary")]
+impl<Pub: 'static, Sig: TSignature<Pub> + 'static> arbitrary::Arbitrary
+    for GenericSignature<Pub, Sig>
+{
+    impl_arbitrary!(BLS_SIG_BYTE_SIZE);
+}
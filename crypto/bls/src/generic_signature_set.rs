@@ -0,0 +1,75 @@
+use crate::{
+    generic_aggregate_public_key::{GenericAggregatePublicKey, TAggregatePublicKey},
+    generic_aggregate_signature::{GenericAggregateSignature, TAggregateSignature},
+    generic_public_key::{GenericPublicKey, TPublicKey},
+    generic_signature::{GenericSignature, TSignature},
+};
+use std::borrow::Cow;
+use std::marker::PhantomData;
+
+type Message = Vec<u8>;
+
+/// A generic way to represent a signature across a message by one or more public keys, which may
+/// be verified individually or in a batch with other sets (see `verify_signature_sets`).
+///
+/// The public keys are aggregated when the set is created.
+#[derive(Clone)]
+pub struct GenericSignatureSet<Pub, AggPub, Sig, AggSig> {
+    /// The aggregate signature, which is `None` if the signature was empty.
+    pub(crate) signature: Option<AggSig>,
+    pub(crate) signing_keys: AggPub,
+    pub(crate) message: Message,
+    _phantom_pub: PhantomData<Pub>,
+    _phantom_signature: PhantomData<Sig>,
+}
+
+impl<Pub, AggPub, Sig, AggSig> GenericSignatureSet<Pub, AggPub, Sig, AggSig>
+where
+    Pub: TPublicKey,
+    AggPub: TAggregatePublicKey<Pub>,
+    Sig: TSignature<Pub>,
+    AggSig: TAggregateSignature<Pub, AggPub, Sig>,
+{
+    /// Instantiate a set for a signature across `message` by a single `signing_key`.
+    pub fn single(
+        signature: &GenericSignature<Pub, Sig>,
+        signing_key: Cow<GenericPublicKey<Pub>>,
+        message: Message,
+    ) -> Self {
+        let aggregate_signature = GenericAggregateSignature::from_signature(signature);
+        Self::new(&aggregate_signature, vec![signing_key], message)
+    }
+
+    /// Instantiate a set for an aggregate signature across `message` by all of the
+    /// `signing_keys`.
+    pub fn new(
+        signature: &GenericAggregateSignature<Pub, AggPub, Sig, AggSig>,
+        signing_keys: Vec<Cow<GenericPublicKey<Pub>>>,
+        message: Message,
+    ) -> Self {
+        let signing_keys_refs: Vec<&GenericPublicKey<Pub>> =
+            signing_keys.iter().map(|pk| pk.as_ref()).collect();
+
+        Self {
+            signature: signature.point().cloned(),
+            signing_keys: GenericAggregatePublicKey::<Pub, AggPub>::aggregate(&signing_keys_refs)
+                .point()
+                .clone(),
+            message,
+            _phantom_pub: PhantomData,
+            _phantom_signature: PhantomData,
+        }
+    }
+
+    /// Returns `true` if the signature is valid.
+    ///
+    /// Always returns `false` if the signature was empty.
+    pub fn is_valid(&self) -> bool {
+        match &self.signature {
+            Some(signature) => {
+                signature.fast_aggregate_verify_pre_aggregated(&self.message, &self.signing_keys)
+            }
+            None => false,
+        }
+    }
+}
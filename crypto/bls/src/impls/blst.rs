@@ -0,0 +1,274 @@
+use crate::{
+    generic_aggregate_public_key::TAggregatePublicKey,
+    generic_aggregate_signature::TAggregateSignature,
+    generic_public_key::{GenericPublicKey, TPublicKey},
+    generic_secret_key::TSecretKey,
+    generic_signature::TSignature,
+    generic_signature_set::GenericSignatureSet,
+    SecretHash, BLS_AGG_SIG_BYTE_SIZE, BLS_PUBLIC_KEY_BYTE_SIZE, BLS_SECRET_KEY_BYTE_SIZE,
+    BLS_SIG_BYTE_SIZE,
+};
+pub use blst::min_pk as blst_core;
+use blst::{blst_scalar, BLST_ERROR};
+use rand::Rng;
+use ssz::DecodeError;
+use zeroize::Zeroize;
+
+/// The domain separation tag of the proof-of-possession ciphersuite used by Ethereum 2.0.
+pub const DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+/// The number of random bits used to weight each set in `verify_signature_sets`.
+pub const RAND_BITS: usize = 64;
+
+/// Returns the compressed encoding of the point at infinity in G1.
+fn infinity_public_key() -> [u8; BLS_PUBLIC_KEY_BYTE_SIZE] {
+    let mut bytes = [0; BLS_PUBLIC_KEY_BYTE_SIZE];
+    bytes[0] = 0xc0;
+    bytes
+}
+
+/// Returns the compressed encoding of the point at infinity in G2.
+fn infinity_signature() -> [u8; BLS_SIG_BYTE_SIZE] {
+    let mut bytes = [0; BLS_SIG_BYTE_SIZE];
+    bytes[0] = 0xc0;
+    bytes
+}
+
+/// The types used to instantiate the generic types of this crate.
+pub mod types {
+    pub use super::blst_core::{PublicKey, SecretKey, Signature};
+    pub use super::BlstAggregatePublicKey as AggregatePublicKey;
+    pub use super::BlstAggregateSignature as AggregateSignature;
+    pub use super::{verify_signature_sets, SignatureSet};
+}
+
+pub type SignatureSet = GenericSignatureSet<
+    blst_core::PublicKey,
+    BlstAggregatePublicKey,
+    blst_core::Signature,
+    BlstAggregateSignature,
+>;
+
+/// Verifies all of the `signature_sets` at once, returning `false` if any of them is invalid or
+/// has an empty signature.
+pub fn verify_signature_sets(signature_sets: &[SignatureSet]) -> bool {
+    let rng = &mut rand::thread_rng();
+
+    let mut rands: Vec<blst_scalar> = Vec::with_capacity(signature_sets.len());
+    let mut msgs_refs = Vec::with_capacity(signature_sets.len());
+    let mut pks = Vec::with_capacity(signature_sets.len());
+    let mut sigs = Vec::with_capacity(signature_sets.len());
+
+    for set in signature_sets {
+        let signature = match &set.signature {
+            Some(signature) => signature.0.to_signature(),
+            None => return false,
+        };
+
+        // The random weights must be non-zero.
+        let mut vals = [0u64; 4];
+        while vals[0] == 0 {
+            vals[0] = rng.gen();
+        }
+        let mut rand_i = std::mem::MaybeUninit::<blst_scalar>::uninit();
+
+        // Safe since `blst_scalar_from_uint64` initializes `rand_i` from the four-element `vals`.
+        unsafe {
+            blst::blst_scalar_from_uint64(rand_i.as_mut_ptr(), vals.as_ptr());
+            rands.push(rand_i.assume_init());
+        }
+
+        msgs_refs.push(set.message.as_slice());
+        pks.push(set.signing_keys.0.to_public_key());
+        sigs.push(signature);
+    }
+
+    let pks_refs = pks.iter().collect::<Vec<_>>();
+    let sigs_refs = sigs.iter().collect::<Vec<_>>();
+
+    // The public keys were validated when they were deserialized, whilst the signatures are
+    // checked to be in the correct subgroup here.
+    blst_core::Signature::verify_multiple_aggregate_signatures(
+        &msgs_refs, DST, &pks_refs, false, &sigs_refs, true, &rands, RAND_BITS,
+    ) == BLST_ERROR::BLST_SUCCESS
+}
+
+fn decode_error(type_name: &str, e: BLST_ERROR) -> DecodeError {
+    DecodeError::BytesInvalid(format!("Invalid {} bytes. Error: {:?}", type_name, e))
+}
+
+impl TPublicKey for blst_core::PublicKey {
+    fn serialize(&self) -> [u8; BLS_PUBLIC_KEY_BYTE_SIZE] {
+        self.compress()
+    }
+
+    fn serialize_uncompressed(&self) -> [u8; BLS_PUBLIC_KEY_BYTE_SIZE * 2] {
+        blst_core::PublicKey::serialize(self)
+    }
+
+    fn deserialize(bytes: &[u8]) -> Result<Self, DecodeError> {
+        // Ensures the key is not the point at infinity and is in the correct subgroup.
+        Self::key_validate(bytes).map_err(|e| decode_error("PublicKey", e))
+    }
+}
+
+/// A wrapper that allows for a `Clone` impl.
+pub struct BlstAggregatePublicKey(blst_core::AggregatePublicKey);
+
+impl Clone for BlstAggregatePublicKey {
+    fn clone(&self) -> Self {
+        Self(blst_core::AggregatePublicKey::from_public_key(
+            &self.0.to_public_key(),
+        ))
+    }
+}
+
+impl TAggregatePublicKey<blst_core::PublicKey> for BlstAggregatePublicKey {
+    fn infinity() -> Self {
+        let infinity = blst_core::PublicKey::from_bytes(&infinity_public_key())
+            .expect("should decode infinity public key");
+        Self(blst_core::AggregatePublicKey::from_public_key(&infinity))
+    }
+
+    fn add_assign(&mut self, other: &blst_core::PublicKey) {
+        // The public key was validated when it was deserialized.
+        self.0
+            .add_public_key(other, false)
+            .expect("should add public key without validation")
+    }
+
+    fn serialize(&self) -> [u8; BLS_PUBLIC_KEY_BYTE_SIZE] {
+        self.0.to_public_key().compress()
+    }
+
+    fn deserialize(bytes: &[u8]) -> Result<Self, DecodeError> {
+        blst_core::PublicKey::from_bytes(bytes)
+            .map(|pubkey| Self(blst_core::AggregatePublicKey::from_public_key(&pubkey)))
+            .map_err(|e| decode_error("AggregatePublicKey", e))
+    }
+}
+
+impl TSignature<blst_core::PublicKey> for blst_core::Signature {
+    fn serialize(&self) -> [u8; BLS_SIG_BYTE_SIZE] {
+        self.compress()
+    }
+
+    fn deserialize(bytes: &[u8]) -> Result<Self, DecodeError> {
+        Self::from_bytes(bytes).map_err(|e| decode_error("Signature", e))
+    }
+
+    fn verify(&self, pubkey: &blst_core::PublicKey, msg: &[u8]) -> bool {
+        // Public keys are validated when they're deserialized, signatures are checked to be in
+        // the correct subgroup here.
+        blst_core::Signature::verify(self, true, msg, DST, &[], pubkey, false)
+            == BLST_ERROR::BLST_SUCCESS
+    }
+}
+
+/// A wrapper that allows for a `Clone` impl.
+pub struct BlstAggregateSignature(blst_core::AggregateSignature);
+
+impl Clone for BlstAggregateSignature {
+    fn clone(&self) -> Self {
+        Self(blst_core::AggregateSignature::from_signature(
+            &self.0.to_signature(),
+        ))
+    }
+}
+
+impl TAggregateSignature<blst_core::PublicKey, BlstAggregatePublicKey, blst_core::Signature>
+    for BlstAggregateSignature
+{
+    fn infinity() -> Self {
+        let infinity = blst_core::Signature::from_bytes(&infinity_signature())
+            .expect("should decode infinity signature");
+        Self(blst_core::AggregateSignature::from_signature(&infinity))
+    }
+
+    fn add_assign(&mut self, other: &blst_core::Signature) {
+        // The subgroup is checked during verification.
+        self.0
+            .add_signature(other, false)
+            .expect("should add signature without a subgroup check")
+    }
+
+    fn add_assign_aggregate(&mut self, other: &Self) {
+        self.0.add_aggregate(&other.0)
+    }
+
+    fn serialize(&self) -> [u8; BLS_AGG_SIG_BYTE_SIZE] {
+        self.0.to_signature().compress()
+    }
+
+    fn deserialize(bytes: &[u8]) -> Result<Self, DecodeError> {
+        blst_core::Signature::from_bytes(bytes)
+            .map(|signature| Self(blst_core::AggregateSignature::from_signature(&signature)))
+            .map_err(|e| decode_error("AggregateSignature", e))
+    }
+
+    fn fast_aggregate_verify_pre_aggregated(
+        &self,
+        msg: &[u8],
+        aggregate_pubkey: &BlstAggregatePublicKey,
+    ) -> bool {
+        self.0.to_signature().verify(
+            true,
+            msg,
+            DST,
+            &[],
+            &aggregate_pubkey.0.to_public_key(),
+            false,
+        ) == BLST_ERROR::BLST_SUCCESS
+    }
+
+    fn fast_aggregate_verify(
+        &self,
+        msg: &[u8],
+        pubkeys: &[&GenericPublicKey<blst_core::PublicKey>],
+    ) -> bool {
+        let pubkeys = pubkeys.iter().map(|pk| pk.point()).collect::<Vec<_>>();
+        self.0
+            .to_signature()
+            .fast_aggregate_verify(true, msg, DST, &pubkeys)
+            == BLST_ERROR::BLST_SUCCESS
+    }
+
+    fn aggregate_verify(
+        &self,
+        msgs: &[&[u8]],
+        pubkeys: &[&GenericPublicKey<blst_core::PublicKey>],
+    ) -> bool {
+        let pubkeys = pubkeys.iter().map(|pk| pk.point()).collect::<Vec<_>>();
+        self.0
+            .to_signature()
+            .aggregate_verify(true, msgs, DST, &pubkeys, false)
+            == BLST_ERROR::BLST_SUCCESS
+    }
+}
+
+impl TSecretKey<blst_core::Signature, blst_core::PublicKey> for blst_core::SecretKey {
+    fn random() -> Self {
+        let rng = &mut rand::thread_rng();
+        let mut ikm = [0u8; BLS_SECRET_KEY_BYTE_SIZE];
+        rng.fill(&mut ikm[..]);
+
+        let sk = blst_core::SecretKey::key_gen(&ikm, &[]).expect("ikm is long enough");
+        ikm.zeroize();
+        sk
+    }
+
+    fn sign(&self, msg: &[u8]) -> blst_core::Signature {
+        blst_core::SecretKey::sign(self, msg, DST, &[])
+    }
+
+    fn public_key(&self) -> blst_core::PublicKey {
+        self.sk_to_pk()
+    }
+
+    fn serialize(&self) -> SecretHash {
+        self.to_bytes().into()
+    }
+
+    fn deserialize(bytes: &[u8]) -> Result<Self, DecodeError> {
+        Self::from_bytes(bytes).map_err(|e| decode_error("SecretKey", e))
+    }
+}
@@ -0,0 +1,192 @@
+//! A backend which does not perform any cryptography, for use in testing. All signatures are
+//! considered valid, however empty (all-zero) signatures are still rejected by the generic types.
+
+use crate::{
+    generic_aggregate_public_key::TAggregatePublicKey,
+    generic_aggregate_signature::TAggregateSignature,
+    generic_public_key::{GenericPublicKey, TPublicKey},
+    generic_secret_key::TSecretKey,
+    generic_signature::TSignature,
+    generic_signature_set::GenericSignatureSet,
+    SecretHash, BLS_AGG_SIG_BYTE_SIZE, BLS_PUBLIC_KEY_BYTE_SIZE, BLS_SECRET_KEY_BYTE_SIZE,
+    BLS_SIG_BYTE_SIZE,
+};
+use rand::Rng;
+use ssz::DecodeError;
+
+/// Returns the compressed encoding of the point at infinity in G2, used as the output of all
+/// signing.
+fn infinity_signature() -> [u8; BLS_SIG_BYTE_SIZE] {
+    let mut bytes = [0; BLS_SIG_BYTE_SIZE];
+    bytes[0] = 0xc0;
+    bytes
+}
+
+/// The types used to instantiate the generic types of this crate.
+pub mod types {
+    pub use super::Signature as AggregateSignature;
+    pub use super::{verify_signature_sets, SignatureSet};
+    pub use super::{AggregatePublicKey, PublicKey, SecretKey, Signature};
+}
+
+pub type SignatureSet = GenericSignatureSet<PublicKey, AggregatePublicKey, Signature, Signature>;
+
+/// _Always_ returns `true` if none of the `signature_sets` have an empty signature.
+pub fn verify_signature_sets(signature_sets: &[SignatureSet]) -> bool {
+    signature_sets.iter().all(|set| set.signature.is_some())
+}
+
+fn check_length(bytes: &[u8], expected: usize) -> Result<(), DecodeError> {
+    if bytes.len() == expected {
+        Ok(())
+    } else {
+        Err(DecodeError::InvalidByteLength {
+            len: bytes.len(),
+            expected,
+        })
+    }
+}
+
+/// Accepts any bytes as a public key.
+#[derive(Clone)]
+pub struct PublicKey([u8; BLS_PUBLIC_KEY_BYTE_SIZE]);
+
+impl TPublicKey for PublicKey {
+    fn serialize(&self) -> [u8; BLS_PUBLIC_KEY_BYTE_SIZE] {
+        self.0
+    }
+
+    fn serialize_uncompressed(&self) -> [u8; BLS_PUBLIC_KEY_BYTE_SIZE * 2] {
+        let mut bytes = [0; BLS_PUBLIC_KEY_BYTE_SIZE * 2];
+        bytes[0..BLS_PUBLIC_KEY_BYTE_SIZE].copy_from_slice(&self.0);
+        bytes
+    }
+
+    fn deserialize(bytes: &[u8]) -> Result<Self, DecodeError> {
+        check_length(bytes, BLS_PUBLIC_KEY_BYTE_SIZE)?;
+        let mut array = [0; BLS_PUBLIC_KEY_BYTE_SIZE];
+        array.copy_from_slice(bytes);
+        Ok(Self(array))
+    }
+}
+
+/// Does not aggregate anything, it only retains its original bytes.
+#[derive(Clone)]
+pub struct AggregatePublicKey([u8; BLS_PUBLIC_KEY_BYTE_SIZE]);
+
+impl TAggregatePublicKey<PublicKey> for AggregatePublicKey {
+    fn infinity() -> Self {
+        Self([0; BLS_PUBLIC_KEY_BYTE_SIZE])
+    }
+
+    fn add_assign(&mut self, _other: &PublicKey) {
+        // Do nothing.
+    }
+
+    fn serialize(&self) -> [u8; BLS_PUBLIC_KEY_BYTE_SIZE] {
+        self.0
+    }
+
+    fn deserialize(bytes: &[u8]) -> Result<Self, DecodeError> {
+        PublicKey::deserialize(bytes).map(|pubkey| Self(pubkey.0))
+    }
+}
+
+/// Accepts any bytes as a signature, which always pass verification.
+///
+/// Also used as the aggregate signature, since nothing is aggregated.
+#[derive(Clone)]
+pub struct Signature([u8; BLS_SIG_BYTE_SIZE]);
+
+impl TSignature<PublicKey> for Signature {
+    fn serialize(&self) -> [u8; BLS_SIG_BYTE_SIZE] {
+        self.0
+    }
+
+    fn deserialize(bytes: &[u8]) -> Result<Self, DecodeError> {
+        check_length(bytes, BLS_SIG_BYTE_SIZE)?;
+        let mut array = [0; BLS_SIG_BYTE_SIZE];
+        array.copy_from_slice(bytes);
+        Ok(Self(array))
+    }
+
+    fn verify(&self, _pubkey: &PublicKey, _msg: &[u8]) -> bool {
+        true
+    }
+}
+
+impl TAggregateSignature<PublicKey, AggregatePublicKey, Signature> for Signature {
+    fn infinity() -> Self {
+        Self(infinity_signature())
+    }
+
+    fn add_assign(&mut self, _other: &Signature) {
+        // Do nothing.
+    }
+
+    fn add_assign_aggregate(&mut self, _other: &Self) {
+        // Do nothing.
+    }
+
+    fn serialize(&self) -> [u8; BLS_AGG_SIG_BYTE_SIZE] {
+        self.0
+    }
+
+    fn deserialize(bytes: &[u8]) -> Result<Self, DecodeError> {
+        <Self as TSignature<PublicKey>>::deserialize(bytes)
+    }
+
+    fn fast_aggregate_verify_pre_aggregated(
+        &self,
+        _msg: &[u8],
+        _aggregate_pubkey: &AggregatePublicKey,
+    ) -> bool {
+        true
+    }
+
+    fn fast_aggregate_verify(
+        &self,
+        _msg: &[u8],
+        _pubkeys: &[&GenericPublicKey<PublicKey>],
+    ) -> bool {
+        true
+    }
+
+    fn aggregate_verify(&self, _msgs: &[&[u8]], _pubkeys: &[&GenericPublicKey<PublicKey>]) -> bool {
+        true
+    }
+}
+
+/// Stores the bytes of the secret key, from which a (fake) public key is derived so that distinct
+/// secret keys have distinct public keys.
+#[derive(Clone)]
+pub struct SecretKey([u8; BLS_SECRET_KEY_BYTE_SIZE]);
+
+impl TSecretKey<Signature, PublicKey> for SecretKey {
+    fn random() -> Self {
+        let mut bytes = [0; BLS_SECRET_KEY_BYTE_SIZE];
+        rand::thread_rng().fill(&mut bytes[..]);
+        Self(bytes)
+    }
+
+    fn sign(&self, _msg: &[u8]) -> Signature {
+        Signature(infinity_signature())
+    }
+
+    fn public_key(&self) -> PublicKey {
+        let mut bytes = [0; BLS_PUBLIC_KEY_BYTE_SIZE];
+        bytes[0..BLS_SECRET_KEY_BYTE_SIZE].copy_from_slice(&self.0);
+        PublicKey(bytes)
+    }
+
+    fn serialize(&self) -> SecretHash {
+        self.0.into()
+    }
+
+    fn deserialize(bytes: &[u8]) -> Result<Self, DecodeError> {
+        check_length(bytes, BLS_SECRET_KEY_BYTE_SIZE)?;
+        let mut array = [0; BLS_SECRET_KEY_BYTE_SIZE];
+        array.copy_from_slice(bytes);
+        Ok(Self(array))
+    }
+}
@@ -0,0 +1,175 @@
+use crate::{
+    generic_aggregate_public_key::TAggregatePublicKey,
+    generic_aggregate_signature::TAggregateSignature,
+    generic_public_key::{GenericPublicKey, TPublicKey},
+    generic_secret_key::TSecretKey,
+    generic_signature::TSignature,
+    generic_signature_set::GenericSignatureSet,
+    SecretHash, BLS_AGG_SIG_BYTE_SIZE, BLS_PUBLIC_KEY_BYTE_SIZE, BLS_SIG_BYTE_SIZE,
+};
+pub use milagro_bls as milagro;
+use ssz::DecodeError;
+
+/// The types used to instantiate the generic types of this crate.
+pub mod types {
+    pub use super::milagro::{
+        AggregatePublicKey, AggregateSignature, PublicKey, SecretKey, Signature,
+    };
+    pub use super::{verify_signature_sets, SignatureSet};
+}
+
+pub type SignatureSet = GenericSignatureSet<
+    milagro::PublicKey,
+    milagro::AggregatePublicKey,
+    milagro::Signature,
+    milagro::AggregateSignature,
+>;
+
+/// Verifies all of the `signature_sets` at once, returning `false` if any of them is invalid or
+/// has an empty signature.
+pub fn verify_signature_sets(signature_sets: &[SignatureSet]) -> bool {
+    let mut verify_sets = Vec::with_capacity(signature_sets.len());
+
+    for set in signature_sets {
+        match &set.signature {
+            Some(signature) => {
+                verify_sets.push((signature, &set.signing_keys, set.message.as_slice()))
+            }
+            None => return false,
+        }
+    }
+
+    milagro::AggregateSignature::verify_multiple_aggregate_signatures(
+        &mut rand::thread_rng(),
+        verify_sets.into_iter(),
+    )
+}
+
+fn decode_error(type_name: &str, bytes: &[u8]) -> DecodeError {
+    DecodeError::BytesInvalid(format!("Invalid {} bytes: {:?}", type_name, bytes))
+}
+
+impl TPublicKey for milagro::PublicKey {
+    fn serialize(&self) -> [u8; BLS_PUBLIC_KEY_BYTE_SIZE] {
+        self.as_bytes()
+    }
+
+    fn serialize_uncompressed(&self) -> [u8; BLS_PUBLIC_KEY_BYTE_SIZE * 2] {
+        self.clone().as_uncompressed_bytes()
+    }
+
+    fn deserialize(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let is_infinity = bytes.first() == Some(&0xc0) && bytes[1..].iter().all(|byte| *byte == 0);
+        if is_infinity {
+            return Err(decode_error("PublicKey", bytes));
+        }
+
+        Self::from_bytes(bytes).map_err(|_| decode_error("PublicKey", bytes))
+    }
+}
+
+impl TAggregatePublicKey<milagro::PublicKey> for milagro::AggregatePublicKey {
+    fn infinity() -> Self {
+        Self::new()
+    }
+
+    fn add_assign(&mut self, other: &milagro::PublicKey) {
+        self.add(other)
+    }
+
+    fn serialize(&self) -> [u8; BLS_PUBLIC_KEY_BYTE_SIZE] {
+        self.as_bytes()
+    }
+
+    fn deserialize(bytes: &[u8]) -> Result<Self, DecodeError> {
+        Self::from_bytes(bytes).map_err(|_| decode_error("AggregatePublicKey", bytes))
+    }
+}
+
+impl TSignature<milagro::PublicKey> for milagro::Signature {
+    fn serialize(&self) -> [u8; BLS_SIG_BYTE_SIZE] {
+        self.as_bytes()
+    }
+
+    fn deserialize(bytes: &[u8]) -> Result<Self, DecodeError> {
+        Self::from_bytes(bytes).map_err(|_| decode_error("Signature", bytes))
+    }
+
+    fn verify(&self, pubkey: &milagro::PublicKey, msg: &[u8]) -> bool {
+        self.verify(msg, pubkey)
+    }
+}
+
+impl TAggregateSignature<milagro::PublicKey, milagro::AggregatePublicKey, milagro::Signature>
+    for milagro::AggregateSignature
+{
+    fn infinity() -> Self {
+        Self::new()
+    }
+
+    fn add_assign(&mut self, other: &milagro::Signature) {
+        self.add(other)
+    }
+
+    fn add_assign_aggregate(&mut self, other: &Self) {
+        self.add_aggregate(other)
+    }
+
+    fn serialize(&self) -> [u8; BLS_AGG_SIG_BYTE_SIZE] {
+        self.as_bytes()
+    }
+
+    fn deserialize(bytes: &[u8]) -> Result<Self, DecodeError> {
+        Self::from_bytes(bytes).map_err(|_| decode_error("AggregateSignature", bytes))
+    }
+
+    fn fast_aggregate_verify_pre_aggregated(
+        &self,
+        msg: &[u8],
+        aggregate_pubkey: &milagro::AggregatePublicKey,
+    ) -> bool {
+        self.fast_aggregate_verify_pre_aggregated(msg, aggregate_pubkey)
+    }
+
+    fn fast_aggregate_verify(
+        &self,
+        msg: &[u8],
+        pubkeys: &[&GenericPublicKey<milagro::PublicKey>],
+    ) -> bool {
+        let pubkeys = pubkeys.iter().map(|pk| pk.point()).collect::<Vec<_>>();
+        self.fast_aggregate_verify(msg, &pubkeys)
+    }
+
+    fn aggregate_verify(
+        &self,
+        msgs: &[&[u8]],
+        pubkeys: &[&GenericPublicKey<milagro::PublicKey>],
+    ) -> bool {
+        let pubkeys = pubkeys.iter().map(|pk| pk.point()).collect::<Vec<_>>();
+        self.aggregate_verify(msgs, &pubkeys)
+    }
+}
+
+impl TSecretKey<milagro::Signature, milagro::PublicKey> for milagro::SecretKey {
+    fn random() -> Self {
+        Self::random(&mut rand::thread_rng())
+    }
+
+    fn sign(&self, msg: &[u8]) -> milagro::Signature {
+        milagro::Signature::new(msg, self)
+    }
+
+    fn public_key(&self) -> milagro::PublicKey {
+        milagro::PublicKey::from_secret_key(self)
+    }
+
+    fn serialize(&self) -> SecretHash {
+        self.as_bytes().into()
+    }
+
+    fn deserialize(bytes: &[u8]) -> Result<Self, DecodeError> {
+        Self::from_bytes(bytes).map_err(|e| {
+            DecodeError::BytesInvalid(format!("Invalid SecretKey bytes. Error: {:?}", e))
+        })
+    }
+}
@@ -0,0 +1,5 @@
+#[cfg(feature = "supranational")]
+pub mod blst;
+pub mod fake_crypto;
+#[cfg(feature = "milagro")]
+pub mod milagro;
@@ -1,67 +1,132 @@
-extern crate milagro_bls;
-extern crate ssz;
+//! Provides the BLS types used throughout Lighthouse (e.g., `PublicKey`, `Signature`), along with
+//! helper functions (e.g., SSZ serialization).
+//!
+//! This crate does not perform any cryptography itself, instead it wraps a BLS library (a
+//! "backend"). The backend is selected at compile time via one of the following features:
+//!
+//! - `milagro`: uses the pure-Rust `milagro_bls` library (default).
+//! - `supranational`: uses the `blst` library.
+//! - `fake_crypto`: uses a backend which considers all signatures to be valid. For testing only.
+//!
+//! If several of these features are enabled, `fake_crypto` takes precedence over
+//! `supranational`, which takes precedence over `milagro`.
+//!
+//! The types in this crate (`GenericPublicKey`, `GenericSignature`, etc.) are generic across the
+//! traits in the `generic_*` modules, which are implemented for each backend in `impls`. Each
+//! enabled backend has a module (e.g., `milagro_implementations`) which provides concrete
+//! versions of the types, whilst the top-level exports of this crate are the types of the
+//! selected backend.
 
 #[macro_use]
 mod macros;
-mod keypair;
+mod generic_aggregate_public_key;
+mod generic_aggregate_signature;
+mod generic_keypair;
+mod generic_public_key;
+mod generic_secret_key;
+mod generic_signature;
+mod generic_signature_set;
 mod public_key_bytes;
 mod secret_hash;
-mod secret_key;
 mod signature_bytes;
-mod signature_set;
 
-pub use crate::keypair::Keypair;
-pub use crate::public_key_bytes::PublicKeyBytes;
-pub use crate::secret_key::SecretKey;
-pub use crate::signature_bytes::SignatureBytes;
+pub mod impls;
+
+pub use generic_aggregate_public_key::TAggregatePublicKey;
+pub use generic_aggregate_signature::TAggregateSignature;
+pub use generic_public_key::TPublicKey;
+pub use generic_secret_key::TSecretKey;
+pub use generic_signature::TSignature;
+pub use public_key_bytes::PublicKeyBytes;
 pub use secret_hash::SecretHash;
-pub use signature_set::{verify_signature_sets, SignatureSet};
+pub use signature_bytes::SignatureBytes;
 
 #[cfg(feature = "arbitrary")]
 pub use arbitrary;
 
-#[cfg(feature = "fake_crypto")]
-mod fake_aggregate_public_key;
-#[cfg(feature = "fake_crypto")]
-mod fake_aggregate_signature;
-#[cfg(feature = "fake_crypto")]
-mod fake_public_key;
-#[cfg(feature = "fake_crypto")]
-mod fake_signature;
+pub const BLS_AGG_SIG_BYTE_SIZE: usize = 96;
+pub const BLS_SIG_BYTE_SIZE: usize = 96;
+pub const BLS_SECRET_KEY_BYTE_SIZE: usize = 32;
+pub const BLS_PUBLIC_KEY_BYTE_SIZE: usize = 48;
 
-#[cfg(not(feature = "fake_crypto"))]
-mod aggregate_public_key;
-#[cfg(not(feature = "fake_crypto"))]
-mod aggregate_signature;
-#[cfg(not(feature = "fake_crypto"))]
-mod public_key;
-#[cfg(not(feature = "fake_crypto"))]
-mod signature;
+#[cfg(not(any(
+    feature = "milagro",
+    feature = "supranational",
+    feature = "fake_crypto"
+)))]
+compile_error!(
+    "a BLS backend must be selected with the milagro, supranational or fake_crypto features"
+);
 
-#[cfg(feature = "fake_crypto")]
-pub use fakes::*;
-#[cfg(feature = "fake_crypto")]
-mod fakes {
-    pub use crate::fake_aggregate_public_key::FakeAggregatePublicKey as AggregatePublicKey;
-    pub use crate::fake_aggregate_signature::FakeAggregateSignature as AggregateSignature;
-    pub use crate::fake_public_key::FakePublicKey as PublicKey;
-    pub use crate::fake_signature::FakeSignature as Signature;
-}
+/// Generates a module which provides concrete versions of the generic types, for the backend
+/// defined in `$backend`.
+macro_rules! define_mod {
+    ($name: ident, $backend: path) => {
+        pub mod $name {
+            use $backend as bls_variant;
 
-#[cfg(not(feature = "fake_crypto"))]
-pub use reals::*;
-#[cfg(not(feature = "fake_crypto"))]
-mod reals {
-    pub use crate::aggregate_public_key::AggregatePublicKey;
-    pub use crate::aggregate_signature::AggregateSignature;
-    pub use crate::public_key::PublicKey;
-    pub use crate::signature::Signature;
+            use crate::generic_aggregate_public_key::GenericAggregatePublicKey;
+            use crate::generic_aggregate_signature::GenericAggregateSignature;
+            use crate::generic_keypair::GenericKeypair;
+            use crate::generic_public_key::GenericPublicKey;
+            use crate::generic_secret_key::GenericSecretKey;
+            use crate::generic_signature::GenericSignature;
+
+            pub use bls_variant::SignatureSet;
+
+            pub type PublicKey = GenericPublicKey<bls_variant::PublicKey>;
+            pub type AggregatePublicKey =
+                GenericAggregatePublicKey<bls_variant::PublicKey, bls_variant::AggregatePublicKey>;
+            pub type Signature = GenericSignature<bls_variant::PublicKey, bls_variant::Signature>;
+            pub type AggregateSignature = GenericAggregateSignature<
+                bls_variant::PublicKey,
+                bls_variant::AggregatePublicKey,
+                bls_variant::Signature,
+                bls_variant::AggregateSignature,
+            >;
+            pub type SecretKey = GenericSecretKey<
+                bls_variant::Signature,
+                bls_variant::PublicKey,
+                bls_variant::SecretKey,
+            >;
+            pub type Keypair = GenericKeypair<
+                bls_variant::PublicKey,
+                bls_variant::SecretKey,
+                bls_variant::Signature,
+            >;
+
+            /// Verifies all of the `sets` at once, which is significantly faster than verifying
+            /// them individually.
+            ///
+            /// Returns `false` if any of the signatures is invalid or empty.
+            pub fn verify_signature_sets(sets: Vec<SignatureSet>) -> bool {
+                bls_variant::verify_signature_sets(&sets)
+            }
+        }
+    };
 }
 
-pub const BLS_AGG_SIG_BYTE_SIZE: usize = 96;
-pub const BLS_SIG_BYTE_SIZE: usize = 96;
-pub const BLS_SECRET_KEY_BYTE_SIZE: usize = 32;
-pub const BLS_PUBLIC_KEY_BYTE_SIZE: usize = 48;
+#[cfg(feature = "milagro")]
+define_mod!(milagro_implementations, crate::impls::milagro::types);
+#[cfg(feature = "supranational")]
+define_mod!(blst_implementations, crate::impls::blst::types);
+define_mod!(
+    fake_crypto_implementations,
+    crate::impls::fake_crypto::types
+);
+
+#[cfg(all(
+    feature = "milagro",
+    not(feature = "supranational"),
+    not(feature = "fake_crypto")
+))]
+pub use milagro_implementations::*;
+
+#[cfg(all(feature = "supranational", not(feature = "fake_crypto")))]
+pub use blst_implementations::*;
+
+#[cfg(feature = "fake_crypto")]
+pub use fake_crypto_implementations::*;
 
 use eth2_hashing::hash;
 use ssz::ssz_encode;
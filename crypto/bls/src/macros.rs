@@ -1,76 +1,144 @@
-macro_rules! impl_ssz {
-    ($type: ident, $byte_size: expr, $item_str: expr) => {
-        impl ssz::Encode for $type {
-            fn is_ssz_fixed_len() -> bool {
-                true
-            }
+/// Contains the functions required for a `ssz::Encode` implementation.
+///
+/// Does not include the `impl` header, which is complicated by the generics of the types in this
+/// crate.
+macro_rules! impl_ssz_encode {
+    ($byte_size: expr) => {
+        fn is_ssz_fixed_len() -> bool {
+            true
+        }
 
-            fn ssz_fixed_len() -> usize {
-                $byte_size
-            }
+        fn ssz_fixed_len() -> usize {
+            $byte_size
+        }
 
-            fn ssz_bytes_len(&self) -> usize {
-                $byte_size
-            }
+        fn ssz_bytes_len(&self) -> usize {
+            $byte_size
+        }
 
-            fn ssz_append(&self, buf: &mut Vec<u8>) {
-                buf.extend_from_slice(&self.as_bytes())
-            }
+        fn ssz_append(&self, buf: &mut Vec<u8>) {
+            buf.extend_from_slice(&self.as_bytes())
         }
+    };
+}
 
-        impl ssz::Decode for $type {
-            fn is_ssz_fixed_len() -> bool {
-                true
-            }
+/// Contains the functions required for a `ssz::Decode` implementation.
+///
+/// Does not include the `impl` header, see `impl_ssz_encode`.
+macro_rules! impl_ssz_decode {
+    ($byte_size: expr) => {
+        fn is_ssz_fixed_len() -> bool {
+            true
+        }
 
-            fn ssz_fixed_len() -> usize {
-                $byte_size
-            }
+        fn ssz_fixed_len() -> usize {
+            $byte_size
+        }
 
-            fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
-                let len = bytes.len();
-                let expected = <Self as ssz::Decode>::ssz_fixed_len();
+        fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, ssz::DecodeError> {
+            let len = bytes.len();
+            let expected = <Self as ssz::Decode>::ssz_fixed_len();
 
-                if len != expected {
-                    Err(ssz::DecodeError::InvalidByteLength { len, expected })
-                } else {
-                    $type::from_bytes(bytes)
-                }
+            if len != expected {
+                Err(ssz::DecodeError::InvalidByteLength { len, expected })
+            } else {
+                Self::from_bytes(bytes)
             }
         }
     };
 }
 
+/// Contains the functions required for a `tree_hash::TreeHash` implementation.
+///
+/// Does not include the `impl` header, see `impl_ssz_encode`.
 macro_rules! impl_tree_hash {
-    ($type: ty, $byte_size: expr) => {
-        impl tree_hash::TreeHash for $type {
-            fn tree_hash_type() -> tree_hash::TreeHashType {
-                tree_hash::TreeHashType::Vector
-            }
+    ($byte_size: expr) => {
+        fn tree_hash_type() -> tree_hash::TreeHashType {
+            tree_hash::TreeHashType::Vector
+        }
 
-            fn tree_hash_packed_encoding(&self) -> Vec<u8> {
-                unreachable!("Vector should never be packed.")
-            }
+        fn tree_hash_packed_encoding(&self) -> Vec<u8> {
+            unreachable!("Vector should never be packed.")
+        }
 
-            fn tree_hash_packing_factor() -> usize {
-                unreachable!("Vector should never be packed.")
-            }
+        fn tree_hash_packing_factor() -> usize {
+            unreachable!("Vector should never be packed.")
+        }
 
-            fn tree_hash_root(&self) -> tree_hash::Hash256 {
-                // We could use the tree hash implementation for `FixedVec<u8, $byte_size>`,
-                // but benchmarks have show that to be at least 15% slower because of the
-                // unnecessary copying and allocation (one Vec per byte)
-                let values_per_chunk = tree_hash::BYTES_PER_CHUNK;
-                let minimum_chunk_count = ($byte_size + values_per_chunk - 1) / values_per_chunk;
+        fn tree_hash_root(&self) -> tree_hash::Hash256 {
+            // We could use the tree hash implementation for `FixedVec<u8, $byte_size>`,
+            // but benchmarks have show that to be at least 15% slower because of the
+            // unnecessary copying and allocation (one Vec per byte)
+            let values_per_chunk = tree_hash::BYTES_PER_CHUNK;
+            let minimum_chunk_count = ($byte_size + values_per_chunk - 1) / values_per_chunk;
+
+            let mut hasher = tree_hash::MerkleHasher::with_leaves(minimum_chunk_count);
+            hasher
+                .write(&self.as_bytes())
+                .expect("bls should not exceed leaf count");
+            hasher
+                .finish()
+                .expect("bls should not exceed leaf count from buffer")
+        }
+    };
+}
 
-                let mut hasher = tree_hash::MerkleHasher::with_leaves(minimum_chunk_count);
-                hasher
-                    .write(&self.as_ssz_bytes())
-                    .expect("bls should not exceed leaf count");
-                hasher
-                    .finish()
-                    .expect("bls should not exceed leaf count from buffer")
-            }
+/// Contains the functions required for a `serde::Serialize` implementation.
+///
+/// Serde serialization is compliant with the Ethereum YAML test format.
+///
+/// Does not include the `impl` header, see `impl_ssz_encode`.
+macro_rules! impl_serde_serialize {
+    () => {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::ser::Serializer,
+        {
+            serializer.serialize_str(&serde_hex::encode(&self.as_bytes()[..]))
+        }
+    };
+}
+
+/// Contains the functions required for a `serde::Deserialize` implementation.
+///
+/// Serde serialization is compliant with the Ethereum YAML test format.
+///
+/// Does not include the `impl` header, see `impl_ssz_encode`.
+macro_rules! impl_serde_deserialize {
+    () => {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: serde::de::Deserializer<'de>,
+        {
+            let bytes = deserializer.deserialize_str(serde_hex::PrefixedHexVisitor)?;
+            Self::from_bytes(&bytes[..])
+                .map_err(|e| serde::de::Error::custom(format!("invalid ssz ({:?})", e)))
+        }
+    };
+}
+
+/// Contains the functions required for a `std::fmt::Debug` implementation, printing the bytes as
+/// a `0x`-prefixed hex string.
+///
+/// Does not include the `impl` header, see `impl_ssz_encode`.
+macro_rules! impl_debug {
+    () => {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "{}", serde_hex::encode(&self.as_bytes()[..]))
+        }
+    };
+}
+
+/// Contains the functions required for an `arbitrary::Arbitrary` implementation.
+///
+/// Does not include the `impl` header, see `impl_ssz_encode`.
+#[cfg(feature = "arbitrary")]
+macro_rules! impl_arbitrary {
+    ($byte_size: expr) => {
+        fn arbitrary(u: &mut arbitrary::Unstructured<'_>) -> arbitrary::Result<Self> {
+            let mut bytes = [0u8; $byte_size];
+            u.fill_buffer(&mut bytes)?;
+            Self::from_bytes(&bytes).map_err(|_| arbitrary::Error::IncorrectFormat)
         }
     };
 }
@@ -95,8 +163,14 @@ macro_rules! bytes_struct {
         }
     };
     ($name: ident, $type: ty, $byte_size: expr, $small_name: expr) => {
-        bytes_struct!($name, $type, $byte_size, $small_name, stringify!($type),
-                      stringify!($byte_size));
+        bytes_struct!(
+            $name,
+            $type,
+            $byte_size,
+            $small_name,
+            stringify!($type),
+            stringify!($byte_size)
+        );
 
         impl $name {
             pub fn from_bytes(bytes: &[u8]) -> Result<Self, ssz::DecodeError> {
@@ -225,8 +299,12 @@ macro_rules! bytes_struct {
                 let minimum_chunk_count = ($byte_size + values_per_chunk - 1) / values_per_chunk;
 
                 let mut hasher = tree_hash::MerkleHasher::with_leaves(minimum_chunk_count);
-                hasher.write(&self.bytes).expect("bls should not exceed leaf count");
-                hasher.finish().expect("bls should not exceed leaf count from buffer")
+                hasher
+                    .write(&self.bytes)
+                    .expect("bls should not exceed leaf count");
+                hasher
+                    .finish()
+                    .expect("bls should not exceed leaf count from buffer")
             }
         }
 
@@ -255,11 +333,13 @@ macro_rules! bytes_struct {
 
         #[cfg(feature = "arbitrary")]
         impl $crate::arbitrary::Arbitrary for $name {
-			fn arbitrary(u: &mut $crate::arbitrary::Unstructured<'_>) -> $crate::arbitrary::Result<Self> {
-				let mut bytes = [0u8; $byte_size];
-				u.fill_buffer(&mut bytes)?;
+            fn arbitrary(
+                u: &mut $crate::arbitrary::Unstructured<'_>,
+            ) -> $crate::arbitrary::Result<Self> {
+                let mut bytes = [0u8; $byte_size];
+                u.fill_buffer(&mut bytes)?;
                 Self::from_bytes(&bytes).map_err(|_| $crate::arbitrary::Error::IncorrectFormat)
-			}
-		}
+            }
+        }
     };
 }
@@ -0,0 +1,309 @@
+//! Tests which are run against each of the real (i.e., not `fake_crypto`) BLS backends.
+
+#![cfg(any(feature = "milagro", feature = "supranational"))]
+
+use std::borrow::Cow;
+
+/// Generates a module of tests for the backend types in `$type`.
+macro_rules! test_suite {
+    ($type: ident) => {
+        use super::*;
+        use bls::$type::*;
+        use bls::{BLS_PUBLIC_KEY_BYTE_SIZE, BLS_SIG_BYTE_SIZE};
+        use ssz::{ssz_encode, Decode};
+
+        const MSG: &[u8] = &[42; 32];
+        const OTHER_MSG: &[u8] = &[43; 32];
+
+        /// Sourced from the interop keypairs (`keygen_10_validators.yaml`).
+        const KNOWN_KEYPAIRS: &[(&str, &str)] = &[
+            (
+                "25295f0d1d592a90b333e26e85149708208e9f8e8bc18f6c77bd62f8ad7a6866",
+                "a99a76ed7796f7be22d5b7e85deeb7c5677e88e511e0b337618f8c4eb61349b4bf2d153f649f7b53359fe8b94a38e44c",
+            ),
+            (
+                "51d0b65185db6989ab0b560d6deed19c7ead0e24b9b6372cbecb1f26bdfad000",
+                "b89bebc699769726a318c8e9971bd3171297c61aea4a6578a7a4f94b547dcba5bac16a89108b6b6a1fe3695d1a874a0b",
+            ),
+        ];
+
+        fn keypairs(n: usize) -> Vec<Keypair> {
+            (0..n).map(|_| Keypair::random()).collect()
+        }
+
+        #[test]
+        fn known_public_keys() {
+            for (sk, pk) in KNOWN_KEYPAIRS {
+                let sk = SecretKey::from_bytes(&hex::decode(sk).unwrap()).unwrap();
+                assert_eq!(hex::encode(&sk.public_key().as_bytes()[..]), *pk);
+            }
+        }
+
+        #[test]
+        fn secret_key_round_trip() {
+            let sk = SecretKey::random();
+            let decoded = SecretKey::from_bytes(sk.as_bytes().as_bytes()).unwrap();
+            assert_eq!(sk.public_key(), decoded.public_key());
+        }
+
+        #[test]
+        fn public_key_ssz_round_trip() {
+            let original = Keypair::random().pk;
+
+            let bytes = ssz_encode(&original);
+            assert_eq!(bytes.len(), BLS_PUBLIC_KEY_BYTE_SIZE);
+
+            let decoded = PublicKey::from_ssz_bytes(&bytes).unwrap();
+            assert_eq!(original, decoded);
+        }
+
+        #[test]
+        fn invalid_public_keys() {
+            assert!(PublicKey::from_bytes(&[0xff; BLS_PUBLIC_KEY_BYTE_SIZE]).is_err());
+            assert!(PublicKey::from_bytes(&[0; BLS_PUBLIC_KEY_BYTE_SIZE]).is_err());
+
+            let mut infinity = [0; BLS_PUBLIC_KEY_BYTE_SIZE];
+            infinity[0] = 0xc0;
+            assert!(PublicKey::from_bytes(&infinity).is_err());
+
+            assert!(PublicKey::from_bytes(&[]).is_err());
+        }
+
+        #[test]
+        fn signature_ssz_round_trip() {
+            let keypair = Keypair::random();
+            let original = keypair.sk.sign(MSG);
+
+            let bytes = ssz_encode(&original);
+            assert_eq!(bytes.len(), BLS_SIG_BYTE_SIZE);
+
+            let decoded = Signature::from_ssz_bytes(&bytes).unwrap();
+            assert_eq!(original, decoded);
+        }
+
+        #[test]
+        fn aggregate_signature_ssz_round_trip() {
+            let mut original = AggregateSignature::new();
+            for keypair in keypairs(4) {
+                original.add(&keypair.sk.sign(MSG));
+            }
+
+            let bytes = ssz_encode(&original);
+            assert_eq!(bytes.len(), BLS_SIG_BYTE_SIZE);
+
+            let decoded = AggregateSignature::from_ssz_bytes(&bytes).unwrap();
+            assert_eq!(original, decoded);
+        }
+
+        #[test]
+        fn empty_signature() {
+            let sig = Signature::empty_signature();
+            assert!(sig.is_empty());
+            assert_eq!(sig.as_bytes().to_vec(), vec![0; BLS_SIG_BYTE_SIZE]);
+
+            let decoded = Signature::from_bytes(&[0; BLS_SIG_BYTE_SIZE]).unwrap();
+            assert!(decoded.is_empty());
+            assert!(!decoded.verify(MSG, &Keypair::random().pk));
+
+            let agg = AggregateSignature::empty_signature();
+            assert!(agg.is_empty());
+            assert_eq!(agg.as_bytes().to_vec(), vec![0; BLS_SIG_BYTE_SIZE]);
+        }
+
+        #[test]
+        fn infinity_aggregate_signature() {
+            let agg = AggregateSignature::new();
+            assert!(!agg.is_empty());
+
+            let bytes = agg.as_bytes();
+            assert_eq!(bytes[0], 0xc0);
+            assert!(bytes[1..].iter().all(|byte| *byte == 0));
+        }
+
+        #[test]
+        fn sign_and_verify() {
+            let keypair = Keypair::random();
+            let sig = keypair.sk.sign(MSG);
+
+            assert!(sig.verify(MSG, &keypair.pk));
+            assert!(!sig.verify(OTHER_MSG, &keypair.pk));
+            assert!(!sig.verify(MSG, &Keypair::random().pk));
+        }
+
+        #[test]
+        fn fast_aggregate_verify() {
+            let keypairs = keypairs(8);
+            let pubkeys = keypairs.iter().map(|kp| &kp.pk).collect::<Vec<_>>();
+
+            let mut agg_sig = AggregateSignature::new();
+            for keypair in &keypairs {
+                agg_sig.add(&keypair.sk.sign(MSG));
+            }
+
+            let agg_pubkey = AggregatePublicKey::aggregate(&pubkeys);
+
+            assert!(agg_sig.verify(MSG, &agg_pubkey));
+            assert!(agg_sig.verify_unaggregated(MSG, &pubkeys));
+            assert!(!agg_sig.verify(OTHER_MSG, &agg_pubkey));
+            assert!(!agg_sig.verify_unaggregated(OTHER_MSG, &pubkeys));
+            assert!(!agg_sig.verify_unaggregated(MSG, &pubkeys[1..]));
+        }
+
+        #[test]
+        fn aggregate_verify() {
+            let keypairs = keypairs(8);
+            let msgs = (0..keypairs.len() as u8)
+                .map(|i| vec![i; 32])
+                .collect::<Vec<_>>();
+
+            let mut agg_sig = AggregateSignature::new();
+            for (keypair, msg) in keypairs.iter().zip(msgs.iter()) {
+                agg_sig.add(&keypair.sk.sign(msg));
+            }
+
+            let pubkeys = keypairs.iter().map(|kp| &kp.pk).collect::<Vec<_>>();
+            let msg_refs = msgs.iter().map(|msg| msg.as_slice()).collect::<Vec<_>>();
+
+            assert!(agg_sig.verify_multiple(&msg_refs, &pubkeys));
+
+            let mut reversed = msg_refs.clone();
+            reversed.reverse();
+            assert!(!agg_sig.verify_multiple(&reversed, &pubkeys));
+        }
+
+        #[test]
+        fn add_aggregate() {
+            let keypairs = keypairs(4);
+            let pubkeys = keypairs.iter().map(|kp| &kp.pk).collect::<Vec<_>>();
+
+            let mut left = AggregateSignature::new();
+            left.add(&keypairs[0].sk.sign(MSG));
+            left.add(&keypairs[1].sk.sign(MSG));
+
+            let mut right = AggregateSignature::empty_signature();
+            right.add(&keypairs[2].sk.sign(MSG));
+            right.add(&keypairs[3].sk.sign(MSG));
+
+            left.add_aggregate(&right);
+
+            assert!(left.verify_unaggregated(MSG, &pubkeys));
+        }
+
+        fn signature_sets(n: usize) -> (Vec<Keypair>, Vec<Signature>) {
+            let keypairs = keypairs(n);
+            let signatures = keypairs
+                .iter()
+                .map(|keypair| keypair.sk.sign(MSG))
+                .collect();
+            (keypairs, signatures)
+        }
+
+        #[test]
+        fn valid_signature_sets() {
+            let (keypairs, signatures) = signature_sets(8);
+
+            let sets = keypairs
+                .iter()
+                .zip(signatures.iter())
+                .map(|(keypair, sig)| {
+                    SignatureSet::single(sig, Cow::Borrowed(&keypair.pk), MSG.to_vec())
+                })
+                .collect::<Vec<_>>();
+
+            assert!(sets.iter().all(|set| set.is_valid()));
+            assert!(verify_signature_sets(sets));
+        }
+
+        #[test]
+        fn valid_aggregate_signature_set() {
+            let (keypairs, signatures) = signature_sets(8);
+
+            let mut agg_sig = AggregateSignature::new();
+            for sig in &signatures {
+                agg_sig.add(sig);
+            }
+
+            let set = SignatureSet::new(
+                &agg_sig,
+                keypairs.iter().map(|kp| Cow::Borrowed(&kp.pk)).collect(),
+                MSG.to_vec(),
+            );
+
+            assert!(set.is_valid());
+            assert!(verify_signature_sets(vec![set]));
+        }
+
+        #[test]
+        fn invalid_signature_sets() {
+            let (keypairs, signatures) = signature_sets(8);
+
+            let mut sets = keypairs
+                .iter()
+                .zip(signatures.iter())
+                .map(|(keypair, sig)| {
+                    SignatureSet::single(sig, Cow::Borrowed(&keypair.pk), MSG.to_vec())
+                })
+                .collect::<Vec<_>>();
+
+            sets.push(SignatureSet::single(
+                &signatures[0],
+                Cow::Borrowed(&keypairs[0].pk),
+                OTHER_MSG.to_vec(),
+            ));
+
+            assert!(!sets.last().unwrap().is_valid());
+            assert!(!verify_signature_sets(sets));
+        }
+
+        #[test]
+        fn empty_signature_set() {
+            let keypair = Keypair::random();
+            let set = SignatureSet::single(
+                &Signature::empty_signature(),
+                Cow::Borrowed(&keypair.pk),
+                MSG.to_vec(),
+            );
+
+            assert!(!set.is_valid());
+            assert!(!verify_signature_sets(vec![set]));
+        }
+    };
+}
+
+#[cfg(feature = "milagro")]
+mod milagro {
+    test_suite!(milagro_implementations);
+}
+
+#[cfg(feature = "supranational")]
+mod blst {
+    test_suite!(blst_implementations);
+}
+
+/// Ensures that both backends produce identical keys and signatures.
+#[cfg(all(feature = "milagro", feature = "supranational"))]
+mod cross_backend {
+    use bls::{blst_implementations as blst, milagro_implementations as milagro};
+
+    #[test]
+    fn identical_keys_and_signatures() {
+        let msg = &[42; 32];
+
+        for _ in 0..8 {
+            let milagro_sk = milagro::SecretKey::random();
+            let blst_sk = blst::SecretKey::from_bytes(milagro_sk.as_bytes().as_bytes()).unwrap();
+
+            let milagro_pk = milagro_sk.public_key();
+            let blst_pk = blst_sk.public_key();
+            assert_eq!(milagro_pk.as_bytes()[..], blst_pk.as_bytes()[..]);
+
+            let milagro_sig = milagro_sk.sign(msg);
+            let blst_sig = blst_sk.sign(msg);
+            assert_eq!(milagro_sig.as_bytes()[..], blst_sig.as_bytes()[..]);
+
+            let blst_decoded = blst::Signature::from_bytes(&milagro_sig.as_bytes()).unwrap();
+            let blst_pk_decoded = blst::PublicKey::from_bytes(&milagro_pk.as_bytes()).unwrap();
+            assert!(blst_decoded.verify(msg, &blst_pk_decoded));
+        }
+    }
+}
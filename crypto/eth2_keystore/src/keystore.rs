@@ -10,7 +10,7 @@ use crate::Uuid;
 use aes_ctr::stream_cipher::generic_array::GenericArray;
 use aes_ctr::stream_cipher::{NewStreamCipher, SyncStreamCipher};
 use aes_ctr::Aes128Ctr as AesCtr;
-use bls::{Keypair, PublicKey, SecretHash, SecretKey};
+use bls::{Keypair, SecretHash, SecretKey};
 use eth2_key_derivation::PlainText;
 use hmac::Hmac;
 use pbkdf2::pbkdf2;
@@ -259,7 +259,7 @@ impl Keystore {
 /// - If `secret` does not represent a point in the BLS curve.
 pub fn keypair_from_secret(secret: &[u8]) -> Result<Keypair, Error> {
     let sk = SecretKey::from_bytes(secret).map_err(Error::InvalidSecretKeyBytes)?;
-    let pk = PublicKey::from_secret_key(&sk);
+    let pk = sk.public_key();
     Ok(Keypair { sk, pk })
 }
 
@@ -1,7 +1,7 @@
 use crate::transition_blocks::load_from_ssz;
 use clap::ArgMatches;
 use ssz::Encode;
-use state_processing::per_slot_processing;
+use state_processing::{BlockReplayError, BlockReplayer};
 use std::fs::File;
 use std::io::prelude::*;
 use std::path::PathBuf;
@@ -38,11 +38,11 @@ pub fn run<T: EthSpec>(matches: &ArgMatches) -> Result<(), String> {
         .build_all_caches(spec)
         .map_err(|e| format!("Unable to build caches: {:?}", e))?;
 
-    // Transition the parent state to the block slot.
-    for i in 0..slots {
-        per_slot_processing(&mut state, None, spec)
-            .map_err(|e| format!("Failed to advance slot on iteration {}: {:?}", i, e))?;
-    }
+    let target_slot = state.slot + slots as u64;
+    let state = BlockReplayer::<_, BlockReplayError>::new(state, spec)
+        .apply_blocks(vec![], Some(target_slot))
+        .map(BlockReplayer::into_state)
+        .map_err(|e| format!("Failed to advance slots: {:?}", e))?;
 
     let mut output_file =
         File::create(output_path).map_err(|e| format!("Unable to create output file: {:?}", e))?;
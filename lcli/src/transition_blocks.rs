@@ -1,6 +1,6 @@
 use clap::ArgMatches;
 use ssz::{Decode, Encode};
-use state_processing::{per_block_processing, per_slot_processing, BlockSignatureStrategy};
+use state_processing::{BlockReplayError, BlockReplayer, BlockSignatureStrategy};
 use std::fs::File;
 use std::io::prelude::*;
 use std::path::PathBuf;
@@ -50,30 +50,30 @@ fn do_transition<T: EthSpec>(
 ) -> Result<BeaconState<T>, String> {
     let spec = &T::default_spec();
 
-    pre_state
-        .build_all_caches(spec)
-        .map_err(|e| format!("Unable to build caches: {:?}", e))?;
-
-    // Transition the parent state to the block slot.
-    for i in pre_state.slot.as_u64()..block.slot().as_u64() {
-        per_slot_processing(&mut pre_state, None, spec)
-            .map_err(|e| format!("Failed to advance slot on iteration {}: {:?}", i, e))?;
+    // The block replayer skips blocks which are prior to the state, so they must be rejected here
+    // rather than silently returning the pre-state. A pre-state which has been advanced to the
+    // slot of the block (e.g., with `skip-slots`) is fine.
+    if block.slot() < pre_state.slot {
+        return Err(format!(
+            "Block slot {} is prior to the pre-state slot {}",
+            block.slot(),
+            pre_state.slot
+        ));
     }
 
     pre_state
         .build_all_caches(spec)
         .map_err(|e| format!("Unable to build caches: {:?}", e))?;
 
-    per_block_processing(
-        &mut pre_state,
-        &block,
-        None,
-        BlockSignatureStrategy::VerifyIndividual,
-        spec,
-    )
-    .map_err(|e| format!("State transition failed: {:?}", e))?;
-
-    Ok(pre_state)
+    // Transition the parent state to the block slot, then apply the block.
+    BlockReplayer::<_, BlockReplayError>::new(pre_state, spec)
+        .block_signature_strategy(BlockSignatureStrategy::VerifyIndividual)
+        .pre_block_hook(Box::new(|state, _| {
+            state.build_all_caches(spec).map_err(Into::into)
+        }))
+        .apply_blocks(vec![block], None)
+        .map(BlockReplayer::into_state)
+        .map_err(|e| format!("State transition failed: {:?}", e))
 }
 
 pub fn load_from_ssz<T: Decode>(path: PathBuf) -> Result<T, String> {
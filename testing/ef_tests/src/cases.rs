@@ -10,6 +10,7 @@ mod bls_sign_msg;
 mod bls_verify_msg;
 mod common;
 mod epoch_processing;
+mod fork_choice;
 mod genesis_initialization;
 mod genesis_validity;
 mod operations;
@@ -26,6 +27,7 @@ pub use bls_sign_msg::*;
 pub use bls_verify_msg::*;
 pub use common::SszStaticType;
 pub use epoch_processing::*;
+pub use fork_choice::*;
 pub use genesis_initialization::*;
 pub use genesis_validity::*;
 pub use operations::*;
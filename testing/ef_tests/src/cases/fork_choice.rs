@@ -0,0 +1,554 @@
+use super::*;
+use crate::decode::{ssz_decode_file, yaml_decode_file};
+use ::fork_choice::{Error as ForkChoiceError, ForkChoice, ForkChoiceStore};
+use serde_derive::Deserialize;
+use state_processing::{
+    common::get_indexed_attestation, per_block_processing,
+    per_block_processing::is_valid_indexed_attestation, per_slot_processing,
+    BlockSignatureStrategy, VerifySignatures,
+};
+use std::collections::HashMap;
+use types::{
+    Attestation, BeaconBlock, BeaconState, Checkpoint, EthSpec, Hash256, IndexedAttestation,
+    RelativeEpoch, SignedBeaconBlock, Slot,
+};
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Head {
+    slot: Slot,
+    root: Hash256,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Checks {
+    head: Option<Head>,
+    time: Option<u64>,
+    genesis_time: Option<u64>,
+    justified_checkpoint: Option<Checkpoint>,
+    justified_checkpoint_root: Option<Hash256>,
+    finalized_checkpoint: Option<Checkpoint>,
+    finalized_checkpoint_root: Option<Hash256>,
+    best_justified_checkpoint: Option<Checkpoint>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum Step<B, A> {
+    Tick { tick: u64 },
+    Block { block: B, valid: Option<bool> },
+    Attestation { attestation: A, valid: Option<bool> },
+    Checks { checks: Box<Checks> },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Metadata {
+    pub description: Option<String>,
+}
+
+/// Replays the `on_tick`, `on_block` and `on_attestation` steps of a fork choice test against
+/// `ForkChoice`, running the `checks` as they're encountered.
+#[derive(Debug)]
+pub struct ForkChoiceTest<E: EthSpec> {
+    pub description: String,
+    pub anchor_state: BeaconState<E>,
+    pub anchor_block: BeaconBlock<E>,
+    pub steps: Vec<Step<SignedBeaconBlock<E>, Attestation<E>>>,
+}
+
+impl<E: EthSpec> LoadCase for ForkChoiceTest<E> {
+    fn load_from_dir(path: &Path) -> Result<Self, Error> {
+        let meta_file = path.join("meta.yaml");
+        let description = if meta_file.is_file() {
+            let metadata: Metadata = yaml_decode_file(&meta_file)?;
+            metadata.description
+        } else {
+            None
+        }
+        .unwrap_or_else(|| {
+            path.file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        });
+
+        let steps: Vec<Step<String, String>> = yaml_decode_file(&path.join("steps.yaml"))?;
+
+        // Resolve the block and attestation names into the objects they reference.
+        let steps = steps
+            .into_iter()
+            .map(|step| match step {
+                Step::Tick { tick } => Ok(Step::Tick { tick }),
+                Step::Block { block, valid } => ssz_decode_file(&ssz_path(path, &block))
+                    .map(|block| Step::Block { block, valid }),
+                Step::Attestation { attestation, valid } => {
+                    ssz_decode_file(&ssz_path(path, &attestation))
+                        .map(|attestation| Step::Attestation { attestation, valid })
+                }
+                Step::Checks { checks } => Ok(Step::Checks { checks }),
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            description,
+            anchor_state: ssz_decode_file(&ssz_path(path, "anchor_state"))?,
+            anchor_block: ssz_decode_file(&ssz_path(path, "anchor_block"))?,
+            steps,
+        })
+    }
+}
+
+/// Returns the path of the SSZ file called `name` in the test directory, which is
+/// snappy-compressed in later releases of the vectors.
+fn ssz_path(path: &Path, name: &str) -> PathBuf {
+    let compressed = path.join(format!("{}.ssz_snappy", name));
+    if compressed.is_file() {
+        compressed
+    } else {
+        path.join(format!("{}.ssz", name))
+    }
+}
+
+impl<E: EthSpec> Case for ForkChoiceTest<E> {
+    fn description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn result(&self, _case_index: usize) -> Result<(), Error> {
+        let mut tester = Tester::new(&self.anchor_state, &self.anchor_block)?;
+
+        for step in &self.steps {
+            match step {
+                Step::Tick { tick } => tester.set_tick(*tick)?,
+                Step::Block { block, valid } => {
+                    tester.process_block(block, valid.unwrap_or(true))?
+                }
+                Step::Attestation { attestation, valid } => {
+                    tester.process_attestation(attestation, valid.unwrap_or(true))?
+                }
+                Step::Checks { checks } => tester.run_checks(checks)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Drives a `ForkChoice` instance through the steps of a test.
+struct Tester<E: EthSpec> {
+    fork_choice: ForkChoice<TestStore<E>, E>,
+    genesis_time: u64,
+    /// The value of the last `tick` step, in seconds since the UNIX epoch.
+    time: u64,
+}
+
+impl<E: EthSpec> Tester<E> {
+    fn new(anchor_state: &BeaconState<E>, anchor_block: &BeaconBlock<E>) -> Result<Self, Error> {
+        if anchor_block.state_root != anchor_state.canonical_root() {
+            return Err(Error::FailedToParseTest(
+                "anchor block does not match anchor state".into(),
+            ));
+        }
+
+        let store = TestStore::new(anchor_state, anchor_block.canonical_root())?;
+        let fork_choice = ForkChoice::from_genesis(store, anchor_block)
+            .map_err(|e| fork_choice_error("initialize fork choice", e))?;
+
+        let genesis_time = anchor_state.genesis_time;
+        let time = genesis_time + anchor_state.slot.as_u64() * seconds_per_slot::<E>();
+
+        Ok(Self {
+            fork_choice,
+            genesis_time,
+            time,
+        })
+    }
+
+    fn current_slot(&self) -> Slot {
+        self.fork_choice.fc_store().get_current_slot()
+    }
+
+    /// Equivalent to the spec `on_tick`.
+    fn set_tick(&mut self, tick: u64) -> Result<(), Error> {
+        let slot = Slot::new(tick.saturating_sub(self.genesis_time) / seconds_per_slot::<E>());
+
+        self.time = tick;
+        self.fork_choice
+            .update_time(slot)
+            .map_err(|e| fork_choice_error("on_tick", e))?;
+
+        Ok(())
+    }
+
+    /// Runs the state transition for `block` and then applies it to fork choice.
+    ///
+    /// The `state_transition` checks (including the proposer index and signature) are performed
+    /// here, since `ForkChoice::on_block` expects them to have been run already.
+    fn process_block(&mut self, block: &SignedBeaconBlock<E>, valid: bool) -> Result<(), Error> {
+        let block_root = block.canonical_root();
+        let result = self.import_block(block, block_root);
+
+        match (result, valid) {
+            (Ok(()), true) | (Err(_), false) => Ok(()),
+            (Ok(()), false) => Err(Error::DidntFail(format!(
+                "block {:?} was imported but should be invalid",
+                block_root
+            ))),
+            (Err(e), true) => Err(Error::NotEqual(format!(
+                "block {:?} failed to import: {}",
+                block_root, e
+            ))),
+        }
+    }
+
+    fn import_block(
+        &mut self,
+        block: &SignedBeaconBlock<E>,
+        block_root: Hash256,
+    ) -> Result<(), String> {
+        let spec = &spec::<E>();
+
+        let mut state = self
+            .fork_choice
+            .fc_store()
+            .states
+            .get(&block.message.parent_root)
+            .cloned()
+            .ok_or_else(|| format!("unknown parent {:?}", block.message.parent_root))?;
+
+        // Blocks from the future must be rejected before the state transition is attempted.
+        if block.message.slot > self.current_slot() {
+            return Err(format!(
+                "block slot {} is later than current slot {}",
+                block.message.slot,
+                self.current_slot()
+            ));
+        }
+
+        while state.slot < block.message.slot {
+            per_slot_processing(&mut state, None, spec).map_err(|e| format!("{:?}", e))?;
+        }
+
+        state
+            .build_all_caches(spec)
+            .map_err(|e| format!("{:?}", e))?;
+
+        per_block_processing(
+            &mut state,
+            block,
+            Some(block_root),
+            BlockSignatureStrategy::VerifyBulk,
+            spec,
+        )
+        .map_err(|e| format!("{:?}", e))?;
+
+        if state.canonical_root() != block.message.state_root {
+            return Err("state root mismatch".into());
+        }
+
+        let current_slot = self.current_slot();
+        self.fork_choice
+            .on_block(current_slot, &block.message, block_root, &state)
+            .map_err(|e| format!("{:?}", e))
+    }
+
+    /// Equivalent to the spec `on_attestation`, including `is_valid_indexed_attestation`.
+    ///
+    /// An attestation must be applied unless the test marks it as invalid.
+    fn process_attestation(
+        &mut self,
+        attestation: &Attestation<E>,
+        valid: bool,
+    ) -> Result<(), Error> {
+        let result = self.apply_attestation(attestation);
+
+        match (result, valid) {
+            (Ok(()), true) | (Err(_), false) => Ok(()),
+            (Ok(()), false) => Err(Error::DidntFail(format!(
+                "attestation {:?} was applied but should be invalid",
+                attestation.data
+            ))),
+            (Err(e), true) => Err(Error::NotEqual(format!(
+                "attestation {:?} failed to apply: {}",
+                attestation.data, e
+            ))),
+        }
+    }
+
+    fn apply_attestation(&mut self, attestation: &Attestation<E>) -> Result<(), String> {
+        let indexed_attestation = self.indexed_attestation(attestation)?;
+
+        let current_slot = self.current_slot();
+        self.fork_choice
+            .on_attestation(current_slot, &indexed_attestation)
+            .map_err(|e| format!("{:?}", e))
+    }
+
+    /// Converts `attestation` into an `IndexedAttestation`, using the state of the target
+    /// checkpoint.
+    fn indexed_attestation(
+        &self,
+        attestation: &Attestation<E>,
+    ) -> Result<IndexedAttestation<E>, String> {
+        let spec = &spec::<E>();
+        let target = attestation.data.target;
+
+        let mut state = self
+            .fork_choice
+            .fc_store()
+            .checkpoint_state(&target)
+            .map_err(|e| format!("{:?}", e))?;
+
+        state
+            .build_committee_cache(RelativeEpoch::Current, spec)
+            .map_err(|e| format!("{:?}", e))?;
+
+        let committee = state
+            .get_beacon_committee(attestation.data.slot, attestation.data.index)
+            .map_err(|e| format!("{:?}", e))?;
+
+        let indexed_attestation = get_indexed_attestation(committee.committee, attestation)
+            .map_err(|e| format!("{:?}", e))?;
+
+        is_valid_indexed_attestation(&state, &indexed_attestation, VerifySignatures::True, spec)
+            .map_err(|e| format!("{:?}", e))?;
+
+        Ok(indexed_attestation)
+    }
+
+    fn run_checks(&mut self, checks: &Checks) -> Result<(), Error> {
+        let Checks {
+            head,
+            time,
+            genesis_time,
+            justified_checkpoint,
+            justified_checkpoint_root,
+            finalized_checkpoint,
+            finalized_checkpoint_root,
+            best_justified_checkpoint,
+        } = checks;
+
+        if let Some(expected) = head {
+            let current_slot = self.current_slot();
+            let root = self
+                .fork_choice
+                .get_head(current_slot)
+                .map_err(|e| fork_choice_error("get_head", e))?;
+            let slot = self
+                .fork_choice
+                .get_block(&root)
+                .map(|block| block.slot)
+                .ok_or_else(|| Error::NotEqual(format!("head {:?} is unknown", root)))?;
+
+            check_equal("head", Head { slot, root }, expected.clone())?;
+        }
+
+        if let Some(expected) = time {
+            check_equal("time", self.time, *expected)?;
+
+            let expected_slot =
+                Slot::new(expected.saturating_sub(self.genesis_time) / seconds_per_slot::<E>());
+            check_equal("current slot", self.current_slot(), expected_slot)?;
+        }
+
+        if let Some(expected) = genesis_time {
+            check_equal("genesis_time", self.genesis_time, *expected)?;
+        }
+
+        let store = self.fork_choice.fc_store();
+
+        if let Some(expected) = justified_checkpoint {
+            check_equal(
+                "justified_checkpoint",
+                *store.justified_checkpoint(),
+                *expected,
+            )?;
+        }
+
+        if let Some(expected) = justified_checkpoint_root {
+            check_equal(
+                "justified_checkpoint_root",
+                store.justified_checkpoint().root,
+                *expected,
+            )?;
+        }
+
+        if let Some(expected) = finalized_checkpoint {
+            check_equal(
+                "finalized_checkpoint",
+                *store.finalized_checkpoint(),
+                *expected,
+            )?;
+        }
+
+        if let Some(expected) = finalized_checkpoint_root {
+            check_equal(
+                "finalized_checkpoint_root",
+                store.finalized_checkpoint().root,
+                *expected,
+            )?;
+        }
+
+        if let Some(expected) = best_justified_checkpoint {
+            check_equal(
+                "best_justified_checkpoint",
+                *store.best_justified_checkpoint(),
+                *expected,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+fn spec<E: EthSpec>() -> types::ChainSpec {
+    E::default_spec()
+}
+
+fn seconds_per_slot<E: EthSpec>() -> u64 {
+    spec::<E>().milliseconds_per_slot / 1_000
+}
+
+fn fork_choice_error<T: std::fmt::Debug>(context: &str, e: ForkChoiceError<T>) -> Error {
+    Error::NotEqual(format!("{} failed: {:?}", context, e))
+}
+
+fn check_equal<T: PartialEq + std::fmt::Debug>(
+    name: &str,
+    result: T,
+    expected: T,
+) -> Result<(), Error> {
+    if result == expected {
+        Ok(())
+    } else {
+        Err(Error::NotEqual(format!(
+            "{}: got {:?}, expected {:?}",
+            name, result, expected
+        )))
+    }
+}
+
+/// An in-memory `ForkChoiceStore` which holds the post-state of every imported block.
+struct TestStore<E: EthSpec> {
+    time: Slot,
+    justified_checkpoint: Checkpoint,
+    justified_balances: Vec<u64>,
+    best_justified_checkpoint: Checkpoint,
+    finalized_checkpoint: Checkpoint,
+    /// Maps a block root to the post-state of that block.
+    states: HashMap<Hash256, BeaconState<E>>,
+}
+
+impl<E: EthSpec> TestStore<E> {
+    fn new(anchor_state: &BeaconState<E>, anchor_block_root: Hash256) -> Result<Self, Error> {
+        let checkpoint = Checkpoint {
+            epoch: anchor_state.current_epoch(),
+            root: anchor_block_root,
+        };
+
+        let mut states = HashMap::new();
+        states.insert(anchor_block_root, anchor_state.clone());
+
+        let mut store = Self {
+            time: anchor_state.slot,
+            justified_checkpoint: checkpoint,
+            justified_balances: vec![],
+            best_justified_checkpoint: checkpoint,
+            finalized_checkpoint: checkpoint,
+            states,
+        };
+
+        store.justified_balances = store
+            .checkpoint_balances(&checkpoint)
+            .map_err(|e| Error::FailedToParseTest(format!("Invalid anchor: {}", e)))?;
+
+        Ok(store)
+    }
+
+    /// Equivalent to `store.checkpoint_states[checkpoint]` in the spec: the state of the
+    /// checkpoint block, advanced to the first slot of the checkpoint epoch.
+    fn checkpoint_state(&self, checkpoint: &Checkpoint) -> Result<BeaconState<E>, String> {
+        let spec = &spec::<E>();
+
+        let mut state = self
+            .states
+            .get(&checkpoint.root)
+            .cloned()
+            .ok_or_else(|| format!("unknown checkpoint root {:?}", checkpoint.root))?;
+
+        let epoch_start_slot = checkpoint.epoch.start_slot(E::slots_per_epoch());
+        while state.slot < epoch_start_slot {
+            per_slot_processing(&mut state, None, spec).map_err(|e| format!("{:?}", e))?;
+        }
+
+        Ok(state)
+    }
+
+    /// Returns the effective balances of the active validators in the checkpoint state, as used
+    /// by the spec `get_latest_attesting_balance`.
+    fn checkpoint_balances(&self, checkpoint: &Checkpoint) -> Result<Vec<u64>, String> {
+        let state = self.checkpoint_state(checkpoint)?;
+
+        Ok(state
+            .validators
+            .iter()
+            .map(|validator| {
+                if validator.is_active_at(state.current_epoch()) {
+                    validator.effective_balance
+                } else {
+                    0
+                }
+            })
+            .collect())
+    }
+}
+
+impl<E: EthSpec> ForkChoiceStore<E> for TestStore<E> {
+    type Error = String;
+
+    fn get_current_slot(&self) -> Slot {
+        self.time
+    }
+
+    fn set_current_slot(&mut self, slot: Slot) {
+        self.time = slot
+    }
+
+    fn on_verified_block(
+        &mut self,
+        _block: &BeaconBlock<E>,
+        block_root: Hash256,
+        state: &BeaconState<E>,
+    ) -> Result<(), Self::Error> {
+        self.states.insert(block_root, state.clone());
+        Ok(())
+    }
+
+    fn justified_checkpoint(&self) -> &Checkpoint {
+        &self.justified_checkpoint
+    }
+
+    fn justified_balances(&self) -> &[u64] {
+        &self.justified_balances
+    }
+
+    fn best_justified_checkpoint(&self) -> &Checkpoint {
+        &self.best_justified_checkpoint
+    }
+
+    fn finalized_checkpoint(&self) -> &Checkpoint {
+        &self.finalized_checkpoint
+    }
+
+    fn set_finalized_checkpoint(&mut self, checkpoint: Checkpoint) {
+        self.finalized_checkpoint = checkpoint
+    }
+
+    fn set_justified_checkpoint(&mut self, checkpoint: Checkpoint) -> Result<(), Self::Error> {
+        self.justified_balances = self.checkpoint_balances(&checkpoint)?;
+        self.justified_checkpoint = checkpoint;
+        Ok(())
+    }
+
+    fn set_best_justified_checkpoint(&mut self, checkpoint: Checkpoint) {
+        self.best_justified_checkpoint = checkpoint
+    }
+}
@@ -3,7 +3,7 @@ use crate::case_result::compare_result;
 use crate::decode::yaml_decode_file;
 use serde_derive::Deserialize;
 use std::marker::PhantomData;
-use swap_or_not_shuffle::{compute_shuffled_index, shuffle_list};
+use swap_or_not_shuffle::{compute_shuffled_index, shuffle_list, shuffle_list_parallel};
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct Shuffling<T> {
@@ -39,7 +39,17 @@ impl<T: EthSpec> Case for Shuffling<T> {
 
             // Test "shuffle_list"
             let input: Vec<usize> = (0..self.count).collect();
-            let shuffling = shuffle_list(input, spec.shuffle_round_count, &seed, false).unwrap();
+            let shuffling =
+                shuffle_list(input.clone(), spec.shuffle_round_count, &seed, false).unwrap();
+            compare_result::<_, Error>(&Ok(shuffling), &Some(self.mapping.clone()))?;
+
+            // Test "shuffle_list_parallel"
+            //
+            // The vectors are shorter than `PARALLEL_SHUFFLE_MIN_LIST_SIZE`, so this only covers
+            // the sequential fallback. The parallel path is checked against `shuffle_list` in the
+            // `swap_or_not_shuffle` tests.
+            let shuffling =
+                shuffle_list_parallel(input, spec.shuffle_round_count, &seed, false).unwrap();
             compare_result::<_, Error>(&Ok(shuffling), &Some(self.mapping.clone()))?;
         }
 
@@ -188,6 +188,15 @@ impl Case for SszGeneric {
                     <>,
                     [type_name => test_container]
                 )?;
+
+                // Valid fixed-length containers must encode and hash identically when wrapped in
+                // a transparent enum. Invalid cases are skipped since their bytes may be valid
+                // for another variant.
+                if (type_name == "SmallTestStruct" || type_name == "FixedTestStruct")
+                    && self.path.join("value.yaml").is_file()
+                {
+                    ssz_generic_test::<TransparentTestEnum>(&self.path)?;
+                }
             }
             _ => panic!("unsupported handler: {}", self.handler_name),
         }
@@ -278,6 +287,19 @@ struct BitsStruct {
     E: BitVector<U8>,
 }
 
+/// Wraps the fixed-length test containers, in order to test `enum_behaviour = "transparent"`.
+///
+/// `FixedTestStruct` is first since a `FixedTestStruct` value would also deserialize as a
+/// `SmallTestStruct` (the extra field would be ignored).
+#[derive(Debug, Clone, PartialEq, Decode, Encode, TreeHash, Deserialize)]
+#[serde(untagged)]
+#[ssz(enum_behaviour = "transparent")]
+#[tree_hash(enum_behaviour = "transparent")]
+enum TransparentTestEnum {
+    Fixed(FixedTestStruct),
+    Small(SmallTestStruct),
+}
+
 fn byte_list_from_hex_str<'de, D, N: Unsigned>(
     deserializer: D,
 ) -> Result<VariableList<u8, N>, D::Error>
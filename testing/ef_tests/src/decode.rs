@@ -14,11 +14,27 @@ pub fn yaml_decode_file<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T
         .and_then(|s| yaml_decode(&s))
 }
 
+/// Decodes the SSZ file at `path`, which is snappy-compressed if it has the `ssz_snappy` extension.
 pub fn ssz_decode_file<T: ssz::Decode>(path: &Path) -> Result<T, Error> {
     fs::read(path)
         .map_err(|e| {
             Error::FailedToParseTest(format!("Unable to load {}: {:?}", path.display(), e))
         })
+        .and_then(|bytes| {
+            if path.extension().map_or(false, |ext| ext == "ssz_snappy") {
+                snap::raw::Decoder::new()
+                    .decompress_vec(&bytes)
+                    .map_err(|e| {
+                        Error::FailedToParseTest(format!(
+                            "Unable to decompress {}: {:?}",
+                            path.display(),
+                            e
+                        ))
+                    })
+            } else {
+                Ok(bytes)
+            }
+        })
         .and_then(|s| {
             T::from_ssz_bytes(&s).map_err(|e| {
                 Error::FailedToParseTest(format!(
@@ -153,6 +153,31 @@ impl<E: EthSpec + TypeName> Handler for ShufflingHandler<E> {
     }
 }
 
+macro_rules! fork_choice_handler {
+    ($handler_ident: ident, $handler_name: expr) => {
+        pub struct $handler_ident<E>(PhantomData<E>);
+
+        impl<E: EthSpec + TypeName> Handler for $handler_ident<E> {
+            type Case = cases::ForkChoiceTest<E>;
+
+            fn config_name() -> &'static str {
+                E::name()
+            }
+
+            fn runner_name() -> &'static str {
+                "fork_choice"
+            }
+
+            fn handler_name() -> String {
+                $handler_name.into()
+            }
+        }
+    };
+}
+
+fork_choice_handler!(ForkChoiceGetHeadHandler, "get_head");
+fork_choice_handler!(ForkChoiceOnBlockHandler, "on_block");
+
 pub struct SanityBlocksHandler<E>(PhantomData<E>);
 
 impl<E: EthSpec + TypeName> Handler for SanityBlocksHandler<E> {
@@ -53,6 +53,22 @@ fn shuffling() {
     ShufflingHandler::<MainnetEthSpec>::run();
 }
 
+#[test]
+// There are no fork choice vectors for the version of the specification at `TESTS_TAG`.
+#[ignore]
+fn fork_choice_get_head() {
+    ForkChoiceGetHeadHandler::<MinimalEthSpec>::run();
+    ForkChoiceGetHeadHandler::<MainnetEthSpec>::run();
+}
+
+#[test]
+// There are no fork choice vectors for the version of the specification at `TESTS_TAG`.
+#[ignore]
+fn fork_choice_on_block() {
+    ForkChoiceOnBlockHandler::<MinimalEthSpec>::run();
+    ForkChoiceOnBlockHandler::<MainnetEthSpec>::run();
+}
+
 #[test]
 fn operations_deposit() {
     OperationsHandler::<MinimalEthSpec, Deposit>::run();
@@ -0,0 +1,753 @@
+//! Provides a network of beacon nodes and validator clients which runs entirely within the
+//! current process.
+//!
+//! Nodes are connected via libp2p's in-memory transport and the slot clocks of every node and
+//! validator client are only advanced when instructed. This allows tests to partition and heal
+//! the network and then assert upon finality, reorgs and peer scores without depending upon
+//! wall-clock timing.
+//!
+//! Intended to be used for testing purposes. Not for production.
+
+use crate::{testing_client_config, ClientConfig, ClientGenesis, ValidatorConfig, ValidatorFiles};
+use beacon_node::beacon_chain::{
+    builder::Witness,
+    eth1_chain::CachingEth1Backend,
+    events::{EventKind, EventTopic, TeeEventHandler},
+    migrate::BackgroundMigrator,
+    slot_clock::{ManualSlotClock, SlotClock},
+    store::LevelDB,
+    BeaconChain, HeadInfo,
+};
+use beacon_node::{Client, ClientBuilder};
+use environment::RuntimeContext;
+use eth2_libp2p::{Multiaddr, NetworkGlobals, PeerAction, PeerId};
+use network::NetworkMessage;
+use parking_lot::Mutex;
+use slog::debug;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tempdir::TempDir;
+use tokio::sync::mpsc::Receiver;
+use tokio::time::delay_for;
+use types::{Attestation, Epoch, EthSpec, Hash256, SignedBeaconBlock, Slot};
+use validator_client::ProductionValidatorClient;
+
+/// The genesis time of every node in an `InMemoryNetwork` (4th September 2019).
+///
+/// Slot clocks are advanced manually, so it does not matter that genesis is in the past.
+pub const IN_MEMORY_GENESIS_TIME: u64 = 1_567_552_690;
+/// The delay between checks whilst waiting for the network to reach an expected state.
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+/// The default number of checks whilst waiting for the network to reach an expected state (e.g.,
+/// for a block to reach every connected node) before giving up.
+pub const DEFAULT_MAX_POLLS: usize = 1_000;
+/// The number of times to check for a node's listen address before giving up.
+const LISTEN_ADDR_RETRIES: usize = 50;
+/// The delay between checks for a node's listen address.
+const LISTEN_ADDR_RETRY_DELAY: Duration = Duration::from_millis(10);
+
+/// The `BeaconChainTypes` of an `InMemoryBeaconNode`.
+///
+/// Identical to the types of a `ProductionClient`, except that the slot clock is manual.
+pub type InMemoryWitness<E> = Witness<
+    BackgroundMigrator<E, LevelDB<E>, LevelDB<E>>,
+    ManualSlotClock,
+    CachingEth1Backend<E>,
+    E,
+    TeeEventHandler<E>,
+    LevelDB<E>,
+    LevelDB<E>,
+>;
+
+pub type InMemoryClient<E> = Client<InMemoryWitness<E>>;
+
+/// A `ManualSlotClock` whose clones share the same time.
+///
+/// The services of a validator client each hold a clone of its slot clock, so they must all be
+/// advanced together.
+#[derive(Clone)]
+pub struct SharedSlotClock(Arc<ManualSlotClock>);
+
+impl SharedSlotClock {
+    /// Sets the slot clock (and all of its clones) to the start of `slot`.
+    pub fn set_slot(&self, slot: Slot) {
+        self.0.set_slot(slot.as_u64())
+    }
+}
+
+impl SlotClock for SharedSlotClock {
+    fn new(genesis_slot: Slot, genesis_duration: Duration, slot_duration: Duration) -> Self {
+        Self(Arc::new(ManualSlotClock::new(
+            genesis_slot,
+            genesis_duration,
+            slot_duration,
+        )))
+    }
+
+    fn now(&self) -> Option<Slot> {
+        self.0.now()
+    }
+
+    fn is_prior_to_genesis(&self) -> Option<bool> {
+        self.0.is_prior_to_genesis()
+    }
+
+    fn now_duration(&self) -> Option<Duration> {
+        self.0.now_duration()
+    }
+
+    fn slot_of(&self, now: Duration) -> Option<Slot> {
+        self.0.slot_of(now)
+    }
+
+    fn slot_duration(&self) -> Duration {
+        self.0.slot_duration()
+    }
+
+    fn duration_to_slot(&self, slot: Slot) -> Option<Duration> {
+        SlotClock::duration_to_slot(self.0.as_ref(), slot)
+    }
+
+    fn start_of(&self, slot: Slot) -> Option<Duration> {
+        SlotClock::start_of(self.0.as_ref(), slot)
+    }
+
+    fn duration_to_next_slot(&self) -> Option<Duration> {
+        self.0.duration_to_next_slot()
+    }
+
+    fn duration_to_next_epoch(&self, slots_per_epoch: u64) -> Option<Duration> {
+        self.0.duration_to_next_epoch(slots_per_epoch)
+    }
+
+    fn genesis_slot(&self) -> Slot {
+        self.0.genesis_slot()
+    }
+}
+
+/// A validator client whose slot clock must be advanced manually.
+pub type InMemoryValidatorClient<E> = ProductionValidatorClient<E, SharedSlotClock>;
+
+/// Provides a beacon node which only communicates with other nodes in the current process and
+/// whose slot clock must be advanced manually.
+///
+/// Intended for use in testing. Not for production.
+pub struct InMemoryBeaconNode<E: EthSpec> {
+    pub client: InMemoryClient<E>,
+    pub datadir: TempDir,
+    reorgs: Mutex<Receiver<EventKind<E>>>,
+}
+
+impl<E: EthSpec> InMemoryBeaconNode<E> {
+    /// Starts a new beacon node on the tokio runtime in the given `context`.
+    ///
+    /// The `client_config` must use an interop genesis, since the slot clock is created from its
+    /// genesis time.
+    pub async fn new(
+        context: RuntimeContext<E>,
+        mut client_config: ClientConfig,
+    ) -> Result<Self, String> {
+        // Creates a temporary directory that will be deleted once this `TempDir` is dropped.
+        let datadir = TempDir::new("lighthouse_in_memory_node")
+            .map_err(|e| format!("Unable to create node data dir: {:?}", e))?;
+
+        client_config.data_dir = datadir.path().into();
+        client_config.network.network_dir = PathBuf::from(datadir.path()).join("network");
+        client_config.network.in_memory_transport = true;
+        client_config.network.disable_discovery = true;
+
+        let genesis_time = match client_config.genesis {
+            ClientGenesis::Interop { genesis_time, .. } => genesis_time,
+            _ => return Err("An in-memory beacon node requires an interop genesis".into()),
+        };
+
+        let http_eth2_config = context.eth2_config().clone();
+        let spec = context.eth2_config().spec.clone();
+        let slot_clock = ManualSlotClock::new(
+            spec.genesis_slot,
+            Duration::from_secs(genesis_time),
+            Duration::from_millis(spec.milliseconds_per_slot),
+        );
+        let db_path = client_config.create_db_path()?;
+        let freezer_db_path = client_config.create_freezer_db_path()?;
+        let executor = context.executor.clone();
+
+        let (builder, events) = ClientBuilder::new(context.eth_spec_instance.clone())
+            .runtime_context(context)
+            .chain_spec(spec)
+            .disk_store(&db_path, &freezer_db_path, client_config.store.clone())?
+            .background_migrator()?
+            .beacon_chain_builder(client_config.genesis.clone(), client_config.clone())
+            .await?
+            .dummy_eth1_backend()?
+            .slot_clock(slot_clock)
+            .tee_event_handler(client_config.websocket_server.clone())?;
+
+        let reorgs = events.subscribe(vec![EventTopic::ChainReorg]);
+
+        // Inject the executor into the discv5 network config.
+        client_config.network.discv5_config.executor = Some(Box::new(executor));
+
+        let builder = builder
+            .build_beacon_chain()?
+            .network(&client_config.network)?;
+
+        let builder = if client_config.rest_api.enabled {
+            builder.http_server(&client_config, &http_eth2_config, events)?
+        } else {
+            builder
+        };
+
+        Ok(Self {
+            client: builder.build(),
+            datadir,
+            reorgs: Mutex::new(reorgs),
+        })
+    }
+
+    /// Returns the `BeaconChain` of this node.
+    pub fn beacon_chain(&self) -> Arc<BeaconChain<InMemoryWitness<E>>> {
+        self.client
+            .beacon_chain()
+            .expect("in-memory nodes always start a beacon chain")
+    }
+
+    /// Returns the libp2p globals of this node.
+    pub fn network_globals(&self) -> Arc<NetworkGlobals<E>> {
+        self.client
+            .network_globals()
+            .expect("in-memory nodes always start a network")
+    }
+
+    /// Returns the libp2p `PeerId` of this node.
+    pub fn peer_id(&self) -> PeerId {
+        self.network_globals().local_peer_id()
+    }
+
+    /// Sends `message` to the network service of this node.
+    pub fn send(&self, message: NetworkMessage<E>) -> Result<(), String> {
+        self.client
+            .network_send()
+            .ok_or_else(|| "Node does not have a network".to_string())?
+            .send(message)
+            .map_err(|e| format!("Unable to send message to network: {:?}", e))
+    }
+
+    /// Waits until libp2p has started listening and returns the in-memory address of this node.
+    pub async fn listen_addr(&self) -> Result<Multiaddr, String> {
+        for _ in 0..LISTEN_ADDR_RETRIES {
+            if let Some(addr) = self.network_globals().listen_multiaddrs().first() {
+                return Ok(addr.clone());
+            }
+            delay_for(LISTEN_ADDR_RETRY_DELAY).await;
+        }
+
+        Err("Node did not start listening".into())
+    }
+
+    /// Sets the slot clock of this node to the start of `slot`.
+    pub fn set_slot(&self, slot: Slot) {
+        self.beacon_chain().slot_clock.set_slot(slot.as_u64())
+    }
+
+    /// Returns information about the head of the canonical chain of this node.
+    pub fn head_info(&self) -> Result<HeadInfo, String> {
+        self.beacon_chain()
+            .head_info()
+            .map_err(|e| format!("Unable to read head: {:?}", e))
+    }
+
+    /// Returns the finalized epoch of the head of this node.
+    pub fn finalized_epoch(&self) -> Result<Epoch, String> {
+        self.head_info().map(|head| head.finalized_checkpoint.epoch)
+    }
+
+    /// Returns `true` if this node is connected to `peer_id`.
+    pub fn is_connected_to(&self, peer_id: &PeerId) -> bool {
+        self.network_globals().peers.read().is_connected(peer_id)
+    }
+
+    /// Returns the score this node has assigned to `peer_id`.
+    pub fn peer_score(&self, peer_id: &PeerId) -> f64 {
+        self.network_globals().peers.read().score(peer_id).score()
+    }
+
+    /// Returns `true` if this node has banned `peer_id`.
+    pub fn is_banned(&self, peer_id: &PeerId) -> bool {
+        self.network_globals().peers.read().is_banned(peer_id)
+    }
+
+    /// Returns the address of the http server of this node, for use by validator clients.
+    fn http_server(&self) -> Result<String, String> {
+        self.client
+            .http_listen_addr()
+            .map(|addr| format!("http://{}", addr))
+            .ok_or_else(|| "In-memory node does not have a http server".to_string())
+    }
+
+    /// Returns the block at `slot` of the canonical chain of this node, if any.
+    fn block_at_slot(&self, slot: Slot) -> Result<Option<SignedBeaconBlock<E>>, String> {
+        let head = self.head_info()?;
+        if head.slot != slot {
+            return Ok(None);
+        }
+
+        self.beacon_chain()
+            .get_block(&head.block_root)
+            .map_err(|e| format!("Unable to read block: {:?}", e))
+    }
+
+    /// Returns the aggregates in the op pool of this node which attest to `slot`.
+    fn aggregates_at_slot(&self, slot: Slot) -> Vec<Attestation<E>> {
+        self.beacon_chain()
+            .op_pool
+            .get_all_attestations()
+            .into_iter()
+            .filter(|attestation| attestation.data.slot == slot)
+            .collect()
+    }
+
+    /// Returns `true` if this node has imported `block`, or is unable to import it because it does
+    /// not know the parent of `block` (in which case it must sync, rather than rely on gossip).
+    fn has_block_or_unknown_parent(&self, block: &SignedBeaconBlock<E>) -> bool {
+        let beacon_chain = self.beacon_chain();
+        let fork_choice = beacon_chain.fork_choice.read();
+
+        !fork_choice.contains_block(&block.message.parent_root)
+            || fork_choice.contains_block(&block.canonical_root())
+    }
+
+    /// Returns `true` if the op pool of this node includes every attestation in `aggregate`, or
+    /// this node is unable to verify `aggregate` because it does not know the attested block.
+    fn has_aggregate_or_unknown_block(&self, aggregate: &Attestation<E>) -> bool {
+        let beacon_chain = self.beacon_chain();
+
+        if !beacon_chain
+            .fork_choice
+            .read()
+            .contains_block(&aggregate.data.beacon_block_root)
+        {
+            return true;
+        }
+
+        beacon_chain
+            .op_pool
+            .get_all_attestations()
+            .iter()
+            .any(|attestation| {
+                attestation.data == aggregate.data
+                    && aggregate
+                        .aggregation_bits
+                        .difference(&attestation.aggregation_bits)
+                        .is_zero()
+            })
+    }
+
+    /// Returns the depth of each reorg this node has performed since the last call.
+    pub fn take_reorg_depths(&self) -> Vec<u64> {
+        let mut reorgs = self.reorgs.lock();
+        let mut depths = vec![];
+
+        while let Ok(event) = reorgs.try_recv() {
+            if let EventKind::BeaconChainReorg { depth, .. } = event {
+                depths.push(depth);
+            }
+        }
+
+        depths
+    }
+}
+
+/// A validator client attached to the beacon node with index `beacon_node` of an
+/// `InMemoryNetwork`.
+struct AttachedValidatorClient<E: EthSpec> {
+    beacon_node: usize,
+    client: InMemoryValidatorClient<E>,
+    _files: ValidatorFiles,
+}
+
+/// A network of `InMemoryBeaconNode`s and `InMemoryValidatorClient`s sharing an interop genesis.
+///
+/// Time only passes when `advance_slot` is called, at which point the slot clock of every node and
+/// validator client is updated and each validator client performs its duties.
+pub struct InMemoryNetwork<E: EthSpec> {
+    context: RuntimeContext<E>,
+    validator_count: usize,
+    beacon_nodes: Vec<InMemoryBeaconNode<E>>,
+    validator_clients: Vec<AttachedValidatorClient<E>>,
+    slot: Slot,
+    max_polls: usize,
+}
+
+impl<E: EthSpec> InMemoryNetwork<E> {
+    /// Creates an empty network with `validator_count` interop validators at genesis.
+    pub fn new(context: RuntimeContext<E>, validator_count: usize) -> Self {
+        Self {
+            slot: context.eth2_config().spec.genesis_slot,
+            context,
+            validator_count,
+            beacon_nodes: vec![],
+            validator_clients: vec![],
+            max_polls: DEFAULT_MAX_POLLS,
+        }
+    }
+
+    /// Sets the number of times to check whether the network has reached an expected state
+    /// (e.g., whether published blocks have reached every connected node) before returning an
+    /// error. Defaults to `DEFAULT_MAX_POLLS`.
+    pub fn set_max_polls(&mut self, max_polls: usize) {
+        self.max_polls = max_polls;
+    }
+
+    /// Returns the current slot of all nodes in the network.
+    pub fn slot(&self) -> Slot {
+        self.slot
+    }
+
+    /// Returns the beacon nodes in the network, ordered by the time they were added.
+    pub fn beacon_nodes(&self) -> &[InMemoryBeaconNode<E>] {
+        &self.beacon_nodes
+    }
+
+    /// Returns the beacon node with the given `index`.
+    pub fn beacon_node(&self, index: usize) -> Result<&InMemoryBeaconNode<E>, String> {
+        self.beacon_nodes
+            .get(index)
+            .ok_or_else(|| format!("No beacon node for index {}", index))
+    }
+
+    /// Adds a beacon node to the network and connects it to all existing nodes, returning its
+    /// index.
+    pub async fn add_beacon_node(&mut self) -> Result<usize, String> {
+        let index = self.beacon_nodes.len();
+
+        let mut client_config = testing_client_config();
+        client_config.genesis = ClientGenesis::Interop {
+            validator_count: self.validator_count,
+            genesis_time: IN_MEMORY_GENESIS_TIME,
+        };
+
+        let beacon_node = InMemoryBeaconNode::new(
+            self.context.service_context(format!("node_{}", index)),
+            client_config,
+        )
+        .await?;
+        beacon_node.set_slot(self.slot);
+
+        for peer in &self.beacon_nodes {
+            beacon_node.send(NetworkMessage::DialMultiaddr {
+                multiaddr: peer.listen_addr().await?,
+            })?;
+        }
+
+        self.beacon_nodes.push(beacon_node);
+
+        let (new_node, peers) = self
+            .beacon_nodes
+            .split_last()
+            .expect("a node was just added");
+        self.wait_until("the new node to connect to all nodes", || {
+            peers.iter().all(|peer| {
+                new_node.is_connected_to(&peer.peer_id())
+                    && peer.is_connected_to(&new_node.peer_id())
+            })
+        })
+        .await?;
+
+        Ok(index)
+    }
+
+    /// Adds a validator client which performs the duties of the interop validators with the
+    /// given indices via the http api of the beacon node with index `beacon_node`.
+    pub async fn add_validator_client(
+        &mut self,
+        beacon_node: usize,
+        validator_indices: Vec<usize>,
+    ) -> Result<(), String> {
+        let http_server = self.beacon_node(beacon_node)?.http_server()?;
+
+        if let Some(index) = validator_indices
+            .iter()
+            .find(|index| **index >= self.validator_count)
+        {
+            return Err(format!("No interop validator for index {}", index));
+        }
+
+        let files = ValidatorFiles::with_keystores(&validator_indices)?;
+
+        let mut config = ValidatorConfig::default();
+        config.http_server = http_server;
+        config.data_dir = files.datadir.path().into();
+        config.secrets_dir = files.secrets_dir.path().into();
+        config.auto_register = true;
+        // Whether the node considers itself synced depends upon the timing of the network, so
+        // it must not prevent the validator client from performing its duties.
+        config.allow_unsynced_beacon_node = true;
+
+        let client = InMemoryValidatorClient::new_with_slot_clock(
+            self.context
+                .service_context(format!("vc_{}", self.validator_clients.len())),
+            config,
+        )
+        .await?;
+        client.slot_clock().set_slot(self.slot);
+
+        self.validator_clients.push(AttachedValidatorClient {
+            beacon_node,
+            client,
+            _files: files,
+        });
+
+        Ok(())
+    }
+
+    /// Advances every node and validator client to the next slot, then has each validator client
+    /// propose and attest.
+    ///
+    /// All blocks are published (and have reached every connected node) before any attestations
+    /// are made, and all aggregates have reached every connected node before this returns.
+    pub async fn advance_slot(&mut self) -> Result<(), String> {
+        self.slot += 1;
+
+        for beacon_node in &self.beacon_nodes {
+            beacon_node.set_slot(self.slot);
+        }
+        for validator_client in &self.validator_clients {
+            validator_client.client.slot_clock().set_slot(self.slot);
+        }
+
+        let mut blocks = vec![];
+        for validator_client in &self.validator_clients {
+            validator_client.client.propose_blocks().await?;
+
+            let beacon_node = self.beacon_node(validator_client.beacon_node)?;
+            if let Some(block) = beacon_node.block_at_slot(self.slot)? {
+                blocks.push((validator_client.beacon_node, block));
+            }
+        }
+        for (origin, block) in &blocks {
+            let reachable = self.reachable_from(*origin);
+            self.wait_until("a block to reach all connected nodes", || {
+                reachable
+                    .iter()
+                    .all(|index| self.beacon_nodes[*index].has_block_or_unknown_parent(block))
+            })
+            .await?;
+        }
+
+        let mut aggregates = vec![];
+        for validator_client in &self.validator_clients {
+            validator_client.client.attest().await?;
+
+            let beacon_node = self.beacon_node(validator_client.beacon_node)?;
+            for aggregate in beacon_node.aggregates_at_slot(self.slot) {
+                aggregates.push((validator_client.beacon_node, aggregate));
+            }
+        }
+        for (origin, aggregate) in &aggregates {
+            let reachable = self.reachable_from(*origin);
+            self.wait_until("an aggregate to reach all connected nodes", || {
+                reachable.iter().all(|index| {
+                    self.beacon_nodes[*index].has_aggregate_or_unknown_block(aggregate)
+                })
+            })
+            .await?;
+        }
+
+        debug!(
+            self.context.log(),
+            "Advanced in-memory network";
+            "slot" => self.slot.as_u64(),
+        );
+
+        Ok(())
+    }
+
+    /// Calls `advance_slot` until all nodes are at the first slot of `epoch`.
+    pub async fn advance_to_epoch(&mut self, epoch: Epoch) -> Result<(), String> {
+        let target_slot = epoch.start_slot(E::slots_per_epoch());
+
+        while self.slot < target_slot {
+            self.advance_slot().await?;
+        }
+
+        Ok(())
+    }
+
+    /// Disconnects every node in `left` from every node in `right` and prevents them from
+    /// reconnecting until `heal` is called.
+    ///
+    /// The bans are explicit, so they are not lifted by the peer manager if the scores of the
+    /// banned peers decay.
+    pub async fn partition(&self, left: &[usize], right: &[usize]) -> Result<(), String> {
+        let pairs = self.pairs(left, right)?;
+
+        for (a, b) in &pairs {
+            a.send(NetworkMessage::BanPeer {
+                peer_id: b.peer_id(),
+            })?;
+            b.send(NetworkMessage::BanPeer {
+                peer_id: a.peer_id(),
+            })?;
+        }
+
+        self.wait_until("the partitioned nodes to disconnect", || {
+            pairs
+                .iter()
+                .all(|(a, b)| !a.is_connected_to(&b.peer_id()) && !b.is_connected_to(&a.peer_id()))
+        })
+        .await
+    }
+
+    /// Reverses a `partition`, reconnecting every node in `left` to every node in `right`.
+    ///
+    /// Returns once the nodes have reconnected. Note: unbanning a peer also resets its score.
+    pub async fn heal(&self, left: &[usize], right: &[usize]) -> Result<(), String> {
+        let pairs = self.pairs(left, right)?;
+
+        for (a, b) in &pairs {
+            a.send(NetworkMessage::UnbanPeer {
+                peer_id: b.peer_id(),
+            })?;
+            b.send(NetworkMessage::UnbanPeer {
+                peer_id: a.peer_id(),
+            })?;
+            a.send(NetworkMessage::DialMultiaddr {
+                multiaddr: b.listen_addr().await?,
+            })?;
+        }
+
+        self.wait_until("the healed nodes to reconnect", || {
+            pairs
+                .iter()
+                .all(|(a, b)| a.is_connected_to(&b.peer_id()) && b.is_connected_to(&a.peer_id()))
+        })
+        .await
+    }
+
+    /// Has the node with index `reporter` report the node with index `offender` for `action`,
+    /// returning once the score of `offender` has changed as a result.
+    pub async fn report_peer(
+        &self,
+        reporter: usize,
+        offender: usize,
+        action: PeerAction,
+    ) -> Result<(), String> {
+        let reporter = self.beacon_node(reporter)?;
+        let peer_id = self.beacon_node(offender)?.peer_id();
+        let score = reporter.peer_score(&peer_id);
+
+        reporter.send(NetworkMessage::ReportPeer {
+            peer_id: peer_id.clone(),
+            action,
+        })?;
+
+        self.wait_until("the reported peer to be scored", || {
+            reporter.peer_score(&peer_id) != score
+        })
+        .await
+    }
+
+    /// Returns an error if any node has not finalized at least `epoch`.
+    pub fn verify_all_finalized_at_least(&self, epoch: Epoch) -> Result<(), String> {
+        for (index, beacon_node) in self.beacon_nodes.iter().enumerate() {
+            let finalized_epoch = beacon_node.finalized_epoch()?;
+            if finalized_epoch < epoch {
+                return Err(format!(
+                    "Node {} finalized epoch {}, expected at least {}",
+                    index, finalized_epoch, epoch
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the common head block root of the given nodes, or an error if they disagree.
+    pub fn verify_same_head(&self, nodes: &[usize]) -> Result<Hash256, String> {
+        let mut heads = nodes
+            .iter()
+            .map(|index| self.beacon_node(*index)?.head_info())
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .map(|head| head.block_root);
+
+        let first = heads
+            .next()
+            .ok_or_else(|| "No nodes to compare".to_string())?;
+
+        if heads.all(|root| root == first) {
+            Ok(first)
+        } else {
+            Err(format!("Nodes {:?} do not share a head", nodes))
+        }
+    }
+
+    /// Waits until `condition` returns `true`, returning an error describing the expected state
+    /// of the network if it does not do so within `max_polls` checks.
+    ///
+    /// The slot clocks do not move whilst waiting, so the outcome never depends upon how long it
+    /// takes for the condition to be met.
+    async fn wait_until<F: Fn() -> bool>(
+        &self,
+        description: &str,
+        condition: F,
+    ) -> Result<(), String> {
+        for _ in 0..self.max_polls {
+            if condition() {
+                return Ok(());
+            }
+            delay_for(POLL_INTERVAL).await;
+        }
+
+        if condition() {
+            Ok(())
+        } else {
+            Err(format!(
+                "Gave up after {} checks waiting for {}",
+                self.max_polls, description
+            ))
+        }
+    }
+
+    /// Returns the indices of all nodes which are connected to the node with index `origin`,
+    /// either directly or via other nodes (including `origin` itself).
+    fn reachable_from(&self, origin: usize) -> Vec<usize> {
+        let mut reachable = HashSet::new();
+        let mut to_visit = vec![origin];
+
+        while let Some(index) = to_visit.pop() {
+            if !reachable.insert(index) {
+                continue;
+            }
+
+            let node = &self.beacon_nodes[index];
+            for (peer_index, peer) in self.beacon_nodes.iter().enumerate() {
+                if !reachable.contains(&peer_index) && node.is_connected_to(&peer.peer_id()) {
+                    to_visit.push(peer_index);
+                }
+            }
+        }
+
+        reachable.into_iter().collect()
+    }
+
+    /// Returns each pair of nodes with one node in `left` and the other in `right`.
+    fn pairs(
+        &self,
+        left: &[usize],
+        right: &[usize],
+    ) -> Result<Vec<(&InMemoryBeaconNode<E>, &InMemoryBeaconNode<E>)>, String> {
+        let mut pairs = vec![];
+
+        for a in left {
+            for b in right {
+                pairs.push((self.beacon_node(*a)?, self.beacon_node(*b)?));
+            }
+        }
+
+        Ok(pairs)
+    }
+}
@@ -2,6 +2,8 @@
 //!
 //! Intended to be used for testing and simulation purposes. Not for production.
 
+pub mod in_memory_network;
+
 use beacon_node::ProductionBeaconNode;
 use environment::RuntimeContext;
 use std::path::PathBuf;
@@ -57,11 +59,7 @@ impl<E: EthSpec> LocalBeaconNode<E> {
             .client
             .http_listen_addr()
             .ok_or_else(|| "A remote beacon node must have a http server".to_string())?;
-        Ok(RemoteBeaconNode::new(format!(
-            "http://{}:{}",
-            socket_addr.ip(),
-            socket_addr.port()
-        ))?)
+        Ok(RemoteBeaconNode::new(format!("http://{}", socket_addr))?)
     }
 }
 
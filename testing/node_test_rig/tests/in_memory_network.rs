@@ -0,0 +1,145 @@
+#![cfg(test)]
+
+use eth2_libp2p::PeerAction;
+use node_test_rig::{
+    environment::{Environment, EnvironmentBuilder},
+    in_memory_network::InMemoryNetwork,
+};
+use types::{Epoch, MinimalEthSpec};
+
+const VALIDATOR_COUNT: usize = 32;
+/// The number of validators attached to the first node, enough to finalize without the second.
+const MAJORITY_COUNT: usize = 24;
+/// The maximum number of slots to wait for the nodes to agree upon a head after healing.
+const MAX_SYNC_SLOTS: usize = 16;
+
+fn build_env() -> Environment<MinimalEthSpec> {
+    EnvironmentBuilder::minimal()
+        .null_logger()
+        .expect("should build env logger")
+        .multi_threaded_tokio_runtime()
+        .expect("should start tokio runtime")
+        .build()
+        .expect("environment should build")
+}
+
+#[test]
+fn partition_and_heal() {
+    let mut env = build_env();
+    let context = env.core_context();
+
+    env.runtime().block_on(async {
+        let mut network = InMemoryNetwork::new(context, VALIDATOR_COUNT);
+        network.add_beacon_node().await.expect("should add node 0");
+        network.add_beacon_node().await.expect("should add node 1");
+        network
+            .add_validator_client(0, (0..MAJORITY_COUNT).collect())
+            .await
+            .expect("should add majority validators");
+        network
+            .add_validator_client(1, (MAJORITY_COUNT..VALIDATOR_COUNT).collect())
+            .await
+            .expect("should add minority validators");
+
+        let peer_0 = network.beacon_node(0).unwrap().peer_id();
+        let peer_1 = network.beacon_node(1).unwrap().peer_id();
+        assert!(
+            network.beacon_node(0).unwrap().is_connected_to(&peer_1),
+            "nodes should connect"
+        );
+
+        network
+            .advance_to_epoch(Epoch::new(4))
+            .await
+            .expect("should advance before partition");
+        network
+            .verify_all_finalized_at_least(Epoch::new(2))
+            .expect("should finalize before partition");
+        network
+            .verify_same_head(&[0, 1])
+            .expect("should share head before partition");
+
+        network
+            .partition(&[0], &[1])
+            .await
+            .expect("should partition");
+        assert!(
+            !network.beacon_node(1).unwrap().is_connected_to(&peer_0),
+            "partitioned nodes should disconnect"
+        );
+
+        network
+            .advance_to_epoch(Epoch::new(6))
+            .await
+            .expect("should advance during partition");
+        assert!(
+            network.verify_same_head(&[0, 1]).is_err(),
+            "partitioned nodes should fork"
+        );
+        assert!(
+            network.beacon_node(0).unwrap().finalized_epoch().unwrap()
+                > network.beacon_node(1).unwrap().finalized_epoch().unwrap(),
+            "only the majority should finalize during the partition"
+        );
+
+        // Penalise the majority node whilst it is banned, as if it had misbehaved before the
+        // partition, so that healing must reset its score for the nodes to reconnect.
+        network
+            .report_peer(1, 0, PeerAction::Fatal)
+            .await
+            .expect("should report peer");
+        let score_before_heal = network.beacon_node(1).unwrap().peer_score(&peer_0);
+        assert!(
+            network.beacon_node(1).unwrap().is_banned(&peer_0),
+            "the majority node should be banned during the partition"
+        );
+
+        network.heal(&[0], &[1]).await.expect("should heal");
+        assert!(
+            network.beacon_node(1).unwrap().is_connected_to(&peer_0)
+                && network.beacon_node(0).unwrap().is_connected_to(&peer_1),
+            "healed nodes should reconnect"
+        );
+        assert!(
+            !network.beacon_node(1).unwrap().is_banned(&peer_0),
+            "healing should lift the ban"
+        );
+
+        let score_after_heal = network.beacon_node(1).unwrap().peer_score(&peer_0);
+        assert!(
+            score_before_heal < 0.0,
+            "the penalised peer should have a negative score, got {}",
+            score_before_heal
+        );
+        assert!(
+            score_after_heal > score_before_heal && score_after_heal >= 0.0,
+            "healing should reset the peer score from {}, got {}",
+            score_before_heal,
+            score_after_heal
+        );
+
+        for _ in 0..MAX_SYNC_SLOTS {
+            if network.verify_same_head(&[0, 1]).is_ok() {
+                break;
+            }
+            network
+                .advance_slot()
+                .await
+                .expect("should advance after healing");
+        }
+
+        network
+            .verify_same_head(&[0, 1])
+            .expect("should share head after healing");
+        assert!(
+            !network
+                .beacon_node(1)
+                .unwrap()
+                .take_reorg_depths()
+                .is_empty(),
+            "the minority node should reorg onto the majority chain"
+        );
+    });
+
+    env.fire_signal();
+}
@@ -127,8 +127,7 @@ impl<E: EthSpec> LocalNetwork<E> {
                 .expect("Must have http started")
         };
 
-        validator_config.http_server =
-            format!("http://{}:{}", socket_addr.ip(), socket_addr.port());
+        validator_config.http_server = format!("http://{}", socket_addr);
         let validator_client = LocalValidatorClient::production_with_insecure_keypairs(
             context,
             validator_config,
@@ -184,27 +184,12 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
                 .checked_sub(slot_duration / 3)
                 .unwrap_or_else(|| Duration::from_secs(0));
 
-        let duties_by_committee_index: HashMap<CommitteeIndex, Vec<DutyAndProof>> = self
-            .duties_service
-            .attesters(slot)
-            .into_iter()
-            .fold(HashMap::new(), |mut map, duty_and_proof| {
-                if let Some(committee_index) = duty_and_proof.duty.attestation_committee_index {
-                    let validator_duties = map.entry(committee_index).or_insert_with(|| vec![]);
-
-                    validator_duties.push(duty_and_proof);
-                }
-
-                map
-            });
-
         // For each committee index for this slot:
         //
         // - Create and publish an `Attestation` for all required validators.
         // - Create and publish `SignedAggregateAndProof` for all aggregating validators.
-        duties_by_committee_index
-            .into_iter()
-            .for_each(|(committee_index, validator_duties)| {
+        self.duties_by_committee_index(slot).into_iter().for_each(
+            |(committee_index, validator_duties)| {
                 // Spawn a separate task for each attestation.
                 self.inner.context.executor.runtime_handle().spawn(
                     self.clone().publish_attestations_and_aggregates(
@@ -214,11 +199,59 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
                         aggregate_production_instant,
                     ),
                 );
-            });
+            },
+        );
 
         Ok(())
     }
 
+    /// Produces and publishes the attestations (and then any aggregates) of the current slot,
+    /// returning once they have all been published.
+    ///
+    /// Unlike `start_update_service`, this does not wait until 1/3rd (or 2/3rds) of the way
+    /// through the slot. Intended for driving a validator client with a manual slot clock.
+    pub(crate) async fn attest_now(&self) -> Result<(), String> {
+        let slot = self
+            .slot_clock
+            .now()
+            .ok_or_else(|| "Failed to read slot clock".to_string())?;
+
+        for (committee_index, validator_duties) in self.duties_by_committee_index(slot) {
+            self.clone()
+                .publish_attestations_and_aggregates(
+                    slot,
+                    committee_index,
+                    validator_duties,
+                    Instant::now(),
+                )
+                .await
+                .map_err(|()| {
+                    format!(
+                        "Failed to attest with committee {} at slot {}",
+                        committee_index, slot
+                    )
+                })?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the duties of all validators which must attest at `slot`, grouped by committee.
+    fn duties_by_committee_index(&self, slot: Slot) -> HashMap<CommitteeIndex, Vec<DutyAndProof>> {
+        self.duties_service.attesters(slot).into_iter().fold(
+            HashMap::new(),
+            |mut map, duty_and_proof| {
+                if let Some(committee_index) = duty_and_proof.duty.attestation_committee_index {
+                    let validator_duties = map.entry(committee_index).or_insert_with(|| vec![]);
+
+                    validator_duties.push(duty_and_proof);
+                }
+
+                map
+            },
+        )
+    }
+
     /// Performs the first step of the attesting process: downloading `Attestation` objects,
     /// signing them and returning them to the validator.
     ///
@@ -1,20 +1,32 @@
+use crate::graffiti_file::GraffitiFile;
+use crate::metrics;
 use crate::validator_store::ValidatorStore;
 use environment::RuntimeContext;
 use futures::channel::mpsc::Receiver;
-use futures::{StreamExt, TryFutureExt};
-use remote_beacon_node::{PublishStatus, RemoteBeaconNode};
-use slog::{crit, debug, error, info, trace, warn};
+use futures::{Future, StreamExt, TryFutureExt};
+use parking_lot::Mutex;
+use remote_beacon_node::{Error as RemoteBeaconNodeError, PublishStatus, RemoteBeaconNode};
+use slog::{crit, debug, error, info, trace, warn, Logger};
 use slot_clock::SlotClock;
 use std::ops::Deref;
 use std::sync::Arc;
-use types::{EthSpec, PublicKey, Slot};
+use std::time::Duration;
+use tokio::time::timeout;
+use types::{EthSpec, Graffiti, PublicKey, Slot};
+
+/// Block proposals are abandoned, rather than published late, once `1 / QUOTIENT` of the slot
+/// has elapsed. By then, most attesters will already have voted for the previous block.
+const BLOCK_PROPOSAL_DEADLINE_QUOTIENT: u32 = 3;
 
 /// Builds a `BlockService`.
 pub struct BlockServiceBuilder<T, E: EthSpec> {
     validator_store: Option<ValidatorStore<T, E>>,
     slot_clock: Option<Arc<T>>,
     beacon_node: Option<RemoteBeaconNode<E>>,
+    fallback_beacon_nodes: Vec<RemoteBeaconNode<E>>,
     context: Option<RuntimeContext<E>>,
+    graffiti: Option<Graffiti>,
+    graffiti_file: Option<GraffitiFile>,
 }
 
 impl<T: SlotClock + 'static, E: EthSpec> BlockServiceBuilder<T, E> {
@@ -23,7 +35,10 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockServiceBuilder<T, E> {
             validator_store: None,
             slot_clock: None,
             beacon_node: None,
+            fallback_beacon_nodes: vec![],
             context: None,
+            graffiti: None,
+            graffiti_file: None,
         }
     }
 
@@ -42,11 +57,26 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockServiceBuilder<T, E> {
         self
     }
 
+    pub fn fallback_beacon_nodes(mut self, beacon_nodes: Vec<RemoteBeaconNode<E>>) -> Self {
+        self.fallback_beacon_nodes = beacon_nodes;
+        self
+    }
+
     pub fn runtime_context(mut self, context: RuntimeContext<E>) -> Self {
         self.context = Some(context);
         self
     }
 
+    pub fn graffiti(mut self, graffiti: Option<Graffiti>) -> Self {
+        self.graffiti = graffiti;
+        self
+    }
+
+    pub fn graffiti_file(mut self, graffiti_file: Option<GraffitiFile>) -> Self {
+        self.graffiti_file = graffiti_file;
+        self
+    }
+
     pub fn build(self) -> Result<BlockService<T, E>, String> {
         Ok(BlockService {
             inner: Arc::new(Inner {
@@ -59,9 +89,12 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockServiceBuilder<T, E> {
                 beacon_node: self
                     .beacon_node
                     .ok_or_else(|| "Cannot build BlockService without beacon_node")?,
+                fallback_beacon_nodes: self.fallback_beacon_nodes,
                 context: self
                     .context
                     .ok_or_else(|| "Cannot build BlockService without runtime_context")?,
+                graffiti: self.graffiti,
+                graffiti_file: self.graffiti_file.map(Mutex::new),
             }),
         })
     }
@@ -72,7 +105,10 @@ pub struct Inner<T, E: EthSpec> {
     validator_store: ValidatorStore<T, E>,
     slot_clock: Arc<T>,
     beacon_node: RemoteBeaconNode<E>,
+    fallback_beacon_nodes: Vec<RemoteBeaconNode<E>>,
     context: RuntimeContext<E>,
+    graffiti: Option<Graffiti>,
+    graffiti_file: Option<Mutex<GraffitiFile>>,
 }
 
 /// Attempts to produce attestations for any block producer(s) at the start of the epoch.
@@ -196,58 +232,373 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
         Ok(())
     }
 
+    /// Produces and publishes a block for each of the `block_proposers` at the current slot,
+    /// returning once they have been published.
+    ///
+    /// Unlike `start_update_service`, this does not wait for a notification from the duties
+    /// service. Intended for driving a validator client with a manual slot clock.
+    pub(crate) async fn propose_now(&self, block_proposers: Vec<PublicKey>) -> Result<(), String> {
+        let slot = self
+            .slot_clock
+            .now()
+            .ok_or_else(|| "Unable to read slot clock".to_string())?;
+
+        if slot == self.context.eth2_config.spec.genesis_slot {
+            return Ok(());
+        }
+
+        for validator_pubkey in block_proposers {
+            self.clone().publish_block(slot, validator_pubkey).await?;
+        }
+
+        Ok(())
+    }
+
     /// Produce a block at the given slot for validator_pubkey
     async fn publish_block(self, slot: Slot, validator_pubkey: PublicKey) -> Result<(), String> {
         let log = self.context.log();
+        let _timer = metrics::start_timer(&metrics::BLOCK_PROPOSAL_TIMES);
 
         let current_slot = self
             .slot_clock
             .now()
             .ok_or_else(|| "Unable to determine current slot from clock".to_string())?;
 
+        let slot_start = self
+            .slot_clock
+            .start_of(slot)
+            .ok_or_else(|| "Unable to determine start of slot from clock".to_string())?;
+        let deadline = slot_start
+            .checked_add(self.slot_clock.slot_duration() / BLOCK_PROPOSAL_DEADLINE_QUOTIENT)
+            .ok_or_else(|| "Unable to determine block proposal deadline".to_string())?;
+
         let randao_reveal = self
             .validator_store
             .randao_reveal(&validator_pubkey, slot.epoch(E::slots_per_epoch()))
             .ok_or_else(|| "Unable to produce randao reveal".to_string())?;
 
-        let block = self
-            .beacon_node
-            .http
-            .validator()
-            .produce_block(slot, randao_reveal)
-            .await
-            .map_err(|e| format!("Error from beacon node when producing block: {:?}", e))?;
+        let graffiti = self.get_graffiti(&validator_pubkey);
+
+        let block = {
+            let _timer = metrics::start_timer(&metrics::BLOCK_PRODUCTION_TIMES);
+            self.request_before_deadline(slot, deadline, "producing block", |beacon_node| {
+                let randao_reveal = randao_reveal.clone();
+                async move {
+                    beacon_node
+                        .http
+                        .validator()
+                        .produce_block(slot, randao_reveal, graffiti)
+                        .await
+                }
+            })
+            .await?
+        };
 
         let signed_block = self
             .validator_store
             .sign_block(&validator_pubkey, block, current_slot)
             .ok_or_else(|| "Unable to sign block".to_string())?;
-
-        let publish_status = self
-            .beacon_node
-            .http
-            .validator()
-            .publish_block(signed_block.clone())
-            .await
-            .map_err(|e| format!("Error from beacon node when publishing block: {:?}", e))?;
+        let block_root = signed_block.canonical_root();
+
+        // The block has been signed, so the same block must be published to any alternate beacon
+        // node. Signing a different block for this slot would be a slashable offence.
+        let publish_status = {
+            let _timer = metrics::start_timer(&metrics::BLOCK_PUBLICATION_TIMES);
+            self.request_before_deadline(slot, deadline, "publishing block", |beacon_node| {
+                let signed_block = signed_block.clone();
+                async move {
+                    beacon_node
+                        .http
+                        .validator()
+                        .publish_block(signed_block)
+                        .await
+                }
+            })
+            .await?
+        };
+
+        if let Some(delay) = self
+            .slot_clock
+            .now_duration()
+            .and_then(|now| now.checked_sub(slot_start))
+        {
+            metrics::observe(&metrics::BLOCK_PUBLICATION_DELAY, delay.as_secs_f64());
+        }
 
         match publish_status {
-            PublishStatus::Valid => info!(
-                log,
-                "Successfully published block";
-                "deposits" => signed_block.message.body.deposits.len(),
-                "attestations" => signed_block.message.body.attestations.len(),
-                "slot" => signed_block.slot().as_u64(),
-            ),
+            PublishStatus::Valid => {
+                metrics::inc_counter(&metrics::BLOCK_PROPOSALS_PUBLISHED);
+                info!(
+                    log,
+                    "Successfully published block";
+                    "block_root" => format!("{:?}", block_root),
+                    "deposits" => signed_block.message.body.deposits.len(),
+                    "attestations" => signed_block.message.body.attestations.len(),
+                    "slot" => signed_block.slot().as_u64(),
+                )
+            }
             PublishStatus::Invalid(msg) => crit!(
                 log,
                 "Published block was invalid";
                 "message" => msg,
+                "block_root" => format!("{:?}", block_root),
+                "slot" => signed_block.slot().as_u64(),
+            ),
+            PublishStatus::Unknown => crit!(
+                log,
+                "Unknown condition when publishing block";
+                "block_root" => format!("{:?}", block_root),
                 "slot" => signed_block.slot().as_u64(),
             ),
-            PublishStatus::Unknown => crit!(log, "Unknown condition when publishing block"),
         }
 
         Ok(())
     }
+
+    /// Sends `request` to the primary beacon node, then to each of the fallback beacon nodes in
+    /// turn, returning the first successful response.
+    async fn request_before_deadline<F, R, U>(
+        &self,
+        slot: Slot,
+        deadline: Duration,
+        description: &str,
+        request: F,
+    ) -> Result<U, String>
+    where
+        F: Fn(RemoteBeaconNode<E>) -> R,
+        R: Future<Output = Result<U, RemoteBeaconNodeError>>,
+    {
+        request_before_deadline(
+            self.slot_clock.as_ref(),
+            std::iter::once(&self.beacon_node).chain(self.fallback_beacon_nodes.iter()),
+            slot,
+            deadline,
+            description,
+            request,
+            self.context.log(),
+        )
+        .await
+    }
+
+    /// Returns the graffiti for `validator_pubkey`.
+    ///
+    /// The graffiti file takes precedence over the `--graffiti` flag. If the graffiti file cannot
+    /// be read, the graffiti it provided when it was last read successfully is used. If neither
+    /// provides any graffiti, `None` is returned and the beacon node will use its own graffiti.
+    fn get_graffiti(&self, validator_pubkey: &PublicKey) -> Option<Graffiti> {
+        let log = self.context.log();
+
+        let file_graffiti = self.graffiti_file.as_ref().and_then(|graffiti_file| {
+            let mut graffiti_file = graffiti_file.lock();
+            graffiti_file
+                .load_graffiti(validator_pubkey)
+                .unwrap_or_else(|e| {
+                    warn!(
+                        log,
+                        "Failed to read graffiti file";
+                        "info" => "using the previously loaded graffiti",
+                        "error" => format!("{:?}", e)
+                    );
+                    graffiti_file.graffiti(validator_pubkey)
+                })
+        });
+
+        file_graffiti.or(self.graffiti)
+    }
+}
+
+/// Sends `request` to each of `beacon_nodes` in turn, returning the first successful response.
+///
+/// Each request is cut short at the proposal `deadline` and no further requests are made once it
+/// has passed, so that a block is never published late.
+async fn request_before_deadline<'a, T, E, F, R, U>(
+    slot_clock: &T,
+    beacon_nodes: impl Iterator<Item = &'a RemoteBeaconNode<E>>,
+    slot: Slot,
+    deadline: Duration,
+    description: &str,
+    request: F,
+    log: &Logger,
+) -> Result<U, String>
+where
+    T: SlotClock,
+    E: EthSpec,
+    F: Fn(RemoteBeaconNode<E>) -> R,
+    R: Future<Output = Result<U, RemoteBeaconNodeError>>,
+{
+    for beacon_node in beacon_nodes {
+        let remaining = slot_clock
+            .now_duration()
+            .and_then(|now| deadline.checked_sub(now))
+            .filter(|remaining| *remaining > Duration::from_secs(0));
+
+        let remaining = match remaining {
+            Some(remaining) => remaining,
+            None => break,
+        };
+
+        let error = match timeout(remaining, request(beacon_node.clone())).await {
+            Ok(Ok(response)) => return Ok(response),
+            Ok(Err(e)) => format!("{:?}", e),
+            Err(_) => "Timed out at the block proposal deadline".to_string(),
+        };
+
+        metrics::inc_counter(&metrics::BLOCK_PROPOSAL_BEACON_NODE_ERRORS);
+        warn!(
+            log,
+            "Beacon node request failed";
+            "request" => description,
+            "beacon_node" => beacon_node.http.server_url().as_str(),
+            "error" => error,
+            "slot" => slot.as_u64(),
+        );
+    }
+
+    metrics::inc_counter(&metrics::BLOCK_PROPOSALS_ABANDONED);
+    Err(format!(
+        "Abandoned block proposal for slot {} whilst {}: no beacon node responded before \
+         the proposal deadline",
+        slot, description
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use slot_clock::ManualSlotClock;
+    use types::MainnetEthSpec;
+
+    type E = MainnetEthSpec;
+
+    const SLOT_DURATION: Duration = Duration::from_millis(300);
+
+    struct Tester {
+        slot_clock: ManualSlotClock,
+        beacon_nodes: Vec<RemoteBeaconNode<E>>,
+        /// The URLs of the beacon nodes which have been sent a request, in order.
+        requested: Mutex<Vec<String>>,
+        log: Logger,
+    }
+
+    impl Tester {
+        fn new() -> Self {
+            let slot_clock =
+                ManualSlotClock::new(Slot::new(0), Duration::from_secs(0), SLOT_DURATION);
+            slot_clock.set_slot(1);
+
+            let beacon_nodes = vec!["http://primary/", "http://fallback/"]
+                .into_iter()
+                .map(|url| RemoteBeaconNode::new(url.to_string()).expect("should parse url"))
+                .collect();
+
+            Self {
+                slot_clock,
+                beacon_nodes,
+                requested: Mutex::new(vec![]),
+                log: Logger::root(slog::Discard, slog::o!()),
+            }
+        }
+
+        fn deadline(&self) -> Duration {
+            self.slot_clock.start_of(Slot::new(1)).unwrap()
+                + SLOT_DURATION / BLOCK_PROPOSAL_DEADLINE_QUOTIENT
+        }
+
+        fn requested(&self) -> Vec<String> {
+            self.requested.lock().clone()
+        }
+
+        /// Sends a request which is handled by `respond`, given the URL of the beacon node.
+        async fn request<F, R>(&self, respond: F) -> Result<String, String>
+        where
+            F: Fn(String) -> R,
+            R: Future<Output = Result<String, RemoteBeaconNodeError>>,
+        {
+            request_before_deadline(
+                &self.slot_clock,
+                self.beacon_nodes.iter(),
+                Slot::new(1),
+                self.deadline(),
+                "testing",
+                |beacon_node| {
+                    let url = beacon_node.http.server_url().to_string();
+                    self.requested.lock().push(url.clone());
+                    respond(url)
+                },
+                &self.log,
+            )
+            .await
+        }
+    }
+
+    #[tokio::test]
+    async fn primary_response_is_used() {
+        let tester = Tester::new();
+
+        let response = tester.request(|url| async move { Ok(url) }).await;
+
+        assert_eq!(response, Ok("http://primary/".to_string()));
+        assert_eq!(tester.requested(), vec!["http://primary/"]);
+    }
+
+    #[tokio::test]
+    async fn fallback_is_used_when_primary_fails() {
+        let tester = Tester::new();
+
+        let response = tester
+            .request(|url| async move {
+                if url == "http://primary/" {
+                    Err(RemoteBeaconNodeError::InvalidInput)
+                } else {
+                    Ok(url)
+                }
+            })
+            .await;
+
+        assert_eq!(response, Ok("http://fallback/".to_string()));
+        assert_eq!(
+            tester.requested(),
+            vec!["http://primary/", "http://fallback/"]
+        );
+    }
+
+    #[tokio::test]
+    async fn proposal_is_abandoned_when_primary_is_slow() {
+        let tester = Tester::new();
+
+        let response = tester
+            .request(|url| {
+                let slot_clock = &tester.slot_clock;
+                async move {
+                    if url == "http://primary/" {
+                        // The primary does not respond until the deadline has passed.
+                        slot_clock.advance_slot();
+                        futures::future::pending::<()>().await;
+                    }
+                    Ok(url)
+                }
+            })
+            .await;
+
+        assert!(response.is_err(), "should abandon the proposal");
+        assert_eq!(
+            tester.requested(),
+            vec!["http://primary/"],
+            "should not request from the fallback after the deadline"
+        );
+    }
+
+    #[tokio::test]
+    async fn no_requests_are_made_after_the_deadline() {
+        let tester = Tester::new();
+        tester.slot_clock.advance_slot();
+
+        let response = tester.request(|url| async move { Ok(url) }).await;
+
+        assert!(response.is_err(), "should abandon the proposal");
+        assert!(
+            tester.requested().is_empty(),
+            "should not make any requests"
+        );
+    }
 }
@@ -1,4 +1,4 @@
-use crate::config::DEFAULT_HTTP_SERVER;
+use crate::config::{DEFAULT_HTTP_SERVER, DEFAULT_METRICS_PORT};
 use clap::{App, Arg};
 
 pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
@@ -16,6 +16,28 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .default_value(&DEFAULT_HTTP_SERVER)
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("fallback-servers")
+                .long("fallback-servers")
+                .value_name("NETWORK_ADDRESSES")
+                .help(
+                    "Comma-separated addresses of alternate BeaconNodes. If the BeaconNode \
+                    given by --server fails to produce or publish a block, each of these is \
+                    tried in turn. Must accept the same --server-api-token-file, if any.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("server-api-token-file")
+                .long("server-api-token-file")
+                .value_name("PATH")
+                .help(
+                    "Path to a file containing the token required by the BeaconNode to publish \
+                    blocks and attestations. Only required if the BeaconNode was started with \
+                    --http-api-token-file.",
+                )
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name("secrets-dir")
                 .long("secrets-dir")
@@ -44,6 +66,28 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 is explicit before new validators are allowed to be used."
             )
         )
+        .arg(
+            Arg::with_name("graffiti")
+                .long("graffiti")
+                .value_name("GRAFFITI")
+                .help(
+                    "Specify your custom graffiti to be included in blocks. Overrides the \
+                    graffiti of the beacon node. Must be at most 32 bytes long.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("graffiti-file")
+                .long("graffiti-file")
+                .value_name("GRAFFITI_FILE")
+                .help(
+                    "Specify a graffiti file to load validator graffitis from. Each line should \
+                    be of the form `<0x-prefixed public key>: <graffiti>`, or \
+                    `default: <graffiti>` for unlisted validators. Takes precedence over \
+                    --graffiti. The file is re-read whenever it changes.",
+                )
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name("allow-unsynced")
                 .long("allow-unsynced")
@@ -52,4 +96,29 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                       node is not synced.",
                 ),
         )
+        .arg(
+            Arg::with_name("metrics")
+                .long("metrics")
+                .help(
+                    "Enable the metrics server, which serves the metrics of the validator client \
+                    (e.g., block proposal timings) at /metrics. Disabled by default.",
+                )
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name("metrics-address")
+                .long("metrics-address")
+                .value_name("ADDRESS")
+                .help("Set the IPv4 or IPv6 listen address for the metrics server.")
+                .default_value("127.0.0.1")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("metrics-port")
+                .long("metrics-port")
+                .value_name("PORT")
+                .help("Set the listen TCP port for the metrics server.")
+                .default_value(&DEFAULT_METRICS_PORT)
+                .takes_value(true),
+        )
 }
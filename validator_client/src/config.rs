@@ -1,9 +1,14 @@
+use crate::graffiti_file::GraffitiFile;
 use clap::ArgMatches;
 use clap_utils::{parse_optional, parse_path_with_default_in_home_dir};
 use serde_derive::{Deserialize, Serialize};
+use std::fs;
+use std::net::{IpAddr, Ipv4Addr};
 use std::path::PathBuf;
+use types::{graffiti_from_str, Graffiti};
 
 pub const DEFAULT_HTTP_SERVER: &str = "http://localhost:5052/";
+pub const DEFAULT_METRICS_PORT: &str = "5064";
 pub const DEFAULT_DATA_DIR: &str = ".lighthouse/validators";
 pub const DEFAULT_SECRETS_DIR: &str = ".lighthouse/secrets";
 /// Path to the slashing protection database within the datadir.
@@ -20,6 +25,11 @@ pub struct Config {
     ///
     /// Should be similar to `http://localhost:8080`
     pub http_server: String,
+    /// The http endpoints of alternate beacon nodes, used when `http_server` fails to produce
+    /// or publish a block.
+    pub fallback_http_servers: Vec<String>,
+    /// The bearer token sent to the beacon node with requests which modify it, if any.
+    pub http_api_token: Option<String>,
     /// If true, the validator client will still poll for duties and produce blocks even if the
     /// beacon node is not synced at startup.
     pub allow_unsynced_beacon_node: bool,
@@ -27,6 +37,17 @@ pub struct Config {
     pub strict: bool,
     /// If true, register new validator keys with the slashing protection database.
     pub auto_register: bool,
+    /// Graffiti to be inserted everytime we create a block, unless overridden by the graffiti
+    /// file.
+    pub graffiti: Option<Graffiti>,
+    /// A file which maps validator public keys to the graffiti of their blocks.
+    pub graffiti_file: Option<GraffitiFile>,
+    /// If true, the metrics of the validator client are served over HTTP.
+    pub metrics_enabled: bool,
+    /// The address the metrics server listens on.
+    pub metrics_listen_address: IpAddr,
+    /// The port the metrics server listens on.
+    pub metrics_port: u16,
 }
 
 impl Default for Config {
@@ -42,9 +63,16 @@ impl Default for Config {
             data_dir,
             secrets_dir,
             http_server: DEFAULT_HTTP_SERVER.to_string(),
+            fallback_http_servers: vec![],
+            http_api_token: None,
             allow_unsynced_beacon_node: false,
             auto_register: false,
             strict: false,
+            graffiti: None,
+            graffiti_file: None,
+            metrics_enabled: false,
+            metrics_listen_address: IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)),
+            metrics_port: 5064,
         }
     }
 }
@@ -72,6 +100,21 @@ impl Config {
             config.http_server = server;
         }
 
+        if let Some(servers) = parse_optional::<String>(cli_args, "fallback-servers")? {
+            config.fallback_http_servers = servers
+                .split(',')
+                .map(str::trim)
+                .filter(|server| !server.is_empty())
+                .map(String::from)
+                .collect();
+        }
+
+        if let Some(token_file) = parse_optional::<PathBuf>(cli_args, "server-api-token-file")? {
+            let token = fs::read_to_string(&token_file)
+                .map_err(|e| format!("Unable to read {:?}: {:?}", token_file, e))?;
+            config.http_api_token = Some(token.trim().to_string());
+        }
+
         config.allow_unsynced_beacon_node = cli_args.is_present("allow-unsynced");
         config.auto_register = cli_args.is_present("auto-register");
         config.strict = cli_args.is_present("strict");
@@ -92,6 +135,111 @@ impl Config {
             ));
         }
 
+        if let Some(graffiti) = parse_optional::<String>(cli_args, "graffiti")? {
+            config.graffiti = Some(graffiti_from_str(&graffiti)?);
+        }
+
+        if let Some(graffiti_file) = parse_optional::<PathBuf>(cli_args, "graffiti-file")? {
+            if !graffiti_file.exists() {
+                return Err(format!(
+                    "The graffiti file (--graffiti-file) does not exist: {:?}",
+                    graffiti_file
+                ));
+            }
+
+            // Read the file now, so that an invalid file is reported at startup rather than when
+            // the first block is proposed.
+            let mut graffiti_file = GraffitiFile::new(graffiti_file);
+            graffiti_file
+                .read_graffiti_file()
+                .map_err(|e| format!("Unable to read graffiti file: {:?}", e))?;
+            config.graffiti_file = Some(graffiti_file);
+        }
+
+        config.metrics_enabled = cli_args.is_present("metrics");
+
+        if let Some(address) = cli_args.value_of("metrics-address") {
+            config.metrics_listen_address = address
+                .parse::<IpAddr>()
+                .map_err(|_| "metrics-address is not a valid IP address.")?;
+        }
+
+        if let Some(port) = cli_args.value_of("metrics-port") {
+            config.metrics_port = port
+                .parse::<u16>()
+                .map_err(|_| "metrics-port is not a valid u16.")?;
+        }
+
         Ok(config)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Arg;
+    use tempdir::TempDir;
+
+    /// Parses the `Config` from the given arguments, with existing data and secrets directories.
+    fn config_from_args(args: &[&str]) -> Result<Config, String> {
+        let dir = TempDir::new("validator_client_config").expect("should create temp dir");
+        let dir = dir.path().to_str().expect("temp dir should be utf8");
+
+        let mut all_args = vec!["validator_client", "--datadir", dir, "--secrets-dir", dir];
+        all_args.extend_from_slice(args);
+
+        // `--datadir` is a global argument of the `lighthouse` binary.
+        let matches = crate::cli_app()
+            .arg(Arg::with_name("datadir").long("datadir").takes_value(true))
+            .get_matches_from_safe(all_args)
+            .map_err(|e| format!("{:?}", e))?;
+
+        Config::from_cli(&matches)
+    }
+
+    #[test]
+    fn fallback_servers_default_to_none() {
+        let config = config_from_args(&[]).unwrap();
+        assert!(config.fallback_http_servers.is_empty());
+    }
+
+    #[test]
+    fn fallback_servers_are_split_and_trimmed() {
+        let config = config_from_args(&[
+            "--fallback-servers",
+            "http://one:5052/, http://two:5052/,,http://three:5052/ ",
+        ])
+        .unwrap();
+        assert_eq!(
+            config.fallback_http_servers,
+            vec![
+                "http://one:5052/".to_string(),
+                "http://two:5052/".to_string(),
+                "http://three:5052/".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn metrics_server_is_configured() {
+        let config = config_from_args(&[]).unwrap();
+        assert!(!config.metrics_enabled);
+
+        let config = config_from_args(&[
+            "--metrics",
+            "--metrics-address",
+            "0.0.0.0",
+            "--metrics-port",
+            "6000",
+        ])
+        .unwrap();
+        assert!(config.metrics_enabled);
+        assert_eq!(
+            config.metrics_listen_address,
+            IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0))
+        );
+        assert_eq!(config.metrics_port, 6000);
+
+        assert!(config_from_args(&["--metrics-port", "invalid"]).is_err());
+    }
+}
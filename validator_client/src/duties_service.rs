@@ -1,20 +1,23 @@
 use crate::{
-    block_service::BlockServiceNotification, is_synced::is_synced, validator_store::ValidatorStore,
+    block_service::BlockServiceNotification, is_synced::is_synced, metrics,
+    validator_store::ValidatorStore,
 };
 use environment::RuntimeContext;
 use futures::channel::mpsc::Sender;
 use futures::{SinkExt, StreamExt};
 use parking_lot::RwLock;
-use remote_beacon_node::{PublishStatus, RemoteBeaconNode};
+use rayon::prelude::*;
+use remote_beacon_node::{HeadEvent, PublishStatus, RemoteBeaconNode};
 use rest_types::{ValidatorDuty, ValidatorDutyBytes, ValidatorSubscription};
-use slog::{debug, error, trace, warn};
+use slog::{debug, error, info, trace, warn};
 use slot_clock::SlotClock;
 use std::collections::HashMap;
 use std::convert::TryInto;
 use std::ops::Deref;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use tokio::time::{interval_at, Duration, Instant};
-use types::{ChainSpec, CommitteeIndex, Epoch, EthSpec, PublicKey, SelectionProof, Slot};
+use tokio::time::{delay_for, interval_at, Duration, Instant};
+use types::{ChainSpec, CommitteeIndex, Epoch, EthSpec, Hash256, PublicKey, SelectionProof, Slot};
 
 /// Delay this period of time after the slot starts. This allows the node to process the new slot.
 const TIME_DELAY_FROM_SLOT: Duration = Duration::from_millis(100);
@@ -22,6 +25,17 @@ const TIME_DELAY_FROM_SLOT: Duration = Duration::from_millis(100);
 /// Remove any duties where the `duties_epoch < current_epoch - PRUNE_DEPTH`.
 const PRUNE_DEPTH: u64 = 4;
 
+/// The name of the server-sent event which is published each time the head of the beacon node
+/// changes.
+const HEAD_EVENT_NAME: &str = "beacon_head_changed";
+
+/// Download the duties of the current and next epochs at least once every this many slots, even
+/// whilst head events are being received.
+///
+/// The beacon node drops events for subscribers which fall behind, so a head event which changed
+/// the `dependent_root` may never arrive.
+const RECONCILIATION_INTERVAL_SLOTS: u64 = 4;
+
 type BaseHashMap = HashMap<PublicKey, HashMap<Epoch, DutyAndProof>>;
 
 #[derive(Debug, Clone)]
@@ -29,6 +43,9 @@ pub struct DutyAndProof {
     /// The validator duty.
     pub duty: ValidatorDuty,
     /// Stores the selection proof if the duty elects the validator to be an aggregator.
+    ///
+    /// Selection proofs are computed in the background once the duty has been stored, so this is
+    /// also `None` until then.
     pub selection_proof: Option<SelectionProof>,
 }
 
@@ -82,6 +99,18 @@ impl DutyAndProof {
             && self.duty.attestation_slot == other.duty.attestation_slot
     }
 
+    /// Returns the subscription which the beacon node requires to perform this duty, if the
+    /// validator is scheduled to attest.
+    pub fn subscription(&self) -> Option<ValidatorSubscription> {
+        Some(ValidatorSubscription {
+            validator_index: self.duty.validator_index?,
+            attestation_committee_index: self.duty.attestation_committee_index?,
+            slot: self.duty.attestation_slot?,
+            committee_count_at_slot: self.duty.committee_count_at_slot?,
+            is_aggregator: self.selection_proof.is_some(),
+        })
+    }
+
     /// Returns `true` if the selection proof between `self` and `other` _should_ be equal.
     ///
     /// It's important to note that this doesn't actually check `self.selection_proof`, instead it
@@ -123,6 +152,7 @@ impl TryInto<DutyAndProof> for ValidatorDutyBytes {
             committee_count_at_slot: self.committee_count_at_slot,
             block_proposal_slots: self.block_proposal_slots,
             aggregator_modulo: self.aggregator_modulo,
+            dependent_root: self.dependent_root,
         };
         Ok(DutyAndProof {
             duty,
@@ -165,9 +195,69 @@ impl InsertOutcome {
 #[derive(Default)]
 pub struct DutiesStore {
     store: RwLock<BaseHashMap>,
+    /// The `dependent_root` of the most recently downloaded duties for each epoch.
+    dependent_roots: RwLock<HashMap<Epoch, Hash256>>,
 }
 
 impl DutiesStore {
+    /// Returns the `dependent_root` of the most recently downloaded duties for the given `epoch`.
+    fn dependent_root(&self, epoch: Epoch) -> Option<Hash256> {
+        self.dependent_roots.read().get(&epoch).copied()
+    }
+
+    fn set_dependent_root(&self, epoch: Epoch, dependent_root: Hash256) {
+        self.dependent_roots.write().insert(epoch, dependent_root);
+    }
+
+    /// Returns `true` if duties for the given `epoch` have been downloaded with a `dependent_root`
+    /// other than `dependent_root`.
+    fn dependent_root_changed(&self, epoch: Epoch, dependent_root: Hash256) -> bool {
+        self.dependent_root(epoch)
+            .map_or(false, |known_root| known_root != dependent_root)
+    }
+
+    /// Returns the epochs (no earlier than `current_epoch`) for which the duties must be downloaded
+    /// again, since their `dependent_root` differs from the one in the `head` event.
+    fn epochs_to_update(
+        &self,
+        head: &HeadEvent,
+        current_epoch: Epoch,
+        slots_per_epoch: u64,
+    ) -> Vec<Epoch> {
+        let head_epoch = head.current_head_slot.epoch(slots_per_epoch);
+        let next_epoch = head_epoch + 1;
+
+        let next_epoch_changed =
+            self.dependent_root_changed(next_epoch, head.next_epoch_dependent_root);
+
+        let mut epochs = vec![];
+        // The block which decides the attesters of the next epoch also decides the proposers of
+        // the head epoch, so the duties of both epochs must be downloaded when it changes.
+        if next_epoch_changed
+            || self.dependent_root_changed(head_epoch, head.current_epoch_dependent_root)
+        {
+            epochs.push(head_epoch);
+        }
+        if next_epoch_changed {
+            epochs.push(next_epoch);
+        }
+
+        epochs.retain(|epoch| *epoch >= current_epoch);
+        epochs
+    }
+
+    /// Returns `true` if the block proposal slots of any validator are known for the given `epoch`.
+    ///
+    /// Proposers are only known once `epoch` has started, so this is `false` for duties which
+    /// were downloaded in advance.
+    fn proposers_known(&self, epoch: Epoch) -> bool {
+        self.store.read().values().any(|validator_map| {
+            validator_map
+                .get(&epoch)
+                .map_or(false, |duties| duties.duty.block_proposal_slots.is_some())
+        })
+    }
+
     /// Returns the total number of validators that should propose in the given epoch.
     fn proposer_count(&self, epoch: Epoch) -> usize {
         self.store
@@ -243,23 +333,32 @@ impl DutiesStore {
             .collect()
     }
 
-    fn is_aggregator(&self, validator_pubkey: &PublicKey, epoch: Epoch) -> Option<bool> {
-        Some(
-            self.store
-                .read()
-                .get(validator_pubkey)?
-                .get(&epoch)?
-                .selection_proof
-                .is_some(),
-        )
+    /// Stores the selection proof of `duties`, returning `false` if the stored duties have since
+    /// been replaced by ones which require a different selection proof.
+    fn set_selection_proof(&self, epoch: Epoch, duties: &DutyAndProof) -> bool {
+        let mut store = self.store.write();
+
+        match store
+            .get_mut(&duties.duty.validator_pubkey)
+            .and_then(|validator_map| validator_map.get_mut(&epoch))
+        {
+            Some(known_duties) if known_duties.selection_proof_eq(duties) => {
+                known_duties.selection_proof = duties.selection_proof.clone();
+                true
+            }
+            _ => false,
+        }
     }
 
-    fn insert<T: SlotClock + 'static, E: EthSpec>(
+    /// Stores the `duties`, without their selection proof.
+    ///
+    /// The selection proof must be computed and passed to `Self::set_selection_proof` for any
+    /// outcome which `is_subscription_candidate`.
+    fn insert(
         &self,
         epoch: Epoch,
         mut duties: DutyAndProof,
         slots_per_epoch: u64,
-        validator_store: &ValidatorStore<T, E>,
     ) -> Result<InsertOutcome, String> {
         let mut store = self.store.write();
 
@@ -281,11 +380,14 @@ impl DutiesStore {
                         Ok(InsertOutcome::Invalid)
                     }
                 } else {
-                    // Compute the selection proof.
-                    duties.compute_selection_proof(validator_store)?;
-
                     // Determine if a re-subscription is required.
-                    let should_resubscribe = duties.subscription_eq(known_duties);
+                    let should_resubscribe = !duties.subscription_eq(known_duties);
+
+                    // Keep the existing selection proof if it is still valid, otherwise it must
+                    // be computed again.
+                    if duties.selection_proof_eq(known_duties) {
+                        duties.selection_proof = known_duties.selection_proof.clone();
+                    }
 
                     // Replace the existing duties.
                     *known_duties = duties;
@@ -293,17 +395,11 @@ impl DutiesStore {
                     Ok(InsertOutcome::Replaced { should_resubscribe })
                 }
             } else {
-                // Compute the selection proof.
-                duties.compute_selection_proof(validator_store)?;
-
                 validator_map.insert(epoch, duties);
 
                 Ok(InsertOutcome::NewEpoch)
             }
         } else {
-            // Compute the selection proof.
-            duties.compute_selection_proof(validator_store)?;
-
             let validator_pubkey = duties.duty.validator_pubkey.clone();
 
             let mut validator_map = HashMap::new();
@@ -322,6 +418,9 @@ impl DutiesStore {
                 validator_map.retain(|epoch, _duties| *epoch >= prior_to);
                 !validator_map.is_empty()
             });
+        self.dependent_roots
+            .write()
+            .retain(|epoch, _dependent_root| *epoch >= prior_to);
     }
 }
 
@@ -387,6 +486,7 @@ impl<T: SlotClock + 'static, E: EthSpec> DutiesServiceBuilder<T, E> {
                     .context
                     .ok_or_else(|| "Cannot build DutiesService without runtime_context")?,
                 allow_unsynced_beacon_node: self.allow_unsynced_beacon_node,
+                head_events_connected: AtomicBool::new(false),
             }),
         })
     }
@@ -402,13 +502,23 @@ pub struct Inner<T, E: EthSpec> {
     /// If true, the duties service will poll for duties from the beacon node even if it is not
     /// synced.
     allow_unsynced_beacon_node: bool,
+    /// True whilst the duties service is receiving head events from the beacon node.
+    head_events_connected: AtomicBool,
 }
 
 /// Maintains a store of the duties for all voting validators in the `validator_store`.
 ///
-/// Polls the beacon node at the start of each slot, collecting duties for the current and next
-/// epoch. The duties service notifies the block production service to run each time it completes,
-/// so it *must* be run every slot.
+/// Looks ahead by one epoch: the duties for the next epoch are downloaded as soon as they're
+/// known, then their selection proofs are computed in the background and the beacon node is
+/// subscribed to the relevant subnets.
+///
+/// Duties are only downloaded again if their `dependent_root` changes, which is detected using
+/// the head events of the beacon node. The duties service falls back to polling the beacon node
+/// each slot if head events are unavailable, and polls every `RECONCILIATION_INTERVAL_SLOTS`
+/// otherwise.
+///
+/// The duties service notifies the block production service at the start of each slot, so it
+/// *must* be run every slot.
 pub struct DutiesService<T, E: EthSpec> {
     inner: Arc<Inner<T, E>>,
 }
@@ -469,14 +579,12 @@ impl<T: SlotClock + 'static, E: EthSpec> DutiesService<T, E> {
             .duration_to_next_slot()
             .ok_or_else(|| "Unable to determine duration to next slot".to_string())?;
 
-        let mut interval = {
-            let slot_duration = Duration::from_millis(spec.milliseconds_per_slot);
-            // Note: `interval_at` panics if `slot_duration` is 0
-            interval_at(
-                Instant::now() + duration_to_next_slot + TIME_DELAY_FROM_SLOT,
-                slot_duration,
-            )
-        };
+        let slot_duration = Duration::from_millis(spec.milliseconds_per_slot);
+        // Note: `interval_at` panics if `slot_duration` is 0
+        let mut interval = interval_at(
+            Instant::now() + duration_to_next_slot + TIME_DELAY_FROM_SLOT,
+            slot_duration,
+        );
 
         // Run an immediate update before starting the updater service.
         let duties_service = self.clone();
@@ -489,6 +597,11 @@ impl<T: SlotClock + 'static, E: EthSpec> DutiesService<T, E> {
 
         let executor = self.inner.context.executor.clone();
 
+        executor.spawn(
+            self.clone().listen_for_head_events(slot_duration),
+            "duties_service_head_events",
+        );
+
         let interval_fut = async move {
             while interval.next().await.is_some() {
                 self.clone().do_update(&mut block_service_tx).await;
@@ -500,7 +613,100 @@ impl<T: SlotClock + 'static, E: EthSpec> DutiesService<T, E> {
         Ok(())
     }
 
-    /// Attempt to download the duties of all managed validators for this epoch and the next.
+    /// Listens to the head events of the beacon node, re-connecting whenever the stream closes.
+    async fn listen_for_head_events(self, slot_duration: Duration) {
+        let log = self.context.log().clone();
+        // Re-open the stream each epoch, rather than relying on the beacon node to close it.
+        let stream_timeout = slot_duration * E::slots_per_epoch() as u32;
+
+        loop {
+            match self
+                .beacon_node
+                .http
+                .events()
+                .stream(&["head"], stream_timeout)
+                .await
+            {
+                Ok(mut stream) => {
+                    self.head_events_connected.store(true, Ordering::Relaxed);
+
+                    loop {
+                        match stream.next_event().await {
+                            Ok(Some(event)) if event.event == HEAD_EVENT_NAME => {
+                                match event.fields::<HeadEvent>() {
+                                    Ok(head) => self.clone().process_head_event(head).await,
+                                    Err(e) => error!(
+                                        log,
+                                        "Unable to decode head event";
+                                        "error" => format!("{:?}", e)
+                                    ),
+                                }
+                            }
+                            Ok(Some(_)) => {}
+                            Ok(None) => break,
+                            Err(e) => {
+                                debug!(
+                                    log,
+                                    "Head event stream closed";
+                                    "error" => format!("{:?}", e)
+                                );
+                                break;
+                            }
+                        }
+                    }
+
+                    self.head_events_connected.store(false, Ordering::Relaxed);
+                }
+                Err(e) => {
+                    warn!(
+                        log,
+                        "Unable to subscribe to head events";
+                        "info" => "polling for duties each slot instead",
+                        "error" => format!("{:?}", e)
+                    );
+                    delay_for(slot_duration).await;
+                }
+            }
+        }
+    }
+
+    /// Downloads the duties for any epoch with a `dependent_root` which differs from the one in
+    /// the `head` event.
+    async fn process_head_event(self, head: HeadEvent) {
+        let log = self.context.log();
+
+        let current_epoch = if let Some(slot) = self.slot_clock.now() {
+            slot.epoch(E::slots_per_epoch())
+        } else {
+            error!(log, "Duties manager failed to read slot clock");
+            return;
+        };
+
+        for epoch in self
+            .store
+            .epochs_to_update(&head, current_epoch, E::slots_per_epoch())
+        {
+            info!(
+                log,
+                "Dependent root changed";
+                "info" => "chain re-org likely occurred, downloading duties again",
+                "head_slot" => head.current_head_slot.as_u64(),
+                "epoch" => epoch.as_u64(),
+            );
+            metrics::inc_counter(&metrics::DUTIES_DEPENDENT_ROOT_CHANGES);
+
+            if let Err(e) = self.clone().update_epoch(epoch).await {
+                error!(
+                    log,
+                    "Failed to get duties after dependent root changed";
+                    "http_error" => format!("{:?}", e)
+                );
+            }
+        }
+    }
+
+    /// Attempt to download the duties of all managed validators for this epoch and the next, if
+    /// they're not yet known.
     async fn do_update(self, block_service_tx: &mut Sender<BlockServiceNotification>) {
         let log = self.context.log();
 
@@ -532,14 +738,28 @@ impl<T: SlotClock + 'static, E: EthSpec> DutiesService<T, E> {
             self.store.prune(prune_below);
         }
 
+        // Without head events, changes to the dependent roots can't be detected so the duties
+        // must be polled each slot. Even with head events, the duties are polled periodically in
+        // case an event was dropped.
+        let poll = !self.head_events_connected.load(Ordering::Relaxed)
+            || slot % RECONCILIATION_INTERVAL_SLOTS == 0;
+
         // Update duties for the current epoch, but keep running if there's an error:
         // block production or the next epoch update could still succeed.
-        if let Err(e) = self.clone().update_epoch(current_epoch).await {
-            error!(
-                log,
-                "Failed to get current epoch duties";
-                "http_error" => format!("{:?}", e)
-            );
+        //
+        // The current epoch duties are normally downloaded in advance, but must be downloaded
+        // again once the epoch starts to learn the block proposers.
+        if poll
+            || self.store.dependent_root(current_epoch).is_none()
+            || !self.store.proposers_known(current_epoch)
+        {
+            if let Err(e) = self.clone().update_epoch(current_epoch).await {
+                error!(
+                    log,
+                    "Failed to get current epoch duties";
+                    "http_error" => format!("{:?}", e)
+                );
+            }
         }
 
         // Notify the block service to produce a block.
@@ -557,18 +777,59 @@ impl<T: SlotClock + 'static, E: EthSpec> DutiesService<T, E> {
             );
         };
 
-        // Update duties for the next epoch.
-        if let Err(e) = self.clone().update_epoch(current_epoch + 1).await {
-            error!(
-                log,
-                "Failed to get next epoch duties";
-                "http_error" => format!("{:?}", e)
-            );
+        // Look ahead to the duties for the next epoch.
+        if poll || self.store.dependent_root(current_epoch + 1).is_none() {
+            if let Err(e) = self.clone().update_epoch(current_epoch + 1).await {
+                error!(
+                    log,
+                    "Failed to get next epoch duties";
+                    "http_error" => format!("{:?}", e)
+                );
+            }
         }
     }
 
+    /// Downloads the duties of the current and next epochs, then computes the selection proofs of
+    /// any new or changed duties and subscribes the beacon node to their subnets before returning.
+    ///
+    /// Unlike `start_update_service`, no timers or background tasks are involved, so the duties
+    /// are known as soon as this returns. Intended for driving a validator client with a manual
+    /// slot clock.
+    pub(crate) async fn update_duties_now(&self) -> Result<(), String> {
+        let current_epoch = self
+            .slot_clock
+            .now()
+            .ok_or_else(|| "Unable to read slot clock".to_string())?
+            .epoch(E::slots_per_epoch());
+
+        for &epoch in &[current_epoch, current_epoch + 1] {
+            let subscription_candidates = self.clone().download_duties(epoch).await?;
+            let subscriptions = self.compute_selection_proofs(epoch, subscription_candidates);
+            self.subscribe(subscriptions).await?;
+        }
+
+        Ok(())
+    }
+
     /// Attempt to download the duties of all managed validators for the given `epoch`.
+    ///
+    /// The selection proofs of any new or changed duties are computed in the background, after
+    /// which the beacon node is subscribed to their subnets.
     async fn update_epoch(self, epoch: Epoch) -> Result<(), String> {
+        let subscription_candidates = self.clone().download_duties(epoch).await?;
+
+        if subscription_candidates.is_empty() {
+            debug!(self.context.log(), "No new subscriptions required");
+        } else {
+            self.spawn_selection_proof_task(epoch, subscription_candidates);
+        }
+
+        Ok(())
+    }
+
+    /// Downloads the duties of all managed validators for the given `epoch` into the store,
+    /// returning any new or changed duties which require a selection proof and subscription.
+    async fn download_duties(self, epoch: Epoch) -> Result<Vec<DutyAndProof>, String> {
         let pubkeys = self.validator_store.voting_pubkeys();
         let all_duties = self
             .beacon_node
@@ -580,6 +841,8 @@ impl<T: SlotClock + 'static, E: EthSpec> DutiesService<T, E> {
 
         let log = self.context.log().clone();
 
+        let dependent_root = all_duties.first().map(|duties| duties.dependent_root);
+
         let mut new_validator = 0;
         let mut new_epoch = 0;
         let mut new_proposal_slots = 0;
@@ -587,9 +850,9 @@ impl<T: SlotClock + 'static, E: EthSpec> DutiesService<T, E> {
         let mut replaced = 0;
         let mut invalid = 0;
 
-        // For each of the duties, attempt to insert them into our local store and build a
-        // list of new or changed selections proofs for any aggregating validators.
-        let validator_subscriptions = all_duties
+        // For each of the duties, attempt to insert them into our local store and build a list of
+        // new or changed duties which require a selection proof and subscription.
+        let subscription_candidates = all_duties
             .into_iter()
             .filter_map(|remote_duties| {
                 // Convert the remote duties into our local representation.
@@ -605,12 +868,10 @@ impl<T: SlotClock + 'static, E: EthSpec> DutiesService<T, E> {
                     })
                     .ok()?;
 
-                let validator_pubkey = duties.duty.validator_pubkey.clone();
-
                 // Attempt to update our local store.
                 let outcome = self
                     .store
-                    .insert(epoch, duties, E::slots_per_epoch(), &self.validator_store)
+                    .insert(epoch, duties.clone(), E::slots_per_epoch())
                     .map_err(|e| {
                         error!(
                             log,
@@ -638,24 +899,18 @@ impl<T: SlotClock + 'static, E: EthSpec> DutiesService<T, E> {
                     InsertOutcome::Invalid => invalid += 1,
                 };
 
-                // The selection proof is computed on `store.insert`, so it's necessary to check
-                // with the store that the validator is an aggregator.
-                let is_aggregator = self.store.is_aggregator(&validator_pubkey, epoch)?;
-
                 if outcome.is_subscription_candidate() {
-                    Some(ValidatorSubscription {
-                        validator_index: remote_duties.validator_index?,
-                        attestation_committee_index: remote_duties.attestation_committee_index?,
-                        slot: remote_duties.attestation_slot?,
-                        committee_count_at_slot: remote_duties.committee_count_at_slot?,
-                        is_aggregator,
-                    })
+                    Some(duties)
                 } else {
                     None
                 }
             })
             .collect::<Vec<_>>();
 
+        if let Some(dependent_root) = dependent_root {
+            self.store.set_dependent_root(epoch, dependent_root);
+        }
+
         if invalid > 0 {
             error!(
                 log,
@@ -685,39 +940,117 @@ impl<T: SlotClock + 'static, E: EthSpec> DutiesService<T, E> {
             )
         }
 
-        let log = self.context.log().clone();
-        let count = validator_subscriptions.len();
+        Ok(subscription_candidates)
+    }
 
-        if count == 0 {
-            debug!(log, "No new subscriptions required");
+    /// Computes the selection proofs of the `duties` on a blocking thread, then subscribes the
+    /// beacon node to the subnets of the `duties`.
+    ///
+    /// Signing the selection proofs is expensive for many validators, so it must not delay the
+    /// download of duties or block production.
+    fn spawn_selection_proof_task(&self, epoch: Epoch, duties: Vec<DutyAndProof>) {
+        let duties_service = self.clone();
+        let log = self.context.log().clone();
+        let handle = self.context.executor.runtime_handle();
 
-            Ok(())
-        } else {
-            self.beacon_node
-                .http
-                .validator()
-                .subscribe(validator_subscriptions)
+        let future = async move {
+            let inner_duties_service = duties_service.clone();
+            let subscriptions = match handle
+                .spawn_blocking(move || {
+                    inner_duties_service.compute_selection_proofs(epoch, duties)
+                })
                 .await
-                .map_err(|e| format!("Failed to subscribe validators: {:?}", e))
-                .map(move |status| {
-                    match status {
-                        PublishStatus::Valid => debug!(
-                            log,
-                            "Successfully subscribed validators";
-                            "count" => count
-                        ),
-                        PublishStatus::Unknown => error!(
-                            log,
-                            "Unknown response from subscription";
-                        ),
-                        PublishStatus::Invalid(e) => error!(
+            {
+                Ok(subscriptions) => subscriptions,
+                Err(e) => {
+                    error!(
+                        log,
+                        "Failed to compute selection proofs";
+                        "error" => format!("{:?}", e)
+                    );
+                    return;
+                }
+            };
+
+            if let Err(e) = duties_service.subscribe(subscriptions).await {
+                error!(log, "Failed to subscribe validators"; "error" => e)
+            }
+        };
+
+        self.context
+            .executor
+            .spawn(future, "duties_service_selection_proofs");
+    }
+
+    /// Computes and stores the selection proofs of the `duties`, returning the subscription of
+    /// each duty which is still in the store.
+    fn compute_selection_proofs(
+        &self,
+        epoch: Epoch,
+        duties: Vec<DutyAndProof>,
+    ) -> Vec<ValidatorSubscription> {
+        let _timer = metrics::start_timer(&metrics::DUTIES_SELECTION_PROOF_TIMES);
+        let log = self.context.log();
+
+        duties
+            .into_par_iter()
+            .filter_map(|mut duties| {
+                duties
+                    .compute_selection_proof(&self.validator_store)
+                    .map_err(|e| {
+                        error!(
                             log,
-                            "Failed to subscribe validator";
-                            "error" => e
-                        ),
-                    };
-                })
+                            "Unable to compute selection proof";
+                            "error" => e,
+                            "validator" => format!("{:?}", duties.validator_pubkey())
+                        )
+                    })
+                    .ok()?;
+
+                // The duties may have been replaced whilst the selection proof was computed, in
+                // which case the new duties have their own subscription.
+                if self.store.set_selection_proof(epoch, &duties) {
+                    duties.subscription()
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Subscribes the beacon node to the subnets required for the `subscriptions`.
+    async fn subscribe(&self, subscriptions: Vec<ValidatorSubscription>) -> Result<(), String> {
+        let log = self.context.log().clone();
+        let count = subscriptions.len();
+
+        if count == 0 {
+            return Ok(());
         }
+
+        self.beacon_node
+            .http
+            .validator()
+            .subscribe(subscriptions)
+            .await
+            .map_err(|e| format!("Failed to subscribe validators: {:?}", e))
+            .map(move |status| {
+                match status {
+                    PublishStatus::Valid => debug!(
+                        log,
+                        "Successfully subscribed validators";
+                        "count" => count
+                    ),
+                    PublishStatus::Unknown => error!(
+                        log,
+                        "Unknown response from subscription";
+                    ),
+                    PublishStatus::Invalid(e) => error!(
+                        log,
+                        "Failed to subscribe validator";
+                        "error" => e
+                    ),
+                };
+            })
     }
 }
 
@@ -732,3 +1065,148 @@ fn duties_match_epoch(duties: &ValidatorDuty, epoch: Epoch, slots_per_epoch: u64
                 .all(|slot| slot.epoch(slots_per_epoch) == epoch)
         })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::{Keypair, Signature};
+
+    const SLOTS_PER_EPOCH: u64 = 8;
+
+    fn duty(keypair: &Keypair, epoch: Epoch, attestation_offset: u64) -> DutyAndProof {
+        DutyAndProof {
+            duty: ValidatorDuty {
+                validator_pubkey: keypair.pk.clone(),
+                validator_index: Some(0),
+                attestation_slot: Some(epoch.start_slot(SLOTS_PER_EPOCH) + attestation_offset),
+                attestation_committee_index: Some(0),
+                attestation_committee_position: Some(0),
+                committee_count_at_slot: Some(1),
+                block_proposal_slots: None,
+                aggregator_modulo: Some(1),
+                dependent_root: Hash256::zero(),
+            },
+            selection_proof: None,
+        }
+    }
+
+    fn with_selection_proof(mut duties: DutyAndProof) -> DutyAndProof {
+        duties.selection_proof = Some(SelectionProof::from(Signature::empty_signature()));
+        duties
+    }
+
+    fn head_event(slot: Slot, current_root: Hash256, next_root: Hash256) -> HeadEvent {
+        HeadEvent {
+            reorg: true,
+            current_head_slot: slot,
+            current_head_beacon_block_root: Hash256::zero(),
+            previous_head_beacon_block_root: Hash256::zero(),
+            current_epoch_dependent_root: current_root,
+            next_epoch_dependent_root: next_root,
+        }
+    }
+
+    #[test]
+    fn changed_dependent_root_requires_update() {
+        let store = DutiesStore::default();
+        let (old_root, new_root) = (Hash256::repeat_byte(1), Hash256::repeat_byte(2));
+        let (epoch, next_epoch) = (Epoch::new(1), Epoch::new(2));
+        let head_slot = epoch.start_slot(SLOTS_PER_EPOCH) + 1;
+
+        store.set_dependent_root(epoch, old_root);
+        store.set_dependent_root(next_epoch, old_root);
+
+        assert!(
+            store
+                .epochs_to_update(
+                    &head_event(head_slot, old_root, old_root),
+                    epoch,
+                    SLOTS_PER_EPOCH
+                )
+                .is_empty(),
+            "unchanged roots should not require an update"
+        );
+        assert_eq!(
+            store.epochs_to_update(
+                &head_event(head_slot, new_root, old_root),
+                epoch,
+                SLOTS_PER_EPOCH
+            ),
+            vec![epoch],
+            "a new current root should only require the head epoch"
+        );
+        assert_eq!(
+            store.epochs_to_update(
+                &head_event(head_slot, old_root, new_root),
+                epoch,
+                SLOTS_PER_EPOCH
+            ),
+            vec![epoch, next_epoch],
+            "a new next root should require the head and next epochs"
+        );
+        assert_eq!(
+            store.epochs_to_update(
+                &head_event(head_slot, old_root, new_root),
+                next_epoch,
+                SLOTS_PER_EPOCH
+            ),
+            vec![next_epoch],
+            "past epochs should never be updated"
+        );
+    }
+
+    #[test]
+    fn unknown_dependent_root_does_not_require_update() {
+        let store = DutiesStore::default();
+        let head_slot = Epoch::new(1).start_slot(SLOTS_PER_EPOCH);
+
+        assert!(store
+            .epochs_to_update(
+                &head_event(head_slot, Hash256::repeat_byte(1), Hash256::repeat_byte(2)),
+                Epoch::new(1),
+                SLOTS_PER_EPOCH
+            )
+            .is_empty());
+    }
+
+    #[test]
+    fn refetched_duties_replace_stale_selection_proofs() {
+        let store = DutiesStore::default();
+        let keypair = Keypair::random();
+        let epoch = Epoch::new(1);
+
+        let original = duty(&keypair, epoch, 0);
+        assert_eq!(
+            store.insert(epoch, original.clone(), SLOTS_PER_EPOCH),
+            Ok(InsertOutcome::NewValidator)
+        );
+        assert_eq!(
+            store.insert(epoch, original.clone(), SLOTS_PER_EPOCH),
+            Ok(InsertOutcome::Identical),
+            "downloading the same duties again should not require a subscription"
+        );
+
+        // The duties are downloaded again after a re-org moves the attestation slot, whilst the
+        // selection proof of the original duties is still being computed.
+        let refetched = duty(&keypair, epoch, 1);
+        assert_eq!(
+            store.insert(epoch, refetched.clone(), SLOTS_PER_EPOCH),
+            Ok(InsertOutcome::Replaced {
+                should_resubscribe: true
+            })
+        );
+
+        assert!(
+            !store.set_selection_proof(epoch, &with_selection_proof(original.clone())),
+            "the selection proof of the replaced duties should be rejected"
+        );
+        assert!(store
+            .attesters(original.duty.attestation_slot.unwrap(), SLOTS_PER_EPOCH)
+            .is_empty());
+
+        assert!(store.set_selection_proof(epoch, &with_selection_proof(refetched.clone())));
+        let attesters = store.attesters(refetched.duty.attestation_slot.unwrap(), SLOTS_PER_EPOCH);
+        assert_eq!(attesters.len(), 1);
+        assert!(attesters[0].selection_proof.is_some());
+    }
+}
@@ -0,0 +1,252 @@
+use serde_derive::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::SystemTime;
+use types::{graffiti_from_str, Graffiti, PublicKey};
+
+/// The key used to specify the graffiti for validators which are not listed in the file.
+const DEFAULT_KEY: &str = "default";
+
+#[derive(Debug)]
+pub enum Error {
+    /// The graffiti file could not be read.
+    UnableToReadFile(io::Error),
+    /// A line of the file is not of the form `<key>: <graffiti>`.
+    InvalidLine(String),
+    /// The key of a line is neither `default` nor a valid `0x`-prefixed public key.
+    InvalidPublicKey(String),
+    /// The graffiti of a line is too long.
+    InvalidGraffiti(String),
+}
+
+/// A file which maps validator public keys to the graffiti they include in their blocks.
+///
+/// Each line of the file has the form `<0x-prefixed public key>: <graffiti>`. A line of the form
+/// `default: <graffiti>` provides the graffiti for any validator which is not listed. Empty lines
+/// are ignored.
+///
+/// The file is re-read whenever it has been modified since it was last read, so graffiti can be
+/// changed without restarting the validator client.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraffitiFile {
+    path: PathBuf,
+    graffitis: HashMap<PublicKey, Graffiti>,
+    default: Option<Graffiti>,
+    last_modified: Option<SystemTime>,
+}
+
+impl GraffitiFile {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            graffitis: HashMap::new(),
+            default: None,
+            last_modified: None,
+        }
+    }
+
+    /// Returns the graffiti for `public_key`, falling back to the `default` graffiti if the
+    /// validator is not listed.
+    ///
+    /// Re-reads the file if it has been modified since it was last read.
+    pub fn load_graffiti(&mut self, public_key: &PublicKey) -> Result<Option<Graffiti>, Error> {
+        if self.last_modified != Some(self.modified()?) {
+            self.read_graffiti_file()?;
+        }
+
+        Ok(self.graffiti(public_key))
+    }
+
+    /// Returns the graffiti for `public_key` from the most recent successful read of the file,
+    /// without checking whether the file has since been modified.
+    pub fn graffiti(&self, public_key: &PublicKey) -> Option<Graffiti> {
+        self.graffitis.get(public_key).copied().or(self.default)
+    }
+
+    /// Reads and parses the entire file, replacing any previously loaded graffiti.
+    ///
+    /// Previously loaded graffiti is left untouched if the file is invalid.
+    pub fn read_graffiti_file(&mut self) -> Result<(), Error> {
+        let last_modified = self.modified()?;
+        let contents = fs::read_to_string(&self.path).map_err(Error::UnableToReadFile)?;
+
+        let mut graffitis = HashMap::new();
+        let mut default = None;
+
+        for line in contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+        {
+            let (key, graffiti) = read_line(line)?;
+
+            match key {
+                Some(public_key) => {
+                    graffitis.insert(public_key, graffiti);
+                }
+                None => default = Some(graffiti),
+            }
+        }
+
+        self.graffitis = graffitis;
+        self.default = default;
+        self.last_modified = Some(last_modified);
+
+        Ok(())
+    }
+
+    /// Returns the time at which the file was last modified.
+    fn modified(&self) -> Result<SystemTime, Error> {
+        fs::metadata(&self.path)
+            .and_then(|metadata| metadata.modified())
+            .map_err(Error::UnableToReadFile)
+    }
+}
+
+/// Parses a single `<key>: <graffiti>` line, returning `None` for the key of the `default` line.
+fn read_line(line: &str) -> Result<(Option<PublicKey>, Graffiti), Error> {
+    let mut split = line.splitn(2, ':');
+
+    let key = split
+        .next()
+        .map(str::trim)
+        .ok_or_else(|| Error::InvalidLine(line.to_string()))?;
+    let value = split
+        .next()
+        .map(str::trim)
+        .ok_or_else(|| Error::InvalidLine(line.to_string()))?;
+
+    let graffiti = graffiti_from_str(value).map_err(Error::InvalidGraffiti)?;
+
+    if key == DEFAULT_KEY {
+        return Ok((None, graffiti));
+    }
+
+    let public_key = if key.starts_with("0x") {
+        hex::decode(&key[2..])
+            .ok()
+            .and_then(|bytes| PublicKey::from_bytes(&bytes).ok())
+    } else {
+        None
+    }
+    .ok_or_else(|| Error::InvalidPublicKey(key.to_string()))?;
+
+    Ok((Some(public_key), graffiti))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bls::Keypair;
+    use tempdir::TempDir;
+
+    fn graffiti(string: &str) -> Graffiti {
+        graffiti_from_str(string).unwrap()
+    }
+
+    #[test]
+    fn loads_graffiti() {
+        let dir = TempDir::new("graffiti_file").unwrap();
+        let path = dir.path().join("graffiti.txt");
+
+        let listed = Keypair::random().pk;
+        let unlisted = Keypair::random().pk;
+
+        fs::write(
+            &path,
+            format!(
+                "default: default graffiti\n\n{}: validator graffiti\n",
+                listed.as_hex_string()
+            ),
+        )
+        .unwrap();
+
+        let mut file = GraffitiFile::new(path);
+
+        assert_eq!(
+            file.load_graffiti(&listed).unwrap(),
+            Some(graffiti("validator graffiti"))
+        );
+        assert_eq!(
+            file.load_graffiti(&unlisted).unwrap(),
+            Some(graffiti("default graffiti"))
+        );
+    }
+
+    #[test]
+    fn no_default() {
+        let dir = TempDir::new("graffiti_file").unwrap();
+        let path = dir.path().join("graffiti.txt");
+
+        let listed = Keypair::random().pk;
+
+        fs::write(&path, format!("{}: graffiti", listed.as_hex_string())).unwrap();
+
+        let mut file = GraffitiFile::new(path);
+
+        assert_eq!(
+            file.load_graffiti(&Keypair::random().pk).unwrap(),
+            None,
+            "unlisted validators should have no graffiti"
+        );
+    }
+
+    #[test]
+    fn keeps_graffiti_after_invalid_read() {
+        let dir = TempDir::new("graffiti_file").unwrap();
+        let path = dir.path().join("graffiti.txt");
+
+        let listed = Keypair::random().pk;
+
+        fs::write(
+            &path,
+            format!(
+                "default: default graffiti\n{}: graffiti",
+                listed.as_hex_string()
+            ),
+        )
+        .unwrap();
+
+        let mut file = GraffitiFile::new(path.clone());
+        file.read_graffiti_file().unwrap();
+
+        fs::write(&path, "default graffiti").unwrap();
+
+        assert!(file.read_graffiti_file().is_err());
+        assert_eq!(
+            file.graffiti(&listed),
+            Some(graffiti("graffiti")),
+            "should keep the previously loaded graffiti"
+        );
+        assert_eq!(
+            file.graffiti(&Keypair::random().pk),
+            Some(graffiti("default graffiti")),
+            "should keep the previously loaded default"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_files() {
+        let dir = TempDir::new("graffiti_file").unwrap();
+        let path = dir.path().join("graffiti.txt");
+        let public_key = Keypair::random().pk;
+
+        for contents in &[
+            "default graffiti".to_string(),
+            "0x1234: graffiti".to_string(),
+            format!("{}: {}", public_key.as_hex_string(), "a".repeat(33)),
+        ] {
+            fs::write(&path, contents).unwrap();
+
+            assert!(
+                GraffitiFile::new(path.clone())
+                    .read_graffiti_file()
+                    .is_err(),
+                "should reject {:?}",
+                contents
+            );
+        }
+    }
+}
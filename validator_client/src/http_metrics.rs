@@ -0,0 +1,121 @@
+//! Serves the metrics of the validator client (e.g., block proposal timings) at `/metrics`, in the
+//! Prometheus text format.
+
+use environment::TaskExecutor;
+use futures::FutureExt;
+use hyper::server::conn::AddrStream;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use lighthouse_metrics::{Encoder, TextEncoder};
+use slog::{info, warn};
+use std::net::SocketAddr;
+
+/// Starts the metrics server on `listen_addr`, shutting it down when `executor` exits.
+///
+/// Returns the address the server is listening on, which differs from `listen_addr` if its port
+/// is 0.
+pub fn serve(executor: TaskExecutor, listen_addr: SocketAddr) -> Result<SocketAddr, String> {
+    let log = executor.log().clone();
+
+    let make_service = make_service_fn(|_socket: &AddrStream| async {
+        Ok::<_, hyper::Error>(service_fn(handle_request))
+    });
+
+    let server = Server::try_bind(&listen_addr)
+        .map_err(|e| format!("Unable to bind metrics server to {}: {:?}", listen_addr, e))?
+        .serve(make_service);
+    let actual_listen_addr = server.local_addr();
+
+    let exit = executor.exit();
+    let inner_log = log.clone();
+    let server_future = server
+        .with_graceful_shutdown(async move {
+            let _ = exit.await;
+        })
+        .map(move |result| {
+            if let Err(e) = result {
+                warn!(inner_log, "Metrics server failed"; "error" => format!("{:?}", e));
+            }
+        });
+    executor.spawn_without_exit(server_future, "metrics_http");
+
+    info!(
+        log,
+        "Metrics server started";
+        "address" => format!("{}", actual_listen_addr.ip()),
+        "port" => actual_listen_addr.port(),
+    );
+
+    Ok(actual_listen_addr)
+}
+
+async fn handle_request(req: Request<Body>) -> Result<Response<Body>, hyper::Error> {
+    let (status, body) = match (req.method(), req.uri().path()) {
+        (&Method::GET, "/metrics") => match gather_metrics() {
+            Ok(metrics) => (StatusCode::OK, metrics),
+            Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e),
+        },
+        _ => (StatusCode::NOT_FOUND, "Not found".to_string()),
+    };
+
+    Ok(Response::builder()
+        .status(status)
+        .header("content-type", "text/plain; charset=utf-8")
+        .body(Body::from(body))
+        .expect("response should build from static parts"))
+}
+
+/// Returns all registered metrics, encoded in the Prometheus text format.
+fn gather_metrics() -> Result<String, String> {
+    let mut buffer = vec![];
+    TextEncoder::new()
+        .encode(&lighthouse_metrics::gather(), &mut buffer)
+        .map_err(|e| format!("Failed to encode metrics: {:?}", e))?;
+
+    String::from_utf8(buffer).map_err(|e| format!("Failed to encode metrics: {:?}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics;
+    use hyper::Client;
+    use std::net::{IpAddr, Ipv4Addr};
+
+    #[tokio::test]
+    async fn serves_block_metrics() {
+        let (_signal, exit) = exit_future::signal();
+        let log = slog::Logger::root(slog::Discard, slog::o!());
+        let executor = TaskExecutor::new(tokio::runtime::Handle::current(), exit, log);
+
+        let listen_addr = serve(
+            executor,
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 0),
+        )
+        .expect("should start metrics server");
+
+        metrics::inc_counter(&metrics::BLOCK_PROPOSALS_PUBLISHED);
+
+        let client = Client::new();
+        let response = client
+            .get(format!("http://{}/metrics", listen_addr).parse().unwrap())
+            .await
+            .expect("should request metrics");
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = hyper::body::to_bytes(response.into_body())
+            .await
+            .expect("should read body");
+        let body = String::from_utf8(body.to_vec()).expect("body should be utf8");
+        assert!(
+            body.contains("vc_block_proposals_published_total"),
+            "should serve the block proposal metrics"
+        );
+
+        let response = client
+            .get(format!("http://{}/unknown", listen_addr).parse().unwrap())
+            .await
+            .expect("should request unknown path");
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -1,10 +1,16 @@
+#[macro_use]
+extern crate lazy_static;
+
 mod attestation_service;
 mod block_service;
 mod cli;
 mod config;
 mod duties_service;
 mod fork_service;
+mod graffiti_file;
+mod http_metrics;
 mod is_synced;
+mod metrics;
 mod notifier;
 mod validator_store;
 
@@ -24,6 +30,7 @@ use remote_beacon_node::RemoteBeaconNode;
 use slog::{error, info, warn, Logger};
 use slot_clock::SlotClock;
 use slot_clock::SystemTimeSlotClock;
+use std::net::SocketAddr;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::time::{delay_for, Duration};
 use types::EthSpec;
@@ -36,12 +43,17 @@ const RETRY_DELAY: Duration = Duration::from_secs(2);
 /// The global timeout for HTTP requests to the beacon node.
 const HTTP_TIMEOUT: Duration = Duration::from_secs(12);
 
-pub struct ProductionValidatorClient<T: EthSpec> {
+/// A validator client whose services are driven by a slot clock of type `S`.
+///
+/// Production uses the `SystemTimeSlotClock`, whilst tests may use a slot clock which is advanced
+/// manually.
+pub struct ProductionValidatorClient<T: EthSpec, S = SystemTimeSlotClock> {
     context: RuntimeContext<T>,
-    duties_service: DutiesService<SystemTimeSlotClock, T>,
-    fork_service: ForkService<SystemTimeSlotClock, T>,
-    block_service: BlockService<SystemTimeSlotClock, T>,
-    attestation_service: AttestationService<SystemTimeSlotClock, T>,
+    slot_clock: S,
+    duties_service: DutiesService<S, T>,
+    fork_service: ForkService<S, T>,
+    block_service: BlockService<S, T>,
+    attestation_service: AttestationService<S, T>,
     config: Config,
 }
 
@@ -59,13 +71,25 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
 
     /// Instantiates the validator client, _without_ starting the timers to trigger block
     /// and attestation production.
-    pub async fn new(mut context: RuntimeContext<T>, config: Config) -> Result<Self, String> {
+    pub async fn new(context: RuntimeContext<T>, config: Config) -> Result<Self, String> {
+        Self::new_with_slot_clock(context, config).await
+    }
+}
+
+impl<T: EthSpec, S: SlotClock + Clone + 'static> ProductionValidatorClient<T, S> {
+    /// Instantiates the validator client with a slot clock of type `S`, _without_ starting the
+    /// timers to trigger block and attestation production.
+    pub async fn new_with_slot_clock(
+        mut context: RuntimeContext<T>,
+        config: Config,
+    ) -> Result<Self, String> {
         let log = context.log().clone();
 
         info!(
             log,
             "Starting validator client";
             "beacon_node" => &config.http_server,
+            "fallback_beacon_nodes" => format!("{:?}", config.fallback_http_servers),
             "datadir" => format!("{:?}", config.data_dir),
         );
 
@@ -95,9 +119,12 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             "count" => validators.len(),
         );
 
-        let beacon_node =
-            RemoteBeaconNode::new_with_timeout(config.http_server.clone(), HTTP_TIMEOUT)
-                .map_err(|e| format!("Unable to init beacon node http client: {}", e))?;
+        let beacon_node = init_beacon_node(&config.http_server, &config)?;
+        let fallback_beacon_nodes = config
+            .fallback_http_servers
+            .iter()
+            .map(|server| init_beacon_node(server, &config))
+            .collect::<Result<Vec<_>, _>>()?;
 
         // TODO: check if all logs in wait_for_node are produed while awaiting
         let beacon_node = wait_for_node(beacon_node, &log).await?;
@@ -167,7 +194,7 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
         // for Lighthouse.
         context.eth2_config = eth2_config;
 
-        let slot_clock = SystemTimeSlotClock::new(
+        let slot_clock = S::new(
             context.eth2_config.spec.genesis_slot,
             Duration::from_secs(genesis_time),
             Duration::from_millis(context.eth2_config.spec.milliseconds_per_slot),
@@ -179,7 +206,7 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             .runtime_context(context.service_context("fork".into()))
             .build()?;
 
-        let validator_store: ValidatorStore<SystemTimeSlotClock, T> = ValidatorStore::new(
+        let validator_store: ValidatorStore<S, T> = ValidatorStore::new(
             validators,
             &config,
             genesis_validators_root,
@@ -212,12 +239,15 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             .slot_clock(slot_clock.clone())
             .validator_store(validator_store.clone())
             .beacon_node(beacon_node.clone())
+            .fallback_beacon_nodes(fallback_beacon_nodes)
             .runtime_context(context.service_context("block".into()))
+            .graffiti(config.graffiti)
+            .graffiti_file(config.graffiti_file.clone())
             .build()?;
 
         let attestation_service = AttestationServiceBuilder::new()
             .duties_service(duties_service.clone())
-            .slot_clock(slot_clock)
+            .slot_clock(slot_clock.clone())
             .validator_store(validator_store)
             .beacon_node(beacon_node)
             .runtime_context(context.service_context("attestation".into()))
@@ -225,6 +255,7 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
 
         Ok(Self {
             context,
+            slot_clock,
             duties_service,
             fork_service,
             block_service,
@@ -262,8 +293,73 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
 
         spawn_notifier(self).map_err(|e| format!("Failed to start notifier: {}", e))?;
 
+        if self.config.metrics_enabled {
+            http_metrics::serve(
+                self.context.executor.clone(),
+                SocketAddr::new(self.config.metrics_listen_address, self.config.metrics_port),
+            )
+            .map_err(|e| format!("Unable to start metrics server: {}", e))?;
+        }
+
         Ok(())
     }
+
+    /// Returns the slot clock which drives the services of this validator client.
+    pub fn slot_clock(&self) -> &S {
+        &self.slot_clock
+    }
+
+    /// Updates the fork and duties, then proposes and publishes a block if one of our validators
+    /// is the proposer at the current slot.
+    ///
+    /// Unlike `start_service`, no timers are involved: each duty is complete when this returns.
+    /// Intended for driving the validator client with a manual slot clock, as an alternative to
+    /// `start_service`.
+    pub async fn propose_blocks(&self) -> Result<(), String> {
+        let slot = self
+            .slot_clock
+            .now()
+            .ok_or_else(|| "Unable to read slot clock".to_string())?;
+
+        self.fork_service
+            .clone()
+            .do_update()
+            .await
+            .map_err(|()| "Unable to update fork".to_string())?;
+        self.duties_service.update_duties_now().await?;
+
+        self.block_service
+            .propose_now(self.duties_service.block_proposers(slot))
+            .await
+    }
+
+    /// Produces and publishes the attestations, and then any aggregates, of our validators at
+    /// the current slot.
+    ///
+    /// Like `propose_blocks`, this is an alternative to `start_service` and requires the duties
+    /// for the current slot to have been updated by `propose_blocks`.
+    pub async fn attest(&self) -> Result<(), String> {
+        self.attestation_service.attest_now().await
+    }
+}
+
+/// Creates a client for the beacon node at `server`, using the API token from `config`.
+fn init_beacon_node<E: EthSpec>(
+    server: &str,
+    config: &Config,
+) -> Result<RemoteBeaconNode<E>, String> {
+    let beacon_node = RemoteBeaconNode::new_with_timeout(server.to_string(), HTTP_TIMEOUT)
+        .map_err(|e| {
+            format!(
+                "Unable to init beacon node http client for {}: {}",
+                server, e
+            )
+        })?;
+
+    Ok(match config.http_api_token.clone() {
+        Some(api_token) => beacon_node.with_api_token(api_token),
+        None => beacon_node,
+    })
 }
 
 /// Request the version from the node, looping back and trying again on failure. Exit once the node
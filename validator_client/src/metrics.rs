@@ -0,0 +1,47 @@
+pub use lighthouse_metrics::*;
+
+lazy_static! {
+    /*
+     * Block proposals
+     */
+    pub static ref BLOCK_PROPOSAL_TIMES: Result<Histogram> = try_create_histogram(
+        "vc_block_proposal_seconds",
+        "Full runtime of producing, signing and publishing a block"
+    );
+    pub static ref BLOCK_PRODUCTION_TIMES: Result<Histogram> = try_create_histogram(
+        "vc_block_production_seconds",
+        "Time taken for a beacon node to produce a block"
+    );
+    pub static ref BLOCK_PUBLICATION_TIMES: Result<Histogram> = try_create_histogram(
+        "vc_block_publication_seconds",
+        "Time taken for a beacon node to accept a published block"
+    );
+    pub static ref BLOCK_PUBLICATION_DELAY: Result<Histogram> = try_create_histogram(
+        "vc_block_publication_delay_seconds",
+        "Duration between the start of the slot and a block being published"
+    );
+    pub static ref BLOCK_PROPOSALS_PUBLISHED: Result<IntCounter> = try_create_int_counter(
+        "vc_block_proposals_published_total",
+        "Count of blocks which were accepted by a beacon node"
+    );
+    pub static ref BLOCK_PROPOSALS_ABANDONED: Result<IntCounter> = try_create_int_counter(
+        "vc_block_proposals_abandoned_total",
+        "Count of block proposals abandoned because the proposal deadline passed"
+    );
+    pub static ref BLOCK_PROPOSAL_BEACON_NODE_ERRORS: Result<IntCounter> = try_create_int_counter(
+        "vc_block_proposal_beacon_node_errors_total",
+        "Count of failed or timed-out beacon node requests whilst proposing a block"
+    );
+
+    /*
+     * Duties
+     */
+    pub static ref DUTIES_DEPENDENT_ROOT_CHANGES: Result<IntCounter> = try_create_int_counter(
+        "vc_duties_dependent_root_changes_total",
+        "Count of duties downloaded again because their dependent root changed"
+    );
+    pub static ref DUTIES_SELECTION_PROOF_TIMES: Result<Histogram> = try_create_histogram(
+        "vc_duties_selection_proof_seconds",
+        "Time taken to compute the selection proofs for a batch of new duties"
+    );
+}
@@ -6,7 +6,9 @@ use tokio::time::{interval_at, Duration, Instant};
 use types::EthSpec;
 
 /// Spawns a notifier service which periodically logs information about the node.
-pub fn spawn_notifier<T: EthSpec>(client: &ProductionValidatorClient<T>) -> Result<(), String> {
+pub fn spawn_notifier<T: EthSpec, S: SlotClock + 'static>(
+    client: &ProductionValidatorClient<T, S>,
+) -> Result<(), String> {
     let context = client.context.service_context("notifier".into());
     let executor = context.executor.clone();
     let duties_service = client.duties_service.clone();